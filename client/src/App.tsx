@@ -28,7 +28,7 @@
  *    - Displays connection status
  * 
  * Extension points:
- *    - Add new input types in currentInputRef and InputState
+ *    - Add new actions as ACTION_* bits (input.ts) and map them in keyMap
  *    - Extend determineAnimation for new animation states
  *    - Add new reducers calls for game features (see handleCastSpellInput)
  *    - Modify game loop timing or prediction logic
@@ -37,6 +37,7 @@
  *    - components/GameScene.tsx: 3D rendering with Three.js
  *    - components/Player.tsx: Character model and animation
  *    - components/DebugPanel.tsx: Developer tools and state inspection
+ *    - input.ts: InputState helpers and the ACTION_* bits
  *    - generated/: Auto-generated TypeScript bindings from the server
  */
 
@@ -50,6 +51,10 @@ import { JoinGameDialog } from './components/JoinGameDialog';
 import * as THREE from 'three';
 import { PlayerUI } from './components/PlayerUI';
 import { VotingPanel } from './components/VotingPanel';
+import {
+  ACTION_ATTACK, ACTION_BLOCK, ACTION_CAST_SPELL, ACTION_JUMP, ACTION_SPRINT,
+  aimFromRotation, hasAction, idleInput, moveAxisFromKeys, sameInput, setAction,
+} from './input';
 
 // Type Aliases
 type DbConnection = moduleBindings.DbConnection;
@@ -74,12 +79,9 @@ function App() {
   const [isPointerLocked, setIsPointerLocked] = useState(false); // State for pointer lock status
 
   // --- Ref for current input state ---
-  const currentInputRef = useRef<InputState>({
-    forward: false, backward: false, left: false, right: false,
-    sprint: false, jump: false, attack: false, castSpell: false,
-    sequence: 0,
-  });
-  const lastSentInputState = useRef<Partial<InputState>>({});
+  const currentInputRef = useRef<InputState>(idleInput());
+  const lastSentInputState = useRef<InputState | null>(null);
+  const heldMoveKeysRef = useRef<Set<string>>(new Set()); // WASD keys currently down
   const animationFrameIdRef = useRef<number | null>(null); // For game loop
 
   // New import for handling player rotation data
@@ -172,18 +174,20 @@ function App() {
   }, []);
 
   // --- Input State Management ---
-  const keyMap: { [key: string]: keyof Omit<InputState, 'sequence' | 'castSpell'> } = {
-      KeyW: 'forward', KeyS: 'backward', KeyA: 'left', KeyD: 'right',
-      ShiftLeft: 'sprint', Space: 'jump',
+  const moveKeys = new Set(['KeyW', 'KeyS', 'KeyA', 'KeyD']);
+  const keyMap: { [key: string]: bigint } = {
+      ShiftLeft: ACTION_SPRINT, Space: ACTION_JUMP,
   };
 
   const determineAnimation = useCallback((input: InputState): string => {
-    if (input.attack) return 'attack1';
-    if (input.castSpell) return 'cast';
-    if (input.jump) return 'jump';
+    if (hasAction(input, ACTION_ATTACK)) return 'attack1';
+    if (hasAction(input, ACTION_CAST_SPELL)) return 'cast';
+    if (hasAction(input, ACTION_JUMP)) return 'jump';
     
-    // Determine animation based on movement keys
-    const { forward, backward, left, right, sprint } = input;
+    // Determine animation based on the move axis
+    const forward = input.moveAxis.y > 0, backward = input.moveAxis.y < 0;
+    const left = input.moveAxis.x < 0, right = input.moveAxis.x > 0;
+    const sprint = hasAction(input, ACTION_SPRINT);
     const isMoving = forward || backward || left || right;
     
     if (!isMoving) return 'idle';
@@ -232,20 +236,20 @@ function App() {
       z: playerRotationRef.current.z
     };
     
+    // Aim where the camera looks
+    currentInputState.aim = aimFromRotation(playerRotationRef.current);
+
     // Determine animation from input state
     const currentAnimation = determineAnimation(currentInputState);
 
-    let changed = false;
-    for (const key in currentInputState) {
-        if (currentInputState[key as keyof InputState] !== lastSentInputState.current[key as keyof InputState]) {
-            changed = true;
-            break;
-        }
-    }
-
-    if (changed || currentInputState.sequence !== lastSentInputState.current.sequence) {
+    const lastSent = lastSentInputState.current;
+    if (!lastSent || !sameInput(currentInputState, lastSent)) {
         conn.reducers.updatePlayerInput(currentInputState, currentPosition, currentRotation, currentAnimation);
-        lastSentInputState.current = { ...currentInputState };
+        lastSentInputState.current = {
+            ...currentInputState,
+            moveAxis: { ...currentInputState.moveAxis },
+            aim: { ...currentInputState.aim },
+        };
     }
   }, [identity, localPlayer, connected, determineAnimation]);
 
@@ -257,36 +261,40 @@ function App() {
 
   const handleKeyDown = useCallback((event: KeyboardEvent) => {
       if (event.repeat) return; 
+      if (moveKeys.has(event.code)) {
+          heldMoveKeysRef.current.add(event.code);
+          currentInputRef.current.moveAxis = moveAxisFromKeys(heldMoveKeysRef.current);
+      }
       const action = keyMap[event.code];
       if (action) {
-          if (!currentInputRef.current[action]) { 
-             currentInputRef.current[action] = true;
-          }
+          setAction(currentInputRef.current, action, true);
       }
   }, []);
 
   const handleKeyUp = useCallback((event: KeyboardEvent) => {
+      if (moveKeys.has(event.code)) {
+          heldMoveKeysRef.current.delete(event.code);
+          currentInputRef.current.moveAxis = moveAxisFromKeys(heldMoveKeysRef.current);
+      }
       const action = keyMap[event.code];
       if (action) {
-          if (currentInputRef.current[action]) { 
-              currentInputRef.current[action] = false;
-          }
+          setAction(currentInputRef.current, action, false);
       }
   }, []);
 
   const handleMouseDown = useCallback((event: MouseEvent) => {
       if (event.button === 0) { 
-           if (!currentInputRef.current.attack) {
-               currentInputRef.current.attack = true;
-           }
+           setAction(currentInputRef.current, ACTION_ATTACK, true);
+      } else if (event.button === 2) {
+           setAction(currentInputRef.current, ACTION_BLOCK, true);
       }
   }, []);
 
   const handleMouseUp = useCallback((event: MouseEvent) => {
       if (event.button === 0) { 
-           if (currentInputRef.current.attack) {
-               currentInputRef.current.attack = false;
-           }
+           setAction(currentInputRef.current, ACTION_ATTACK, false);
+      } else if (event.button === 2) {
+           setAction(currentInputRef.current, ACTION_BLOCK, false);
       }
   }, []);
 
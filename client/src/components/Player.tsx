@@ -11,6 +11,7 @@ import * as THREE from 'three';
 import { useAnimations, Html, Sphere } from '@react-three/drei'; // Assuming Sphere might be used for debug/hitbox
 import { FBXLoader } from 'three/examples/jsm/loaders/FBXLoader.js';
 import { PlayerData, InputState } from '../generated'; // Adjust path if needed
+import { ACTION_ATTACK, ACTION_CAST_SPELL, ACTION_JUMP, ACTION_SPRINT, hasAction } from '../input';
 
 // Define animation names for reuse
 const ANIMATIONS = {
@@ -128,7 +129,7 @@ export const Player: React.FC<PlayerProps> = ({
     // --- Client-Side Movement Calculation (Prediction) ---
     const calculateClientMovement = useCallback((currentPos: THREE.Vector3, currentRotY: number, inputState: InputState, delta: number): THREE.Vector3 => {
       // ... (guard clauses, speed calculation) ...
-      const speed = inputState.sprint ? PLAYER_SPEED * SPRINT_MULTIPLIER : PLAYER_SPEED;
+      const speed = hasAction(inputState, ACTION_SPRINT) ? PLAYER_SPEED * SPRINT_MULTIPLIER : PLAYER_SPEED;
       let localMoveX = 0;
       let localMoveZ = 0;
   
@@ -136,17 +137,15 @@ export const Player: React.FC<PlayerProps> = ({
           // ... (keep orbital logic as is for now) ...
       } else { // FOLLOW mode - Fix the movement mapping
           // --- CORRECTED INPUT MAPPING ---
-          if (inputState.forward) localMoveZ -= 1; // Forward is negative Z in Three.js
-          if (inputState.backward) localMoveZ += 1; // Backward is positive Z
-          if (inputState.left) localMoveX -= 1;    // Left is negative X
-          if (inputState.right) localMoveX += 1;   // Right is positive X
+          localMoveZ = -inputState.moveAxis.y; // Forward (+y) is negative Z in Three.js
+          localMoveX = inputState.moveAxis.x;  // Right (+x) is positive X
           // --- END CORRECTED INPUT MAPPING ---
       }
   
       // ... (rest of vector normalization, rotation, scaling) ...
       const localMoveVector = new THREE.Vector3(localMoveX, 0, localMoveZ);
-      if (localMoveVector.lengthSq() > 0) {
-          localMoveVector.normalize();
+      if (localMoveVector.lengthSq() > 1) {
+          localMoveVector.normalize(); // Cap at 1 like the server's move_vector
       }
       const rotationYaw = (cameraMode === CAMERA_MODES.ORBITAL)
           ? orbitalCameraRef.current.playerFacingRotation
@@ -683,14 +682,16 @@ export const Player: React.FC<PlayerProps> = ({
             let desiredAnimation = ANIMATIONS.IDLE;
             if (currentInput) {
                 // Prioritize actions
-                if (currentInput.attack) desiredAnimation = ANIMATIONS.ATTACK;
-                else if (currentInput.jump) desiredAnimation = ANIMATIONS.JUMP; // Add jump check
-                else if (currentInput.castSpell) desiredAnimation = ANIMATIONS.CAST; // Add cast check
+                const sprint = hasAction(currentInput, ACTION_SPRINT);
+                const { x: strafe, y: forward } = currentInput.moveAxis;
+                if (hasAction(currentInput, ACTION_ATTACK)) desiredAnimation = ANIMATIONS.ATTACK;
+                else if (hasAction(currentInput, ACTION_JUMP)) desiredAnimation = ANIMATIONS.JUMP; // Add jump check
+                else if (hasAction(currentInput, ACTION_CAST_SPELL)) desiredAnimation = ANIMATIONS.CAST; // Add cast check
                 // Then movement
-                else if (currentInput.forward) desiredAnimation = currentInput.sprint ? ANIMATIONS.RUN_FORWARD : ANIMATIONS.WALK_FORWARD;
-                else if (currentInput.backward) desiredAnimation = currentInput.sprint ? ANIMATIONS.RUN_BACK : ANIMATIONS.WALK_BACK;
-                else if (currentInput.left) desiredAnimation = currentInput.sprint ? ANIMATIONS.RUN_LEFT : ANIMATIONS.WALK_LEFT;
-                else if (currentInput.right) desiredAnimation = currentInput.sprint ? ANIMATIONS.RUN_RIGHT : ANIMATIONS.WALK_RIGHT;
+                else if (forward > 0) desiredAnimation = sprint ? ANIMATIONS.RUN_FORWARD : ANIMATIONS.WALK_FORWARD;
+                else if (forward < 0) desiredAnimation = sprint ? ANIMATIONS.RUN_BACK : ANIMATIONS.WALK_BACK;
+                else if (strafe < 0) desiredAnimation = sprint ? ANIMATIONS.RUN_LEFT : ANIMATIONS.WALK_LEFT;
+                else if (strafe > 0) desiredAnimation = sprint ? ANIMATIONS.RUN_RIGHT : ANIMATIONS.WALK_RIGHT;
             }
             // Play the determined animation (unless a non-looping one is already playing)
             if (currentAnimation !== desiredAnimation && animations[currentAnimation]?.loop !== THREE.LoopOnce) {
@@ -0,0 +1,61 @@
+/**
+ * Vibe Coding Starter Pack: 3D Multiplayer - input.ts
+ *
+ * Helpers for building the InputState sent to the server's
+ * update_player_input reducer.
+ *
+ * Key components:
+ * - ACTION_*: Digital action bits packed into InputState.actions. These must
+ *   match the ACTION_* constants in server/src/common.rs bit for bit
+ * - idleInput: An InputState with nothing held and no movement
+ * - hasAction / setAction: Read and toggle one action bit
+ * - moveAxisFromKeys: WASD to move_axis (x = strafe, +right; y = forward,
+ *   +forward), each component -1, 0 or 1
+ * - aimFromRotation: The camera's look direction in world space, sent as aim
+ * - sameInput: Field-by-field comparison used to skip redundant sends
+ *
+ * Related files:
+ * - App.tsx: Keyboard and mouse handling that fills the InputState
+ * - components/Player.tsx: Client-side movement prediction and animation
+ * - server/src/common.rs: The InputState definition the server decodes
+ */
+
+import * as THREE from 'three';
+import { InputState } from './generated';
+
+// Append new bits; never renumber existing ones (the server reads them raw)
+export const ACTION_SPRINT = 1n << 0n;
+export const ACTION_JUMP = 1n << 1n;
+export const ACTION_ATTACK = 1n << 2n;
+export const ACTION_CAST_SPELL = 1n << 3n;
+export const ACTION_BLOCK = 1n << 4n;
+
+export const idleInput = (): InputState => ({
+  actions: 0n,
+  moveAxis: { x: 0, y: 0 },
+  aim: { x: 0, y: 0, z: 0 },
+  sequence: 0,
+});
+
+export const hasAction = (input: InputState, action: bigint): boolean =>
+  (input.actions & action) !== 0n;
+
+export const setAction = (input: InputState, action: bigint, held: boolean) => {
+  input.actions = held ? input.actions | action : input.actions & ~action;
+};
+
+export const moveAxisFromKeys = (held: ReadonlySet<string>) => ({
+  x: (held.has('KeyD') ? 1 : 0) - (held.has('KeyA') ? 1 : 0),
+  y: (held.has('KeyW') ? 1 : 0) - (held.has('KeyS') ? 1 : 0),
+});
+
+export const aimFromRotation = (rotation: THREE.Euler) => {
+  const direction = new THREE.Vector3(0, 0, -1).applyEuler(rotation);
+  return { x: direction.x, y: direction.y, z: direction.z };
+};
+
+export const sameInput = (a: InputState, b: InputState): boolean =>
+  a.actions === b.actions &&
+  a.moveAxis.x === b.moveAxis.x && a.moveAxis.y === b.moveAxis.y &&
+  a.aim.x === b.aim.x && a.aim.y === b.aim.y && a.aim.z === b.aim.z &&
+  a.sequence === b.sequence;
@@ -0,0 +1,48 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - admin.rs
+ *
+ * Operator identities allowed to call admin-only reducers.
+ *
+ * Key components:
+ * - Admin table: Identities with admin rights (private)
+ * - seed_admin: Called from init so whoever publishes the module becomes admin
+ * - require_admin: Guard used at the top of admin reducers
+ * - grant_admin: Lets an existing admin add another one
+ */
+
+use spacetimedb::{Identity, ReducerContext, Table, Timestamp};
+
+#[spacetimedb::table(name = admin)]
+pub struct Admin {
+    #[primary_key]
+    pub identity: Identity,
+    pub granted_at: Timestamp,
+}
+
+pub fn seed_admin(ctx: &ReducerContext) {
+    if ctx.db.admin().identity().find(ctx.sender).is_none() {
+        spacetimedb::log::info!("[INIT] Granting admin rights to publisher {}", ctx.sender);
+        ctx.db.admin().insert(Admin { identity: ctx.sender, granted_at: ctx.timestamp });
+    }
+}
+
+pub fn is_admin(ctx: &ReducerContext) -> bool {
+    ctx.sender == ctx.identity() || ctx.db.admin().identity().find(ctx.sender).is_some()
+}
+
+pub fn require_admin(ctx: &ReducerContext) -> Result<(), String> {
+    if is_admin(ctx) {
+        Ok(())
+    } else {
+        Err("Admin rights required".to_string())
+    }
+}
+
+#[spacetimedb::reducer]
+pub fn grant_admin(ctx: &ReducerContext, identity: Identity) -> Result<(), String> {
+    require_admin(ctx)?;
+    if ctx.db.admin().identity().find(identity).is_none() {
+        ctx.db.admin().insert(Admin { identity, granted_at: ctx.timestamp });
+    }
+    Ok(())
+}
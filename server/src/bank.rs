@@ -0,0 +1,221 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - bank.rs
+ *
+ * Per-player bank storage, larger than the carried inventory and only
+ * reachable at a banker.
+ *
+ * Key components:
+ * - Banker table (public): Marks an NPC or a decoration (a vault, a chest
+ *   of drawers) in a room as a banker. Room owners add and remove them
+ * - BankSlot table (public): One stack in one of a player's BANK_SLOTS.
+ *   Players only see their own bank (RLS). Stacks follow the same max_stack
+ *   rules as the inventory, and rolled items keep a slot to themselves
+ * - deposit_item / withdraw_item: Player reducers. The player must be alive
+ *   and within INTERACT_RANGE of a banker in their room. Items go in and
+ *   out by stack and quantity; a transfer that doesn't fit fails as a whole
+ * - add_banker / remove_banker: Room-owner reducers
+ *
+ * Related files:
+ * - inventory.rs: The carried side of every transfer
+ * - npc.rs / decoration.rs: What a banker can be
+ * - room.rs: close_room clears a room's bankers (banks belong to players
+ *   and stay)
+ */
+
+use spacetimedb::{client_visibility_filter, Filter, Identity, ReducerContext, SpacetimeType, Table};
+
+use crate::common::{Vector3, BANK_SLOTS, INTERACT_RANGE, MAX_BANKERS_PER_ROOM};
+use crate::decoration::decoration as _;
+use crate::inventory;
+use crate::item::{item_def as _, ItemDef};
+use crate::npc::npc as _;
+use crate::player as _;
+use crate::room;
+use crate::PlayerData;
+
+#[derive(SpacetimeType, Clone, Copy, Debug, PartialEq)]
+pub enum BankerId {
+    Npc(u64),
+    Decoration(u64),
+}
+
+#[spacetimedb::table(name = banker, public)]
+#[derive(Clone)]
+pub struct Banker {
+    #[primary_key]
+    #[auto_inc]
+    pub banker_id: u64,
+    #[index(btree)]
+    pub room_name: String,
+    pub target: BankerId,
+}
+
+#[spacetimedb::table(name = bank_slot, public)]
+#[derive(Clone)]
+pub struct BankSlot {
+    #[primary_key]
+    #[auto_inc]
+    pub bank_slot_id: u64,
+    #[index(btree)]
+    pub owner: Identity,
+    pub slot: u32, // 0..BANK_SLOTS
+    pub item_def_id: u64,
+    pub quantity: u32,
+    pub instance_id: Option<u64>, // Rolled items (item_instance.rs)
+}
+
+#[client_visibility_filter]
+const OWNER_SEES_BANK: Filter = Filter::Sql(
+    "SELECT * FROM bank_slot WHERE owner = :sender",
+);
+
+// Where the banker stands, if it still exists
+fn banker_position(ctx: &ReducerContext, target: BankerId) -> Option<(String, Vector3)> {
+    match target {
+        BankerId::Npc(npc_id) => ctx.db.npc().npc_id().find(npc_id).map(|n| (n.room_name, n.position)),
+        BankerId::Decoration(decoration_id) => {
+            ctx.db.decoration().decoration_id().find(decoration_id).map(|d| (d.room_name, d.position))
+        }
+    }
+}
+
+// The live sender, if they stand at the banker
+fn require_at_banker(ctx: &ReducerContext, banker_id: u64) -> Result<PlayerData, String> {
+    let Some(player) = ctx.db.player().identity().find(ctx.sender) else {
+        return Err("Player not found".to_string());
+    };
+    let Some(banker) = ctx.db.banker().banker_id().find(banker_id) else {
+        return Err("Banker not found".to_string());
+    };
+    let Some((room_name, position)) = banker_position(ctx, banker.target) else {
+        return Err("Banker not found".to_string());
+    };
+    if player.is_dead {
+        return Err("Dead players can't bank".to_string());
+    }
+    if player.room_name != room_name || player.position.distance_xz(&position) > INTERACT_RANGE {
+        return Err("Too far away".to_string());
+    }
+    Ok(player)
+}
+
+// Put items in the owner's bank, topping up stacks before using free slots.
+// A rolled item takes a free slot. Fails without writing anything if they
+// don't fit
+fn store(
+    ctx: &ReducerContext,
+    owner: Identity,
+    def: &ItemDef,
+    quantity: u32,
+    instance_id: Option<u64>,
+) -> Result<(), String> {
+    let max_stack = if instance_id.is_some() { 1 } else { def.max_stack.max(1) };
+    let slots: Vec<BankSlot> = ctx.db.bank_slot().owner().filter(owner).collect();
+    let stacks: Vec<BankSlot> = slots.iter()
+        .filter(|s| s.item_def_id == def.item_def_id && s.instance_id.is_none() && s.quantity < max_stack)
+        .cloned()
+        .collect();
+    let free: Vec<u32> = (0..BANK_SLOTS).filter(|slot| slots.iter().all(|s| s.slot != *slot)).collect();
+    let room: u64 = stacks.iter().map(|s| (max_stack - s.quantity) as u64).sum::<u64>()
+        + free.len() as u64 * max_stack as u64;
+    if room < quantity as u64 {
+        return Err("Your bank is full".to_string());
+    }
+
+    let mut remaining = quantity;
+    for mut stack in stacks {
+        if remaining == 0 {
+            break;
+        }
+        let added = remaining.min(max_stack - stack.quantity);
+        stack.quantity += added;
+        remaining -= added;
+        ctx.db.bank_slot().bank_slot_id().update(stack);
+    }
+    for slot in free {
+        if remaining == 0 {
+            break;
+        }
+        let added = remaining.min(max_stack);
+        remaining -= added;
+        ctx.db.bank_slot().insert(BankSlot {
+            bank_slot_id: 0,
+            owner,
+            slot,
+            item_def_id: def.item_def_id,
+            quantity: added,
+            instance_id,
+        });
+    }
+    Ok(())
+}
+
+pub fn clear_room(ctx: &ReducerContext, room_name: &str) {
+    let bankers: Vec<u64> = ctx.db.banker().room_name().filter(room_name).map(|b| b.banker_id).collect();
+    for banker_id in bankers {
+        ctx.db.banker().banker_id().delete(banker_id);
+    }
+}
+
+// --- Bank Reducers ---
+
+// Move `quantity` of an inventory stack into the bank
+#[spacetimedb::reducer]
+pub fn deposit_item(ctx: &ReducerContext, banker_id: u64, item_id: u64, quantity: u32) -> Result<(), String> {
+    let player = require_at_banker(ctx, banker_id)?;
+    let (item_def_id, instance_id) = inventory::remove_from_stack(ctx, &player, item_id, quantity)?;
+    let Some(def) = ctx.db.item_def().item_def_id().find(item_def_id) else {
+        return Err("Item not found".to_string());
+    };
+    store(ctx, player.identity, &def, quantity, instance_id)
+}
+
+// Move `quantity` of a bank stack into the inventory
+#[spacetimedb::reducer]
+pub fn withdraw_item(ctx: &ReducerContext, banker_id: u64, bank_slot_id: u64, quantity: u32) -> Result<(), String> {
+    let player = require_at_banker(ctx, banker_id)?;
+    let Some(mut stack) = ctx.db.bank_slot().bank_slot_id().find(bank_slot_id).filter(|s| s.owner == ctx.sender) else {
+        return Err("Item not found".to_string());
+    };
+    if quantity == 0 || quantity > stack.quantity {
+        return Err(format!("Pick between 1 and {} of those", stack.quantity));
+    }
+    let Some(def) = ctx.db.item_def().item_def_id().find(stack.item_def_id) else {
+        return Err("Item not found".to_string());
+    };
+    inventory::add_item(ctx, player.identity, &def, quantity, stack.instance_id)?;
+    if quantity == stack.quantity {
+        ctx.db.bank_slot().bank_slot_id().delete(bank_slot_id);
+    } else {
+        stack.quantity -= quantity;
+        ctx.db.bank_slot().bank_slot_id().update(stack);
+    }
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn add_banker(ctx: &ReducerContext, target: BankerId) -> Result<(), String> {
+    let Some((room_name, _)) = banker_position(ctx, target) else {
+        return Err("Banker not found".to_string());
+    };
+    room::require_room_owner(ctx, &room_name)?;
+    let bankers: Vec<Banker> = ctx.db.banker().room_name().filter(&room_name).collect();
+    if bankers.iter().any(|b| b.target == target) {
+        return Err("That is already a banker".to_string());
+    }
+    if bankers.len() >= MAX_BANKERS_PER_ROOM {
+        return Err(format!("A room can have at most {} bankers", MAX_BANKERS_PER_ROOM));
+    }
+    ctx.db.banker().insert(Banker { banker_id: 0, room_name, target });
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn remove_banker(ctx: &ReducerContext, banker_id: u64) -> Result<(), String> {
+    let Some(banker) = ctx.db.banker().banker_id().find(banker_id) else {
+        return Err("Banker not found".to_string());
+    };
+    room::require_room_owner(ctx, &banker.room_name)?;
+    ctx.db.banker().banker_id().delete(banker_id);
+    Ok(())
+}
@@ -0,0 +1,265 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - boss.rs
+ *
+ * Scripted boss encounters: a boss NPC that changes phase at health
+ * thresholds and rewards everyone who fought it.
+ *
+ * Key components:
+ * - BossSpec: What a room owner authors: name, health, starting AI
+ *   personality, the phases and the loot pool (item_def ids)
+ * - BossPhase: Entered once the boss drops to health_fraction of its max
+ *   health. A phase can switch the boss to a new AI personality (new
+ *   abilities), spawn adds around it and turn on BOSS_ARENA_HAZARD_COUNT
+ *   periodic hazards on a ring around its home (hazard.rs)
+ * - BossEncounter table (public): The running encounter, its current phase,
+ *   the adds and hazards it spawned and every player who damaged the boss
+ * - BossReward table (public): Loot awarded to participants when the boss
+ *   dies; each participant gets one random item from the pool. There is no
+ *   inventory yet, so rewards are recorded here
+ * - tick_bosses: Called from game_tick after the NPC AI. Advances phases
+ *   and, once the boss NPC is gone, marks the encounter defeated, awards
+ *   loot, notifies participants and removes its arena hazards
+ * - start_boss_encounter: Room-owner reducer
+ *
+ * Related files:
+ * - combat.rs: record_participant is called when a player damages an NPC
+ * - npc.rs / npc_ai.rs: The boss and its adds are regular Monster NPCs
+ */
+
+use spacetimedb::rand::Rng;
+use spacetimedb::{Identity, ReducerContext, SpacetimeType, Table, Timestamp};
+
+use crate::common::{
+    Vector3, BOSS_ADD_SPAWN_RADIUS, BOSS_ARENA_HAZARD_ACTIVE_MS, BOSS_ARENA_HAZARD_COUNT, BOSS_ARENA_HAZARD_DISTANCE,
+    BOSS_ARENA_HAZARD_PERIOD_MS, BOSS_ARENA_HAZARD_RADIUS, MAX_BOSS_ADDS_PER_PHASE, MAX_BOSS_PHASES,
+};
+use crate::hazard::{self, periodic_hazard as _, HazardCycle, PeriodicHazardKind};
+use crate::item::item_def as _;
+use crate::notification::{self, NotificationKind};
+use crate::npc::{self, npc as _, NpcKind};
+use crate::npc_ai;
+use crate::population;
+use crate::room;
+
+#[derive(SpacetimeType, Clone, Debug, PartialEq)]
+pub struct BossPhase {
+    pub health_fraction: f32,  // Entered at or below this fraction of max health
+    pub personality: String,   // Empty = keep the current one
+    pub adds: u32,             // Monsters spawned around the boss
+    pub adds_personality: String,
+    pub arena_hazard: Option<PeriodicHazardKind>,
+}
+
+#[derive(SpacetimeType, Clone, Debug, PartialEq)]
+pub struct BossSpec {
+    pub name: String,
+    pub health: i32,
+    pub personality: String,
+    pub phases: Vec<BossPhase>, // Ordered by descending health_fraction
+    pub loot: Vec<u64>,         // item_def ids
+}
+
+#[derive(SpacetimeType, Clone, Copy, Debug, PartialEq)]
+pub enum BossState {
+    Active,
+    Defeated,
+}
+
+#[spacetimedb::table(name = boss_encounter, public)]
+#[derive(Clone)]
+pub struct BossEncounter {
+    #[primary_key]
+    #[auto_inc]
+    pub encounter_id: u64,
+    #[index(btree)]
+    pub room_name: String,
+    #[index(btree)]
+    pub npc_id: u64,
+    pub spec: BossSpec,
+    pub state: BossState,
+    pub phase: u32, // Number of phases entered so far
+    pub adds: Vec<u64>,
+    pub arena_hazards: Vec<u64>,
+    pub participants: Vec<Identity>,
+    pub started_at: Timestamp,
+    pub defeated_at: Option<Timestamp>,
+}
+
+#[spacetimedb::table(name = boss_reward, public)]
+#[derive(Clone)]
+pub struct BossReward {
+    #[primary_key]
+    #[auto_inc]
+    pub reward_id: u64,
+    #[index(btree)]
+    pub identity: Identity,
+    pub encounter_id: u64,
+    pub item_def_id: u64,
+    pub awarded_at: Timestamp,
+}
+
+// Remember that a player fought this NPC, if it's a boss
+pub fn is_boss(ctx: &ReducerContext, npc_id: u64) -> bool {
+    ctx.db.boss_encounter().npc_id().filter(npc_id).any(|e| e.state == BossState::Active)
+}
+
+pub fn record_participant(ctx: &ReducerContext, npc_id: u64, identity: Identity) {
+    let encounters: Vec<BossEncounter> = ctx.db.boss_encounter().npc_id().filter(npc_id)
+        .filter(|e| e.state == BossState::Active && !e.participants.contains(&identity))
+        .collect();
+    for mut encounter in encounters {
+        encounter.participants.push(identity);
+        ctx.db.boss_encounter().encounter_id().update(encounter);
+    }
+}
+
+fn enter_phase(ctx: &ReducerContext, encounter: &mut BossEncounter, phase: &BossPhase, boss_position: &Vector3, home: &Vector3) {
+    if !phase.personality.is_empty() {
+        if let Some(mut boss) = ctx.db.npc().npc_id().find(encounter.npc_id) {
+            boss.personality = phase.personality.clone();
+            ctx.db.npc().npc_id().update(boss);
+        }
+    }
+    let mut rng = ctx.rng();
+    for _ in 0..phase.adds {
+        let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+        let position = Vector3 {
+            x: boss_position.x + angle.cos() * BOSS_ADD_SPAWN_RADIUS,
+            y: boss_position.y,
+            z: boss_position.z + angle.sin() * BOSS_ADD_SPAWN_RADIUS,
+        };
+        let add = npc::spawn_npc_at(ctx, &encounter.room_name, NpcKind::Monster, position, phase.adds_personality.clone());
+        encounter.adds.push(add.npc_id);
+    }
+    if let Some(kind) = phase.arena_hazard {
+        // Staggered so the ring fires in sequence rather than all at once
+        let stagger = BOSS_ARENA_HAZARD_PERIOD_MS / BOSS_ARENA_HAZARD_COUNT as u64;
+        for i in 0..BOSS_ARENA_HAZARD_COUNT {
+            let angle = i as f32 / BOSS_ARENA_HAZARD_COUNT as f32 * std::f32::consts::TAU;
+            let position = Vector3 {
+                x: home.x + angle.cos() * BOSS_ARENA_HAZARD_DISTANCE,
+                y: home.y,
+                z: home.z + angle.sin() * BOSS_ARENA_HAZARD_DISTANCE,
+            };
+            let cycle = HazardCycle {
+                period_ms: BOSS_ARENA_HAZARD_PERIOD_MS,
+                active_ms: BOSS_ARENA_HAZARD_ACTIVE_MS,
+                phase_offset_ms: i as u64 * stagger,
+            };
+            let hazard = hazard::spawn_periodic_hazard(
+                ctx,
+                &encounter.room_name,
+                kind,
+                position,
+                BOSS_ARENA_HAZARD_RADIUS,
+                cycle,
+            );
+            encounter.arena_hazards.push(hazard.hazard_id);
+        }
+    }
+    encounter.phase += 1;
+    spacetimedb::log::info!("Boss '{}' entered phase {}.", encounter.spec.name, encounter.phase);
+}
+
+fn defeat(ctx: &ReducerContext, encounter: &mut BossEncounter) {
+    encounter.state = BossState::Defeated;
+    encounter.defeated_at = Some(ctx.timestamp);
+    for hazard_id in encounter.arena_hazards.drain(..) {
+        ctx.db.periodic_hazard().hazard_id().delete(hazard_id);
+    }
+    let pool: Vec<u64> = encounter.spec.loot.iter().copied()
+        .filter(|id| ctx.db.item_def().item_def_id().find(*id).is_some())
+        .collect();
+    let mut rng = ctx.rng();
+    for identity in &encounter.participants {
+        let message = match pool.get(rng.gen_range(0..pool.len().max(1))) {
+            Some(item_def_id) => {
+                ctx.db.boss_reward().insert(BossReward {
+                    reward_id: 0,
+                    identity: *identity,
+                    encounter_id: encounter.encounter_id,
+                    item_def_id: *item_def_id,
+                    awarded_at: ctx.timestamp,
+                });
+                format!("{} was defeated! You received a reward", encounter.spec.name)
+            }
+            None => format!("{} was defeated!", encounter.spec.name),
+        };
+        notification::notify(ctx, *identity, NotificationKind::BossDefeated, message);
+    }
+    spacetimedb::log::info!(
+        "Boss '{}' defeated in room '{}' by {} players.",
+        encounter.spec.name,
+        encounter.room_name,
+        encounter.participants.len()
+    );
+}
+
+pub fn tick_bosses(ctx: &ReducerContext, room_name: &str) {
+    let encounters: Vec<BossEncounter> = ctx.db.boss_encounter().room_name().filter(room_name)
+        .filter(|e| e.state == BossState::Active)
+        .collect();
+    for mut encounter in encounters {
+        // Adds that died no longer need tracking
+        encounter.adds.retain(|npc_id| ctx.db.npc().npc_id().find(*npc_id).is_some());
+        match ctx.db.npc().npc_id().find(encounter.npc_id) {
+            None => defeat(ctx, &mut encounter),
+            Some(boss) => {
+                let health_fraction = boss.health as f32 / boss.max_health.max(1) as f32;
+                while let Some(phase) = encounter.spec.phases.get(encounter.phase as usize).cloned() {
+                    if health_fraction > phase.health_fraction {
+                        break;
+                    }
+                    enter_phase(ctx, &mut encounter, &phase, &boss.position, &boss.home_position);
+                }
+            }
+        }
+        ctx.db.boss_encounter().encounter_id().update(encounter);
+    }
+}
+
+// --- Boss Reducers ---
+
+#[spacetimedb::reducer]
+pub fn start_boss_encounter(ctx: &ReducerContext, room_name: String, position: Vector3, spec: BossSpec) -> Result<(), String> {
+    room::require_room_owner(ctx, &room_name)?;
+    if spec.name.is_empty() || spec.health <= 0 {
+        return Err("A boss needs a name and positive health".to_string());
+    }
+    if spec.phases.len() > MAX_BOSS_PHASES {
+        return Err(format!("A boss can have at most {} phases", MAX_BOSS_PHASES));
+    }
+    if spec.phases.windows(2).any(|w| w[1].health_fraction > w[0].health_fraction) {
+        return Err("Phases must be ordered by descending health fraction".to_string());
+    }
+    let personalities = std::iter::once(&spec.personality)
+        .chain(spec.phases.iter().flat_map(|p| [&p.personality, &p.adds_personality]));
+    for personality in personalities {
+        if !personality.is_empty() && !npc_ai::personality_exists(ctx, personality) {
+            return Err(format!("Unknown AI personality '{}'", personality));
+        }
+    }
+    if spec.phases.iter().any(|p| p.adds > MAX_BOSS_ADDS_PER_PHASE) {
+        return Err(format!("A phase can spawn at most {} adds", MAX_BOSS_ADDS_PER_PHASE));
+    }
+
+    let mut boss = npc::spawn_npc_at(ctx, &room_name, NpcKind::Monster, position, spec.personality.clone());
+    population::set_base_health(ctx, &mut boss, spec.health);
+    let npc_id = boss.npc_id;
+    ctx.db.npc().npc_id().update(boss);
+    spacetimedb::log::info!("Boss '{}' (NPC {}) spawned in room '{}'.", spec.name, npc_id, room_name);
+    ctx.db.boss_encounter().insert(BossEncounter {
+        encounter_id: 0,
+        room_name,
+        npc_id,
+        spec,
+        state: BossState::Active,
+        phase: 0,
+        adds: Vec::new(),
+        arena_hazards: Vec::new(),
+        participants: Vec::new(),
+        started_at: ctx.timestamp,
+        defeated_at: None,
+    });
+    Ok(())
+}
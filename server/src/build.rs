@@ -0,0 +1,202 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - build.rs
+ *
+ * Runtime terrain editing, Minecraft-style: blocks snap to the tile grid
+ * (TILE_SIZE across, BUILD_BLOCK_HEIGHT tall, level 0 is the ground layer)
+ * and are added to or removed from the room's own tile set.
+ *
+ * Key components:
+ * - place_tile / remove_tile: Player reducers. The editor must be alive and
+ *   within BUILD_RANGE of the block, and either own the room (or be an
+ *   admin) or be in a room whose owner turned build_mode on
+ * - Adjacency: A new block must share a face with an existing tile (above,
+ *   below or beside it) and can't overlap a tile or a player. Tiles someone
+ *   is standing on can't be removed. Blocks belong to whoever built them;
+ *   only they and the room owner can remove them (protection.rs)
+ * - BuildRate table (private): A fixed window of BUILD_RATE_WINDOW_MS in
+ *   which each player gets BUILD_EDITS_PER_WINDOW edits
+ * - set_build_mode: Room-owner reducer
+ *
+ * Related files:
+ * - map.rs: Room tile sets (instances)
+ * - chunk.rs: Placed blocks join their chunk and stream to nearby players
+ * - room.rs: Room.build_mode
+ */
+
+use spacetimedb::{Identity, ReducerContext, Table, Timestamp};
+use std::time::Duration;
+
+use crate::admin;
+use crate::chunk;
+use crate::common::{
+    Vector3, BUILD_BLOCK_HEIGHT, BUILD_EDITS_PER_WINDOW, BUILD_MAX_LEVEL, BUILD_RANGE, BUILD_RATE_WINDOW_MS, TILE_SIZE,
+};
+use crate::minimap;
+use crate::player as _;
+use crate::protection::{self, PlacedObject};
+use crate::room::{self, room as _, Room};
+use crate::spatial;
+use crate::{game_tile, GameTile, PlayerData};
+
+#[spacetimedb::table(name = build_rate)]
+pub struct BuildRate {
+    #[primary_key]
+    pub identity: Identity,
+    pub window_started_at: Timestamp,
+    pub edits: u32,
+}
+
+// Vertical overlap of a tile with [min_y, max_y], ignoring touching faces
+fn overlaps(tile: &GameTile, min_y: f32, max_y: f32) -> bool {
+    let (bottom, top) = (tile.position.y - tile.size.y * 0.5, tile.position.y + tile.size.y * 0.5);
+    bottom < max_y - 0.01 && top > min_y + 0.01
+}
+
+// Whether someone at `position` is standing on top of the tile
+fn supports(tile: &GameTile, position: &Vector3) -> bool {
+    let top = tile.position.y + tile.size.y * 0.5;
+    (position.x - tile.position.x).abs() <= tile.size.x * 0.5
+        && (position.z - tile.position.z).abs() <= tile.size.z * 0.5
+        && position.y >= top - 0.01
+        && position.y <= top + 1.0
+}
+
+fn tiles_in_column(ctx: &ReducerContext, map_id: u32, grid_x: i32, grid_z: i32) -> Vec<GameTile> {
+    let center = Vector3 { x: grid_x as f32 * TILE_SIZE, y: 0.0, z: grid_z as f32 * TILE_SIZE };
+    ctx.db.game_tile().map_cell().filter((map_id, spatial::grid_cell_of(&center))).collect()
+}
+
+// The live player, their room, and whether they may edit its terrain
+fn require_builder(ctx: &ReducerContext) -> Result<(PlayerData, Room), String> {
+    let Some(player) = ctx.db.player().identity().find(ctx.sender) else {
+        return Err("Player not found".to_string());
+    };
+    if player.is_dead {
+        return Err("Dead players can't build".to_string());
+    }
+    let Some(room) = room::find_room(ctx, &player.room_name) else {
+        return Err("Room not found".to_string());
+    };
+    if !room.build_mode && room.owner != Some(ctx.sender) && !admin::is_admin(ctx) {
+        return Err("Only the room owner can build here".to_string());
+    }
+    Ok((player, room))
+}
+
+fn check_range(player: &PlayerData, position: &Vector3) -> Result<(), String> {
+    if player.position.distance_xz(position) > BUILD_RANGE {
+        return Err("Too far away".to_string());
+    }
+    Ok(())
+}
+
+// Count one edit against the sender's window
+fn consume_edit(ctx: &ReducerContext) -> Result<(), String> {
+    let window = Duration::from_millis(BUILD_RATE_WINDOW_MS);
+    match ctx.db.build_rate().identity().find(ctx.sender) {
+        Some(mut rate) if rate.window_started_at + window > ctx.timestamp => {
+            if rate.edits >= BUILD_EDITS_PER_WINDOW {
+                return Err("You're building too fast".to_string());
+            }
+            rate.edits += 1;
+            ctx.db.build_rate().identity().update(rate);
+        }
+        Some(mut rate) => {
+            rate.window_started_at = ctx.timestamp;
+            rate.edits = 1;
+            ctx.db.build_rate().identity().update(rate);
+        }
+        None => {
+            ctx.db.build_rate().insert(BuildRate { identity: ctx.sender, window_started_at: ctx.timestamp, edits: 1 });
+        }
+    }
+    Ok(())
+}
+
+pub fn clear_player(ctx: &ReducerContext, identity: Identity) {
+    ctx.db.build_rate().identity().delete(identity);
+}
+
+// --- Build Reducers ---
+
+#[spacetimedb::reducer]
+pub fn place_tile(ctx: &ReducerContext, grid_x: i32, grid_z: i32, level: i32) -> Result<(), String> {
+    let (player, room) = require_builder(ctx)?;
+    if !(0..=BUILD_MAX_LEVEL).contains(&level) {
+        return Err(format!("Blocks go from level 0 to {}", BUILD_MAX_LEVEL));
+    }
+    let position = Vector3 {
+        x: grid_x as f32 * TILE_SIZE,
+        y: level as f32 * BUILD_BLOCK_HEIGHT,
+        z: grid_z as f32 * TILE_SIZE,
+    };
+    check_range(&player, &position)?;
+    let (min_y, max_y) = (position.y - BUILD_BLOCK_HEIGHT * 0.5, position.y + BUILD_BLOCK_HEIGHT * 0.5);
+
+    let column = tiles_in_column(ctx, room.map_id, grid_x, grid_z);
+    if column.iter().any(|t| overlaps(t, min_y, max_y)) {
+        return Err("There is already a block there".to_string());
+    }
+    let supported_vertically = column.iter().any(|t| overlaps(t, min_y - BUILD_BLOCK_HEIGHT, max_y + BUILD_BLOCK_HEIGHT));
+    let supported_sideways = [(1, 0), (-1, 0), (0, 1), (0, -1)].iter().any(|(dx, dz)| {
+        tiles_in_column(ctx, room.map_id, grid_x + dx, grid_z + dz).iter().any(|t| overlaps(t, min_y, max_y))
+    });
+    if !supported_vertically && !supported_sideways {
+        return Err("Blocks must touch an existing block".to_string());
+    }
+    let blocked = ctx.db.player().room_name().filter(&room.room_name).any(|p| {
+        (p.position.x - position.x).abs() < TILE_SIZE * 0.5
+            && (p.position.z - position.z).abs() < TILE_SIZE * 0.5
+            && p.position.y > min_y - 0.01
+            && p.position.y < max_y + 1.0
+    });
+    if blocked {
+        return Err("Something is in the way".to_string());
+    }
+    consume_edit(ctx)?;
+
+    let tile = ctx.db.game_tile().insert(GameTile {
+        tile_id: 0,
+        map_id: room.map_id,
+        room_name: room.room_name.clone(),
+        chunk_id: chunk::chunk_id_at(ctx, &room.room_name, room.map_id, &position),
+        hazard: None,
+        grid_cell: spatial::grid_cell_of(&position),
+        position,
+        size: Vector3 { x: TILE_SIZE, y: BUILD_BLOCK_HEIGHT, z: TILE_SIZE },
+        placed_by: Some(ctx.sender),
+    });
+    minimap::mark_dirty(ctx, tile.chunk_id);
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn remove_tile(ctx: &ReducerContext, tile_id: u64) -> Result<(), String> {
+    let (player, room) = require_builder(ctx)?;
+    let Some(tile) = ctx.db.game_tile().tile_id().find(tile_id) else {
+        return Err("Tile not found".to_string());
+    };
+    if tile.room_name != room.room_name || tile.map_id != room.map_id {
+        return Err("Tile not found".to_string());
+    }
+    check_range(&player, &tile.position)?;
+    if !protection::may_modify(ctx, &room, PlacedObject::Tile, tile_id, tile.placed_by) {
+        return Ok(());
+    }
+    let standing_on = ctx.db.player().room_name().filter(&room.room_name).any(|p| supports(&tile, &p.position));
+    if standing_on {
+        return Err("Someone is standing on that block".to_string());
+    }
+    consume_edit(ctx)?;
+    ctx.db.game_tile().tile_id().delete(tile_id);
+    minimap::mark_dirty(ctx, tile.chunk_id);
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn set_build_mode(ctx: &ReducerContext, room_name: String, enabled: bool) -> Result<(), String> {
+    let mut room = room::require_room_owner(ctx, &room_name)?;
+    room.build_mode = enabled;
+    ctx.db.room().room_name().update(room);
+    Ok(())
+}
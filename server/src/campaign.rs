@@ -0,0 +1,110 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - campaign.rs
+ *
+ * Checkpoints for co-op campaigns, so a party can stop a dungeon run and
+ * pick it up again later.
+ *
+ * Key components:
+ * - CampaignProgress table (public): One row per run of a campaign: the
+ *   group that plays it (members at the last save), the room instance it was
+ *   saved in, the current chapter, completed objectives and unlocked doors
+ * - save_checkpoint: Party-leader reducer. Updates the latest run of the
+ *   campaign the leader belongs to, or starts a new run
+ * - resume_campaign: Party-leader reducer. The leader must have been part of
+ *   the saved group; the party travels back to the saved room (party_travel)
+ *   and clients restore chapter / doors from the row
+ *
+ * Related files:
+ * - party.rs: require_party_leader / party_travel
+ */
+
+use spacetimedb::{Identity, ReducerContext, Table, Timestamp};
+
+use crate::common::MAX_CAMPAIGN_ENTRIES;
+use crate::party;
+use crate::player as _;
+
+#[spacetimedb::table(name = campaign_progress, public)]
+#[derive(Clone)]
+pub struct CampaignProgress {
+    #[primary_key]
+    #[auto_inc]
+    pub progress_id: u64,
+    #[index(btree)]
+    pub campaign_id: String,
+    pub members: Vec<Identity>, // Party roster at the last save; any of them may resume
+    pub room_name: String,      // Instance the checkpoint was saved in
+    pub chapter: u32,
+    pub completed_objectives: Vec<String>,
+    pub unlocked_doors: Vec<String>,
+    pub created_at: Timestamp,
+    pub saved_at: Timestamp,
+}
+
+#[spacetimedb::reducer]
+pub fn save_checkpoint(
+    ctx: &ReducerContext,
+    campaign_id: String,
+    chapter: u32,
+    completed_objectives: Vec<String>,
+    unlocked_doors: Vec<String>,
+) -> Result<(), String> {
+    let party_id = party::require_party_leader(ctx)?;
+    let Some(leader) = ctx.db.player().identity().find(ctx.sender) else {
+        return Err("Player not found".to_string());
+    };
+    if campaign_id.is_empty() {
+        return Err("Campaign id must not be empty".to_string());
+    }
+    if completed_objectives.len() > MAX_CAMPAIGN_ENTRIES || unlocked_doors.len() > MAX_CAMPAIGN_ENTRIES {
+        return Err(format!("At most {} objectives and doors can be saved", MAX_CAMPAIGN_ENTRIES));
+    }
+    let members: Vec<Identity> = party::members_of(ctx, party_id).into_iter().map(|m| m.identity).collect();
+    let existing = ctx.db.campaign_progress().campaign_id().filter(&campaign_id)
+        .filter(|p| p.members.contains(&ctx.sender))
+        .max_by_key(|p| p.saved_at);
+    match existing {
+        Some(mut progress) => {
+            progress.members = members;
+            progress.room_name = leader.room_name;
+            progress.chapter = chapter;
+            progress.completed_objectives = completed_objectives;
+            progress.unlocked_doors = unlocked_doors;
+            progress.saved_at = ctx.timestamp;
+            ctx.db.campaign_progress().progress_id().update(progress);
+        }
+        None => {
+            ctx.db.campaign_progress().insert(CampaignProgress {
+                progress_id: 0,
+                campaign_id,
+                members,
+                room_name: leader.room_name,
+                chapter,
+                completed_objectives,
+                unlocked_doors,
+                created_at: ctx.timestamp,
+                saved_at: ctx.timestamp,
+            });
+        }
+    }
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn resume_campaign(ctx: &ReducerContext, progress_id: u64, password: Option<String>) -> Result<(), String> {
+    party::require_party_leader(ctx)?;
+    let Some(progress) = ctx.db.campaign_progress().progress_id().find(progress_id) else {
+        return Err("Campaign progress not found".to_string());
+    };
+    if !progress.members.contains(&ctx.sender) {
+        return Err("You weren't part of that campaign run".to_string());
+    }
+    party::party_travel(ctx, progress.room_name.clone(), password)?;
+    spacetimedb::log::info!(
+        "Party led by {} resumed campaign '{}' at chapter {}.",
+        ctx.sender,
+        progress.campaign_id,
+        progress.chapter
+    );
+    Ok(())
+}
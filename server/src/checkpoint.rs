@@ -0,0 +1,130 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - checkpoint.rs
+ *
+ * Checkpoints for obstacle courses and hazard-heavy rooms: touch one and a
+ * fall or a hazard death sends you back there instead of to the room's
+ * spawn points. (Not to be confused with campaign.rs's saved progress.)
+ *
+ * Key components:
+ * - Checkpoint table (public): A trigger box in a room and the position
+ *   players return to
+ * - track_checkpoints: Called from game_tick.
+ *   - A living player inside a checkpoint's box gets it as their
+ *     last_checkpoint
+ *   - A living player who has fallen into the void (no tile of the room's
+ *     map beneath them, or below VOID_Y) is put back at their checkpoint,
+ *     or at a spawn point if they haven't touched one
+ * - respawn_position: Where revive_player (spawn.rs) puts a player killed
+ *   by the environment (hazard tiles, periodic hazards, burning)
+ * - place_checkpoint / remove_checkpoint: Room-owner reducers
+ *
+ * Related files:
+ * - lib.rs: PlayerData.last_checkpoint, game_tick
+ * - spawn.rs: revive_player / select_spawn_point
+ * - room.rs: close_room clears a room's checkpoints
+ */
+
+use spacetimedb::{ReducerContext, Table};
+
+use crate::common::{Vector3, MAX_CHECKPOINTS_PER_ROOM, VOID_Y};
+use crate::map;
+use crate::player as _;
+use crate::player_logic;
+use crate::room::{self, Room};
+use crate::spawn;
+use crate::PlayerData;
+
+#[spacetimedb::table(name = checkpoint, public)]
+#[derive(Clone)]
+pub struct Checkpoint {
+    #[primary_key]
+    #[auto_inc]
+    pub checkpoint_id: u64,
+    #[index(btree)]
+    pub room_name: String,
+    pub min: Vector3,
+    pub max: Vector3,
+    pub respawn_position: Vector3,
+}
+
+impl Checkpoint {
+    fn contains(&self, position: &Vector3) -> bool {
+        (self.min.x..=self.max.x).contains(&position.x)
+            && (self.min.y..=self.max.y).contains(&position.y)
+            && (self.min.z..=self.max.z).contains(&position.z)
+    }
+}
+
+// The player's last checkpoint, if it is still in their room
+pub fn respawn_position(ctx: &ReducerContext, player: &PlayerData) -> Option<Vector3> {
+    let checkpoint = ctx.db.checkpoint().checkpoint_id().find(player.last_checkpoint?)?;
+    (checkpoint.room_name == player.room_name).then_some(checkpoint.respawn_position)
+}
+
+pub fn track_checkpoints(ctx: &ReducerContext, room: &Room) {
+    let checkpoints: Vec<Checkpoint> = ctx.db.checkpoint().room_name().filter(&room.room_name).collect();
+    let players: Vec<PlayerData> = ctx.db.player().room_name().filter(&room.room_name)
+        .filter(|p| !p.is_dead)
+        .collect();
+    for mut player in players {
+        if player.position.y < VOID_Y || !map::is_on_map(ctx, room.map_id, &player.position) {
+            let position = respawn_position(ctx, &player)
+                .unwrap_or_else(|| spawn::select_spawn_point(ctx, &room.room_name, player.identity));
+            // A room without ground to return to: leave the player be
+            if !map::is_on_map(ctx, room.map_id, &position) {
+                continue;
+            }
+            player_logic::set_player_position(ctx, &mut player, position);
+            ctx.db.player().identity().update(player);
+            continue;
+        }
+        let Some(checkpoint) = checkpoints.iter().find(|c| c.contains(&player.position)) else {
+            continue;
+        };
+        if player.last_checkpoint != Some(checkpoint.checkpoint_id) {
+            player.last_checkpoint = Some(checkpoint.checkpoint_id);
+            ctx.db.player().identity().update(player);
+        }
+    }
+}
+
+pub fn clear_room(ctx: &ReducerContext, room_name: &str) {
+    let checkpoints: Vec<u64> = ctx.db.checkpoint().room_name().filter(room_name).map(|c| c.checkpoint_id).collect();
+    for checkpoint_id in checkpoints {
+        ctx.db.checkpoint().checkpoint_id().delete(checkpoint_id);
+    }
+}
+
+// --- Checkpoint Reducers ---
+
+#[spacetimedb::reducer]
+pub fn place_checkpoint(
+    ctx: &ReducerContext,
+    room_name: String,
+    min: Vector3,
+    max: Vector3,
+    respawn_position: Vector3,
+) -> Result<(), String> {
+    let room = room::require_room_owner(ctx, &room_name)?;
+    if min.x >= max.x || min.y >= max.y || min.z >= max.z {
+        return Err("A checkpoint's min corner must be below its max corner".to_string());
+    }
+    if !map::is_on_map(ctx, room.map_id, &respawn_position) {
+        return Err("Checkpoints must return players onto the map".to_string());
+    }
+    if ctx.db.checkpoint().room_name().filter(&room_name).count() >= MAX_CHECKPOINTS_PER_ROOM {
+        return Err(format!("A room can have at most {} checkpoints", MAX_CHECKPOINTS_PER_ROOM));
+    }
+    ctx.db.checkpoint().insert(Checkpoint { checkpoint_id: 0, room_name, min, max, respawn_position });
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn remove_checkpoint(ctx: &ReducerContext, checkpoint_id: u64) -> Result<(), String> {
+    let Some(checkpoint) = ctx.db.checkpoint().checkpoint_id().find(checkpoint_id) else {
+        return Err("Checkpoint not found".to_string());
+    };
+    room::require_room_owner(ctx, &checkpoint.room_name)?;
+    ctx.db.checkpoint().checkpoint_id().delete(checkpoint_id);
+    Ok(())
+}
@@ -0,0 +1,247 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - chunk.rs
+ *
+ * World streaming: maps are split into square chunks of
+ * CHUNK_SIZE_TILES x CHUNK_SIZE_TILES tiles, and clients only receive the
+ * tiles of the chunks around them.
+ *
+ * Key components:
+ * - WorldChunk table (public): One row per chunk of a room's tile set.
+ *   Every instanced GameTile points at its chunk (chunk_id); authored map
+ *   sources have no chunk and are never streamed
+ * - Streamed maps (DEFAULT_MAP_ID) have no authored tiles: a chunk's flat
+ *   tiles are generated the first time a player comes within
+ *   CHUNK_VIEW_RADIUS chunks of it, at most MAX_CHUNKS_GENERATED_PER_TICK
+ *   per room tick. The map ends WORLD_CHUNK_LIMIT chunks from the origin
+ * - ChunkView table (public): The chunks each player currently sees.
+ *   stream_chunks (called from game_tick) refreshes a player's views
+ *   whenever they enter another chunk or their room changes maps
+ * - RLS: Clients only see their own views, the chunks of their own room,
+ *   and only the tiles of chunks they view. Chunks belong to one room, so
+ *   rooms never see each other's tiles
+ *
+ * Related files:
+ * - lib.rs: GameTile
+ * - room.rs: The hub room generates the chunks around the origin when it is
+ *   created, so it has ground for its spawn points
+ * - map.rs / mapgen.rs: Instanced tiles join their room's chunks
+ * - heightmap.rs: Optional rolling terrain per chunk
+ * - minimap.rs: A flag summary of each chunk's terrain
+ */
+
+use spacetimedb::{client_visibility_filter, Filter, Identity, ReducerContext, Table, Timestamp};
+
+use crate::common::{
+    Vector3, CHUNK_SIZE_TILES, CHUNK_VIEW_RADIUS, DEFAULT_MAP_ID, MAX_CHUNKS_GENERATED_PER_TICK, TILE_SIZE,
+    WORLD_CHUNK_LIMIT,
+};
+use crate::heightmap;
+use crate::minimap;
+use crate::player as _;
+use crate::room::Room;
+use crate::spatial;
+use crate::{game_tile, GameTile};
+
+#[spacetimedb::table(name = world_chunk, public, index(name = map_chunk, btree(columns = [map_id, chunk_x, chunk_z])))]
+#[derive(Clone)]
+pub struct WorldChunk {
+    #[primary_key]
+    #[auto_inc]
+    pub chunk_id: u64,
+    #[index(btree)]
+    pub room_name: String,
+    pub map_id: u32,
+    pub chunk_x: i32,
+    pub chunk_z: i32,
+    pub created_at: Timestamp,
+}
+
+#[spacetimedb::table(name = chunk_view, public)]
+#[derive(Clone)]
+pub struct ChunkView {
+    #[primary_key]
+    #[auto_inc]
+    pub view_id: u64,
+    #[index(btree)]
+    pub identity: Identity,
+    #[index(btree)]
+    pub chunk_id: u64,
+}
+
+// The chunk a player's views were last computed for
+#[spacetimedb::table(name = chunk_anchor)]
+#[derive(Clone)]
+pub struct ChunkAnchor {
+    #[primary_key]
+    pub identity: Identity,
+    pub map_id: u32,
+    pub chunk_x: i32,
+    pub chunk_z: i32,
+}
+
+#[client_visibility_filter]
+const PLAYER_SEES_OWN_CHUNK_VIEWS: Filter = Filter::Sql(
+    "SELECT * FROM chunk_view WHERE identity = :sender",
+);
+
+#[client_visibility_filter]
+const PLAYER_SEES_OWN_ROOM_CHUNKS: Filter = Filter::Sql(
+    "SELECT world_chunk.* FROM world_chunk JOIN player ON world_chunk.room_name = player.room_name WHERE player.identity = :sender",
+);
+
+#[client_visibility_filter]
+const PLAYER_SEES_TILES_OF_VIEWED_CHUNKS: Filter = Filter::Sql(
+    "SELECT game_tile.* FROM game_tile JOIN chunk_view ON game_tile.chunk_id = chunk_view.chunk_id WHERE chunk_view.identity = :sender",
+);
+
+// Maps whose tiles are generated chunk by chunk instead of authored
+pub fn is_streamed(map_id: u32) -> bool {
+    map_id == DEFAULT_MAP_ID
+}
+
+fn tile_index(value: f32) -> i32 {
+    (value / TILE_SIZE).round() as i32
+}
+
+// The (x, z) coordinates of the chunk containing `position`
+pub fn chunk_coords_of(position: &Vector3) -> (i32, i32) {
+    (
+        tile_index(position.x).div_euclid(CHUNK_SIZE_TILES),
+        tile_index(position.z).div_euclid(CHUNK_SIZE_TILES),
+    )
+}
+
+fn in_world(chunk_x: i32, chunk_z: i32) -> bool {
+    chunk_x.abs() <= WORLD_CHUNK_LIMIT && chunk_z.abs() <= WORLD_CHUNK_LIMIT
+}
+
+fn find_chunk(ctx: &ReducerContext, map_id: u32, chunk_x: i32, chunk_z: i32) -> Option<WorldChunk> {
+    ctx.db.world_chunk().map_chunk().filter((map_id, chunk_x, chunk_z)).next()
+}
+
+// The existing chunk containing `position`, if any
+pub fn chunk_at(ctx: &ReducerContext, map_id: u32, position: &Vector3) -> Option<WorldChunk> {
+    let (chunk_x, chunk_z) = chunk_coords_of(position);
+    find_chunk(ctx, map_id, chunk_x, chunk_z)
+}
+
+fn generate_tiles(ctx: &ReducerContext, chunk: &WorldChunk) {
+    for dx in 0..CHUNK_SIZE_TILES {
+        for dz in 0..CHUNK_SIZE_TILES {
+            let position = Vector3 {
+                x: (chunk.chunk_x * CHUNK_SIZE_TILES + dx) as f32 * TILE_SIZE,
+                y: 0.0,
+                z: (chunk.chunk_z * CHUNK_SIZE_TILES + dz) as f32 * TILE_SIZE,
+            };
+            ctx.db.game_tile().insert(GameTile {
+                tile_id: 0,
+                map_id: chunk.map_id,
+                room_name: chunk.room_name.clone(),
+                chunk_id: chunk.chunk_id,
+                hazard: None,
+                grid_cell: spatial::grid_cell_of(&position),
+                position,
+                size: Vector3 { x: TILE_SIZE, y: 1.0, z: TILE_SIZE },
+                placed_by: None,
+            });
+        }
+    }
+}
+
+// The chunk row, created (and on streamed maps filled with tiles) if missing
+pub fn ensure_chunk(ctx: &ReducerContext, room_name: &str, map_id: u32, chunk_x: i32, chunk_z: i32) -> WorldChunk {
+    if let Some(chunk) = find_chunk(ctx, map_id, chunk_x, chunk_z) {
+        return chunk;
+    }
+    let chunk = ctx.db.world_chunk().insert(WorldChunk {
+        chunk_id: 0,
+        room_name: room_name.to_string(),
+        map_id,
+        chunk_x,
+        chunk_z,
+        created_at: ctx.timestamp,
+    });
+    if is_streamed(map_id) && in_world(chunk_x, chunk_z) {
+        generate_tiles(ctx, &chunk);
+    }
+    minimap::mark_dirty(ctx, chunk.chunk_id);
+    chunk
+}
+
+// The chunk a room's tile at `position` belongs to
+pub fn chunk_id_at(ctx: &ReducerContext, room_name: &str, map_id: u32, position: &Vector3) -> u64 {
+    let (chunk_x, chunk_z) = chunk_coords_of(position);
+    ensure_chunk(ctx, room_name, map_id, chunk_x, chunk_z).chunk_id
+}
+
+// Generate every chunk of a streamed map within `radius` chunks of `center`
+pub fn generate_around(ctx: &ReducerContext, room_name: &str, map_id: u32, center: &Vector3, radius: i32) {
+    let (center_x, center_z) = chunk_coords_of(center);
+    for chunk_x in center_x - radius..=center_x + radius {
+        for chunk_z in center_z - radius..=center_z + radius {
+            if in_world(chunk_x, chunk_z) {
+                ensure_chunk(ctx, room_name, map_id, chunk_x, chunk_z);
+            }
+        }
+    }
+}
+
+pub fn delete_map_chunks(ctx: &ReducerContext, map_id: u32) {
+    let chunks: Vec<u64> = ctx.db.world_chunk().map_chunk().filter(map_id).map(|c| c.chunk_id).collect();
+    for chunk_id in chunks {
+        ctx.db.world_chunk().chunk_id().delete(chunk_id);
+        ctx.db.chunk_view().chunk_id().delete(chunk_id);
+        heightmap::clear_chunk(ctx, chunk_id);
+        minimap::clear_chunk(ctx, chunk_id);
+    }
+}
+
+pub fn clear_player(ctx: &ReducerContext, identity: Identity) {
+    ctx.db.chunk_anchor().identity().delete(identity);
+    let views: Vec<u64> = ctx.db.chunk_view().identity().filter(identity).map(|v| v.view_id).collect();
+    for view_id in views {
+        ctx.db.chunk_view().view_id().delete(view_id);
+    }
+}
+
+// The chunks around (center_x, center_z) a player should see. Returns None
+// if generating them would exceed this tick's `budget`
+fn visible_chunks(ctx: &ReducerContext, room: &Room, center_x: i32, center_z: i32, budget: &mut usize) -> Option<Vec<u64>> {
+    let map_id = room.map_id;
+    let mut chunk_ids = Vec::new();
+    for chunk_x in center_x - CHUNK_VIEW_RADIUS..=center_x + CHUNK_VIEW_RADIUS {
+        for chunk_z in center_z - CHUNK_VIEW_RADIUS..=center_z + CHUNK_VIEW_RADIUS {
+            if let Some(chunk) = find_chunk(ctx, map_id, chunk_x, chunk_z) {
+                chunk_ids.push(chunk.chunk_id);
+            } else if is_streamed(map_id) && in_world(chunk_x, chunk_z) {
+                if *budget == 0 {
+                    return None;
+                }
+                *budget -= 1;
+                chunk_ids.push(ensure_chunk(ctx, &room.room_name, map_id, chunk_x, chunk_z).chunk_id);
+            }
+        }
+    }
+    Some(chunk_ids)
+}
+
+pub fn stream_chunks(ctx: &ReducerContext, room: &Room) {
+    let mut budget = MAX_CHUNKS_GENERATED_PER_TICK;
+    for player in ctx.db.player().room_name().filter(&room.room_name) {
+        let (chunk_x, chunk_z) = chunk_coords_of(&player.position);
+        let anchor = ChunkAnchor { identity: player.identity, map_id: room.map_id, chunk_x, chunk_z };
+        let existing = ctx.db.chunk_anchor().identity().find(player.identity);
+        if existing.as_ref().is_some_and(|a| a.map_id == anchor.map_id && a.chunk_x == chunk_x && a.chunk_z == chunk_z) {
+            continue;
+        }
+        // Out of budget: keep the old views and retry next tick
+        let Some(chunk_ids) = visible_chunks(ctx, room, chunk_x, chunk_z, &mut budget) else {
+            break;
+        };
+        clear_player(ctx, player.identity);
+        for chunk_id in chunk_ids {
+            ctx.db.chunk_view().insert(ChunkView { view_id: 0, identity: player.identity, chunk_id });
+        }
+        ctx.db.chunk_anchor().insert(anchor);
+    }
+}
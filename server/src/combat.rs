@@ -0,0 +1,761 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - combat.rs
+ *
+ * Server-authoritative combat: hit detection, the damage pipeline and the
+ * bookkeeping derived from it.
+ *
+ * Key components:
+ *
+ * 1. Hit Detection:
+ *    - resolve_melee_attack: Finds every player/NPC in front of the attacker
+ *      within their weapon's range in the same room and damages them with
+ *      the weapon's damage (item.rs), scaled by the attacker's combo stage
+ *      (combo.rs)
+ *
+ *    - resolve_spell_cast: Class spells (Wizard fireball launches a
+ *      projectile, see projectile.rs; Paladin heals an ally, see support.rs)
+ *
+ * 2. Damage Pipeline:
+ *    - apply_damage: The single entry point for dealing damage to any EntityId.
+ *      Does nothing while the combat feature flag is off (config.rs)
+ *    - roll_damage: Each hit rolls +/- DAMAGE_VARIANCE and a CRIT_CHANCE for
+ *      CRIT_MULTIPLIER damage using the server RNG; crits are flagged with
+ *      is_crit in the combat log
+ *    - Every hit (and every heal, see support.rs) is appended to the public
+ *      combat_log table, which clients use for floating damage numbers and
+ *      kill feeds. Rows older than
+ *      COMBAT_LOG_RETENTION_SECS are removed by the prune_combat_log schedule
+ *    - Lethal damage marks players dead and schedules their respawn;
+ *      NPCs that reach zero health are removed, and so are destructible
+ *      props (destructible.rs)
+ *    - Player-vs-player damage is gated by the room's pvp_enabled and
+ *      friendly_fire flags (see can_damage_player), except between players
+ *      in an active duel (duel.rs), whose finishing blows are non-lethal.
+ *      Inside safe zones (safe_zone.rs) it is blocked entirely and the
+ *      zone's guards turn on the attacker
+ *    - Blocking players take reduced damage from the front; a fresh block
+ *      (parry window) negates the hit and staggers the attacker (resolve_block)
+ *    - Players ignore all damage until invulnerable_until, which is set on
+ *      respawn and briefly after a hit of BIG_HIT_FRACTION of max health
+ *
+ * 3. Area of Effect:
+ *    - apply_aoe: Finds players and NPCs within a radius via the spatial index
+ *      (spatial.rs), applies linear falloff damage and knockback, and
+ *      records an aoe_event row for clients (pruned after AOE_EVENT_TTL_SECS)
+ *    - Attacks and explosions emit noise events (noise.rs) that NPCs can hear
+ *
+ * 4. Kill Attribution:
+ *    - Players remember their last damager (last_damager / last_damaged_at)
+ *    - Every death emits a kill_event row (killer, victim, weapon, room) with
+ *      assist credit for other players who recently damaged the victim
+ *    - Damage, kills, deaths and assists also accumulate in the per-room
+ *      scoreboard (scoreboard.rs)
+ *    - Killed NPCs drop their loot table, owned by the killing player's
+ *      party for a while (loot.rs)
+ *
+ * 5. Threat:
+ *    - threat table keyed by (npc_id, player_identity); damage dealt to an NPC
+ *      adds threat, which decays each tick (decay_threat)
+ *    - highest_threat_player: Used by npc_ai.rs to choose who to attack
+ *    - Threat is cleared when the NPC or the player dies, and when the NPC
+ *      is dragged past its leash and resets (npc_ai.rs)
+ *
+ * 6. DPS Meter:
+ *    - update_dps_meters: Called from game_tick, sums each player's damage over
+ *      the last DPS_WINDOW_SECS seconds of the combat log
+ *
+ * Related files:
+ *    - common.rs: EntityId, CombatEventKind and combat constants
+ *    - npc.rs: NPC targets (training dummies are damageable but invulnerable)
+ *    - lib.rs: Calls resolve_melee_attack when a player starts an attack
+ */
+
+use spacetimedb::rand::Rng;
+use spacetimedb::{Identity, ReducerContext, ScheduleAt, Table, Timestamp};
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::common::{
+    forward_from_yaw, CombatEventKind, EntityId, Vector3, AOE_EVENT_TTL_SECS,
+    ASSIST_WINDOW_SECS, BIG_HIT_FRACTION, BIG_HIT_INVULNERABLE_MS, CRIT_CHANCE, CRIT_MULTIPLIER, DAMAGE_VARIANCE, ATTACK_NOISE_RADIUS, COMBAT_LOG_PRUNE_INTERVAL_SECS, KILL_EVENT_RETENTION_SECS, COMBAT_LOG_RETENTION_SECS, BLOCKED_DAMAGE_FRACTION, BLOCK_ARC_COS, CLASS_PALADIN, CLASS_WIZARD, EXPLOSION_NOISE_RADIUS,
+    MIN_THREAT, PARRY_WINDOW_MS, STAGGER_DURATION_MS, THREAT_DECAY_PER_SEC, THREAT_PER_DAMAGE, DPS_WINDOW_SECS, FIREBALL_DAMAGE, FIREBALL_KNOCKBACK, FIREBALL_MANA_COST,
+    FIREBALL_RADIUS, FIREBALL_RANGE, FIREBALL_SPEED, MELEE_ARC_COS, NO_TEAM,
+};
+use crate::boss;
+use crate::combo;
+use crate::config;
+use crate::duel;
+use crate::escort;
+use crate::dialogue;
+use crate::destructible::{self, destructible as _};
+use crate::durability;
+use crate::item::ItemDef;
+use crate::loot;
+use crate::noise;
+use crate::pathfinding;
+use crate::perception;
+use crate::npc::npc as _;
+use crate::npc_script;
+use crate::player as _;
+use crate::player_logic;
+use crate::projectile::{self, ProjectileSpec};
+use crate::room;
+use crate::safe_zone;
+use crate::scoreboard;
+use crate::spatial::{self, SpatialEntity};
+use crate::shop;
+use crate::spawn;
+use crate::status;
+use crate::support;
+use crate::PlayerData;
+
+#[spacetimedb::table(name = combat_log, public)]
+#[derive(Clone)]
+pub struct CombatLogEntry {
+    #[primary_key]
+    #[auto_inc]
+    pub log_id: u64,
+    #[index(btree)]
+    pub room_name: String,
+    pub source: EntityId,
+    pub target: EntityId,
+    pub amount: i32,
+    pub kind: CombatEventKind,
+    pub is_crit: bool,
+    pub timestamp: Timestamp,
+}
+
+#[spacetimedb::table(name = combat_log_prune_schedule, scheduled(prune_combat_log))]
+pub struct CombatLogPruneSchedule {
+    #[primary_key]
+    #[auto_inc]
+    pub scheduled_id: u64,
+    pub scheduled_at: ScheduleAt,
+}
+
+#[spacetimedb::table(name = kill_event, public)]
+#[derive(Clone)]
+pub struct KillEvent {
+    #[primary_key]
+    #[auto_inc]
+    pub event_id: u64,
+    #[index(btree)]
+    pub room_name: String,
+    pub killer: EntityId,
+    pub victim: EntityId,
+    pub weapon: CombatEventKind,
+    pub assists: Vec<Identity>,
+    pub timestamp: Timestamp,
+}
+
+#[spacetimedb::table(name = aoe_event, public)]
+#[derive(Clone)]
+pub struct AoeEvent {
+    #[primary_key]
+    #[auto_inc]
+    pub event_id: u64,
+    #[index(btree)]
+    pub room_name: String,
+    pub source: EntityId,
+    pub center: Vector3,
+    pub radius: f32,
+    pub targets_hit: u32,
+    pub timestamp: Timestamp,
+}
+
+#[spacetimedb::table(name = threat)]
+#[derive(Clone)]
+pub struct Threat {
+    #[primary_key]
+    #[auto_inc]
+    pub threat_id: u64,
+    #[index(btree)]
+    pub npc_id: u64,
+    #[index(btree)]
+    pub player_identity: Identity,
+    pub amount: f32,
+    pub updated_at: Timestamp,
+}
+
+#[spacetimedb::table(name = dps_meter, public)]
+#[derive(Clone)]
+pub struct DpsMeter {
+    #[primary_key]
+    pub identity: Identity,
+    pub room_name: String,
+    pub damage_in_window: i64,
+    pub dps: f32,
+    pub updated_at: Timestamp,
+}
+
+// Whether `target` lies inside the attacker's melee reach and frontal arc
+fn in_melee_arc(origin: &Vector3, yaw: f32, range: f32, target: &Vector3) -> bool {
+    let distance = origin.distance_xz(target);
+    if distance > range {
+        return false;
+    }
+    if distance < 0.01 {
+        return true;
+    }
+    let forward = forward_from_yaw(yaw);
+    let dot = ((target.x - origin.x) * forward.x + (target.z - origin.z) * forward.z) / distance;
+    dot >= MELEE_ARC_COS
+}
+
+// Resolve a swing of `weapon` from `attacker` against everything in front of them
+pub fn resolve_melee_attack(ctx: &ReducerContext, attacker: &PlayerData, weapon: &ItemDef) {
+    let source = EntityId::Player(attacker.identity);
+    let yaw = attacker.rotation.y;
+
+    let targets: Vec<EntityId> = spatial::query_radius(ctx, &attacker.room_name, &attacker.position, weapon.range)
+        .into_iter()
+        .filter(|hit| in_melee_arc(&attacker.position, yaw, weapon.range, &hit.position))
+        .filter_map(|hit| hit.entity.combatant())
+        .filter(|target| target != &source)
+        .collect();
+
+    let step = combo::current_step(ctx, attacker);
+    let damage = (weapon.damage as f32 * step.damage_multiplier).round() as i32;
+    noise::emit_noise(ctx, &attacker.room_name, source.clone(), &attacker.position, ATTACK_NOISE_RADIUS);
+    let mut targets_hit = 0;
+    for target in targets {
+        if apply_damage(ctx, &source, &target, damage, CombatEventKind::Melee).is_some() {
+            targets_hit += 1;
+        }
+    }
+    if targets_hit > 0 {
+        durability::wear_weapon(ctx, attacker.identity);
+    }
+    combo::on_melee_resolved(ctx, attacker.identity, targets_hit);
+}
+
+// Resolve the class spell a player just started casting
+pub fn resolve_spell_cast(ctx: &ReducerContext, caster: &PlayerData) {
+    match caster.character_class.as_str() {
+        CLASS_WIZARD => cast_fireball(ctx, caster),
+        CLASS_PALADIN => support::cast_heal(ctx, caster),
+        _ => {}
+    }
+}
+
+fn cast_fireball(ctx: &ReducerContext, caster: &PlayerData) {
+    let Some(mut caster) = ctx.db.player().identity().find(caster.identity) else {
+        return;
+    };
+    if caster.mana < FIREBALL_MANA_COST {
+        return;
+    }
+    caster.mana -= FIREBALL_MANA_COST;
+    let forward = forward_from_yaw(caster.rotation.y);
+    let velocity = Vector3 { x: forward.x * FIREBALL_SPEED, y: 0.0, z: forward.z * FIREBALL_SPEED };
+    let (identity, room_name, origin) = (caster.identity, caster.room_name.clone(), caster.position.clone());
+    ctx.db.player().identity().update(caster);
+
+    // Flies until it hits something, or explodes at max range
+    projectile::spawn_projectile(
+        ctx,
+        EntityId::Player(identity),
+        &room_name,
+        origin,
+        velocity,
+        ProjectileSpec {
+            damage: FIREBALL_DAMAGE,
+            explosion_radius: FIREBALL_RADIUS,
+            knockback: FIREBALL_KNOCKBACK,
+            lifetime: Duration::from_secs_f32(FIREBALL_RANGE / FIREBALL_SPEED),
+        },
+    );
+}
+
+// Damage everything within `radius` of `center` in a room. Damage and
+// knockback fall off linearly from full at the center to zero at the edge.
+// The source is never hit by its own AoE. Returns the number of targets hit.
+pub fn apply_aoe(
+    ctx: &ReducerContext,
+    source: &EntityId,
+    room_name: &str,
+    center: &Vector3,
+    radius: f32,
+    max_damage: i32,
+    knockback: f32,
+) -> u32 {
+    let targets: Vec<(EntityId, f32)> = spatial::query_radius(ctx, room_name, center, radius)
+        .into_iter()
+        .filter_map(|hit| Some((hit.entity.combatant()?, hit.distance)))
+        .filter(|(target, _)| target != source)
+        .collect();
+
+    let mut targets_hit = 0;
+    for (target, distance) in targets {
+        let falloff = 1.0 - (distance / radius).clamp(0.0, 1.0);
+        let damage = ((max_damage as f32) * falloff).round() as i32;
+        if damage <= 0 {
+            continue;
+        }
+        if apply_damage(ctx, source, &target, damage, CombatEventKind::Aoe).is_none() {
+            continue;
+        }
+        targets_hit += 1;
+        if let EntityId::Player(identity) = target {
+            apply_knockback(ctx, identity, center, knockback * falloff);
+        }
+    }
+
+    noise::emit_noise(ctx, room_name, source.clone(), center, EXPLOSION_NOISE_RADIUS);
+    ctx.db.aoe_event().insert(AoeEvent {
+        event_id: 0,
+        room_name: room_name.to_string(),
+        source: source.clone(),
+        center: center.clone(),
+        radius,
+        targets_hit,
+        timestamp: ctx.timestamp,
+    });
+    targets_hit
+}
+
+// Push a (living) player directly away from `origin` along the ground
+pub fn apply_knockback(ctx: &ReducerContext, identity: Identity, origin: &Vector3, strength: f32) {
+    let Some(mut player) = ctx.db.player().identity().find(identity) else {
+        return;
+    };
+    if player.is_dead || strength <= 0.0 {
+        return;
+    }
+    let distance = origin.distance_xz(&player.position);
+    let (dx, dz) = if distance < 0.01 {
+        let forward = forward_from_yaw(player.rotation.y);
+        (-forward.x, -forward.z)
+    } else {
+        ((player.position.x - origin.x) / distance, (player.position.z - origin.z) / distance)
+    };
+    let pushed = Vector3 {
+        x: player.position.x + dx * strength,
+        y: player.position.y,
+        z: player.position.z + dz * strength,
+    };
+    player_logic::set_player_position(ctx, &mut player, pushed);
+    ctx.db.player().identity().update(player);
+}
+
+pub fn prune_aoe_events(ctx: &ReducerContext, room_name: &str) {
+    let Some(cutoff) = ctx.timestamp.checked_sub_duration(Duration::from_secs(AOE_EVENT_TTL_SECS)) else {
+        return;
+    };
+    let expired: Vec<u64> = ctx.db.aoe_event().room_name().filter(room_name)
+        .filter(|event| event.timestamp < cutoff)
+        .map(|event| event.event_id)
+        .collect();
+    for event_id in expired {
+        ctx.db.aoe_event().event_id().delete(event_id);
+    }
+}
+
+// Room rules for player-vs-player damage: nothing at all without PvP, and
+// teammates only hurt each other when friendly fire is on
+pub fn can_damage_player(ctx: &ReducerContext, attacker: &PlayerData, victim: &PlayerData) -> bool {
+    if attacker.room_name != victim.room_name {
+        return false;
+    }
+    // Duelists may always hit each other
+    if duel::are_dueling(ctx, attacker.identity, victim.identity) {
+        return true;
+    }
+    let Some(room) = room::find_room(ctx, &victim.room_name) else {
+        return false;
+    };
+    if !room.pvp_enabled {
+        return false;
+    }
+    let same_team = attacker.team != NO_TEAM && attacker.team == victim.team;
+    !same_team || room.friendly_fire
+}
+
+pub fn entity_position(ctx: &ReducerContext, entity: &EntityId) -> Option<Vector3> {
+    match entity {
+        EntityId::Player(identity) => ctx.db.player().identity().find(*identity).map(|p| p.position),
+        EntityId::Npc(npc_id) => ctx.db.npc().npc_id().find(*npc_id).map(|n| n.position),
+        EntityId::Destructible(destructible_id) => ctx.db.destructible().destructible_id().find(*destructible_id)
+            .map(|d| d.position),
+        EntityId::Environment => None,
+    }
+}
+
+fn stagger(ctx: &ReducerContext, entity: &EntityId) {
+    let until = ctx.timestamp + Duration::from_millis(STAGGER_DURATION_MS);
+    match entity {
+        EntityId::Player(identity) => {
+            if let Some(mut player) = ctx.db.player().identity().find(*identity) {
+                player.staggered_until = Some(until);
+                ctx.db.player().identity().update(player);
+            }
+        }
+        EntityId::Npc(npc_id) => {
+            if let Some(mut npc) = ctx.db.npc().npc_id().find(*npc_id) {
+                npc.staggered_until = Some(until);
+                ctx.db.npc().npc_id().update(npc);
+            }
+        }
+        EntityId::Destructible(_) | EntityId::Environment => {}
+    }
+}
+
+// Adjust incoming damage for a blocking victim. Only hits from the front are
+// blocked. A block raised within PARRY_WINDOW_MS negates the hit and staggers
+// the attacker; otherwise damage is reduced and drains block stamina, and a
+// guard that runs out of stamina breaks and lets the full hit through.
+fn resolve_block(ctx: &ReducerContext, victim: &mut PlayerData, source: &EntityId, amount: i32) -> i32 {
+    if !victim.is_blocking {
+        return amount;
+    }
+    let Some(source_position) = entity_position(ctx, source) else {
+        return amount;
+    };
+    let distance = victim.position.distance_xz(&source_position);
+    if distance > 0.01 {
+        let forward = forward_from_yaw(victim.rotation.y);
+        let dot = ((source_position.x - victim.position.x) * forward.x
+            + (source_position.z - victim.position.z) * forward.z) / distance;
+        if dot < BLOCK_ARC_COS {
+            return amount;
+        }
+    }
+
+    let parry_window = Duration::from_millis(PARRY_WINDOW_MS);
+    let parried = victim.block_started_at
+        .is_some_and(|started| ctx.timestamp.duration_since(started).is_some_and(|held| held <= parry_window));
+    if parried {
+        stagger(ctx, source);
+        return 0;
+    }
+
+    let blocked = amount - (amount as f32 * BLOCKED_DAMAGE_FRACTION).round() as i32;
+    if victim.block_stamina >= blocked {
+        victim.block_stamina -= blocked;
+        amount - blocked
+    } else {
+        victim.block_stamina = 0;
+        victim.is_blocking = false;
+        amount
+    }
+}
+
+// Roll variance and crit for a hit of `base` damage. All randomness stays on
+// the server (ctx.rng() is seeded per reducer call), so clients can't game it.
+fn roll_damage(ctx: &ReducerContext, base: i32) -> (i32, bool) {
+    if base <= 0 {
+        return (base, false);
+    }
+    let mut rng = ctx.rng();
+    let variance = rng.gen_range(1.0 - DAMAGE_VARIANCE..=1.0 + DAMAGE_VARIANCE);
+    let is_crit = rng.gen::<f32>() < CRIT_CHANCE;
+    let multiplier = if is_crit { variance * CRIT_MULTIPLIER } else { variance };
+    (((base as f32 * multiplier).round() as i32).max(1), is_crit)
+}
+
+// Apply damage to a target and record it. Returns the amount actually dealt,
+// or None if the target doesn't exist or can't be hit.
+pub fn apply_damage(
+    ctx: &ReducerContext,
+    source: &EntityId,
+    target: &EntityId,
+    mut amount: i32,
+    kind: CombatEventKind,
+) -> Option<i32> {
+    if !config::combat_enabled(ctx) {
+        return None;
+    }
+    // Only attackers roll; environment and hazard ticks deal flat damage
+    let (rolled, is_crit) = match source {
+        EntityId::Player(_) | EntityId::Npc(_) => roll_damage(ctx, amount),
+        _ => (amount, false),
+    };
+    amount = rolled;
+    let mut killed = false;
+    let room_name = match target {
+        EntityId::Player(identity) => {
+            let mut player = ctx.db.player().identity().find(*identity)?;
+            if player.is_dead {
+                return None;
+            }
+            if let EntityId::Player(attacker_identity) = source {
+                let attacker = ctx.db.player().identity().find(*attacker_identity)?;
+                if let Some(zone) = safe_zone::zone_for_pvp(ctx, &attacker, &player) {
+                    safe_zone::guard_zone(ctx, &zone, attacker.identity);
+                    return None;
+                }
+                if !can_damage_player(ctx, &attacker, &player) {
+                    return None;
+                }
+            }
+            if player.invulnerable_until > ctx.timestamp {
+                return None;
+            }
+            amount = resolve_block(ctx, &mut player, source, amount);
+            player.health = (player.health - amount).max(0);
+            // Duels are non-lethal: the finishing blow ends the duel instead
+            if player.health == 0 {
+                if let EntityId::Player(attacker) = source {
+                    if duel::resolve_defeat(ctx, *attacker, player.identity) {
+                        player.health = player.max_health;
+                        player.mana = player.max_mana;
+                    }
+                }
+            }
+            if amount > 0 {
+                player.last_damager = Some(source.clone());
+                player.last_damaged_at = Some(ctx.timestamp);
+                durability::wear_armor(ctx, player.identity);
+            }
+            if player.health > 0 && amount as f32 >= player.max_health as f32 * BIG_HIT_FRACTION {
+                player.invulnerable_until = ctx.timestamp + Duration::from_millis(BIG_HIT_INVULNERABLE_MS);
+            }
+            if player.health == 0 {
+                killed = true;
+                player.is_dead = true;
+                player.current_animation = "death".to_string();
+                spacetimedb::log::info!("Player {} was killed by {:?}.", player.identity, source);
+                spawn::schedule_respawn(ctx, player.identity);
+                clear_threat_from_player(ctx, player.identity);
+                status::clear_player(ctx, player.identity);
+                durability::on_player_died(ctx, player.identity);
+            }
+            let room_name = player.room_name.clone();
+            ctx.db.player().identity().update(player);
+            room_name
+        }
+        EntityId::Npc(npc_id) => {
+            let mut npc = ctx.db.npc().npc_id().find(*npc_id)?;
+            // NPCs that just broke their leash evade every hit
+            if npc.reset_invulnerable_until.is_some_and(|until| until > ctx.timestamp) {
+                return None;
+            }
+            if !npc.is_invulnerable() {
+                npc.health = (npc.health - amount).max(0);
+            }
+            if let EntityId::Player(attacker) = source {
+                boss::record_participant(ctx, *npc_id, *attacker);
+            }
+            let room_name = npc.room_name.clone();
+            if npc.health == 0 {
+                killed = true;
+                spacetimedb::log::info!("NPC {} was killed by {:?}.", npc.npc_id, source);
+                ctx.db.npc().npc_id().delete(npc.npc_id);
+                forget_npc(ctx, npc.npc_id);
+                let killer = match source {
+                    EntityId::Player(identity) => Some(*identity),
+                    _ => None,
+                };
+                loot::on_npc_killed(ctx, npc.npc_id, &room_name, &npc.position, killer);
+                escort::on_npc_killed(ctx, npc.npc_id);
+            } else {
+                ctx.db.npc().npc_id().update(npc);
+                if let EntityId::Player(attacker) = source {
+                    add_threat(ctx, *npc_id, *attacker, amount as f32 * THREAT_PER_DAMAGE);
+                }
+            }
+            room_name
+        }
+        // Destroyed props don't show up in the kill feed
+        EntityId::Destructible(destructible_id) => destructible::damage_destructible(ctx, *destructible_id, amount)?.0,
+        EntityId::Environment => return None,
+    };
+
+    ctx.db.combat_log().insert(CombatLogEntry {
+        log_id: 0,
+        room_name: room_name.clone(),
+        source: source.clone(),
+        target: target.clone(),
+        amount,
+        kind,
+        is_crit,
+        timestamp: ctx.timestamp,
+    });
+    scoreboard::record_damage(ctx, &room_name, source, target, amount);
+    if killed {
+        record_kill(ctx, source, target, kind, &room_name);
+    }
+    Some(amount)
+}
+
+// Drop everything other systems keep about an NPC whose row was just deleted
+// (killed, or tamed into a pet)
+pub fn forget_npc(ctx: &ReducerContext, npc_id: u64) {
+    spatial::untrack(ctx, &SpatialEntity::Npc(npc_id));
+    perception::clear_npc(ctx, npc_id);
+    pathfinding::clear_npc(ctx, npc_id);
+    dialogue::clear_npc(ctx, npc_id);
+    shop::clear_npc(ctx, npc_id);
+    durability::clear_npc(ctx, npc_id);
+    npc_script::clear_npc(ctx, npc_id);
+    safe_zone::clear_npc(ctx, npc_id);
+    clear_threat_on_npc(ctx, npc_id);
+}
+
+// --- Kill Attribution ---
+
+// Emit a kill_event for the feed. Every other player who damaged the victim
+// within ASSIST_WINDOW_SECS gets assist credit.
+fn record_kill(ctx: &ReducerContext, killer: &EntityId, victim: &EntityId, weapon: CombatEventKind, room_name: &str) {
+    let since = ctx.timestamp.checked_sub_duration(Duration::from_secs(ASSIST_WINDOW_SECS)).unwrap_or(ctx.timestamp);
+    let mut assists: Vec<Identity> = Vec::new();
+    for entry in ctx.db.combat_log().room_name().filter(room_name) {
+        if &entry.target != victim || entry.timestamp < since || &entry.source == killer || entry.amount <= 0
+            || entry.kind == CombatEventKind::Heal
+        {
+            continue;
+        }
+        if let EntityId::Player(identity) = entry.source {
+            if !assists.contains(&identity) {
+                assists.push(identity);
+            }
+        }
+    }
+    scoreboard::record_kill(ctx, room_name, killer, victim, &assists);
+    ctx.db.kill_event().insert(KillEvent {
+        event_id: 0,
+        room_name: room_name.to_string(),
+        killer: killer.clone(),
+        victim: victim.clone(),
+        weapon,
+        assists,
+        timestamp: ctx.timestamp,
+    });
+}
+
+// --- Threat ---
+
+pub fn add_threat(ctx: &ReducerContext, npc_id: u64, player_identity: Identity, amount: f32) {
+    let existing = ctx.db.threat().npc_id().filter(npc_id).find(|t| t.player_identity == player_identity);
+    match existing {
+        Some(mut row) => {
+            row.amount += amount;
+            row.updated_at = ctx.timestamp;
+            ctx.db.threat().threat_id().update(row);
+        }
+        None => {
+            ctx.db.threat().insert(Threat {
+                threat_id: 0,
+                npc_id,
+                player_identity,
+                amount,
+                updated_at: ctx.timestamp,
+            });
+        }
+    }
+}
+
+// The living player in `room_name` with the most threat on this NPC
+pub fn highest_threat_player(ctx: &ReducerContext, npc_id: u64, room_name: &str) -> Option<PlayerData> {
+    ctx.db.threat().npc_id().filter(npc_id)
+        .filter_map(|t| ctx.db.player().identity().find(t.player_identity).map(|p| (t.amount, p)))
+        .filter(|(_, p)| !p.is_dead && p.room_name == room_name)
+        .max_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, p)| p)
+}
+
+pub fn decay_threat(ctx: &ReducerContext, room_name: &str, delta_time: f64) {
+    let keep = (1.0 - THREAT_DECAY_PER_SEC).powf(delta_time as f32);
+    let rows: Vec<Threat> = ctx.db.npc().room_name().filter(room_name)
+        .flat_map(|n| ctx.db.threat().npc_id().filter(n.npc_id))
+        .collect();
+    for mut row in rows {
+        row.amount *= keep;
+        if row.amount < MIN_THREAT {
+            ctx.db.threat().threat_id().delete(row.threat_id);
+        } else {
+            ctx.db.threat().threat_id().update(row);
+        }
+    }
+}
+
+pub fn clear_threat_on_npc(ctx: &ReducerContext, npc_id: u64) {
+    let rows: Vec<u64> = ctx.db.threat().npc_id().filter(npc_id).map(|t| t.threat_id).collect();
+    for threat_id in rows {
+        ctx.db.threat().threat_id().delete(threat_id);
+    }
+}
+
+pub fn clear_threat_from_player(ctx: &ReducerContext, player_identity: Identity) {
+    let rows: Vec<u64> = ctx.db.threat().player_identity().filter(player_identity).map(|t| t.threat_id).collect();
+    for threat_id in rows {
+        ctx.db.threat().threat_id().delete(threat_id);
+    }
+}
+
+// Recompute the rolling DPS of every player in a room from the combat log (called from game_tick)
+pub fn update_dps_meters(ctx: &ReducerContext, room_name: &str) {
+    let window = Duration::from_secs(DPS_WINDOW_SECS);
+    let window_start = ctx.timestamp.checked_sub_duration(window).unwrap_or(ctx.timestamp);
+
+    let mut damage_by_player: HashMap<Identity, i64> = HashMap::new();
+    for entry in ctx.db.combat_log().room_name().filter(room_name) {
+        if entry.timestamp < window_start || entry.kind == CombatEventKind::Heal {
+            continue;
+        }
+        if let EntityId::Player(identity) = entry.source {
+            *damage_by_player.entry(identity).or_default() += entry.amount as i64;
+        }
+    }
+
+    for player in ctx.db.player().room_name().filter(room_name) {
+        let damage_in_window = damage_by_player.get(&player.identity).copied().unwrap_or(0);
+        let meter = DpsMeter {
+            identity: player.identity,
+            room_name: player.room_name.clone(),
+            damage_in_window,
+            dps: damage_in_window as f32 / DPS_WINDOW_SECS as f32,
+            updated_at: ctx.timestamp,
+        };
+        if ctx.db.dps_meter().identity().find(player.identity).is_some() {
+            ctx.db.dps_meter().identity().update(meter);
+        } else {
+            ctx.db.dps_meter().insert(meter);
+        }
+    }
+}
+
+// --- Combat Log Retention ---
+
+pub fn schedule_log_pruning(ctx: &ReducerContext) {
+    if ctx.db.combat_log_prune_schedule().count() == 0 {
+        let interval = Duration::from_secs(COMBAT_LOG_PRUNE_INTERVAL_SECS);
+        ctx.db.combat_log_prune_schedule().insert(CombatLogPruneSchedule {
+            scheduled_id: 0,
+            scheduled_at: ScheduleAt::Interval(interval.into()),
+        });
+    }
+}
+
+#[spacetimedb::reducer]
+pub fn prune_combat_log(ctx: &ReducerContext, _schedule: CombatLogPruneSchedule) -> Result<(), String> {
+    if ctx.sender != ctx.identity() {
+        return Err("prune_combat_log may only be invoked by the scheduler".to_string());
+    }
+    let Some(cutoff) = ctx.timestamp.checked_sub_duration(Duration::from_secs(COMBAT_LOG_RETENTION_SECS)) else {
+        return Ok(());
+    };
+    let expired: Vec<u64> = ctx.db.combat_log().iter()
+        .filter(|entry| entry.timestamp < cutoff)
+        .map(|entry| entry.log_id)
+        .collect();
+    for log_id in expired {
+        ctx.db.combat_log().log_id().delete(log_id);
+    }
+
+    // The kill feed is kept longer than raw hits
+    if let Some(kill_cutoff) = ctx.timestamp.checked_sub_duration(Duration::from_secs(KILL_EVENT_RETENTION_SECS)) {
+        let expired_kills: Vec<u64> = ctx.db.kill_event().iter()
+            .filter(|event| event.timestamp < kill_cutoff)
+            .map(|event| event.event_id)
+            .collect();
+        for event_id in expired_kills {
+            ctx.db.kill_event().event_id().delete(event_id);
+        }
+    }
+    Ok(())
+}
+
+pub fn clear_dps_meter(ctx: &ReducerContext, identity: Identity) {
+    ctx.db.dps_meter().identity().delete(identity);
+}
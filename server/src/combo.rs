@@ -0,0 +1,128 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - combo.rs
+ *
+ * Melee combo chains. Each class has a fixed chain of attacks; a swing that
+ * lands within COMBO_WINDOW_MS of the previous landed swing advances the
+ * chain, and later stages hit harder.
+ *
+ * Key components:
+ * - ComboStep / combo_chain: Class-specific chains (move name and damage
+ *   multiplier per stage)
+ * - current_step: The stage a player's next swing will use (stage 0 once the
+ *   window has expired)
+ * - on_melee_resolved: Advances the chain on a hit and records a combo_event,
+ *   or resets it on a whiff
+ * - ComboEvent table (public): One row per landed combo swing so clients can
+ *   play stage-specific VFX; pruned after COMBO_EVENT_TTL_SECS
+ *
+ * Related files:
+ * - combat.rs: resolve_melee_attack scales weapon damage by the current step
+ * - lib.rs: combo_stage / combo_expires_at on PlayerData, pruning in game_tick
+ */
+
+use spacetimedb::{Identity, ReducerContext, Table, Timestamp};
+use std::time::Duration;
+
+use crate::common::{CLASS_PALADIN, CLASS_WIZARD, COMBO_EVENT_TTL_SECS, COMBO_WINDOW_MS};
+use crate::player as _;
+use crate::PlayerData;
+
+pub struct ComboStep {
+    pub name: &'static str,
+    pub damage_multiplier: f32,
+}
+
+const PALADIN_CHAIN: &[ComboStep] = &[
+    ComboStep { name: "slash", damage_multiplier: 1.0 },
+    ComboStep { name: "backhand", damage_multiplier: 1.2 },
+    ComboStep { name: "smite", damage_multiplier: 1.6 },
+];
+
+const WIZARD_CHAIN: &[ComboStep] = &[
+    ComboStep { name: "staff_jab", damage_multiplier: 1.0 },
+    ComboStep { name: "staff_sweep", damage_multiplier: 1.25 },
+];
+
+const DEFAULT_CHAIN: &[ComboStep] = &[
+    ComboStep { name: "strike", damage_multiplier: 1.0 },
+    ComboStep { name: "strike", damage_multiplier: 1.15 },
+    ComboStep { name: "finisher", damage_multiplier: 1.4 },
+];
+
+pub fn combo_chain(character_class: &str) -> &'static [ComboStep] {
+    match character_class {
+        CLASS_PALADIN => PALADIN_CHAIN,
+        CLASS_WIZARD => WIZARD_CHAIN,
+        _ => DEFAULT_CHAIN,
+    }
+}
+
+#[spacetimedb::table(name = combo_event, public)]
+#[derive(Clone)]
+pub struct ComboEvent {
+    #[primary_key]
+    #[auto_inc]
+    pub event_id: u64,
+    #[index(btree)]
+    pub room_name: String,
+    pub identity: Identity,
+    pub stage: u8,
+    pub move_name: String,
+    pub targets_hit: u32,
+    pub timestamp: Timestamp,
+}
+
+fn active_stage(ctx: &ReducerContext, player: &PlayerData) -> u8 {
+    if player.combo_expires_at.is_some_and(|at| at > ctx.timestamp) {
+        player.combo_stage
+    } else {
+        0
+    }
+}
+
+// The step the player's next swing uses
+pub fn current_step(ctx: &ReducerContext, player: &PlayerData) -> &'static ComboStep {
+    let chain = combo_chain(&player.character_class);
+    &chain[active_stage(ctx, player) as usize % chain.len()]
+}
+
+// Advance (or reset) the attacker's chain once their swing has been resolved
+pub fn on_melee_resolved(ctx: &ReducerContext, identity: Identity, targets_hit: u32) {
+    let Some(mut player) = ctx.db.player().identity().find(identity) else {
+        return;
+    };
+    if targets_hit == 0 {
+        player.combo_stage = 0;
+        player.combo_expires_at = None;
+        ctx.db.player().identity().update(player);
+        return;
+    }
+    let chain = combo_chain(&player.character_class);
+    let stage = active_stage(ctx, &player) as usize % chain.len();
+    ctx.db.combo_event().insert(ComboEvent {
+        event_id: 0,
+        room_name: player.room_name.clone(),
+        identity,
+        stage: stage as u8,
+        move_name: chain[stage].name.to_string(),
+        targets_hit,
+        timestamp: ctx.timestamp,
+    });
+    // The finisher wraps back to the opener
+    player.combo_stage = ((stage + 1) % chain.len()) as u8;
+    player.combo_expires_at = Some(ctx.timestamp + Duration::from_millis(COMBO_WINDOW_MS));
+    ctx.db.player().identity().update(player);
+}
+
+pub fn prune_combo_events(ctx: &ReducerContext, room_name: &str) {
+    let Some(cutoff) = ctx.timestamp.checked_sub_duration(Duration::from_secs(COMBO_EVENT_TTL_SECS)) else {
+        return;
+    };
+    let expired: Vec<u64> = ctx.db.combo_event().room_name().filter(room_name)
+        .filter(|event| event.timestamp < cutoff)
+        .map(|event| event.event_id)
+        .collect();
+    for event_id in expired {
+        ctx.db.combo_event().event_id().delete(event_id);
+    }
+}
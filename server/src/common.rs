@@ -1,25 +1,29 @@
-/**
+/*!
  * Vibe Coding Starter Pack: 3D Multiplayer - common.rs
  * 
  * This file contains shared data structures and constants used throughout the application.
  * 
  * Key components:
  * - Vector3: 3D vector struct for positions, rotations and movement
- * - InputState: Player input tracking with all possible input actions
- * - Game constants: Speed values that affect player movement
+ * - InputState: Player input as an action bitmask (ACTION_* flags) plus analog
+ *   move / aim axes, with decoding helpers
+ * - EntityId: Reference to any combatant (player or NPC) used by the combat log
+ * - Game constants: Speed values that affect player movement, combat tuning values
  * 
  * These structures are used by:
  * - lib.rs: For database table definitions
  * - player_logic.rs: For movement calculations and state updates
+ * - combat.rs / npc.rs: For hit detection and damage bookkeeping
  * 
  * When modifying:
  * - Changes to Vector3 or InputState will affect database schema
  * - You may need to run 'spacetime delete <db_name>' after schema changes
  * - Adjust PLAYER_SPEED and SPRINT_MULTIPLIER to change movement feel
- * - Adding new input types requires updates to InputState and UI event handlers
+ * - New digital inputs only need a new ACTION_* bit (no schema change) and a UI
+ *   binding that sets it
  */
 
-use spacetimedb::{SpacetimeType};
+use spacetimedb::{SpacetimeType, Identity};
 
 // --- Shared Structs ---
 
@@ -31,21 +35,592 @@ pub struct Vector3 {
     pub z: f32,
 }
 
+impl Vector3 {
+    // Distance on the ground plane, ignoring height differences
+    pub fn distance_xz(&self, other: &Vector3) -> f32 {
+        ((self.x - other.x).powi(2) + (self.z - other.z).powi(2)).sqrt()
+    }
+}
+
+// Helper struct for 2D analog axes
+#[derive(SpacetimeType, Clone, Debug, PartialEq)]
+pub struct Vector2 {
+    pub x: f32,
+    pub y: f32,
+}
+
+// Unit vector a player/NPC is facing for a given yaw (-Z is forward in Three.js)
+pub fn forward_from_yaw(yaw: f32) -> Vector3 {
+    Vector3 { x: -yaw.sin(), y: 0.0, z: -yaw.cos() }
+}
+
+// Digital input actions, one bit each in InputState.actions.
+// Append new bits; never renumber existing ones (clients send them raw).
+pub const ACTION_SPRINT: u64 = 1 << 0;
+pub const ACTION_JUMP: u64 = 1 << 1;
+pub const ACTION_ATTACK: u64 = 1 << 2;
+pub const ACTION_CAST_SPELL: u64 = 1 << 3;
+pub const ACTION_BLOCK: u64 = 1 << 4;
+
 // Helper struct for player input state
 #[derive(SpacetimeType, Clone, Debug)]
 pub struct InputState {
-    pub forward: bool,
-    pub backward: bool,
-    pub left: bool,
-    pub right: bool,
-    pub sprint: bool,
-    pub jump: bool,
-    pub attack: bool,
-    pub cast_spell: bool,
+    pub actions: u64, // Bitmask of ACTION_* flags currently held
+    pub move_axis: Vector2, // x = strafe (+right), y = forward (+forward); keys send -1/0/1
+    pub aim: Vector3, // Aim direction in world space (zero when unused)
     pub sequence: u32,
 }
 
+impl InputState {
+    // No actions held, no movement
+    pub fn idle() -> Self {
+        InputState {
+            actions: 0,
+            move_axis: Vector2 { x: 0.0, y: 0.0 },
+            aim: Vector3 { x: 0.0, y: 0.0, z: 0.0 },
+            sequence: 0,
+        }
+    }
+
+    pub fn has(&self, action: u64) -> bool {
+        self.actions & action != 0
+    }
+
+    pub fn clear(&mut self, action: u64) {
+        self.actions &= !action;
+    }
+
+    // The move axis with NaNs zeroed and its length capped at 1, so a
+    // tampered client can't move faster than a full stick deflection
+    pub fn move_vector(&self) -> Vector2 {
+        let x = if self.move_axis.x.is_finite() { self.move_axis.x } else { 0.0 };
+        let y = if self.move_axis.y.is_finite() { self.move_axis.y } else { 0.0 };
+        let length = (x * x + y * y).sqrt();
+        if length > 1.0 {
+            Vector2 { x: x / length, y: y / length }
+        } else {
+            Vector2 { x, y }
+        }
+    }
+
+    pub fn is_moving(&self) -> bool {
+        let axis = self.move_vector();
+        axis.x.abs() > MOVE_AXIS_DEADZONE || axis.y.abs() > MOVE_AXIS_DEADZONE
+    }
+
+    // Drop all movement (and sprint, which only matters while moving)
+    pub fn stop_moving(&mut self) {
+        self.move_axis = Vector2 { x: 0.0, y: 0.0 };
+        self.clear(ACTION_SPRINT);
+    }
+}
+
+// Anything that can deal or receive damage
+#[derive(SpacetimeType, Clone, Debug, PartialEq)]
+pub enum EntityId {
+    Player(Identity),
+    Npc(u64),
+    Destructible(u64),
+    Environment, // Hazardous terrain and other world damage; never a target
+}
+
+// What produced a combat log entry
+#[derive(SpacetimeType, Clone, Copy, Debug, PartialEq)]
+pub enum CombatEventKind {
+    Melee,
+    Aoe,
+    Projectile,
+    Hitscan,
+    Heal,
+    Trap,
+    Hazard,
+}
+
+// Lingering effect a hazardous tile applies to players standing on it
+#[derive(SpacetimeType, Clone, Copy, Debug, PartialEq)]
+pub enum HazardEffect {
+    None,
+    Burn, // Keeps burning for a while after leaving the tile
+    Slow,
+}
+
+// Damaging terrain (lava, spikes) attached to a GameTile
+#[derive(SpacetimeType, Clone, Debug, PartialEq)]
+pub struct TileHazard {
+    pub damage_per_sec: i32,
+    pub effect: HazardEffect,
+}
+
 // --- Game Constants ---
 
 pub const PLAYER_SPEED: f32 = 7.0;
-pub const SPRINT_MULTIPLIER: f32 = 1.5;
\ No newline at end of file
+pub const SPRINT_MULTIPLIER: f32 = 1.5;
+
+// Room every player lands in after registering
+pub const DEFAULT_HUB_ROOM: &str = "lobby";
+// Map seeded by init; rooms start on it (see map.rs)
+pub const DEFAULT_MAP_ID: u32 = 0;
+pub const MAX_ROOM_NAME_LEN: usize = 32;
+pub const MAX_ROOMS_PER_OWNER: usize = 3; // Each room generates a map and runs its own game_tick
+pub const MAX_NPC_NAME_LEN: usize = 32;
+
+// Players sharing a non-zero team are allies (team 0 means no team)
+pub const NO_TEAM: u8 = 0;
+pub const MAX_TEAM: u8 = 8;
+
+// Character classes offered by the join dialog
+pub const CLASS_WIZARD: &str = "Wizard";
+pub const CLASS_PALADIN: &str = "Paladin";
+
+// Melee attacks hit anything within the weapon's range (see item.rs) and
+// inside the attacker's frontal arc
+pub const MELEE_ARC_COS: f32 = 0.5; // cos(60deg) half-angle
+
+// Rolling window used for the DPS meter
+pub const DPS_WINDOW_SECS: u64 = 10;
+
+// How long combat_log rows are kept (must cover the DPS window) and how often they're pruned
+pub const COMBAT_LOG_RETENTION_SECS: u64 = 30;
+pub const COMBAT_LOG_PRUNE_INTERVAL_SECS: u64 = 5;
+
+// Kill feed: recent damagers earn assists; events kept for KILL_EVENT_RETENTION_SECS
+pub const ASSIST_WINDOW_SECS: u64 = 15;
+pub const KILL_EVENT_RETENTION_SECS: u64 = 300;
+
+// Size of a spatial grid cell (matches the game tile size)
+pub const GRID_CELL_SIZE: f32 = 10.0;
+
+// World streaming (see chunk.rs): tiles come in chunks of CHUNK_SIZE_TILES^2
+pub const TILE_SIZE: f32 = 10.0;
+pub const CHUNK_SIZE_TILES: i32 = 8;
+pub const CHUNK_VIEW_RADIUS: i32 = 2; // Chunks around a player that are generated and visible
+pub const INITIAL_CHUNK_RADIUS: i32 = 2; // Generated around the origin by init
+pub const WORLD_CHUNK_LIMIT: i32 = 8; // Streamed maps end this many chunks from the origin
+pub const MAX_CHUNKS_GENERATED_PER_TICK: usize = 8;
+pub const MAX_HEIGHTMAP_RESOLUTION: u32 = 65; // Height samples per chunk side (heightmap.rs)
+pub const MAX_HEIGHTMAP_SCALE: f32 = 100.0;
+
+// Terrain editing (see build.rs)
+pub const BUILD_BLOCK_HEIGHT: f32 = 1.0;
+pub const BUILD_MAX_LEVEL: i32 = 16;
+pub const BUILD_RANGE: f32 = 30.0;
+pub const BUILD_EDITS_PER_WINDOW: u32 = 10;
+pub const BUILD_RATE_WINDOW_MS: u64 = 5000;
+
+// Day/night cycle (see room.rs): time_of_day is in hours
+pub const DEFAULT_DAY_LENGTH_SECS: u32 = 1200;
+pub const MIN_DAY_LENGTH_SECS: u32 = 60;
+pub const MAX_DAY_LENGTH_SECS: u32 = 86_400;
+pub const DAWN_HOUR: f32 = 6.0; // New rooms start at dawn
+pub const DUSK_HOUR: f32 = 20.0;
+pub const NIGHT_SPAWN_DELAY_FACTOR: f32 = 0.5; // Spawners refill faster at night
+
+// Light sources (see light.rs)
+pub const MAX_LIGHTS_PER_ROOM: usize = 128;
+pub const MAX_LIGHT_COLOR_LEN: usize = 32;
+pub const MAX_LIGHT_RADIUS: f32 = 100.0;
+
+// Room decorations (see decoration.rs)
+pub const MAX_DECORATIONS_PER_ROOM: usize = 200;
+pub const MAX_DECORATION_MODEL_LEN: usize = 64;
+pub const DECORATION_MIN_SCALE: f32 = 0.25;
+pub const DECORATION_MAX_SCALE: f32 = 4.0;
+
+// Checkpoints (see checkpoint.rs)
+pub const MAX_CHECKPOINTS_PER_ROOM: usize = 32;
+pub const VOID_Y: f32 = -50.0; // Players below this have fallen out of the world
+
+// Positional sound cues (see sound.rs)
+pub const MAX_SOUND_EMITTERS_PER_ROOM: usize = 64; // Looping ones; one-shots expire
+pub const MAX_SOUND_ID_LEN: usize = 48;
+pub const MAX_SOUND_RADIUS: f32 = 100.0;
+pub const SOUND_ONE_SHOT_TTL_SECS: u64 = 3;
+pub const INTERACTION_SOUND_RADIUS: f32 = 20.0; // Doors and chests
+pub const SOUND_DOOR_OPEN: &str = "door_open";
+pub const SOUND_DOOR_CLOSE: &str = "door_close";
+pub const SOUND_DOOR_UNLOCK: &str = "door_unlock";
+pub const SOUND_CHEST_OPEN: &str = "chest_open";
+pub const SOUND_CHEST_UNLOCK: &str = "chest_unlock";
+
+// Player inventories (see inventory.rs)
+pub const INVENTORY_SLOTS: u32 = 24;
+
+// Rolled items (see item_instance.rs)
+pub const RARITY_WEIGHTS: [u32; 5] = [600, 250, 100, 40, 10]; // Common to Legendary
+pub const AFFIX_DAMAGE_PER_TIER: (i32, i32) = (1, 3);
+pub const AFFIX_ATTACK_SPEED_PER_TIER: (i32, i32) = (2, 5); // Percent
+pub const AFFIX_REACH_PER_TIER: (i32, i32) = (2, 5); // Percent
+
+// Cosmetics (see wardrobe.rs)
+pub const PLAYER_COLORS: [&str; 6] = ["cyan", "magenta", "yellow", "lightgreen", "white", "orange"]; // Owned by everyone
+pub const MAX_COSMETIC_ID_LEN: usize = 64;
+
+// Party need / greed rolls (see loot_roll.rs)
+pub const LOOT_ROLL_SECS: u64 = 60;
+
+// Gathering professions (see gathering.rs)
+pub const MAX_PROFESSION_SKILL: u32 = 300;
+pub const GATHERS_PER_SKILL_POINT: u32 = 3;
+pub const GATHER_TRIVIAL_MARGIN: u32 = 50; // Nodes this far below a player's skill teach nothing
+pub const GATHER_BONUS_YIELD_STEP: u32 = 25; // Skill above the requirement per extra item
+pub const MAX_GATHER_BONUS_YIELD: u32 = 3;
+pub const MIN_RESOURCE_RESPAWN_SECS: u64 = 60;
+pub const MAX_RESOURCE_RESPAWN_SECS: u64 = 3600;
+pub const MAX_RESOURCE_BASE_YIELD: u32 = 10;
+
+// Gear wear and repair (see durability.rs)
+pub const ITEM_MAX_DURABILITY: u32 = 100;
+pub const WEAPON_WEAR_PER_HIT: u32 = 1; // Per swing that hits anything
+pub const ARMOR_WEAR_PER_HIT: u32 = 1; // Per piece, per hit taken
+pub const DEATH_WEAR_PERCENT: u32 = 10; // Of max durability, every worn item
+
+// Auction house (see market.rs)
+pub const MARKET_FEE_PERCENT: u64 = 5; // Of the asking price, charged when listing
+pub const MARKET_LISTING_SECS: u64 = 48 * 3600;
+pub const MAX_MARKET_LISTINGS_PER_PLAYER: usize = 20;
+
+// Bank storage (see bank.rs)
+pub const BANK_SLOTS: u32 = 96;
+pub const MAX_BANKERS_PER_ROOM: usize = 8;
+
+// Potions and food (see consumable.rs)
+pub const POTION_COOLDOWN_SECS: u64 = 20; // Shared by every potion
+pub const MAX_CONSUMABLE_EFFECT_SECS: u32 = 600;
+
+// Player-to-player trades (see trade.rs)
+pub const TRADE_RANGE: f32 = 5.0;
+pub const TRADE_TIMEOUT_SECS: u64 = 120; // Without any change to the offer
+pub const MAX_TRADE_ITEMS: usize = 12; // Stacks per side
+
+// Player gold (see wallet.rs)
+pub const WALLET_LOG_KEPT: usize = 100;
+
+// Character levels (see progression.rs)
+pub const MAX_PLAYER_LEVEL: u32 = 60;
+
+// Garbage collection of orphaned rows (see gc.rs)
+pub const GC_INTERVAL_SECS: u64 = 600;
+pub const GC_RUNS_KEPT: usize = 20;
+
+// Minimap cell flags (see minimap.rs)
+pub const MINIMAP_WALKABLE: u8 = 1;
+pub const MINIMAP_HAZARD: u8 = 2;
+pub const MINIMAP_WATER: u8 = 4;
+pub const MINIMAP_OBSTACLE: u8 = 8;
+
+// Seasonal world themes (see theme.rs)
+pub const THEME_ROLLOVER_CHECK_SECS: u64 = 60;
+pub const MAX_THEME_NAME_LEN: usize = 32;
+pub const MAX_THEME_ASSET_NAME_LEN: usize = 64;
+
+// Refused edits of other players' objects (see protection.rs)
+pub const GRIEF_REPORT_TTL_SECS: u64 = 3600;
+
+// Weather (see weather.rs)
+pub const WEATHER_CHECK_SECS: u64 = 30;
+pub const MIN_WEATHER_SECS: u64 = 120;
+pub const MAX_WEATHER_SECS: u64 = 900;
+pub const RAIN_MOVE_MULTIPLIER: f32 = 0.9;
+pub const STORM_MOVE_MULTIPLIER: f32 = 0.8;
+pub const FOG_VISION_MULTIPLIER: f32 = 0.5;
+
+// Named regions (see region.rs)
+pub const MAX_REGION_NAME_LEN: usize = 48;
+pub const REGION_EVENT_TTL_SECS: u64 = 5;
+
+// Physics props (see prop.rs)
+pub const MAX_PROPS_PER_ROOM: usize = 64;
+pub const PLAYER_BODY_RADIUS: f32 = 0.4;
+pub const PROP_PUSH_FACTOR: f32 = 0.8; // Share of the player's step a pushed prop moves
+pub const PROP_GRAVITY: f32 = 9.8;
+pub const PROP_FRICTION: f32 = 4.0; // Horizontal speed lost per second on the ground
+pub const PROP_REST_SPEED: f32 = 0.05;
+pub const PROP_KILL_Y: f32 = -50.0;
+
+// Teleporter pads (see teleporter.rs)
+pub const MAX_TELEPORTER_COOLDOWN_SECS: u64 = 300;
+
+// World snapshots (see snapshot.rs)
+pub const MAX_SNAPSHOT_NAME_LEN: usize = 32;
+pub const MAX_SNAPSHOTS_PER_ROOM: usize = 10;
+
+// Procedural room maps (see mapgen.rs)
+pub const GENERATED_MAP_ID_BASE: u32 = 1_000_000; // Authored maps use ids below this
+pub const DEFAULT_MAP_TEMPLATE: &str = "arena";
+pub const MAPGEN_MAX_HALF_EXTENT_TILES: i32 = 32;
+pub const MAPGEN_CLEAR_TILES: i32 = 3; // Kept free of obstacles and hazards around the origin
+pub const MAPGEN_SPAWN_POINTS: usize = 8;
+pub const MAPGEN_HAZARD_DPS: i32 = 10;
+pub const MAPGEN_MAX_PROPS: u32 = 32;
+pub const MAPGEN_PROP_HEALTH: i32 = 40;
+
+// Wizard fireball: a projectile that explodes on impact or at FIREBALL_RANGE
+pub const FIREBALL_MANA_COST: i32 = 20;
+pub const FIREBALL_RANGE: f32 = 8.0;
+pub const FIREBALL_SPEED: f32 = 16.0;
+pub const FIREBALL_RADIUS: f32 = 4.0;
+pub const FIREBALL_DAMAGE: i32 = 30;
+pub const FIREBALL_KNOCKBACK: f32 = 3.0;
+
+// Collision radius of projectiles against players and NPCs
+pub const PROJECTILE_HIT_RADIUS: f32 = 0.75;
+
+// How long aoe_event rows stay around for clients to render
+pub const AOE_EVENT_TTL_SECS: u64 = 5;
+
+// Noise radii for stealth: how far away NPCs can hear each action
+pub const SPRINT_NOISE_RADIUS: f32 = 12.0;
+pub const JUMP_NOISE_RADIUS: f32 = 8.0;
+pub const ATTACK_NOISE_RADIUS: f32 = 10.0;
+pub const EXPLOSION_NOISE_RADIUS: f32 = 25.0;
+pub const NOISE_EVENT_TTL_SECS: u64 = 3;
+
+// Blocking: hits from the front while blocking are reduced and drain block
+// stamina; a block raised within the parry window negates the hit entirely
+// and staggers the attacker
+pub const BLOCK_ARC_COS: f32 = 0.0; // Front 180 degrees
+pub const BLOCKED_DAMAGE_FRACTION: f32 = 0.25;
+pub const MAX_BLOCK_STAMINA: i32 = 100;
+pub const BLOCK_STAMINA_REGEN_PER_SEC: f32 = 15.0;
+pub const PARRY_WINDOW_MS: u64 = 250;
+pub const STAGGER_DURATION_MS: u64 = 1500;
+
+// Stamina: drained by sprinting and melee swings, regenerates while not
+// sprinting. Players at zero stamina are forced to walk.
+pub const MAX_STAMINA: i32 = 100;
+pub const STAMINA_REGEN_PER_SEC: f32 = 15.0;
+pub const SPRINT_STAMINA_DRAIN_PER_SEC: f32 = 10.0;
+pub const MELEE_STAMINA_COST: i32 = 15;
+
+// NPC vision: players inside the cone fill a detection meter (0..1) instead
+// of triggering aggro instantly; anyone within PROXIMITY_DETECTION_RADIUS is
+// noticed regardless of facing. Players heard (noise.rs) fill the meter at
+// HEARING_FILL_PER_SEC, but hearing alone never goes past HEARING_MAX_LEVEL:
+// the NPC has to see a player to become alerted
+pub const VISION_CONE_COS: f32 = 0.5; // cos(60deg) half-angle
+pub const PROXIMITY_DETECTION_RADIUS: f32 = 2.0;
+pub const DETECTION_FILL_PER_SEC: f32 = 0.6;
+pub const DETECTION_DECAY_PER_SEC: f32 = 0.2;
+pub const DETECTION_SUSPICIOUS_THRESHOLD: f32 = 0.3;
+pub const DETECTION_ALERT_THRESHOLD: f32 = 1.0;
+pub const HEARING_FILL_PER_SEC: f32 = 0.4;
+pub const HEARING_MAX_LEVEL: f32 = 0.9;
+
+// Local avoidance: mobile NPCs closer than SEPARATION_RADIUS to another
+// entity are pushed apart by at most SEPARATION_MAX_STEP per tick
+pub const SEPARATION_RADIUS: f32 = 1.5;
+pub const SEPARATION_MAX_STEP: f32 = 1.0;
+
+// Flocking: moving NPCs of the same spawner further than COHESION_RADIUS from
+// their group's center are pulled towards it by at most COHESION_MAX_STEP;
+// at most FLOCK_MAX_NPCS_PER_TICK NPCs per room are steered each tick
+pub const COHESION_RADIUS: f32 = 4.0;
+pub const COHESION_MAX_STEP: f32 = 0.5;
+pub const FLOCK_MAX_NPCS_PER_TICK: usize = 64;
+
+// Threat: damage against an NPC adds threat 1:1; spotting a player adds a
+// small amount so the NPC stays engaged; all threat decays over time
+pub const THREAT_PER_DAMAGE: f32 = 1.0;
+pub const THREAT_ON_DETECTION: f32 = 1.0;
+pub const THREAT_DECAY_PER_SEC: f32 = 0.1; // Fraction lost per second
+pub const MIN_THREAT: f32 = 0.5;
+
+// Room population scaling (population.rs): each player beyond the first
+// (counting up to POPULATION_SCALE_MAX_PLAYERS) adds these fractions of an
+// NPC's base health / damage
+pub const NPC_HEALTH_PER_EXTRA_PLAYER: f32 = 0.5;
+pub const NPC_DAMAGE_PER_EXTRA_PLAYER: f32 = 0.15;
+pub const POPULATION_SCALE_MAX_PLAYERS: u32 = 8;
+
+// Leash resets (npc_ai.rs): an NPC dragged past its leash evades all damage
+// for NPC_RESET_INVULNERABLE_MS and regenerates this fraction of its max
+// health per second while walking home
+pub const NPC_RESET_INVULNERABLE_MS: u64 = 3000;
+pub const NPC_RESET_REGEN_PER_SEC: f32 = 0.2;
+
+// AI simulation LOD for crowded rooms
+pub const AI_LOD_NEAR_DISTANCE: f32 = 40.0;
+pub const AI_LOD_MAX_FULL_PER_ROOM: usize = 64;
+pub const AI_LOD_COARSE_INTERVAL_SECS: f32 = 5.0;
+
+// Ambient critters (critter.rs): sparse per room and updated on a slow
+// cadence (every third 1-second game tick) to stay cheap
+pub const CRITTERS_PER_ROOM: u32 = 4;
+pub const CRITTER_SPAWN_AREA: f32 = 20.0; // Half-extent around the room origin
+pub const CRITTER_UPDATE_INTERVAL_SECS: f32 = 3.0;
+pub const CRITTER_FLEE_RADIUS: f32 = 8.0;
+pub const CRITTER_FLEE_SPEED: f32 = 4.0;
+pub const CRITTER_WANDER_RADIUS: f32 = 5.0;
+pub const CRITTER_WANDER_SPEED: f32 = 1.0;
+
+// Hitscan weapon (fire_hitscan)
+pub const HITSCAN_RANGE: f32 = 40.0;
+pub const HITSCAN_DAMAGE: i32 = 15;
+pub const HITSCAN_HIT_RADIUS: f32 = 0.5;
+pub const HITSCAN_COOLDOWN_MS: u64 = 500;
+
+// Lag compensation: players' positions are sampled every
+// POSITION_HISTORY_INTERVAL_MS into a ring of POSITION_HISTORY_SLOTS rows,
+// and shots can rewind targets by at most MAX_LAG_COMPENSATION_MS
+pub const POSITION_HISTORY_INTERVAL_MS: i64 = 50;
+pub const POSITION_HISTORY_SLOTS: i64 = 8;
+pub const MAX_LAG_COMPENSATION_MS: u32 = 300;
+
+// Paladin heal: instant heal on the most injured ally in front (within
+// HEAL_RANGE and line of sight), followed by a heal-over-time
+pub const HEAL_MANA_COST: i32 = 15;
+pub const HEAL_RANGE: f32 = 10.0;
+pub const HEAL_TARGET_CONE_COS: f32 = 0.7;
+pub const HEAL_AMOUNT: i32 = 20;
+pub const HEAL_OVER_TIME_PER_SEC: i32 = 4;
+pub const HEAL_OVER_TIME_SECS: u64 = 5;
+
+// Damage rolls (apply_damage): every hit varies by +/- DAMAGE_VARIANCE and
+// has a CRIT_CHANCE to be multiplied by CRIT_MULTIPLIER
+pub const DAMAGE_VARIANCE: f32 = 0.15;
+pub const CRIT_CHANCE: f32 = 0.1;
+pub const CRIT_MULTIPLIER: f32 = 1.5;
+
+// Melee combos: a swing that lands within COMBO_WINDOW_MS of the previous
+// landed swing advances the class chain (combo.rs)
+pub const COMBO_WINDOW_MS: u64 = 1200;
+pub const COMBO_EVENT_TTL_SECS: u64 = 5;
+
+// Player-placed traps (trap.rs)
+pub const TRAP_PLACE_RANGE: f32 = 5.0;
+pub const TRAP_TRIGGER_RADIUS: f32 = 1.5;
+pub const TRAP_ARM_DELAY_MS: u64 = 1000;
+pub const TRAP_TTL_SECS: u64 = 120;
+pub const SPIKE_TRAP_DAMAGE: i32 = 25;
+pub const SNARE_DAMAGE: i32 = 5;
+pub const SNARE_ROOT_MS: u64 = 2000;
+
+// Parties (party.rs)
+pub const MAX_PARTY_SIZE: usize = 5;
+pub const PARTY_SPAWN_SPACING: f32 = 1.5; // Ring radius around the leader after party_travel
+
+// Duels (duel.rs)
+pub const DUEL_INVITE_TTL_SECS: u64 = 30;
+pub const DUEL_COUNTDOWN_SECS: u64 = 3;
+pub const DUEL_MAX_DURATION_SECS: u64 = 180;
+pub const DUEL_RESULT_TTL_SECS: u64 = 10;
+
+// Terrain hazards (hazard.rs): burns deal BURN_DAMAGE_FRACTION of the tile's
+// damage per second for BURN_SECS after leaving it; slows scale movement
+pub const BURN_SECS: u64 = 3;
+pub const BURN_DAMAGE_FRACTION: f32 = 0.5;
+pub const SLOW_SECS: u64 = 2;
+pub const SLOW_MOVE_MULTIPLIER: f32 = 0.5;
+pub const HASTE_MOVE_MULTIPLIER: f32 = 1.3;
+
+// Periodic hazards (hazard.rs). Active windows must last at least one game
+// tick so no activation is skipped
+pub const PERIODIC_HAZARD_MIN_ACTIVE_MS: u64 = 1000;
+pub const PERIODIC_HAZARD_MAX_RADIUS: f32 = 6.0;
+pub const FLAME_JET_DAMAGE: i32 = 15;
+pub const CRUSHER_DAMAGE: i32 = 40;
+pub const CRUSHER_KNOCKBACK: f32 = 4.0;
+pub const SPIKE_FLOOR_DAMAGE: i32 = 20;
+
+// Max completed objectives / unlocked doors stored per campaign checkpoint (campaign.rs)
+pub const MAX_CAMPAIGN_ENTRIES: usize = 128;
+
+// NPC pathfinding (pathfinding.rs): navigation grid resolution, per-room
+// search budget per tick and when a cached path is recomputed
+pub const PATH_CELL_SIZE: f32 = 2.0;
+pub const PATH_SEARCHES_PER_TICK: u32 = 4;
+pub const PATH_MAX_EXPANSIONS: u32 = 400;
+pub const PATH_REPLAN_DISTANCE: f32 = 3.0;
+pub const PATH_HAZARD_PENALTY: f32 = 4.0;
+
+// Boss encounters (boss.rs)
+pub const MAX_BOSS_PHASES: usize = 8;
+pub const MAX_BOSS_ADDS_PER_PHASE: u32 = 10;
+pub const BOSS_ADD_SPAWN_RADIUS: f32 = 4.0;
+pub const BOSS_ARENA_HAZARD_COUNT: u32 = 4;
+pub const BOSS_ARENA_HAZARD_DISTANCE: f32 = 6.0;
+pub const BOSS_ARENA_HAZARD_RADIUS: f32 = 2.0;
+pub const BOSS_ARENA_HAZARD_PERIOD_MS: u64 = 4000;
+pub const BOSS_ARENA_HAZARD_ACTIVE_MS: u64 = 1000;
+
+// Shared NPC patrol routes (patrol.rs)
+pub const MAX_PATROL_WAYPOINTS: usize = 32;
+pub const MAX_PATROL_PAUSE_MS: u64 = 60_000;
+
+// How close a player must be to talk to an NPC (dialogue.rs), use a door or
+// switch (door.rs) or loot a container (container.rs)
+pub const INTERACT_RANGE: f32 = 3.0;
+pub const MAX_SWITCH_LINKS: usize = 16; // Doors one switch can control
+
+// Escorted NPCs (escort.rs)
+pub const ESCORT_MOVE_SPEED: f32 = 3.0;
+pub const ESCORT_FOLLOW_DISTANCE: f32 = 2.5; // Trails the escorting player at this distance
+pub const ESCORT_ARRIVE_RADIUS: f32 = 3.0;
+
+// Longest designer-authored NPC behavior script (npc_script.rs)
+pub const MAX_SCRIPT_STEPS: usize = 32;
+
+// Taming (pet.rs): the success chance rises from TAME_MIN_CHANCE at full
+// health to TAME_MAX_CHANCE at no health
+pub const TAME_MIN_CHANCE: f32 = 0.1;
+pub const TAME_MAX_CHANCE: f32 = 0.9;
+pub const TAME_FAIL_THREAT: f32 = 5.0;
+pub const PET_FOLLOW_DISTANCE: f32 = 2.0;
+pub const PET_MOVE_SPEED: f32 = 6.0;
+
+// Safe zones (safe_zone.rs)
+pub const MAX_SAFE_ZONE_RADIUS: f32 = 50.0;
+pub const SAFE_ZONE_GUARD_THREAT: f32 = 50.0; // Enough to pull guards off anything else
+
+// Loot tables and dropped items (loot.rs)
+pub const MAX_LOOT_ROLLS: u32 = 10;
+pub const MAX_LOOT_ENTRIES: usize = 32;
+pub const LOOT_SCATTER_RADIUS: f32 = 1.5;
+pub const LOOT_OWNERSHIP_SECS: u64 = 30; // Killer's party gets first pick
+pub const LOOT_DROP_LIFETIME_SECS: u64 = 300;
+pub const PICKUP_RANGE: f32 = 2.5;
+
+// World pickups (pickup.rs)
+pub const MIN_PICKUP_RESPAWN_SECS: u64 = 60;
+pub const MAX_PICKUP_RESPAWN_SECS: u64 = 3600;
+
+// Items a single vendor can list (shop.rs)
+pub const MAX_SHOP_LISTINGS: usize = 50;
+
+// Largest objective zone a room owner can place (objective.rs)
+pub const MAX_OBJECTIVE_RADIUS: f32 = 10.0;
+
+// NPC spawners (spawner.rs)
+pub const MAX_SPAWNER_ALIVE: u32 = 20;
+pub const SPAWNER_SCATTER_RADIUS: f32 = 2.0;
+
+// How often the dashboard summary tables are refreshed (observer.rs)
+pub const OBSERVER_REFRESH_SECS: u64 = 5;
+
+// Notifications kept per inbox before the oldest are evicted (notification.rs)
+pub const MAX_NOTIFICATIONS: usize = 50;
+
+// Reviving dead teammates (revive.rs)
+pub const REVIVE_RANGE: f32 = 3.0;
+pub const REVIVE_CHANNEL_MS: u64 = 3000;
+pub const REVIVE_MOVE_TOLERANCE: f32 = 0.5;
+pub const REVIVE_HEALTH_FRACTION: f32 = 0.3;
+
+// Analog move input below this (per axis) counts as no input
+pub const MOVE_AXIS_DEADZONE: f32 = 0.1;
+
+// Invulnerability frames: players ignore damage for a while after respawning
+// (spawn camping) and briefly after any hit of at least BIG_HIT_FRACTION of
+// their max health (instant re-kill loops)
+pub const RESPAWN_INVULNERABLE_MS: u64 = 2000;
+pub const BIG_HIT_FRACTION: f32 = 0.25;
+pub const BIG_HIT_INVULNERABLE_MS: u64 = 400;
+
+// Height of an entity's eyes above its position, for line-of-sight checks
+pub const EYE_HEIGHT: f32 = 1.5;
+
+// Delay between a player's death and their scheduled respawn
+pub const RESPAWN_DELAY_SECS: u64 = 5;
+
+// Spawn points a room owner can place (spawn.rs)
+pub const MAX_SPAWN_POINTS_PER_ROOM: usize = 64;
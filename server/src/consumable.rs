@@ -0,0 +1,69 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - consumable.rs
+ *
+ * Potions, food and other items used up from the inventory.
+ *
+ * Key components:
+ * - consume: Called by use_item (inventory.rs), which then takes one item
+ *   off the stack. Applies every effect of the item's definition:
+ *   - Heal / RestoreMana: Instant, capped at the player's maximum
+ *   - HealOverTime / ManaOverTime / Haste: Timed status effects (status.rs)
+ *     the player applies to themselves, so using another refreshes the
+ *     duration instead of stacking
+ * - Items with uses_potion_cooldown share one cooldown of
+ *   POTION_COOLDOWN_SECS (PlayerData.last_potion_at); food and other
+ *   consumables don't
+ *
+ * Related files:
+ * - item.rs: ConsumableEffect definitions
+ * - status.rs / support.rs: Timed effects, healing and mana
+ */
+
+use spacetimedb::ReducerContext;
+use std::time::Duration;
+
+use crate::common::{EntityId, POTION_COOLDOWN_SECS};
+use crate::item::{ConsumableEffectKind, ItemDef};
+use crate::player as _;
+use crate::status::{self, StatusKind};
+use crate::support;
+use crate::PlayerData;
+
+pub fn consume(ctx: &ReducerContext, player: &PlayerData, def: &ItemDef) -> Result<(), String> {
+    if player.is_dead {
+        return Err("Dead players can't use items".to_string());
+    }
+    if def.uses_potion_cooldown {
+        let cooldown = Duration::from_secs(POTION_COOLDOWN_SECS);
+        if player.last_potion_at.is_some_and(|at| at + cooldown > ctx.timestamp) {
+            return Err("Your potions are on cooldown".to_string());
+        }
+        let mut player = player.clone();
+        player.last_potion_at = Some(ctx.timestamp);
+        ctx.db.player().identity().update(player);
+    }
+
+    let identity = player.identity;
+    let source = EntityId::Player(identity);
+    for effect in &def.effects {
+        let duration = Duration::from_secs(effect.duration_secs as u64);
+        match effect.kind {
+            ConsumableEffectKind::Heal => {
+                support::heal_target(ctx, &source, identity, effect.amount);
+            }
+            ConsumableEffectKind::RestoreMana => {
+                support::restore_mana(ctx, identity, effect.amount);
+            }
+            ConsumableEffectKind::HealOverTime => {
+                status::apply_status(ctx, identity, StatusKind::HealOverTime, source.clone(), effect.amount, duration);
+            }
+            ConsumableEffectKind::ManaOverTime => {
+                status::apply_status(ctx, identity, StatusKind::ManaOverTime, source.clone(), effect.amount, duration);
+            }
+            ConsumableEffectKind::Haste => {
+                status::apply_status(ctx, identity, StatusKind::Hasted, source.clone(), 0, duration);
+            }
+        }
+    }
+    Ok(())
+}
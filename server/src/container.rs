@@ -0,0 +1,228 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - container.rs
+ *
+ * Chests and other containers players loot item by item.
+ *
+ * Key components:
+ * - Container table (public): A chest in a room, filled from a loot table
+ *   (loot.rs roll_loot). Shared containers are filled once when placed and
+ *   whoever is quickest takes the items. Instanced containers roll a
+ *   separate set of slots for every player the first time they open it, so
+ *   party members don't take each other's loot
+ * - ContainerSlot table (public): One stack of items (or one rolled item) in
+ *   a container; `owner` is the player an instanced slot belongs to (None =
+ *   shared)
+ * - ContainerLooter table (private): Who has already rolled an instanced
+ *   container, so emptying your slots doesn't let you roll again
+ * - Containers with a key_item_def_id are locked: only players carrying the
+ *   key in their inventory can open them. The room owner can unlock them
+ *   (set_container_lock)
+ * - open_container / take_from_container: Player reducers. The player must
+ *   be alive, in the room and within INTERACT_RANGE. Taken items are granted
+ *   like pickups (item::grant_item)
+ * - place_container / remove_container: Room-owner reducers
+ *
+ * Related files:
+ * - loot.rs: Loot tables
+ * - sound.rs: Opening and unlocking a chest play a sound cue
+ * - item.rs: grant_item
+ * - room.rs: close_room clears a room's containers
+ */
+
+use spacetimedb::{Identity, ReducerContext, Table};
+
+use crate::common::{Vector3, INTERACTION_SOUND_RADIUS, INTERACT_RANGE, SOUND_CHEST_OPEN, SOUND_CHEST_UNLOCK};
+use crate::inventory;
+use crate::item::{self, item_def as _};
+use crate::item_instance;
+use crate::loot::{self, loot_table as _};
+use crate::player as _;
+use crate::room;
+use crate::sound;
+use crate::PlayerData;
+
+#[spacetimedb::table(name = container, public)]
+#[derive(Clone)]
+pub struct Container {
+    #[primary_key]
+    #[auto_inc]
+    pub container_id: u64,
+    #[index(btree)]
+    pub room_name: String,
+    pub position: Vector3,
+    pub loot_table_id: u64,
+    pub instanced: bool, // Every player gets their own roll
+    pub key_item_def_id: Option<u64>, // Locked until opened with this key
+}
+
+#[spacetimedb::table(name = container_slot, public)]
+#[derive(Clone)]
+pub struct ContainerSlot {
+    #[primary_key]
+    #[auto_inc]
+    pub slot_id: u64,
+    #[index(btree)]
+    pub container_id: u64,
+    pub item_def_id: u64,
+    pub quantity: u32,
+    pub instance_id: Option<u64>, // Rolled items (item_instance.rs)
+    pub owner: Option<Identity>, // Instanced slots only
+}
+
+#[spacetimedb::table(name = container_looter)]
+pub struct ContainerLooter {
+    #[primary_key]
+    #[auto_inc]
+    pub looter_id: u64,
+    #[index(btree)]
+    pub container_id: u64,
+    pub identity: Identity,
+}
+
+fn fill(ctx: &ReducerContext, container: &Container, owner: Option<Identity>) {
+    for (item_def_id, quantity, instance_id) in loot::roll_loot(ctx, container.loot_table_id) {
+        ctx.db.container_slot().insert(ContainerSlot {
+            slot_id: 0,
+            container_id: container.container_id,
+            item_def_id,
+            quantity,
+            instance_id,
+            owner,
+        });
+    }
+}
+
+// The live sender, if they can reach and open the container
+fn require_access(ctx: &ReducerContext, container: &Container) -> Result<PlayerData, String> {
+    let Some(player) = ctx.db.player().identity().find(ctx.sender) else {
+        return Err("Player not found".to_string());
+    };
+    if player.is_dead {
+        return Err("Dead players can't loot".to_string());
+    }
+    if player.room_name != container.room_name || player.position.distance_xz(&container.position) > INTERACT_RANGE {
+        return Err("Too far away".to_string());
+    }
+    if container.key_item_def_id.is_some_and(|key| !inventory::has_item(ctx, player.identity, key)) {
+        return Err("This container is locked and needs a key".to_string());
+    }
+    Ok(player)
+}
+
+fn delete_container(ctx: &ReducerContext, container_id: u64) {
+    let slots: Vec<ContainerSlot> = ctx.db.container_slot().container_id().filter(container_id).collect();
+    for slot in slots {
+        item_instance::discard(ctx, slot.instance_id);
+        ctx.db.container_slot().slot_id().delete(slot.slot_id);
+    }
+    let looters: Vec<u64> = ctx.db.container_looter().container_id().filter(container_id).map(|l| l.looter_id).collect();
+    for looter_id in looters {
+        ctx.db.container_looter().looter_id().delete(looter_id);
+    }
+    ctx.db.container().container_id().delete(container_id);
+}
+
+pub fn clear_room(ctx: &ReducerContext, room_name: &str) {
+    let containers: Vec<u64> = ctx.db.container().room_name().filter(room_name).map(|c| c.container_id).collect();
+    for container_id in containers {
+        delete_container(ctx, container_id);
+    }
+}
+
+// --- Container Reducers ---
+
+// Open a container. Instanced containers roll the sender's slots on first open
+#[spacetimedb::reducer]
+pub fn open_container(ctx: &ReducerContext, container_id: u64) -> Result<(), String> {
+    let Some(container) = ctx.db.container().container_id().find(container_id) else {
+        return Err("Container not found".to_string());
+    };
+    require_access(ctx, &container)?;
+    sound::play_sound(ctx, &container.room_name, SOUND_CHEST_OPEN, container.position.clone(), INTERACTION_SOUND_RADIUS);
+    if container.instanced
+        && !ctx.db.container_looter().container_id().filter(container_id).any(|l| l.identity == ctx.sender)
+    {
+        ctx.db.container_looter().insert(ContainerLooter { looter_id: 0, container_id, identity: ctx.sender });
+        fill(ctx, &container, Some(ctx.sender));
+    }
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn take_from_container(ctx: &ReducerContext, slot_id: u64) -> Result<(), String> {
+    let Some(slot) = ctx.db.container_slot().slot_id().find(slot_id) else {
+        return Err("Item not found".to_string());
+    };
+    let Some(container) = ctx.db.container().container_id().find(slot.container_id) else {
+        return Err("Container not found".to_string());
+    };
+    let player = require_access(ctx, &container)?;
+    if slot.owner.is_some_and(|owner| owner != ctx.sender) {
+        return Err("That loot belongs to someone else".to_string());
+    }
+    let Some(def) = ctx.db.item_def().item_def_id().find(slot.item_def_id) else {
+        ctx.db.container_slot().slot_id().delete(slot_id);
+        return Err("Item not found".to_string());
+    };
+    item::grant_item(ctx, &player, &def, slot.quantity, slot.instance_id)?;
+    ctx.db.container_slot().slot_id().delete(slot_id);
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn place_container(
+    ctx: &ReducerContext,
+    room_name: String,
+    position: Vector3,
+    loot_table_id: u64,
+    instanced: bool,
+    key_item_def_id: Option<u64>,
+) -> Result<(), String> {
+    room::require_room_owner(ctx, &room_name)?;
+    if ctx.db.loot_table().loot_table_id().find(loot_table_id).is_none() {
+        return Err("Loot table not found".to_string());
+    }
+    if key_item_def_id.is_some_and(|id| ctx.db.item_def().item_def_id().find(id).is_none()) {
+        return Err("Key item not found".to_string());
+    }
+    let container = ctx.db.container().insert(Container {
+        container_id: 0,
+        room_name,
+        position,
+        loot_table_id,
+        instanced,
+        key_item_def_id,
+    });
+    if !instanced {
+        fill(ctx, &container, None);
+    }
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn remove_container(ctx: &ReducerContext, container_id: u64) -> Result<(), String> {
+    let Some(container) = ctx.db.container().container_id().find(container_id) else {
+        return Err("Container not found".to_string());
+    };
+    room::require_room_owner(ctx, &container.room_name)?;
+    delete_container(ctx, container_id);
+    Ok(())
+}
+
+// Lock a container behind a key item, or unlock it with None
+#[spacetimedb::reducer]
+pub fn set_container_lock(ctx: &ReducerContext, container_id: u64, key_item_def_id: Option<u64>) -> Result<(), String> {
+    let Some(mut container) = ctx.db.container().container_id().find(container_id) else {
+        return Err("Container not found".to_string());
+    };
+    room::require_room_owner(ctx, &container.room_name)?;
+    if key_item_def_id.is_some_and(|id| ctx.db.item_def().item_def_id().find(id).is_none()) {
+        return Err("Key item not found".to_string());
+    }
+    if container.key_item_def_id.is_some() && key_item_def_id.is_none() {
+        sound::play_sound(ctx, &container.room_name, SOUND_CHEST_UNLOCK, container.position.clone(), INTERACTION_SOUND_RADIUS);
+    }
+    container.key_item_def_id = key_item_def_id;
+    ctx.db.container().container_id().update(container);
+    Ok(())
+}
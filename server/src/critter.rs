@@ -0,0 +1,104 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - critter.rs
+ *
+ * Ambient wildlife (rabbits, birds) that makes rooms feel alive.
+ *
+ * Key components:
+ * - spawn_critters: Scatters CRITTERS_PER_ROOM critters (NpcKind::Critter)
+ *   around a new room's origin
+ * - update_critters: Called from each room's game_tick, but a critter only
+ *   acts every CRITTER_UPDATE_INTERVAL_SECS. Critters with a living player
+ *   within CRITTER_FLEE_RADIUS run straight away from the nearest one
+ *   (NpcState::Flee); the rest sometimes hop to a random spot near home
+ *
+ * Critters are not hostile, so npc_ai.rs, perception and threat never look
+ * at them. They can be hit and killed like any other NPC, which is what
+ * gathering/hunting will build on.
+ *
+ * Related files:
+ * - npc.rs: NpcKind::Critter
+ * - npc_ai.rs: move_towards / yaw_towards
+ * - room.rs: Spawns critters when a room is created
+ */
+
+use spacetimedb::rand::Rng;
+use spacetimedb::ReducerContext;
+
+use crate::common::{
+    Vector3, CRITTERS_PER_ROOM, CRITTER_FLEE_RADIUS, CRITTER_FLEE_SPEED, CRITTER_SPAWN_AREA,
+    CRITTER_UPDATE_INTERVAL_SECS, CRITTER_WANDER_RADIUS, CRITTER_WANDER_SPEED,
+};
+use crate::escort;
+use crate::npc::{self, npc as _, Npc, NpcKind, NpcState};
+use crate::npc_ai::{move_towards, yaw_towards};
+use crate::player as _;
+use crate::room::Room;
+use crate::spatial::{self, SpatialEntity};
+
+pub fn spawn_critters(ctx: &ReducerContext, room_name: &str) {
+    let mut rng = ctx.rng();
+    for _ in 0..CRITTERS_PER_ROOM {
+        let position = Vector3 {
+            x: rng.gen_range(-CRITTER_SPAWN_AREA..CRITTER_SPAWN_AREA),
+            y: 1.0,
+            z: rng.gen_range(-CRITTER_SPAWN_AREA..CRITTER_SPAWN_AREA),
+        };
+        npc::spawn_npc_at(ctx, room_name, NpcKind::Critter, position, String::new());
+    }
+}
+
+pub fn update_critters(ctx: &ReducerContext, room: &Room) {
+    if room.current_player_count == 0 {
+        return;
+    }
+    let critters: Vec<Npc> = ctx.db.npc().room_name().filter(&room.room_name)
+        .filter(|n| n.kind == NpcKind::Critter && !escort::is_escorted(ctx, n.npc_id))
+        .collect();
+    if critters.is_empty() {
+        return;
+    }
+    let player_positions: Vec<Vector3> = ctx.db.player().room_name().filter(&room.room_name)
+        .filter(|p| !p.is_dead)
+        .map(|p| p.position)
+        .collect();
+
+    let mut rng = ctx.rng();
+    for mut critter in critters {
+        let elapsed = ctx.timestamp.duration_since(critter.last_ai_update).unwrap_or_default().as_secs_f32();
+        if elapsed < CRITTER_UPDATE_INTERVAL_SECS {
+            continue;
+        }
+        let threat = player_positions.iter()
+            .filter(|p| critter.position.distance_xz(p) <= CRITTER_FLEE_RADIUS)
+            .min_by(|a, b| critter.position.distance_xz(a).total_cmp(&critter.position.distance_xz(b)));
+        match threat {
+            Some(player) => {
+                // A point past the critter on the line from the player
+                let away = Vector3 {
+                    x: critter.position.x * 2.0 - player.x,
+                    y: critter.position.y,
+                    z: critter.position.z * 2.0 - player.z,
+                };
+                critter.state = NpcState::Flee;
+                critter.rotation.y = yaw_towards(&critter.position, &away);
+                critter.position = move_towards(&critter.position, &away, CRITTER_FLEE_SPEED * elapsed);
+            }
+            None if rng.gen_bool(0.5) => {
+                let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+                let distance = rng.gen_range(0.0..CRITTER_WANDER_RADIUS);
+                let goal = Vector3 {
+                    x: critter.home_position.x + angle.cos() * distance,
+                    y: critter.position.y,
+                    z: critter.home_position.z + angle.sin() * distance,
+                };
+                critter.state = NpcState::Patrol;
+                critter.rotation.y = yaw_towards(&critter.position, &goal);
+                critter.position = move_towards(&critter.position, &goal, CRITTER_WANDER_SPEED * elapsed);
+            }
+            None => critter.state = NpcState::Idle,
+        }
+        critter.last_ai_update = ctx.timestamp;
+        spatial::track(ctx, SpatialEntity::Npc(critter.npc_id), &critter.room_name, &critter.position);
+        ctx.db.npc().npc_id().update(critter);
+    }
+}
@@ -0,0 +1,130 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - decoration.rs
+ *
+ * Furniture and other decorations placed to customize rooms. Decorations
+ * are purely cosmetic: they don't block movement or line of sight.
+ *
+ * Key components:
+ * - Decoration table (public): Which model (an asset name the client knows),
+ *   where, its yaw and its scale
+ * - place_decoration / move_decoration / remove_decoration: The room owner
+ *   decorates any time, other players while build_mode is on. Only the
+ *   player who placed a decoration (or the room owner) can move or remove
+ *   it. A decoration must stand on the room's map, use a scale within
+ *   DECORATION_MIN_SCALE..=DECORATION_MAX_SCALE, and a room holds at most
+ *   MAX_DECORATIONS_PER_ROOM of them
+ *
+ * Related files:
+ * - map.rs: is_on_map
+ * - protection.rs: require_placer / may_modify
+ * - room.rs: close_room clears a room's decorations
+ */
+
+use spacetimedb::{Identity, ReducerContext, Table, Timestamp};
+
+use crate::common::{
+    Vector3, DECORATION_MAX_SCALE, DECORATION_MIN_SCALE, MAX_DECORATIONS_PER_ROOM, MAX_DECORATION_MODEL_LEN,
+};
+use crate::map;
+use crate::protection::{self, PlacedObject};
+use crate::room::{self, Room};
+
+#[spacetimedb::table(name = decoration, public)]
+#[derive(Clone)]
+pub struct Decoration {
+    #[primary_key]
+    #[auto_inc]
+    pub decoration_id: u64,
+    #[index(btree)]
+    pub room_name: String,
+    pub model: String,
+    pub position: Vector3,
+    pub yaw: f32,
+    pub scale: f32,
+    pub placed_by: Identity,
+    pub placed_at: Timestamp,
+}
+
+fn validate_placement(ctx: &ReducerContext, room: &Room, position: &Vector3, scale: f32) -> Result<(), String> {
+    if !(DECORATION_MIN_SCALE..=DECORATION_MAX_SCALE).contains(&scale) {
+        return Err(format!("Decoration scale must be between {} and {}", DECORATION_MIN_SCALE, DECORATION_MAX_SCALE));
+    }
+    if !map::is_on_map(ctx, room.map_id, position) {
+        return Err("Decorations must be placed on the map".to_string());
+    }
+    Ok(())
+}
+
+pub fn clear_room(ctx: &ReducerContext, room_name: &str) {
+    let decorations: Vec<u64> = ctx.db.decoration().room_name().filter(room_name).map(|d| d.decoration_id).collect();
+    for decoration_id in decorations {
+        ctx.db.decoration().decoration_id().delete(decoration_id);
+    }
+}
+
+// --- Decoration Reducers ---
+
+#[spacetimedb::reducer]
+pub fn place_decoration(
+    ctx: &ReducerContext,
+    room_name: String,
+    model: String,
+    position: Vector3,
+    yaw: f32,
+    scale: f32,
+) -> Result<(), String> {
+    let room = protection::require_placer(ctx, &room_name)?;
+    let model = model.trim().to_string();
+    if model.is_empty() || model.len() > MAX_DECORATION_MODEL_LEN {
+        return Err(format!("Model names must be 1-{} characters", MAX_DECORATION_MODEL_LEN));
+    }
+    if ctx.db.decoration().room_name().filter(&room_name).count() >= MAX_DECORATIONS_PER_ROOM {
+        return Err(format!("A room can have at most {} decorations", MAX_DECORATIONS_PER_ROOM));
+    }
+    validate_placement(ctx, &room, &position, scale)?;
+    ctx.db.decoration().insert(Decoration {
+        decoration_id: 0,
+        room_name,
+        model,
+        position,
+        yaw,
+        scale,
+        placed_by: ctx.sender,
+        placed_at: ctx.timestamp,
+    });
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn move_decoration(ctx: &ReducerContext, decoration_id: u64, position: Vector3, yaw: f32, scale: f32) -> Result<(), String> {
+    let Some(mut decoration) = ctx.db.decoration().decoration_id().find(decoration_id) else {
+        return Err("Decoration not found".to_string());
+    };
+    let Some(room) = room::find_room(ctx, &decoration.room_name) else {
+        return Err("Room not found".to_string());
+    };
+    validate_placement(ctx, &room, &position, scale)?;
+    if !protection::may_modify(ctx, &room, PlacedObject::Decoration, decoration_id, Some(decoration.placed_by)) {
+        return Ok(());
+    }
+    decoration.position = position;
+    decoration.yaw = yaw;
+    decoration.scale = scale;
+    ctx.db.decoration().decoration_id().update(decoration);
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn remove_decoration(ctx: &ReducerContext, decoration_id: u64) -> Result<(), String> {
+    let Some(decoration) = ctx.db.decoration().decoration_id().find(decoration_id) else {
+        return Err("Decoration not found".to_string());
+    };
+    let Some(room) = room::find_room(ctx, &decoration.room_name) else {
+        return Err("Room not found".to_string());
+    };
+    if !protection::may_modify(ctx, &room, PlacedObject::Decoration, decoration_id, Some(decoration.placed_by)) {
+        return Ok(());
+    }
+    ctx.db.decoration().decoration_id().delete(decoration_id);
+    Ok(())
+}
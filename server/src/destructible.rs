@@ -0,0 +1,125 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - destructible.rs
+ *
+ * Destructible props (crates, barrels, ...) that take damage from every
+ * attack type like players and NPCs do.
+ *
+ * Key components:
+ * - Destructible table (public): Position, size, health and an optional loot
+ *   table per prop. Props are registered in the spatial index, so melee,
+ *   projectiles, hitscan and AoE find them through the same queries
+ * - damage_destructible: Called by apply_damage for EntityId::Destructible;
+ *   removes the prop at zero health and drops its loot
+ * - seed_destructibles: A few crates around the hub room
+ * - clear_room: Removes every prop of a room (mapgen.rs replaces them)
+ * - place_destructible: Admin reducer for placing props in any room
+ *
+ * Related files:
+ * - combat.rs: apply_damage routes prop damage here
+ * - spatial.rs: SpatialEntity::Destructible
+ * - loot.rs: Loot tables and dropped items
+ */
+
+use spacetimedb::{ReducerContext, Table};
+
+use crate::admin;
+use crate::common::Vector3;
+use crate::loot::{self, loot_table as _};
+use crate::minimap;
+use crate::room;
+use crate::spatial::{self, SpatialEntity};
+
+#[spacetimedb::table(name = destructible, public)]
+#[derive(Clone)]
+pub struct Destructible {
+    #[primary_key]
+    #[auto_inc]
+    pub destructible_id: u64,
+    #[index(btree)]
+    pub room_name: String,
+    pub position: Vector3,
+    pub size: Vector3,
+    pub health: i32,
+    pub max_health: i32,
+    pub loot_table_id: Option<u64>,
+}
+
+pub fn spawn_destructible(
+    ctx: &ReducerContext,
+    room_name: &str,
+    position: Vector3,
+    size: Vector3,
+    health: i32,
+    loot_table_id: Option<u64>,
+) -> Destructible {
+    let prop = ctx.db.destructible().insert(Destructible {
+        destructible_id: 0,
+        room_name: room_name.to_string(),
+        position,
+        size,
+        health,
+        max_health: health,
+        loot_table_id,
+    });
+    spatial::track(ctx, SpatialEntity::Destructible(prop.destructible_id), room_name, &prop.position);
+    minimap::mark_dirty_at(ctx, room_name, &prop.position);
+    prop
+}
+
+pub fn seed_destructibles(ctx: &ReducerContext, room_name: &str) {
+    let crate_size = Vector3 { x: 1.0, y: 1.0, z: 1.0 };
+    for (x, z) in [(6.0, 6.0), (-6.0, 6.0), (6.0, -6.0), (-6.0, -6.0)] {
+        spawn_destructible(ctx, room_name, Vector3 { x, y: 0.5, z }, crate_size.clone(), 40, None);
+    }
+}
+
+pub fn clear_room(ctx: &ReducerContext, room_name: &str) {
+    let props: Vec<u64> = ctx.db.destructible().room_name().filter(room_name).map(|d| d.destructible_id).collect();
+    for destructible_id in props {
+        ctx.db.destructible().destructible_id().delete(destructible_id);
+        spatial::untrack(ctx, &SpatialEntity::Destructible(destructible_id));
+    }
+}
+
+// Apply damage to a prop. Returns the prop's room and whether it was
+// destroyed, or None if it no longer exists.
+pub fn damage_destructible(ctx: &ReducerContext, destructible_id: u64, amount: i32) -> Option<(String, bool)> {
+    let mut prop = ctx.db.destructible().destructible_id().find(destructible_id)?;
+    prop.health = (prop.health - amount).max(0);
+    let room_name = prop.room_name.clone();
+    if prop.health > 0 {
+        ctx.db.destructible().destructible_id().update(prop);
+        return Some((room_name, false));
+    }
+    spacetimedb::log::info!("Destructible {} destroyed in room '{}'.", destructible_id, room_name);
+    ctx.db.destructible().destructible_id().delete(destructible_id);
+    spatial::untrack(ctx, &SpatialEntity::Destructible(destructible_id));
+    minimap::mark_dirty_at(ctx, &room_name, &prop.position);
+    if let Some(loot_table_id) = prop.loot_table_id {
+        loot::drop_loot(ctx, &room_name, &prop.position, loot_table_id, None);
+    }
+    Some((room_name, true))
+}
+
+#[spacetimedb::reducer]
+pub fn place_destructible(
+    ctx: &ReducerContext,
+    room_name: String,
+    position: Vector3,
+    size: Vector3,
+    health: i32,
+    loot_table_id: Option<u64>,
+) -> Result<(), String> {
+    admin::require_admin(ctx)?;
+    if room::find_room(ctx, &room_name).is_none() {
+        return Err("Room not found".to_string());
+    }
+    if health <= 0 {
+        return Err("Health must be positive".to_string());
+    }
+    if loot_table_id.is_some_and(|id| ctx.db.loot_table().loot_table_id().find(id).is_none()) {
+        return Err("Loot table not found".to_string());
+    }
+    spawn_destructible(ctx, &room_name, position, size, health, loot_table_id);
+    Ok(())
+}
@@ -0,0 +1,173 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - dialogue.rs
+ *
+ * Talking to NPCs: branching conversation trees for towns and quest givers.
+ *
+ * Key components:
+ * - DialogueNode table (public): One line of an NPC's conversation and the
+ *   player's choices; each choice leads to another node or ends the talk
+ * - NpcDialogue table (public): Which root node an NPC starts talking from
+ * - PlayerDialogueState table (public, filtered per player): Where each
+ *   player is in their current conversation
+ * - interact_npc: Starts a conversation with an NPC within INTERACT_RANGE
+ *   (npc::require_in_range)
+ * - choose_dialogue_option / end_dialogue: Advance or leave the conversation.
+ *   Walking out of range ends it
+ * - upsert_dialogue_node (admin) / set_npc_dialogue (room owner): Authoring
+ *
+ * Related files:
+ * - npc.rs: The NPCs being talked to
+ * - combat.rs: Conversations end when the NPC dies (clear_npc)
+ * - lib.rs: Conversations end on disconnect (clear_player)
+ */
+
+use spacetimedb::{client_visibility_filter, Filter, Identity, ReducerContext, SpacetimeType, Table, Timestamp};
+
+use crate::admin;
+use crate::npc::{self, npc as _};
+use crate::room;
+
+#[derive(SpacetimeType, Clone, Debug, PartialEq)]
+pub struct DialogueChoice {
+    pub text: String,
+    pub next_node: Option<u64>, // None = the conversation ends
+}
+
+#[spacetimedb::table(name = dialogue_node, public)]
+#[derive(Clone)]
+pub struct DialogueNode {
+    #[primary_key]
+    #[auto_inc]
+    pub node_id: u64,
+    pub speaker: String,
+    pub text: String,
+    pub choices: Vec<DialogueChoice>,
+}
+
+#[spacetimedb::table(name = npc_dialogue, public)]
+#[derive(Clone)]
+pub struct NpcDialogue {
+    #[primary_key]
+    pub npc_id: u64,
+    pub root_node: u64,
+}
+
+#[spacetimedb::table(name = player_dialogue_state, public)]
+#[derive(Clone)]
+pub struct PlayerDialogueState {
+    #[primary_key]
+    pub identity: Identity,
+    #[index(btree)]
+    pub npc_id: u64,
+    pub node_id: u64,
+    pub started_at: Timestamp,
+    pub updated_at: Timestamp,
+}
+
+#[client_visibility_filter]
+const PLAYER_SEES_OWN_DIALOGUE: Filter = Filter::Sql(
+    "SELECT * FROM player_dialogue_state WHERE identity = :sender",
+);
+
+pub fn clear_player(ctx: &ReducerContext, identity: Identity) {
+    ctx.db.player_dialogue_state().identity().delete(identity);
+}
+
+pub fn clear_npc(ctx: &ReducerContext, npc_id: u64) {
+    ctx.db.npc_dialogue().npc_id().delete(npc_id);
+    let talking: Vec<Identity> = ctx.db.player_dialogue_state().npc_id().filter(npc_id).map(|s| s.identity).collect();
+    for identity in talking {
+        ctx.db.player_dialogue_state().identity().delete(identity);
+    }
+}
+
+// --- Dialogue Reducers ---
+
+#[spacetimedb::reducer]
+pub fn interact_npc(ctx: &ReducerContext, npc_id: u64) -> Result<(), String> {
+    npc::require_in_range(ctx, npc_id)?;
+    let Some(dialogue) = ctx.db.npc_dialogue().npc_id().find(npc_id) else {
+        return Err("This NPC has nothing to say".to_string());
+    };
+    let state = PlayerDialogueState {
+        identity: ctx.sender,
+        npc_id,
+        node_id: dialogue.root_node,
+        started_at: ctx.timestamp,
+        updated_at: ctx.timestamp,
+    };
+    // Talking to someone new abandons the previous conversation
+    if ctx.db.player_dialogue_state().identity().find(ctx.sender).is_some() {
+        ctx.db.player_dialogue_state().identity().update(state);
+    } else {
+        ctx.db.player_dialogue_state().insert(state);
+    }
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn choose_dialogue_option(ctx: &ReducerContext, choice_index: u32) -> Result<(), String> {
+    let Some(mut state) = ctx.db.player_dialogue_state().identity().find(ctx.sender) else {
+        return Err("Not in a conversation".to_string());
+    };
+    if let Err(e) = npc::require_in_range(ctx, state.npc_id) {
+        clear_player(ctx, ctx.sender);
+        return Err(e);
+    }
+    let Some(node) = ctx.db.dialogue_node().node_id().find(state.node_id) else {
+        clear_player(ctx, ctx.sender);
+        return Err("The conversation no longer exists".to_string());
+    };
+    let Some(choice) = node.choices.get(choice_index as usize) else {
+        return Err("Invalid choice".to_string());
+    };
+    match choice.next_node {
+        Some(next_node) => {
+            state.node_id = next_node;
+            state.updated_at = ctx.timestamp;
+            ctx.db.player_dialogue_state().identity().update(state);
+        }
+        None => clear_player(ctx, ctx.sender),
+    }
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn end_dialogue(ctx: &ReducerContext) -> Result<(), String> {
+    clear_player(ctx, ctx.sender);
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn upsert_dialogue_node(ctx: &ReducerContext, node: DialogueNode) -> Result<(), String> {
+    admin::require_admin(ctx)?;
+    let dangling = node.choices.iter()
+        .filter_map(|c| c.next_node)
+        .any(|next| next != node.node_id && ctx.db.dialogue_node().node_id().find(next).is_none());
+    if dangling {
+        return Err("A choice leads to a node that doesn't exist".to_string());
+    }
+    if node.node_id != 0 && ctx.db.dialogue_node().node_id().find(node.node_id).is_some() {
+        ctx.db.dialogue_node().node_id().update(node);
+    } else {
+        ctx.db.dialogue_node().insert(DialogueNode { node_id: 0, ..node });
+    }
+    Ok(())
+}
+
+// Room owner: give an NPC a conversation (or take it away with None)
+#[spacetimedb::reducer]
+pub fn set_npc_dialogue(ctx: &ReducerContext, npc_id: u64, root_node: Option<u64>) -> Result<(), String> {
+    let Some(npc) = ctx.db.npc().npc_id().find(npc_id) else {
+        return Err("NPC not found".to_string());
+    };
+    room::require_room_owner(ctx, &npc.room_name)?;
+    ctx.db.npc_dialogue().npc_id().delete(npc_id);
+    if let Some(root_node) = root_node {
+        if ctx.db.dialogue_node().node_id().find(root_node).is_none() {
+            return Err("Dialogue node not found".to_string());
+        }
+        ctx.db.npc_dialogue().insert(NpcDialogue { npc_id, root_node });
+    }
+    Ok(())
+}
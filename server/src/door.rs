@@ -0,0 +1,310 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - door.rs
+ *
+ * Interactive doors and the switches wired to them.
+ *
+ * Key components:
+ * - Door table (public): A box in a room that is open or closed. A closed
+ *   door stands in the room's tile set as a tile (tile_id), so it blocks
+ *   line of sight; players can't walk into it (blocks, used by
+ *   player_logic.rs) and NPCs path around it (pathfinding.rs)
+ * - Doors with a key_item_def_id are locked: only players carrying the key
+ *   in their inventory can open them, and switches leave them shut. The
+ *   room owner can unlock them (set_door_lock)
+ * - Switch table (public): A lever that toggles every unlocked door it is
+ *   linked to
+ * - activate: Player reducer for both (InteractableId). The player must be
+ *   alive, in the room and within INTERACT_RANGE. Doors don't close on
+ *   someone standing in the doorway
+ * - place_door / place_switch / remove_door / remove_switch / set_door_lock:
+ *   Room-owner reducers
+ *
+ * Related files:
+ * - map.rs: Room tile sets. Moving a room to another tile set rebuilds its
+ *   closed doors' tiles (rebuild_tiles)
+ * - sound.rs: Doors play a sound cue when they open, close or unlock
+ * - room.rs: close_room clears a room's doors and switches
+ */
+
+use spacetimedb::{ReducerContext, SpacetimeType, Table};
+use std::collections::HashSet;
+
+use crate::chunk;
+use crate::common::{
+    Vector3, INTERACTION_SOUND_RADIUS, INTERACT_RANGE, MAX_SWITCH_LINKS, SOUND_DOOR_CLOSE, SOUND_DOOR_OPEN, SOUND_DOOR_UNLOCK,
+};
+use crate::inventory;
+use crate::item::item_def as _;
+use crate::minimap;
+use crate::player as _;
+use crate::room;
+use crate::sound;
+use crate::spatial;
+use crate::{game_tile, GameTile};
+
+#[derive(SpacetimeType, Clone, Copy, Debug, PartialEq)]
+pub enum InteractableId {
+    Door(u64),
+    Switch(u64),
+}
+
+#[spacetimedb::table(name = door, public)]
+#[derive(Clone)]
+pub struct Door {
+    #[primary_key]
+    #[auto_inc]
+    pub door_id: u64,
+    #[index(btree)]
+    pub room_name: String,
+    pub position: Vector3,
+    pub size: Vector3,
+    pub is_open: bool,
+    pub key_item_def_id: Option<u64>, // Locked until opened with this key
+    pub tile_id: Option<u64>, // The blocking tile while closed
+}
+
+#[spacetimedb::table(name = switch, public)]
+#[derive(Clone)]
+pub struct Switch {
+    #[primary_key]
+    #[auto_inc]
+    pub switch_id: u64,
+    #[index(btree)]
+    pub room_name: String,
+    pub position: Vector3,
+    pub door_ids: Vec<u64>,
+    pub is_on: bool,
+}
+
+fn contains(door: &Door, position: &Vector3) -> bool {
+    (position.x - door.position.x).abs() <= door.size.x * 0.5
+        && (position.z - door.position.z).abs() <= door.size.z * 0.5
+        && (position.y - door.position.y).abs() <= door.size.y * 0.5 + 1.0
+}
+
+// Whether a closed door of the room is in the way at `position`
+pub fn blocks(ctx: &ReducerContext, room_name: &str, position: &Vector3) -> bool {
+    ctx.db.door().room_name().filter(room_name).any(|door| !door.is_open && contains(&door, position))
+}
+
+// Closed doors of the room as (center, size) boxes, for path planning
+pub fn closed_door_boxes(ctx: &ReducerContext, room_name: &str) -> Vec<(Vector3, Vector3)> {
+    ctx.db.door().room_name().filter(room_name)
+        .filter(|door| !door.is_open)
+        .map(|door| (door.position, door.size))
+        .collect()
+}
+
+fn insert_door_tile(ctx: &ReducerContext, door: &Door) -> Option<u64> {
+    let room = room::find_room(ctx, &door.room_name)?;
+    let tile = ctx.db.game_tile().insert(GameTile {
+        tile_id: 0,
+        map_id: room.map_id,
+        room_name: room.room_name.clone(),
+        chunk_id: chunk::chunk_id_at(ctx, &room.room_name, room.map_id, &door.position),
+        hazard: None,
+        grid_cell: spatial::grid_cell_of(&door.position),
+        position: door.position.clone(),
+        size: door.size.clone(),
+        placed_by: None,
+    });
+    minimap::mark_dirty(ctx, tile.chunk_id);
+    Some(tile.tile_id)
+}
+
+fn remove_door_tile(ctx: &ReducerContext, door: &mut Door) {
+    if let Some(tile_id) = door.tile_id.take() {
+        if let Some(tile) = ctx.db.game_tile().tile_id().find(tile_id) {
+            minimap::mark_dirty(ctx, tile.chunk_id);
+        }
+        ctx.db.game_tile().tile_id().delete(tile_id);
+    }
+}
+
+// Open or close a door. Fails if it would close on a player
+fn set_open(ctx: &ReducerContext, mut door: Door, open: bool) -> Result<(), String> {
+    if door.is_open == open {
+        return Ok(());
+    }
+    if open {
+        remove_door_tile(ctx, &mut door);
+    } else {
+        let occupied = ctx.db.player().room_name().filter(&door.room_name).any(|p| contains(&door, &p.position));
+        if occupied {
+            return Err("Something is in the doorway".to_string());
+        }
+        door.tile_id = insert_door_tile(ctx, &door);
+    }
+    let sound_id = if open { SOUND_DOOR_OPEN } else { SOUND_DOOR_CLOSE };
+    sound::play_sound(ctx, &door.room_name, sound_id, door.position.clone(), INTERACTION_SOUND_RADIUS);
+    door.is_open = open;
+    ctx.db.door().door_id().update(door);
+    Ok(())
+}
+
+// The room moved to a new tile set (map.rs move_room_to_map): closed doors
+// need their blocking tiles on it
+pub fn rebuild_tiles(ctx: &ReducerContext, room_name: &str) {
+    let closed: Vec<Door> = ctx.db.door().room_name().filter(room_name).filter(|d| !d.is_open).collect();
+    for mut door in closed {
+        remove_door_tile(ctx, &mut door);
+        door.tile_id = insert_door_tile(ctx, &door);
+        ctx.db.door().door_id().update(door);
+    }
+}
+
+// Tiles that stand in for closed doors rather than terrain
+pub fn door_tile_ids(ctx: &ReducerContext, room_name: &str) -> HashSet<u64> {
+    ctx.db.door().room_name().filter(room_name).filter_map(|d| d.tile_id).collect()
+}
+
+pub fn clear_room(ctx: &ReducerContext, room_name: &str) {
+    let doors: Vec<Door> = ctx.db.door().room_name().filter(room_name).collect();
+    for mut door in doors {
+        remove_door_tile(ctx, &mut door);
+        ctx.db.door().door_id().delete(door.door_id);
+    }
+    let switches: Vec<u64> = ctx.db.switch().room_name().filter(room_name).map(|s| s.switch_id).collect();
+    for switch_id in switches {
+        ctx.db.switch().switch_id().delete(switch_id);
+    }
+}
+
+// --- Door Reducers ---
+
+#[spacetimedb::reducer]
+pub fn activate(ctx: &ReducerContext, entity_id: InteractableId) -> Result<(), String> {
+    let Some(player) = ctx.db.player().identity().find(ctx.sender) else {
+        return Err("Player not found".to_string());
+    };
+    if player.is_dead {
+        return Err("Dead players can't interact".to_string());
+    }
+    let (room_name, position) = match entity_id {
+        InteractableId::Door(door_id) => {
+            let door = ctx.db.door().door_id().find(door_id).ok_or("Door not found")?;
+            (door.room_name, door.position)
+        }
+        InteractableId::Switch(switch_id) => {
+            let switch = ctx.db.switch().switch_id().find(switch_id).ok_or("Switch not found")?;
+            (switch.room_name, switch.position)
+        }
+    };
+    if player.room_name != room_name || player.position.distance_xz(&position) > INTERACT_RANGE {
+        return Err("Too far away".to_string());
+    }
+
+    match entity_id {
+        InteractableId::Door(door_id) => {
+            let Some(door) = ctx.db.door().door_id().find(door_id) else {
+                return Err("Door not found".to_string());
+            };
+            if door.key_item_def_id.is_some_and(|key| !inventory::has_item(ctx, player.identity, key)) {
+                return Err("This door is locked and needs a key".to_string());
+            }
+            let open = !door.is_open;
+            set_open(ctx, door, open)
+        }
+        InteractableId::Switch(switch_id) => {
+            let Some(mut switch) = ctx.db.switch().switch_id().find(switch_id) else {
+                return Err("Switch not found".to_string());
+            };
+            for door_id in &switch.door_ids {
+                let Some(door) = ctx.db.door().door_id().find(*door_id) else {
+                    continue;
+                };
+                if door.key_item_def_id.is_some() {
+                    continue;
+                }
+                let open = !door.is_open;
+                set_open(ctx, door, open)?;
+            }
+            switch.is_on = !switch.is_on;
+            ctx.db.switch().switch_id().update(switch);
+            Ok(())
+        }
+    }
+}
+
+#[spacetimedb::reducer]
+pub fn place_door(
+    ctx: &ReducerContext,
+    room_name: String,
+    position: Vector3,
+    size: Vector3,
+    key_item_def_id: Option<u64>,
+) -> Result<(), String> {
+    room::require_room_owner(ctx, &room_name)?;
+    if size.x <= 0.0 || size.y <= 0.0 || size.z <= 0.0 {
+        return Err("Door size must be positive".to_string());
+    }
+    if key_item_def_id.is_some_and(|id| ctx.db.item_def().item_def_id().find(id).is_none()) {
+        return Err("Key item not found".to_string());
+    }
+    let mut door = Door {
+        door_id: 0,
+        room_name,
+        position,
+        size,
+        is_open: false,
+        key_item_def_id,
+        tile_id: None,
+    };
+    door.tile_id = insert_door_tile(ctx, &door);
+    ctx.db.door().insert(door);
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn remove_door(ctx: &ReducerContext, door_id: u64) -> Result<(), String> {
+    let Some(mut door) = ctx.db.door().door_id().find(door_id) else {
+        return Err("Door not found".to_string());
+    };
+    room::require_room_owner(ctx, &door.room_name)?;
+    remove_door_tile(ctx, &mut door);
+    ctx.db.door().door_id().delete(door_id);
+    Ok(())
+}
+
+// Lock a door behind a key item, or unlock it with None
+#[spacetimedb::reducer]
+pub fn set_door_lock(ctx: &ReducerContext, door_id: u64, key_item_def_id: Option<u64>) -> Result<(), String> {
+    let Some(mut door) = ctx.db.door().door_id().find(door_id) else {
+        return Err("Door not found".to_string());
+    };
+    room::require_room_owner(ctx, &door.room_name)?;
+    if key_item_def_id.is_some_and(|id| ctx.db.item_def().item_def_id().find(id).is_none()) {
+        return Err("Key item not found".to_string());
+    }
+    if door.key_item_def_id.is_some() && key_item_def_id.is_none() {
+        sound::play_sound(ctx, &door.room_name, SOUND_DOOR_UNLOCK, door.position.clone(), INTERACTION_SOUND_RADIUS);
+    }
+    door.key_item_def_id = key_item_def_id;
+    ctx.db.door().door_id().update(door);
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn place_switch(ctx: &ReducerContext, room_name: String, position: Vector3, door_ids: Vec<u64>) -> Result<(), String> {
+    room::require_room_owner(ctx, &room_name)?;
+    if door_ids.len() > MAX_SWITCH_LINKS {
+        return Err(format!("A switch can control at most {} doors", MAX_SWITCH_LINKS));
+    }
+    let foreign = door_ids.iter()
+        .any(|id| !ctx.db.door().door_id().find(*id).is_some_and(|d| d.room_name == room_name));
+    if foreign {
+        return Err("Switches can only control doors in their room".to_string());
+    }
+    ctx.db.switch().insert(Switch { switch_id: 0, room_name, position, door_ids, is_on: false });
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn remove_switch(ctx: &ReducerContext, switch_id: u64) -> Result<(), String> {
+    let Some(switch) = ctx.db.switch().switch_id().find(switch_id) else {
+        return Err("Switch not found".to_string());
+    };
+    room::require_room_owner(ctx, &switch.room_name)?;
+    ctx.db.switch().switch_id().delete(switch_id);
+    Ok(())
+}
@@ -0,0 +1,236 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - duel.rs
+ *
+ * Opt-in 1v1 duels between two players in a non-PvP room.
+ *
+ * Key components:
+ * - DuelState: Pending (challenge sent) -> Countdown -> Active -> Finished
+ * - Duel table (public): The two duelists, their room, the countdown end and
+ *   the result. Finished duels are kept for DUEL_RESULT_TTL_SECS so clients
+ *   can show the outcome
+ * - challenge_duel / accept_duel / decline_duel: Player reducers
+ * - are_dueling: Lets can_damage_player allow damage between the duelists
+ *   despite the room's PvP rules
+ * - resolve_defeat: Called by apply_damage when a duelist takes a blow that
+ *   would kill them. Duels are non-lethal: the blow ends the duel instead
+ * - tick_duels: Called from game_tick. Starts duels after the countdown,
+ *   expires unanswered challenges, forfeits duelists who died, left the room
+ *   or disconnected, and calls a draw after DUEL_MAX_DURATION_SECS
+ * - Both duelists are restored to full health and mana when a duel ends
+ *
+ * Related files:
+ * - combat.rs: can_damage_player / apply_damage hooks
+ * - lib.rs: game_tick
+ */
+
+use spacetimedb::{Identity, ReducerContext, SpacetimeType, Table, Timestamp};
+use std::time::Duration;
+
+use crate::common::{DUEL_COUNTDOWN_SECS, DUEL_INVITE_TTL_SECS, DUEL_MAX_DURATION_SECS, DUEL_RESULT_TTL_SECS};
+use crate::notification::{self, NotificationKind};
+use crate::player as _;
+use crate::room;
+
+#[derive(SpacetimeType, Clone, Copy, Debug, PartialEq)]
+pub enum DuelState {
+    Pending,
+    Countdown,
+    Active,
+    Finished,
+}
+
+#[spacetimedb::table(name = duel, public)]
+#[derive(Clone)]
+pub struct Duel {
+    #[primary_key]
+    #[auto_inc]
+    pub duel_id: u64,
+    #[index(btree)]
+    pub room_name: String,
+    #[index(btree)]
+    pub challenger: Identity,
+    #[index(btree)]
+    pub target: Identity,
+    pub state: DuelState,
+    pub created_at: Timestamp,
+    pub starts_at: Option<Timestamp>, // End of the countdown
+    pub ended_at: Option<Timestamp>,
+    pub winner: Option<Identity>, // None after a draw
+}
+
+impl Duel {
+    fn opponent_of(&self, identity: Identity) -> Identity {
+        if self.challenger == identity { self.target } else { self.challenger }
+    }
+}
+
+// The player's duel that hasn't finished yet, if any
+fn open_duel(ctx: &ReducerContext, identity: Identity) -> Option<Duel> {
+    ctx.db.duel().challenger().filter(identity)
+        .chain(ctx.db.duel().target().filter(identity))
+        .find(|d| d.state != DuelState::Finished)
+}
+
+pub fn are_dueling(ctx: &ReducerContext, a: Identity, b: Identity) -> bool {
+    open_duel(ctx, a).is_some_and(|d| d.state == DuelState::Active && d.opponent_of(a) == b)
+}
+
+fn restore(ctx: &ReducerContext, identity: Identity) {
+    if let Some(mut player) = ctx.db.player().identity().find(identity) {
+        if !player.is_dead {
+            player.health = player.max_health;
+            player.mana = player.max_mana;
+            ctx.db.player().identity().update(player);
+        }
+    }
+}
+
+fn finish(ctx: &ReducerContext, mut duel: Duel, winner: Option<Identity>) {
+    spacetimedb::log::info!("Duel {} finished, winner: {:?}", duel.duel_id, winner);
+    let was_running = duel.state == DuelState::Active;
+    duel.state = DuelState::Finished;
+    duel.ended_at = Some(ctx.timestamp);
+    duel.winner = winner;
+    let (challenger, target) = (duel.challenger, duel.target);
+    ctx.db.duel().duel_id().update(duel);
+    if was_running {
+        restore(ctx, challenger);
+        restore(ctx, target);
+    }
+}
+
+// A duelist took a blow from their opponent that would have killed them.
+// Returns true if that ended a duel (the caller must not kill the player).
+pub fn resolve_defeat(ctx: &ReducerContext, attacker: Identity, loser: Identity) -> bool {
+    if !are_dueling(ctx, attacker, loser) {
+        return false;
+    }
+    if let Some(duel) = open_duel(ctx, loser) {
+        finish(ctx, duel, Some(attacker));
+    }
+    true
+}
+
+pub fn tick_duels(ctx: &ReducerContext, room_name: &str) {
+    let duels: Vec<Duel> = ctx.db.duel().room_name().filter(room_name).collect();
+    for mut duel in duels {
+        match duel.state {
+            DuelState::Finished => {
+                let expired = duel.ended_at
+                    .is_none_or(|at| at + Duration::from_secs(DUEL_RESULT_TTL_SECS) <= ctx.timestamp);
+                if expired {
+                    ctx.db.duel().duel_id().delete(duel.duel_id);
+                }
+                continue;
+            }
+            DuelState::Pending => {
+                if duel.created_at + Duration::from_secs(DUEL_INVITE_TTL_SECS) <= ctx.timestamp {
+                    ctx.db.duel().duel_id().delete(duel.duel_id);
+                }
+                continue;
+            }
+            _ => {}
+        }
+        // A duelist who is gone, dead or elsewhere forfeits
+        let present = |identity: Identity| {
+            ctx.db.player().identity().find(identity).is_some_and(|p| !p.is_dead && p.room_name == duel.room_name)
+        };
+        match (present(duel.challenger), present(duel.target)) {
+            (true, true) => {}
+            (true, false) => {
+                let winner = duel.challenger;
+                finish(ctx, duel, Some(winner));
+                continue;
+            }
+            (false, true) => {
+                let winner = duel.target;
+                finish(ctx, duel, Some(winner));
+                continue;
+            }
+            (false, false) => {
+                finish(ctx, duel, None);
+                continue;
+            }
+        }
+        let starts_at = duel.starts_at.unwrap_or(duel.created_at);
+        if duel.state == DuelState::Countdown && starts_at <= ctx.timestamp {
+            duel.state = DuelState::Active;
+            ctx.db.duel().duel_id().update(duel);
+        } else if duel.state == DuelState::Active
+            && starts_at + Duration::from_secs(DUEL_MAX_DURATION_SECS) <= ctx.timestamp
+        {
+            finish(ctx, duel, None);
+        }
+    }
+}
+
+// --- Duel Reducers ---
+
+#[spacetimedb::reducer]
+pub fn challenge_duel(ctx: &ReducerContext, target: Identity) -> Result<(), String> {
+    if target == ctx.sender {
+        return Err("You can't duel yourself".to_string());
+    }
+    let Some(challenger) = ctx.db.player().identity().find(ctx.sender) else {
+        return Err("Player not found".to_string());
+    };
+    let Some(opponent) = ctx.db.player().identity().find(target) else {
+        return Err("Target player not found".to_string());
+    };
+    if challenger.room_name != opponent.room_name {
+        return Err("You can only duel players in your room".to_string());
+    }
+    if room::find_room(ctx, &challenger.room_name).is_none_or(|r| r.pvp_enabled) {
+        return Err("Duels are only available in non-PvP rooms".to_string());
+    }
+    if challenger.is_dead || opponent.is_dead {
+        return Err("Dead players can't duel".to_string());
+    }
+    if open_duel(ctx, ctx.sender).is_some() || open_duel(ctx, target).is_some() {
+        return Err("One of you already has a duel pending or in progress".to_string());
+    }
+    notification::notify(
+        ctx,
+        target,
+        NotificationKind::DuelChallenge,
+        format!("{} challenged you to a duel", challenger.username),
+    );
+    ctx.db.duel().insert(Duel {
+        duel_id: 0,
+        room_name: challenger.room_name,
+        challenger: ctx.sender,
+        target,
+        state: DuelState::Pending,
+        created_at: ctx.timestamp,
+        starts_at: None,
+        ended_at: None,
+        winner: None,
+    });
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn accept_duel(ctx: &ReducerContext, duel_id: u64) -> Result<(), String> {
+    let Some(mut duel) = ctx.db.duel().duel_id().find(duel_id) else {
+        return Err("Duel not found".to_string());
+    };
+    if duel.target != ctx.sender || duel.state != DuelState::Pending {
+        return Err("No pending challenge to accept".to_string());
+    }
+    duel.state = DuelState::Countdown;
+    duel.starts_at = Some(ctx.timestamp + Duration::from_secs(DUEL_COUNTDOWN_SECS));
+    ctx.db.duel().duel_id().update(duel);
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn decline_duel(ctx: &ReducerContext, duel_id: u64) -> Result<(), String> {
+    let Some(duel) = ctx.db.duel().duel_id().find(duel_id) else {
+        return Err("Duel not found".to_string());
+    };
+    if duel.state != DuelState::Pending || (duel.target != ctx.sender && duel.challenger != ctx.sender) {
+        return Err("No pending challenge to decline".to_string());
+    }
+    ctx.db.duel().duel_id().delete(duel_id);
+    Ok(())
+}
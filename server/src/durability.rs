@@ -0,0 +1,141 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - durability.rs
+ *
+ * Gear wear and repair. Rolled items (item_instance.rs) wear down while
+ * worn and break at zero durability; blacksmiths repair them for gold.
+ * Plain, unrolled items don't wear.
+ *
+ * Key components:
+ * - wear_weapon: A swing that hits something costs the equipped weapon
+ *   WEAPON_WEAR_PER_HIT (combat.rs resolve_melee_attack)
+ * - wear_armor: Every hit a player takes costs each worn armor piece
+ *   ARMOR_WEAR_PER_HIT (combat.rs apply_damage)
+ * - on_player_died: Dying costs every worn item DEATH_WEAR_PERCENT of its
+ *   maximum durability
+ * - An item worn down to zero is broken: it is taken off and can't be
+ *   equipped again until it is repaired (equipment.rs), and its owner is
+ *   notified
+ * - Blacksmith table (public): Turns an NPC into a blacksmith charging
+ *   gold_per_point per point of durability restored
+ * - repair_item: Player reducer. Next to a blacksmith (npc::require_in_range),
+ *   pays for and restores an inventory item to full durability
+ * - set_blacksmith: Room-owner reducer (None stops the NPC repairing)
+ *
+ * Related files:
+ * - item_instance.rs: Where durability is stored
+ * - equipment.rs: Broken items can't be equipped
+ * - wallet.rs: Repairs are paid in gold
+ * - combat.rs: Blacksmiths stop repairing when the NPC dies (clear_npc)
+ */
+
+use spacetimedb::{Identity, ReducerContext, Table};
+
+use crate::common::{ARMOR_WEAR_PER_HIT, DEATH_WEAR_PERCENT, WEAPON_WEAR_PER_HIT};
+use crate::equipment::{self, equipment as _, Equipment};
+use crate::inventory::inventory_item as _;
+use crate::item::{item_def as _, EquipSlot};
+use crate::item_instance::item_instance as _;
+use crate::notification::{self, NotificationKind};
+use crate::npc::{self, npc as _};
+use crate::room;
+use crate::wallet::{self, WalletReason};
+
+#[spacetimedb::table(name = blacksmith, public)]
+#[derive(Clone)]
+pub struct Blacksmith {
+    #[primary_key]
+    pub npc_id: u64,
+    pub gold_per_point: u64,
+}
+
+// Take `amount` durability off a worn item, breaking it at zero
+fn wear(ctx: &ReducerContext, owner: Identity, worn: &Equipment, amount: u32) {
+    let Some(mut instance) = worn.instance_id.and_then(|id| ctx.db.item_instance().instance_id().find(id)) else {
+        return;
+    };
+    if amount == 0 || instance.durability == 0 {
+        return;
+    }
+    instance.durability = instance.durability.saturating_sub(amount);
+    let broken = instance.durability == 0;
+    ctx.db.item_instance().instance_id().update(instance);
+    if broken {
+        ctx.db.equipment().equipment_id().delete(worn.equipment_id);
+        let name = ctx.db.item_def().item_def_id().find(worn.item_def_id).map_or("An item".to_string(), |d| d.name);
+        notification::notify(ctx, owner, NotificationKind::ItemBroken, format!("{} broke and needs repairs", name));
+    }
+}
+
+pub fn wear_weapon(ctx: &ReducerContext, identity: Identity) {
+    if let Some(weapon) = equipment::equipped(ctx, identity, EquipSlot::Weapon) {
+        wear(ctx, identity, &weapon, WEAPON_WEAR_PER_HIT);
+    }
+}
+
+pub fn wear_armor(ctx: &ReducerContext, identity: Identity) {
+    let armor: Vec<Equipment> = ctx.db.equipment().owner().filter(identity)
+        .filter(|e| e.slot != EquipSlot::Weapon)
+        .collect();
+    for piece in armor {
+        wear(ctx, identity, &piece, ARMOR_WEAR_PER_HIT);
+    }
+}
+
+pub fn on_player_died(ctx: &ReducerContext, identity: Identity) {
+    let worn: Vec<Equipment> = ctx.db.equipment().owner().filter(identity).collect();
+    for item in worn {
+        let max_durability = item.instance_id
+            .and_then(|id| ctx.db.item_instance().instance_id().find(id))
+            .map_or(0, |i| i.max_durability);
+        wear(ctx, identity, &item, (max_durability * DEATH_WEAR_PERCENT).div_ceil(100));
+    }
+}
+
+pub fn is_broken(ctx: &ReducerContext, instance_id: Option<u64>) -> bool {
+    instance_id
+        .and_then(|id| ctx.db.item_instance().instance_id().find(id))
+        .is_some_and(|i| i.durability == 0)
+}
+
+pub fn clear_npc(ctx: &ReducerContext, npc_id: u64) {
+    ctx.db.blacksmith().npc_id().delete(npc_id);
+}
+
+// --- Durability Reducers ---
+
+// Repair an inventory item (by its inventory item_id) at a blacksmith
+#[spacetimedb::reducer]
+pub fn repair_item(ctx: &ReducerContext, npc_id: u64, item_id: u64) -> Result<(), String> {
+    let Some(blacksmith) = ctx.db.blacksmith().npc_id().find(npc_id) else {
+        return Err("That NPC doesn't repair items".to_string());
+    };
+    npc::require_in_range(ctx, npc_id)?;
+    let Some(item) = ctx.db.inventory_item().item_id().find(item_id).filter(|i| i.owner == ctx.sender) else {
+        return Err("Item not found".to_string());
+    };
+    let Some(mut instance) = item.instance_id.and_then(|id| ctx.db.item_instance().instance_id().find(id)) else {
+        return Err("That item doesn't wear".to_string());
+    };
+    let missing = instance.max_durability.saturating_sub(instance.durability);
+    if missing == 0 {
+        return Err("That item doesn't need repairs".to_string());
+    }
+    let cost = blacksmith.gold_per_point.saturating_mul(missing as u64);
+    wallet::spend(ctx, ctx.sender, cost, WalletReason::Repair)?;
+    instance.durability = instance.max_durability;
+    ctx.db.item_instance().instance_id().update(instance);
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn set_blacksmith(ctx: &ReducerContext, npc_id: u64, gold_per_point: Option<u64>) -> Result<(), String> {
+    let Some(smith) = ctx.db.npc().npc_id().find(npc_id) else {
+        return Err("NPC not found".to_string());
+    };
+    room::require_room_owner(ctx, &smith.room_name)?;
+    ctx.db.blacksmith().npc_id().delete(npc_id);
+    if let Some(gold_per_point) = gold_per_point {
+        ctx.db.blacksmith().insert(Blacksmith { npc_id, gold_per_point });
+    }
+    Ok(())
+}
@@ -0,0 +1,167 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - equipment.rs
+ *
+ * What each player wears and wields, one item per EquipSlot.
+ *
+ * Key components:
+ * - Equipment table (public): One row per filled slot. Equipped items stay
+ *   in the inventory; the row names the item definition (and the rolled
+ *   instance, if any, whose affixes apply) and copies its cosmetic_id so
+ *   every client can draw other players' gear. An empty weapon slot fights
+ *   with the UNARMED_WEAPON definition (item.rs). Rows granted by a room's
+ *   loadout (from_loadout) have no inventory item behind them and are taken
+ *   back when the player leaves the room or disconnects
+ * - equip: Checks the item's slot, class restriction and required level
+ *   (progression.rs), refuses broken items (durability.rs) and fills the
+ *   slot
+ * - set_slot: Fills or empties a slot without checks (room loadouts)
+ * - clear_loadout_items: Empties the slots a room's loadout filled
+ * - unequip_if_gone: Empties slots holding an item the player no longer
+ *   carries (called when items leave the inventory)
+ * - equip_item / unequip_item: Player reducers. The weapon slot can't
+ *   change while the room's loadout locks weapons (loadout.rs)
+ *
+ * Related files:
+ * - item.rs: Item definitions, equip_slot and requirements
+ * - inventory.rs: use_item equips weapons and armor too
+ */
+
+use spacetimedb::{Identity, ReducerContext, Table};
+
+use crate::durability;
+use crate::inventory::{self, inventory_item as _};
+use crate::item::{item_def as _, EquipSlot, ItemDef};
+use crate::loadout;
+use crate::player as _;
+use crate::progression;
+use crate::PlayerData;
+
+#[spacetimedb::table(name = equipment, public)]
+#[derive(Clone)]
+pub struct Equipment {
+    #[primary_key]
+    #[auto_inc]
+    pub equipment_id: u64,
+    #[index(btree)]
+    pub owner: Identity,
+    pub slot: EquipSlot,
+    pub item_def_id: u64,
+    pub instance_id: Option<u64>, // Rolled items (item_instance.rs)
+    pub cosmetic_id: String,
+    pub from_loadout: bool, // Granted by the room's loadout (loadout.rs), not carried
+}
+
+pub fn equipped(ctx: &ReducerContext, owner: Identity, slot: EquipSlot) -> Option<Equipment> {
+    ctx.db.equipment().owner().filter(owner).find(|e| e.slot == slot)
+}
+
+pub fn set_slot(
+    ctx: &ReducerContext,
+    owner: Identity,
+    slot: EquipSlot,
+    def: Option<&ItemDef>,
+    instance_id: Option<u64>,
+    from_loadout: bool,
+) {
+    let existing = equipped(ctx, owner, slot);
+    match (existing, def) {
+        (Some(mut row), Some(def)) => {
+            row.item_def_id = def.item_def_id;
+            row.instance_id = instance_id;
+            row.cosmetic_id = def.cosmetic_id.clone();
+            row.from_loadout = from_loadout;
+            ctx.db.equipment().equipment_id().update(row);
+        }
+        (None, Some(def)) => {
+            ctx.db.equipment().insert(Equipment {
+                equipment_id: 0,
+                owner,
+                slot,
+                item_def_id: def.item_def_id,
+                instance_id,
+                cosmetic_id: def.cosmetic_id.clone(),
+                from_loadout,
+            });
+        }
+        (Some(row), None) => {
+            ctx.db.equipment().equipment_id().delete(row.equipment_id);
+        }
+        (None, None) => {}
+    }
+}
+
+pub fn clear_loadout_items(ctx: &ReducerContext, owner: Identity) {
+    let granted: Vec<u64> = ctx.db.equipment().owner().filter(owner)
+        .filter(|e| e.from_loadout)
+        .map(|e| e.equipment_id)
+        .collect();
+    for equipment_id in granted {
+        ctx.db.equipment().equipment_id().delete(equipment_id);
+    }
+}
+
+fn require_slot_unlocked(ctx: &ReducerContext, player: &PlayerData, slot: EquipSlot) -> Result<(), String> {
+    if slot == EquipSlot::Weapon && loadout::weapon_locked(ctx, &player.room_name) {
+        return Err("This room's loadout doesn't allow changing weapons".to_string());
+    }
+    Ok(())
+}
+
+// Equip an item the player carries, if they meet its requirements
+pub fn equip(ctx: &ReducerContext, player: &PlayerData, def: &ItemDef, instance_id: Option<u64>) -> Result<(), String> {
+    let Some(slot) = def.equip_slot else {
+        return Err(format!("{} can't be equipped", def.name));
+    };
+    require_slot_unlocked(ctx, player, slot)?;
+    if let Some(class) = def.required_class.as_ref().filter(|class| **class != player.character_class) {
+        return Err(format!("Only a {} can equip {}", class, def.name));
+    }
+    if progression::level_of(ctx, player.identity) < def.required_level {
+        return Err(format!("{} requires level {}", def.name, def.required_level));
+    }
+    if durability::is_broken(ctx, instance_id) {
+        return Err(format!("{} is broken and needs repairs", def.name));
+    }
+    set_slot(ctx, player.identity, slot, Some(def), instance_id, false);
+    Ok(())
+}
+
+pub fn unequip_if_gone(ctx: &ReducerContext, player: &PlayerData, item_def_id: u64) {
+    let stale: Vec<Equipment> = ctx.db.equipment().owner().filter(player.identity)
+        .filter(|e| e.item_def_id == item_def_id && !e.from_loadout)
+        .filter(|e| !inventory::holds(ctx, player.identity, item_def_id, e.instance_id))
+        .collect();
+    for row in stale {
+        // A locked loadout weapon isn't the player's to lose
+        if require_slot_unlocked(ctx, player, row.slot).is_ok() {
+            ctx.db.equipment().equipment_id().delete(row.equipment_id);
+        }
+    }
+}
+
+// --- Equipment Reducers ---
+
+// Equip an inventory item (by its inventory item_id) into its slot
+#[spacetimedb::reducer]
+pub fn equip_item(ctx: &ReducerContext, item_id: u64) -> Result<(), String> {
+    let Some(player) = ctx.db.player().identity().find(ctx.sender) else {
+        return Err("Player not found".to_string());
+    };
+    let Some(item) = ctx.db.inventory_item().item_id().find(item_id).filter(|i| i.owner == ctx.sender) else {
+        return Err("Item not found".to_string());
+    };
+    let Some(def) = ctx.db.item_def().item_def_id().find(item.item_def_id) else {
+        return Err("Item not found".to_string());
+    };
+    equip(ctx, &player, &def, item.instance_id)
+}
+
+#[spacetimedb::reducer]
+pub fn unequip_item(ctx: &ReducerContext, slot: EquipSlot) -> Result<(), String> {
+    let Some(player) = ctx.db.player().identity().find(ctx.sender) else {
+        return Err("Player not found".to_string());
+    };
+    require_slot_unlocked(ctx, &player, slot)?;
+    set_slot(ctx, ctx.sender, slot, None, None, false);
+    Ok(())
+}
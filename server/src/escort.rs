@@ -0,0 +1,182 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - escort.rs
+ *
+ * Escort objectives: an NPC that has to be kept alive while it follows a
+ * player to a destination or walks a patrol route.
+ *
+ * Key components:
+ * - EscortGoal: FollowTo (trail the escorting player until the NPC reaches
+ *   a position) or Route (walk a patrol.rs route once, start to end)
+ * - EscortState table (public): One row per escorted NPC with its goal,
+ *   progress and EscortStatus. Finished rows stay for whatever started the
+ *   escort to read, until the NPC is escorted again
+ * - tick_escorts: Called from game_tick. Moves active escorts at
+ *   ESCORT_MOVE_SPEED; npc_ai.rs and critter.rs leave escorted NPCs alone
+ * - The escort fails when the NPC dies (on_npc_killed, from combat.rs) or
+ *   the escorting player leaves the room; it completes on arrival. Either
+ *   way the escorting player is notified
+ * - start_escort / cancel_escort: Room-owner reducers
+ *
+ * Related files:
+ * - patrol.rs: Routes for EscortGoal::Route
+ * - combat.rs: NPC deaths fail the escort
+ * - notification.rs: EscortFinished
+ */
+
+use spacetimedb::{Identity, ReducerContext, SpacetimeType, Table, Timestamp};
+
+use crate::common::{Vector3, ESCORT_ARRIVE_RADIUS, ESCORT_FOLLOW_DISTANCE, ESCORT_MOVE_SPEED};
+use crate::notification::{self, NotificationKind};
+use crate::npc::{npc as _, NpcState};
+use crate::npc_ai::{move_towards, yaw_towards};
+use crate::patrol::{self, patrol_route as _};
+use crate::player as _;
+use crate::room;
+use crate::spatial::{self, SpatialEntity};
+
+#[derive(SpacetimeType, Clone, Debug, PartialEq)]
+pub enum EscortGoal {
+    FollowTo(Vector3),
+    Route(u64), // patrol route id
+}
+
+#[derive(SpacetimeType, Clone, Copy, Debug, PartialEq)]
+pub enum EscortStatus {
+    Active,
+    Completed,
+    Failed,
+}
+
+#[spacetimedb::table(name = escort_state, public)]
+#[derive(Clone)]
+pub struct EscortState {
+    #[primary_key]
+    pub npc_id: u64,
+    #[index(btree)]
+    pub room_name: String,
+    pub escorted_by: Identity,
+    pub goal: EscortGoal,
+    pub waypoint_index: u32, // Route progress
+    pub status: EscortStatus,
+    pub started_at: Timestamp,
+    pub finished_at: Option<Timestamp>,
+}
+
+// Whether the NPC is being escorted right now (the AI doesn't move it then)
+pub fn is_escorted(ctx: &ReducerContext, npc_id: u64) -> bool {
+    ctx.db.escort_state().npc_id().find(npc_id).is_some_and(|e| e.status == EscortStatus::Active)
+}
+
+fn finish(ctx: &ReducerContext, mut escort: EscortState, status: EscortStatus, message: &str) {
+    escort.status = status;
+    escort.finished_at = Some(ctx.timestamp);
+    notification::notify(ctx, escort.escorted_by, NotificationKind::EscortFinished, message.to_string());
+    ctx.db.escort_state().npc_id().update(escort);
+}
+
+pub fn on_npc_killed(ctx: &ReducerContext, npc_id: u64) {
+    if let Some(escort) = ctx.db.escort_state().npc_id().find(npc_id) {
+        if escort.status == EscortStatus::Active {
+            finish(ctx, escort, EscortStatus::Failed, "Escort failed: the NPC died");
+        }
+    }
+}
+
+pub fn tick_escorts(ctx: &ReducerContext, room_name: &str, delta_time: f64) {
+    let active: Vec<EscortState> = ctx.db.escort_state().room_name().filter(room_name)
+        .filter(|e| e.status == EscortStatus::Active)
+        .collect();
+    let max_step = ESCORT_MOVE_SPEED * delta_time as f32;
+    for mut escort in active {
+        let Some(mut npc) = ctx.db.npc().npc_id().find(escort.npc_id) else {
+            finish(ctx, escort, EscortStatus::Failed, "Escort failed: the NPC is gone");
+            continue;
+        };
+        let Some(leader) = ctx.db.player().identity().find(escort.escorted_by).filter(|p| p.room_name == room_name) else {
+            finish(ctx, escort, EscortStatus::Failed, "Escort failed: you left the escort behind");
+            continue;
+        };
+        let mut arrived = false;
+        match escort.goal.clone() {
+            EscortGoal::FollowTo(destination) => {
+                if npc.position.distance_xz(&leader.position) > ESCORT_FOLLOW_DISTANCE {
+                    npc.state = NpcState::Patrol;
+                    npc.rotation.y = yaw_towards(&npc.position, &leader.position);
+                    npc.position = move_towards(&npc.position, &leader.position, max_step);
+                } else {
+                    npc.state = NpcState::Idle;
+                }
+                arrived = npc.position.distance_xz(&destination) <= ESCORT_ARRIVE_RADIUS;
+            }
+            EscortGoal::Route(route_id) => {
+                let waypoints = patrol::route_waypoints(ctx, route_id);
+                match waypoints.get(escort.waypoint_index as usize) {
+                    Some(waypoint) => {
+                        npc.state = NpcState::Patrol;
+                        npc.rotation.y = yaw_towards(&npc.position, &waypoint.position);
+                        npc.position = move_towards(&npc.position, &waypoint.position, max_step);
+                        if npc.position.distance_xz(&waypoint.position) < 0.5 {
+                            escort.waypoint_index += 1;
+                            arrived = escort.waypoint_index as usize >= waypoints.len();
+                        }
+                    }
+                    None => arrived = true,
+                }
+            }
+        }
+        if arrived {
+            npc.state = NpcState::Idle;
+        }
+        spatial::track(ctx, SpatialEntity::Npc(npc.npc_id), &npc.room_name, &npc.position);
+        ctx.db.npc().npc_id().update(npc);
+        if arrived {
+            finish(ctx, escort, EscortStatus::Completed, "Escort complete");
+        } else {
+            ctx.db.escort_state().npc_id().update(escort);
+        }
+    }
+}
+
+// --- Escort Reducers ---
+
+// Room owner: have `escorted_by` escort the NPC to its goal
+#[spacetimedb::reducer]
+pub fn start_escort(ctx: &ReducerContext, npc_id: u64, escorted_by: Identity, goal: EscortGoal) -> Result<(), String> {
+    let Some(npc) = ctx.db.npc().npc_id().find(npc_id) else {
+        return Err("NPC not found".to_string());
+    };
+    room::require_room_owner(ctx, &npc.room_name)?;
+    if !ctx.db.player().identity().find(escorted_by).is_some_and(|p| p.room_name == npc.room_name) {
+        return Err("The escorting player must be in the NPC's room".to_string());
+    }
+    if let EscortGoal::Route(route_id) = goal {
+        if ctx.db.patrol_route().route_id().find(route_id).is_none() {
+            return Err("Patrol route not found".to_string());
+        }
+    }
+    if is_escorted(ctx, npc_id) {
+        return Err("That NPC is already being escorted".to_string());
+    }
+    ctx.db.escort_state().npc_id().delete(npc_id);
+    ctx.db.escort_state().insert(EscortState {
+        npc_id,
+        room_name: npc.room_name,
+        escorted_by,
+        goal,
+        waypoint_index: 0,
+        status: EscortStatus::Active,
+        started_at: ctx.timestamp,
+        finished_at: None,
+    });
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn cancel_escort(ctx: &ReducerContext, npc_id: u64) -> Result<(), String> {
+    let Some(escort) = ctx.db.escort_state().npc_id().find(npc_id) else {
+        return Err("That NPC isn't being escorted".to_string());
+    };
+    room::require_room_owner(ctx, &escort.room_name)?;
+    ctx.db.escort_state().npc_id().delete(npc_id);
+    Ok(())
+}
@@ -0,0 +1,149 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - experiment.rs
+ *
+ * A/B experiment flags. Players are bucketed into weighted variants by a
+ * stable hash of their identity and the experiment name, so a player always
+ * sees the same variant and different experiments split cohorts independently.
+ *
+ * Key components:
+ * - Experiment table (private): Name, weighted variants and whether it's
+ *   running. Each variant carries a numeric value for gameplay code to use
+ *   (a multiplier, a layout index, ...)
+ * - ExperimentExposure table (private): First time each player was exposed to
+ *   each experiment and the variant they got, for analysis
+ * - variant_for: Reads a player's variant inside a reducer and logs exposure
+ * - tuning: variant_for's value, or a default when the experiment is off
+ * - upsert_experiment / set_experiment_active: Admin reducers
+ *
+ * Experiments in use:
+ * - STAMINA_REGEN_EXPERIMENT: Multiplier on stamina regeneration
+ *   (player_logic.rs)
+ *
+ * Related files:
+ * - admin.rs: require_admin
+ */
+
+use spacetimedb::{Identity, ReducerContext, SpacetimeType, Table, Timestamp};
+
+use crate::admin;
+
+pub const STAMINA_REGEN_EXPERIMENT: &str = "stamina_regen";
+
+#[derive(SpacetimeType, Clone, Debug)]
+pub struct ExperimentVariant {
+    pub name: String,
+    pub weight: u32,
+    pub value: f32,
+}
+
+#[spacetimedb::table(name = experiment)]
+#[derive(Clone)]
+pub struct Experiment {
+    #[primary_key]
+    pub name: String,
+    pub variants: Vec<ExperimentVariant>,
+    pub is_active: bool,
+    pub created_at: Timestamp,
+}
+
+#[spacetimedb::table(name = experiment_exposure)]
+#[derive(Clone)]
+pub struct ExperimentExposure {
+    #[primary_key]
+    #[auto_inc]
+    pub exposure_id: u64,
+    #[index(btree)]
+    pub identity: Identity,
+    pub experiment_name: String,
+    pub variant: String,
+    pub exposed_at: Timestamp,
+}
+
+// FNV-1a over the identity and experiment name; stable across module
+// versions, unlike std's hasher
+fn bucket_hash(identity: Identity, experiment_name: &str) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in identity.to_byte_array().iter().chain(experiment_name.as_bytes()) {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+fn pick_variant(experiment: &Experiment, identity: Identity) -> Option<&ExperimentVariant> {
+    let total: u64 = experiment.variants.iter().map(|v| v.weight as u64).sum();
+    if total == 0 {
+        return None;
+    }
+    let mut roll = bucket_hash(identity, &experiment.name) % total;
+    for variant in &experiment.variants {
+        if roll < variant.weight as u64 {
+            return Some(variant);
+        }
+        roll -= variant.weight as u64;
+    }
+    None
+}
+
+// The variant `identity` is bucketed into, or None if the experiment doesn't
+// exist or isn't running. Logs the player's first exposure.
+pub fn variant_for(ctx: &ReducerContext, experiment_name: &str, identity: Identity) -> Option<ExperimentVariant> {
+    let experiment = ctx.db.experiment().name().find(experiment_name.to_string())?;
+    if !experiment.is_active {
+        return None;
+    }
+    let variant = pick_variant(&experiment, identity)?.clone();
+    let seen = ctx.db.experiment_exposure().identity().filter(identity)
+        .any(|e| e.experiment_name == experiment_name);
+    if !seen {
+        ctx.db.experiment_exposure().insert(ExperimentExposure {
+            exposure_id: 0,
+            identity,
+            experiment_name: experiment_name.to_string(),
+            variant: variant.name.clone(),
+            exposed_at: ctx.timestamp,
+        });
+    }
+    Some(variant)
+}
+
+// The numeric value of a player's variant, or `default` outside the experiment
+pub fn tuning(ctx: &ReducerContext, experiment_name: &str, identity: Identity, default: f32) -> f32 {
+    variant_for(ctx, experiment_name, identity).map_or(default, |v| v.value)
+}
+
+// --- Experiment Reducers ---
+
+#[spacetimedb::reducer]
+pub fn upsert_experiment(ctx: &ReducerContext, name: String, variants: Vec<ExperimentVariant>) -> Result<(), String> {
+    admin::require_admin(ctx)?;
+    if name.is_empty() {
+        return Err("Experiment name must not be empty".to_string());
+    }
+    if variants.iter().all(|v| v.weight == 0) {
+        return Err("Experiment needs at least one variant with a weight".to_string());
+    }
+    match ctx.db.experiment().name().find(name.clone()) {
+        Some(mut experiment) => {
+            // Changing weights re-buckets players; exposures keep what they saw
+            experiment.variants = variants;
+            ctx.db.experiment().name().update(experiment);
+        }
+        None => {
+            ctx.db.experiment().insert(Experiment { name, variants, is_active: false, created_at: ctx.timestamp });
+        }
+    }
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn set_experiment_active(ctx: &ReducerContext, name: String, is_active: bool) -> Result<(), String> {
+    admin::require_admin(ctx)?;
+    let Some(mut experiment) = ctx.db.experiment().name().find(name) else {
+        return Err("Experiment not found".to_string());
+    };
+    spacetimedb::log::info!("Experiment {} is now {}", experiment.name, if is_active { "active" } else { "inactive" });
+    experiment.is_active = is_active;
+    ctx.db.experiment().name().update(experiment);
+    Ok(())
+}
@@ -0,0 +1,178 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - faction.rs
+ *
+ * Data-driven aggression: who fights whom is decided by factions and a
+ * hostility matrix instead of hardcoded NPC kinds.
+ *
+ * Key components:
+ * - Faction table (public): Named factions. Players all belong to
+ *   FACTION_PLAYERS; every NPC has a `faction` (defaulted from its kind)
+ * - FactionStance table (public): The hostility matrix. One row per
+ *   unordered pair of factions; pairs without a row are Neutral and a
+ *   faction is always Friendly to itself
+ * - seed_factions: players / monsters / guards / wildlife / neutral in init.
+ *   Monsters attack players and guards, guards attack monsters, wildlife
+ *   and training dummies are neutral
+ * - FactionMatrix: The matrix loaded once per AI tick (npc_ai.rs)
+ * - upsert_faction / set_faction_stance (admin) / set_npc_faction (room
+ *   owner): Authoring
+ *
+ * Any NPC still fights back against players that damage it (threat), even
+ * if its faction is not hostile to players.
+ *
+ * Related files:
+ * - npc.rs: Npc.faction
+ * - npc_ai.rs: Target selection consults the matrix
+ */
+
+use spacetimedb::{ReducerContext, SpacetimeType, Table};
+use std::collections::HashMap;
+
+use crate::admin;
+use crate::npc::{npc as _, NpcKind};
+use crate::room;
+
+pub const FACTION_PLAYERS: &str = "players";
+pub const FACTION_MONSTERS: &str = "monsters";
+pub const FACTION_GUARDS: &str = "guards";
+pub const FACTION_WILDLIFE: &str = "wildlife";
+pub const FACTION_NEUTRAL: &str = "neutral";
+
+#[derive(SpacetimeType, Clone, Copy, Debug, PartialEq)]
+pub enum Stance {
+    Friendly,
+    Neutral,
+    Hostile,
+}
+
+#[spacetimedb::table(name = faction, public)]
+#[derive(Clone)]
+pub struct Faction {
+    #[primary_key]
+    pub name: String,
+}
+
+#[spacetimedb::table(name = faction_stance, public)]
+#[derive(Clone)]
+pub struct FactionStance {
+    #[primary_key]
+    #[auto_inc]
+    pub stance_id: u64,
+    #[index(btree)]
+    pub faction_a: String, // faction_a <= faction_b
+    pub faction_b: String,
+    pub stance: Stance,
+}
+
+// Order a pair the way FactionStance stores it
+fn ordered(a: &str, b: &str) -> (String, String) {
+    if a <= b {
+        (a.to_string(), b.to_string())
+    } else {
+        (b.to_string(), a.to_string())
+    }
+}
+
+pub fn default_faction(kind: NpcKind) -> &'static str {
+    match kind {
+        NpcKind::TrainingDummy => FACTION_NEUTRAL,
+        NpcKind::Monster => FACTION_MONSTERS,
+        NpcKind::Critter => FACTION_WILDLIFE,
+    }
+}
+
+pub fn faction_exists(ctx: &ReducerContext, name: &str) -> bool {
+    ctx.db.faction().name().find(name.to_string()).is_some()
+}
+
+fn set_stance(ctx: &ReducerContext, a: &str, b: &str, stance: Stance) {
+    let (faction_a, faction_b) = ordered(a, b);
+    let existing = ctx.db.faction_stance().faction_a().filter(&faction_a).find(|s| s.faction_b == faction_b);
+    match existing {
+        Some(mut row) => {
+            row.stance = stance;
+            ctx.db.faction_stance().stance_id().update(row);
+        }
+        None => {
+            ctx.db.faction_stance().insert(FactionStance { stance_id: 0, faction_a, faction_b, stance });
+        }
+    }
+}
+
+pub fn seed_factions(ctx: &ReducerContext) {
+    if ctx.db.faction().count() > 0 {
+        return;
+    }
+    for name in [FACTION_PLAYERS, FACTION_MONSTERS, FACTION_GUARDS, FACTION_WILDLIFE, FACTION_NEUTRAL] {
+        ctx.db.faction().insert(Faction { name: name.to_string() });
+    }
+    set_stance(ctx, FACTION_MONSTERS, FACTION_PLAYERS, Stance::Hostile);
+    set_stance(ctx, FACTION_MONSTERS, FACTION_GUARDS, Stance::Hostile);
+    set_stance(ctx, FACTION_GUARDS, FACTION_PLAYERS, Stance::Friendly);
+}
+
+// The hostility matrix, read once and queried many times
+pub struct FactionMatrix {
+    stances: HashMap<(String, String), Stance>,
+}
+
+impl FactionMatrix {
+    pub fn load(ctx: &ReducerContext) -> Self {
+        let stances = ctx.db.faction_stance().iter()
+            .map(|s| ((s.faction_a, s.faction_b), s.stance))
+            .collect();
+        FactionMatrix { stances }
+    }
+
+    pub fn stance(&self, a: &str, b: &str) -> Stance {
+        if a == b {
+            return Stance::Friendly;
+        }
+        self.stances.get(&ordered(a, b)).copied().unwrap_or(Stance::Neutral)
+    }
+
+    pub fn is_hostile(&self, a: &str, b: &str) -> bool {
+        self.stance(a, b) == Stance::Hostile
+    }
+}
+
+// --- Faction Reducers ---
+
+#[spacetimedb::reducer]
+pub fn upsert_faction(ctx: &ReducerContext, name: String) -> Result<(), String> {
+    admin::require_admin(ctx)?;
+    if name.is_empty() {
+        return Err("Faction name must not be empty".to_string());
+    }
+    if !faction_exists(ctx, &name) {
+        ctx.db.faction().insert(Faction { name });
+    }
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn set_faction_stance(ctx: &ReducerContext, faction_a: String, faction_b: String, stance: Stance) -> Result<(), String> {
+    admin::require_admin(ctx)?;
+    if !faction_exists(ctx, &faction_a) || !faction_exists(ctx, &faction_b) {
+        return Err("Faction not found".to_string());
+    }
+    if faction_a == faction_b {
+        return Err("A faction is always friendly to itself".to_string());
+    }
+    set_stance(ctx, &faction_a, &faction_b, stance);
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn set_npc_faction(ctx: &ReducerContext, npc_id: u64, faction: String) -> Result<(), String> {
+    let Some(mut npc) = ctx.db.npc().npc_id().find(npc_id) else {
+        return Err("NPC not found".to_string());
+    };
+    room::require_room_owner(ctx, &npc.room_name)?;
+    if !faction_exists(ctx, &faction) {
+        return Err(format!("Unknown faction '{}'", faction));
+    }
+    npc.faction = faction;
+    ctx.db.npc().npc_id().update(npc);
+    Ok(())
+}
@@ -0,0 +1,248 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - gathering.rs
+ *
+ * Gathering professions: ore veins and herbs placed in a room that players
+ * harvest for materials, getting better at it the more they gather.
+ *
+ * Key components:
+ * - Profession: Mining and Herbalism
+ * - ProfessionSkill table (public): A player's skill in one profession.
+ *   Players without a row have skill 1
+ * - ResourceNode table (public): A vein or herb at a fixed position. Only
+ *   players with at least `required_skill` in its profession can gather it.
+ *   Like world pickups (pickup.rs), a gathered node is hidden until it
+ *   respawns
+ * - gather_resource: Player reducer. Within INTERACT_RANGE of an available
+ *   node, grants base_yield of its item plus one more for every
+ *   GATHER_BONUS_YIELD_STEP skill above the requirement (at most
+ *   MAX_GATHER_BONUS_YIELD). Every GATHERS_PER_SKILL_POINT successful
+ *   gathers raise the skill by one, up to MAX_PROFESSION_SKILL; nodes
+ *   GATHER_TRIVIAL_MARGIN or more below a player's skill teach nothing
+ * - ResourceNodeRespawnSchedule table: One-shot schedule rows driving
+ *   respawn_resource_node
+ * - place_resource_node / remove_resource_node: Room-owner reducers. A node
+ *   only yields a material gathered by its profession (ItemDef.gathered_by),
+ *   at most MAX_RESOURCE_BASE_YIELD before skill bonuses, and respawns
+ *   after MIN_RESOURCE_RESPAWN_SECS-MAX_RESOURCE_RESPAWN_SECS
+ *
+ * Related files:
+ * - item.rs: The materials nodes yield (seeded ore and herbs, tagged with
+ *   gathered_by)
+ * - pickup.rs: The same place-and-respawn pattern for plain collectibles
+ * - room.rs: close_room clears a room's nodes
+ */
+
+use spacetimedb::{Identity, ReducerContext, ScheduleAt, SpacetimeType, Table};
+use std::time::Duration;
+
+use crate::common::{
+    Vector3, GATHERS_PER_SKILL_POINT, GATHER_BONUS_YIELD_STEP, GATHER_TRIVIAL_MARGIN, INTERACT_RANGE,
+    MAX_GATHER_BONUS_YIELD, MAX_PROFESSION_SKILL, MAX_RESOURCE_BASE_YIELD, MAX_RESOURCE_RESPAWN_SECS,
+    MIN_RESOURCE_RESPAWN_SECS,
+};
+use crate::item::{self, item_def as _};
+use crate::player as _;
+use crate::room;
+
+#[derive(SpacetimeType, Clone, Copy, Debug, PartialEq)]
+pub enum Profession {
+    Mining,
+    Herbalism,
+}
+
+// What a node yields and who can gather it (place_resource_node)
+#[derive(SpacetimeType, Clone, Debug, PartialEq)]
+pub struct ResourceNodeSpec {
+    pub profession: Profession,
+    pub required_skill: u32,
+    pub item_def_id: u64,
+    pub base_yield: u32, // 1..=MAX_RESOURCE_BASE_YIELD
+    pub respawn_secs: u64,
+}
+
+#[spacetimedb::table(name = profession_skill, public)]
+#[derive(Clone)]
+pub struct ProfessionSkill {
+    #[primary_key]
+    #[auto_inc]
+    pub skill_id: u64,
+    #[index(btree)]
+    pub identity: Identity,
+    pub profession: Profession,
+    pub skill: u32,
+    pub progress: u32, // Gathers towards the next skill point
+}
+
+#[spacetimedb::table(name = resource_node, public)]
+#[derive(Clone)]
+pub struct ResourceNode {
+    #[primary_key]
+    #[auto_inc]
+    pub node_id: u64,
+    #[index(btree)]
+    pub room_name: String,
+    pub profession: Profession,
+    pub required_skill: u32,
+    pub item_def_id: u64,
+    pub base_yield: u32,
+    pub position: Vector3,
+    pub respawn_secs: u64,
+    pub available: bool,
+}
+
+#[spacetimedb::table(name = resource_node_respawn_schedule, scheduled(respawn_resource_node))]
+pub struct ResourceNodeRespawnSchedule {
+    #[primary_key]
+    #[auto_inc]
+    pub scheduled_id: u64,
+    pub scheduled_at: ScheduleAt,
+    #[index(btree)]
+    pub node_id: u64,
+}
+
+fn skill_row(ctx: &ReducerContext, identity: Identity, profession: Profession) -> Option<ProfessionSkill> {
+    ctx.db.profession_skill().identity().filter(identity).find(|s| s.profession == profession)
+}
+
+pub fn skill_of(ctx: &ReducerContext, identity: Identity, profession: Profession) -> u32 {
+    skill_row(ctx, identity, profession).map_or(1, |s| s.skill)
+}
+
+// Count a successful gather of a node needing `required_skill` towards the
+// player's next skill point
+fn train(ctx: &ReducerContext, identity: Identity, profession: Profession, required_skill: u32) {
+    let mut row = skill_row(ctx, identity, profession).unwrap_or(ProfessionSkill {
+        skill_id: 0,
+        identity,
+        profession,
+        skill: 1,
+        progress: 0,
+    });
+    if row.skill >= MAX_PROFESSION_SKILL || row.skill >= required_skill + GATHER_TRIVIAL_MARGIN {
+        return;
+    }
+    row.progress += 1;
+    if row.progress >= GATHERS_PER_SKILL_POINT {
+        row.progress = 0;
+        row.skill += 1;
+    }
+    if row.skill_id == 0 {
+        ctx.db.profession_skill().insert(row);
+    } else {
+        ctx.db.profession_skill().skill_id().update(row);
+    }
+}
+
+fn delete_node(ctx: &ReducerContext, node_id: u64) {
+    let schedules: Vec<u64> = ctx.db.resource_node_respawn_schedule().node_id().filter(node_id)
+        .map(|s| s.scheduled_id)
+        .collect();
+    for scheduled_id in schedules {
+        ctx.db.resource_node_respawn_schedule().scheduled_id().delete(scheduled_id);
+    }
+    ctx.db.resource_node().node_id().delete(node_id);
+}
+
+pub fn clear_room(ctx: &ReducerContext, room_name: &str) {
+    let nodes: Vec<u64> = ctx.db.resource_node().room_name().filter(room_name).map(|n| n.node_id).collect();
+    for node_id in nodes {
+        delete_node(ctx, node_id);
+    }
+}
+
+// --- Gathering Reducers ---
+
+#[spacetimedb::reducer]
+pub fn gather_resource(ctx: &ReducerContext, node_id: u64) -> Result<(), String> {
+    let Some(player) = ctx.db.player().identity().find(ctx.sender) else {
+        return Err("Player not found".to_string());
+    };
+    let Some(mut node) = ctx.db.resource_node().node_id().find(node_id) else {
+        return Err("Resource not found".to_string());
+    };
+    if player.is_dead {
+        return Err("Dead players can't gather".to_string());
+    }
+    if !node.available {
+        return Err("Someone already gathered that".to_string());
+    }
+    if player.room_name != node.room_name || player.position.distance_xz(&node.position) > INTERACT_RANGE {
+        return Err("Too far away".to_string());
+    }
+    let skill = skill_of(ctx, player.identity, node.profession);
+    if skill < node.required_skill {
+        return Err(format!("Requires {:?} {}", node.profession, node.required_skill));
+    }
+    let Some(def) = ctx.db.item_def().item_def_id().find(node.item_def_id) else {
+        return Err("Item not found".to_string());
+    };
+    let bonus = ((skill - node.required_skill) / GATHER_BONUS_YIELD_STEP).min(MAX_GATHER_BONUS_YIELD);
+    item::grant_item(ctx, &player, &def, node.base_yield.saturating_add(bonus), None)?;
+    train(ctx, player.identity, node.profession, node.required_skill);
+
+    ctx.db.resource_node_respawn_schedule().insert(ResourceNodeRespawnSchedule {
+        scheduled_id: 0,
+        scheduled_at: ScheduleAt::Time(ctx.timestamp + Duration::from_secs(node.respawn_secs)),
+        node_id,
+    });
+    node.available = false;
+    ctx.db.resource_node().node_id().update(node);
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn respawn_resource_node(ctx: &ReducerContext, schedule: ResourceNodeRespawnSchedule) -> Result<(), String> {
+    if ctx.sender != ctx.identity() {
+        return Err("respawn_resource_node may only be invoked by the scheduler".to_string());
+    }
+    if let Some(mut node) = ctx.db.resource_node().node_id().find(schedule.node_id) {
+        node.available = true;
+        ctx.db.resource_node().node_id().update(node);
+    }
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn place_resource_node(ctx: &ReducerContext, room_name: String, position: Vector3, spec: ResourceNodeSpec) -> Result<(), String> {
+    room::require_room_owner(ctx, &room_name)?;
+    let Some(def) = ctx.db.item_def().item_def_id().find(spec.item_def_id) else {
+        return Err("Item not found".to_string());
+    };
+    if def.gathered_by != Some(spec.profession) {
+        return Err(format!("{} isn't a {:?} material", def.name, spec.profession));
+    }
+    if spec.base_yield == 0 || spec.base_yield > MAX_RESOURCE_BASE_YIELD {
+        return Err(format!("A node yields 1-{} items", MAX_RESOURCE_BASE_YIELD));
+    }
+    if spec.required_skill > MAX_PROFESSION_SKILL {
+        return Err(format!("Skills go up to {}", MAX_PROFESSION_SKILL));
+    }
+    if !(MIN_RESOURCE_RESPAWN_SECS..=MAX_RESOURCE_RESPAWN_SECS).contains(&spec.respawn_secs) {
+        return Err(format!(
+            "Resources respawn after {}-{} seconds",
+            MIN_RESOURCE_RESPAWN_SECS, MAX_RESOURCE_RESPAWN_SECS
+        ));
+    }
+    ctx.db.resource_node().insert(ResourceNode {
+        node_id: 0,
+        room_name,
+        profession: spec.profession,
+        required_skill: spec.required_skill,
+        item_def_id: spec.item_def_id,
+        base_yield: spec.base_yield,
+        position,
+        respawn_secs: spec.respawn_secs,
+        available: true,
+    });
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn remove_resource_node(ctx: &ReducerContext, node_id: u64) -> Result<(), String> {
+    let Some(node) = ctx.db.resource_node().node_id().find(node_id) else {
+        return Err("Resource not found".to_string());
+    };
+    room::require_room_owner(ctx, &node.room_name)?;
+    delete_node(ctx, node_id);
+    Ok(())
+}
@@ -0,0 +1,217 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - gc.rs
+ *
+ * Garbage collection for world rows that outlived what they belong to.
+ * close_room tears a room down in one go, but rows written by older module
+ * versions, failed jobs or hand-edited data can still be left behind, and
+ * nothing else would ever delete them.
+ *
+ * Key components:
+ * - collect_garbage: Scheduled every GC_INTERVAL_SECS (and run_gc for
+ *   admins). Two passes:
+ *   - Orphaned rooms: Any room name found on tiles, chunks, NPCs, props,
+ *     pickups, drops, loot rolls, triggers (regions, teleporters,
+ *     checkpoints, traps, doors, switches) or other placed objects (bankers,
+ *     decorations, resource nodes) without a room row gets room.rs
+ *     clear_room_contents
+ *   - Dangling references: Container slots without their container,
+ *     spawner links without their NPC or spawner, chunk views, heightmaps
+ *     and minimaps without their chunk are deleted; closed doors whose
+ *     blocking tile is gone get it rebuilt
+ * - GcRun table (private): What each run found, the last GC_RUNS_KEPT runs
+ *
+ * Related files:
+ * - room.rs: clear_room_contents
+ * - admin.rs: require_admin
+ */
+
+use spacetimedb::{ReducerContext, ScheduleAt, Table, Timestamp};
+use std::collections::{BTreeSet, HashSet};
+use std::time::Duration;
+
+use crate::admin;
+use crate::bank::banker as _;
+use crate::checkpoint::checkpoint as _;
+use crate::chunk::{chunk_view as _, world_chunk as _};
+use crate::common::{GC_INTERVAL_SECS, GC_RUNS_KEPT};
+use crate::container::{container as _, container_slot as _};
+use crate::decoration::decoration as _;
+use crate::destructible::destructible as _;
+use crate::door::{self, door as _, switch as _};
+use crate::gathering::resource_node as _;
+use crate::hazard::periodic_hazard as _;
+use crate::heightmap::{self, chunk_heightmap as _};
+use crate::light::light_source as _;
+use crate::loot::dropped_item as _;
+use crate::loot_roll::loot_roll as _;
+use crate::minimap::{self, minimap_chunk as _};
+use crate::npc::npc as _;
+use crate::pickup::world_pickup as _;
+use crate::prop::prop as _;
+use crate::region::region as _;
+use crate::room::{self, room as _};
+use crate::sound::sound_emitter as _;
+use crate::spawn::spawn_point as _;
+use crate::spawner::{spawned_npc as _, spawner as _};
+use crate::teleporter::teleporter as _;
+use crate::trap::trap as _;
+use crate::game_tick_schedule as _;
+use crate::game_tile;
+
+#[spacetimedb::table(name = gc_run)]
+pub struct GcRun {
+    #[primary_key]
+    #[auto_inc]
+    pub run_id: u64,
+    pub ran_at: Timestamp,
+    pub orphaned_rooms: u32,
+    pub dangling_rows: u32,
+}
+
+#[spacetimedb::table(name = gc_schedule, scheduled(collect_garbage))]
+pub struct GcSchedule {
+    #[primary_key]
+    #[auto_inc]
+    pub scheduled_id: u64,
+    pub scheduled_at: ScheduleAt,
+}
+
+pub fn schedule_gc(ctx: &ReducerContext) {
+    if ctx.db.gc_schedule().count() > 0 {
+        return;
+    }
+    ctx.db.gc_schedule().insert(GcSchedule {
+        scheduled_id: 0,
+        scheduled_at: ScheduleAt::Interval(Duration::from_secs(GC_INTERVAL_SECS).into()),
+    });
+}
+
+// Room names referenced by world rows that have no room row
+fn orphaned_rooms(ctx: &ReducerContext) -> BTreeSet<String> {
+    let live: HashSet<String> = ctx.db.room().iter().map(|r| r.room_name).collect();
+    let mut seen: BTreeSet<String> = BTreeSet::new();
+    // Authored map sources belong to no room
+    seen.extend(ctx.db.game_tile().iter().map(|t| t.room_name).filter(|name| !name.is_empty()));
+    seen.extend(ctx.db.world_chunk().iter().map(|c| c.room_name));
+    seen.extend(ctx.db.game_tick_schedule().iter().map(|s| s.room_name));
+    seen.extend(ctx.db.npc().iter().map(|n| n.room_name));
+    seen.extend(ctx.db.spawner().iter().map(|s| s.room_name));
+    seen.extend(ctx.db.destructible().iter().map(|d| d.room_name));
+    seen.extend(ctx.db.prop().iter().map(|p| p.room_name));
+    seen.extend(ctx.db.decoration().iter().map(|d| d.room_name));
+    seen.extend(ctx.db.banker().iter().map(|b| b.room_name));
+    seen.extend(ctx.db.light_source().iter().map(|l| l.room_name));
+    seen.extend(ctx.db.sound_emitter().iter().map(|s| s.room_name));
+    seen.extend(ctx.db.container().iter().map(|c| c.room_name));
+    seen.extend(ctx.db.world_pickup().iter().map(|p| p.room_name));
+    seen.extend(ctx.db.resource_node().iter().map(|n| n.room_name));
+    seen.extend(ctx.db.dropped_item().iter().map(|d| d.room_name));
+    seen.extend(ctx.db.loot_roll().iter().map(|r| r.room_name));
+    seen.extend(ctx.db.spawn_point().iter().map(|s| s.room_name));
+    seen.extend(ctx.db.region().iter().map(|r| r.room_name));
+    seen.extend(ctx.db.teleporter().iter().map(|t| t.room_name));
+    seen.extend(ctx.db.checkpoint().iter().map(|c| c.room_name));
+    seen.extend(ctx.db.trap().iter().map(|t| t.room_name));
+    seen.extend(ctx.db.periodic_hazard().iter().map(|h| h.room_name));
+    seen.extend(ctx.db.door().iter().map(|d| d.room_name));
+    seen.extend(ctx.db.switch().iter().map(|s| s.room_name));
+    seen.retain(|name| !live.contains(name));
+    seen
+}
+
+// Delete rows pointing at rows that no longer exist. Returns how many
+fn repair_references(ctx: &ReducerContext) -> u32 {
+    let mut dangling = 0;
+
+    let slots: Vec<u64> = ctx.db.container_slot().iter()
+        .filter(|s| ctx.db.container().container_id().find(s.container_id).is_none())
+        .map(|s| s.slot_id)
+        .collect();
+    dangling += slots.len();
+    for slot_id in slots {
+        ctx.db.container_slot().slot_id().delete(slot_id);
+    }
+
+    let links: Vec<u64> = ctx.db.spawned_npc().iter()
+        .filter(|l| {
+            ctx.db.npc().npc_id().find(l.npc_id).is_none()
+                || ctx.db.spawner().spawner_id().find(l.spawner_id).is_none()
+        })
+        .map(|l| l.npc_id)
+        .collect();
+    dangling += links.len();
+    for npc_id in links {
+        ctx.db.spawned_npc().npc_id().delete(npc_id);
+    }
+
+    let views: Vec<u64> = ctx.db.chunk_view().iter()
+        .filter(|v| ctx.db.world_chunk().chunk_id().find(v.chunk_id).is_none())
+        .map(|v| v.view_id)
+        .collect();
+    dangling += views.len();
+    for view_id in views {
+        ctx.db.chunk_view().view_id().delete(view_id);
+    }
+
+    let chunk_rows: BTreeSet<u64> = ctx.db.chunk_heightmap().iter().map(|h| h.chunk_id)
+        .chain(ctx.db.minimap_chunk().iter().map(|m| m.chunk_id))
+        .filter(|chunk_id| ctx.db.world_chunk().chunk_id().find(*chunk_id).is_none())
+        .collect();
+    dangling += chunk_rows.len();
+    for chunk_id in chunk_rows {
+        heightmap::clear_chunk(ctx, chunk_id);
+        minimap::clear_chunk(ctx, chunk_id);
+    }
+
+    let doorless_rooms: BTreeSet<String> = ctx.db.door().iter()
+        .filter(|d| !d.is_open && d.tile_id.is_none_or(|tile_id| ctx.db.game_tile().tile_id().find(tile_id).is_none()))
+        .map(|d| d.room_name)
+        .collect();
+    dangling += doorless_rooms.len();
+    for room_name in doorless_rooms {
+        door::rebuild_tiles(ctx, &room_name);
+    }
+
+    dangling as u32
+}
+
+fn run(ctx: &ReducerContext) {
+    let orphans = orphaned_rooms(ctx);
+    for room_name in &orphans {
+        spacetimedb::log::warn!("[GC] Clearing rows of missing room '{}'.", room_name);
+        room::clear_room_contents(ctx, room_name);
+    }
+    let dangling_rows = repair_references(ctx);
+    ctx.db.gc_run().insert(GcRun {
+        run_id: 0,
+        ran_at: ctx.timestamp,
+        orphaned_rooms: orphans.len() as u32,
+        dangling_rows,
+    });
+    let mut runs: Vec<u64> = ctx.db.gc_run().iter().map(|r| r.run_id).collect();
+    runs.sort_unstable_by(|a, b| b.cmp(a)); // Newest first
+    for run_id in runs.into_iter().skip(GC_RUNS_KEPT) {
+        ctx.db.gc_run().run_id().delete(run_id);
+    }
+    if !orphans.is_empty() || dangling_rows > 0 {
+        spacetimedb::log::info!("[GC] Cleared {} orphaned rooms and {} dangling rows.", orphans.len(), dangling_rows);
+    }
+}
+
+// --- GC Reducers ---
+
+#[spacetimedb::reducer]
+pub fn collect_garbage(ctx: &ReducerContext, _schedule: GcSchedule) -> Result<(), String> {
+    if ctx.sender != ctx.identity() {
+        return Err("collect_garbage may only be invoked by the scheduler".to_string());
+    }
+    run(ctx);
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn run_gc(ctx: &ReducerContext) -> Result<(), String> {
+    admin::require_admin(ctx)?;
+    run(ctx);
+    Ok(())
+}
@@ -0,0 +1,242 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - hazard.rs
+ *
+ * Damaging terrain. A GameTile with a hazard (lava, spikes, ...) damages
+ * players standing on it every tick and can leave a lingering effect.
+ * Periodic hazards (flame jets, crushers, spike floors) switch on and off on
+ * a fixed cycle instead.
+ *
+ * Key components:
+ * - apply_tile_hazards: Called from game_tick. Looks up the tile under each
+ *   living player on the room's map, deals its damage_per_sec through
+ *   apply_damage (source EntityId::Environment, kind Hazard) and applies its
+ *   effect through the status framework:
+ *   - Burn: Burning for BURN_SECS at BURN_DAMAGE_FRACTION of the tile's damage
+ *   - Slow: Slowed for SLOW_SECS
+ * - PeriodicHazard table (public): A hazard entity whose HazardCycle lasts
+ *   period_ms and is active for the first active_ms of each cycle (shifted
+ *   by phase_offset_ms). Clients compute the phase from cycle_started_at to
+ *   animate in sync; is_active mirrors the server's view
+ * - tick_periodic_hazards: Called from game_tick. Fires each hazard once per
+ *   active window, hitting living players within its radius:
+ *   - FlameJet: FLAME_JET_DAMAGE and Burning
+ *   - Crusher: CRUSHER_DAMAGE and CRUSHER_KNOCKBACK away from its center
+ *   - SpikeFloor: SPIKE_FLOOR_DAMAGE
+ * - place_periodic_hazard / remove_periodic_hazard: Room-owner reducers
+ *
+ * Related files:
+ * - common.rs: TileHazard / HazardEffect
+ * - map.rs: tile_at, add_map_tile / set_tile_hazard
+ * - status.rs: Burning / Slowed
+ * - combat.rs: apply_damage / apply_knockback
+ */
+
+use spacetimedb::{ReducerContext, SpacetimeType, Table, Timestamp};
+use std::time::Duration;
+
+use crate::combat;
+use crate::common::{
+    CombatEventKind, EntityId, HazardEffect, Vector3, BURN_DAMAGE_FRACTION, BURN_SECS, CRUSHER_DAMAGE,
+    CRUSHER_KNOCKBACK, FLAME_JET_DAMAGE, PERIODIC_HAZARD_MAX_RADIUS, PERIODIC_HAZARD_MIN_ACTIVE_MS, SLOW_SECS,
+    SPIKE_FLOOR_DAMAGE,
+};
+use crate::map;
+use crate::player as _;
+use crate::room::{self, Room};
+use crate::status::{self, StatusKind};
+use crate::PlayerData;
+
+#[derive(SpacetimeType, Clone, Copy, Debug, PartialEq)]
+pub enum PeriodicHazardKind {
+    FlameJet,
+    Crusher,
+    SpikeFloor,
+}
+
+// On/off timing of a periodic hazard
+#[derive(SpacetimeType, Clone, Debug, PartialEq)]
+pub struct HazardCycle {
+    pub period_ms: u64,
+    pub active_ms: u64,
+    pub phase_offset_ms: u64,
+}
+
+#[spacetimedb::table(name = periodic_hazard, public)]
+#[derive(Clone)]
+pub struct PeriodicHazard {
+    #[primary_key]
+    #[auto_inc]
+    pub hazard_id: u64,
+    #[index(btree)]
+    pub room_name: String,
+    pub kind: PeriodicHazardKind,
+    pub position: Vector3,
+    pub radius: f32,
+    pub cycle: HazardCycle,
+    pub cycle_started_at: Timestamp,
+    pub is_active: bool,
+    pub last_fired_cycle: Option<u64>, // Cycle index of the last activation that hit
+}
+
+pub fn apply_tile_hazards(ctx: &ReducerContext, room: &Room, delta_time: f64) {
+    let players: Vec<PlayerData> = ctx.db.player().room_name().filter(&room.room_name)
+        .filter(|p| !p.is_dead)
+        .collect();
+    for player in players {
+        let Some(hazard) = map::tile_at(ctx, room.map_id, &player.position).and_then(|t| t.hazard) else {
+            continue;
+        };
+        let damage = (hazard.damage_per_sec as f64 * delta_time).round() as i32;
+        if damage > 0 {
+            combat::apply_damage(ctx, &EntityId::Environment, &EntityId::Player(player.identity), damage, CombatEventKind::Hazard);
+        }
+        match hazard.effect {
+            HazardEffect::None => {}
+            HazardEffect::Burn => {
+                let burn_per_sec = (hazard.damage_per_sec as f32 * BURN_DAMAGE_FRACTION).round() as i32;
+                status::apply_status(
+                    ctx,
+                    player.identity,
+                    StatusKind::Burning,
+                    EntityId::Environment,
+                    burn_per_sec,
+                    Duration::from_secs(BURN_SECS),
+                );
+            }
+            HazardEffect::Slow => {
+                status::apply_status(
+                    ctx,
+                    player.identity,
+                    StatusKind::Slowed,
+                    EntityId::Environment,
+                    0,
+                    Duration::from_secs(SLOW_SECS),
+                );
+            }
+        }
+    }
+}
+
+// (cycle index, whether the hazard is in its active window) at `now`
+fn hazard_phase(hazard: &PeriodicHazard, now: Timestamp) -> (u64, bool) {
+    let elapsed = now.duration_since(hazard.cycle_started_at).unwrap_or_default().as_millis() as u64
+        + hazard.cycle.phase_offset_ms;
+    let period = hazard.cycle.period_ms.max(1);
+    (elapsed / period, elapsed % period < hazard.cycle.active_ms)
+}
+
+pub fn tick_periodic_hazards(ctx: &ReducerContext, room_name: &str) {
+    let hazards: Vec<PeriodicHazard> = ctx.db.periodic_hazard().room_name().filter(room_name).collect();
+    for mut hazard in hazards {
+        let (cycle, active) = hazard_phase(&hazard, ctx.timestamp);
+        let fires = active && hazard.last_fired_cycle != Some(cycle);
+        if !fires && active == hazard.is_active {
+            continue;
+        }
+        if fires {
+            hazard.last_fired_cycle = Some(cycle);
+            let victims: Vec<PlayerData> = ctx.db.player().room_name().filter(room_name)
+                .filter(|p| !p.is_dead && p.position.distance_xz(&hazard.position) <= hazard.radius)
+                .collect();
+            for victim in victims {
+                fire_at(ctx, &hazard, &victim);
+            }
+        }
+        hazard.is_active = active;
+        ctx.db.periodic_hazard().hazard_id().update(hazard);
+    }
+}
+
+fn fire_at(ctx: &ReducerContext, hazard: &PeriodicHazard, victim: &PlayerData) {
+    let target = EntityId::Player(victim.identity);
+    let damage = match hazard.kind {
+        PeriodicHazardKind::FlameJet => FLAME_JET_DAMAGE,
+        PeriodicHazardKind::Crusher => CRUSHER_DAMAGE,
+        PeriodicHazardKind::SpikeFloor => SPIKE_FLOOR_DAMAGE,
+    };
+    combat::apply_damage(ctx, &EntityId::Environment, &target, damage, CombatEventKind::Hazard);
+    match hazard.kind {
+        PeriodicHazardKind::FlameJet => {
+            let burn_per_sec = (FLAME_JET_DAMAGE as f32 * BURN_DAMAGE_FRACTION).round() as i32;
+            status::apply_status(
+                ctx,
+                victim.identity,
+                StatusKind::Burning,
+                EntityId::Environment,
+                burn_per_sec,
+                Duration::from_secs(BURN_SECS),
+            );
+        }
+        PeriodicHazardKind::Crusher => {
+            combat::apply_knockback(ctx, victim.identity, &hazard.position, CRUSHER_KNOCKBACK);
+        }
+        PeriodicHazardKind::SpikeFloor => {}
+    }
+}
+
+pub fn spawn_periodic_hazard(
+    ctx: &ReducerContext,
+    room_name: &str,
+    kind: PeriodicHazardKind,
+    position: Vector3,
+    radius: f32,
+    cycle: HazardCycle,
+) -> PeriodicHazard {
+    ctx.db.periodic_hazard().insert(PeriodicHazard {
+        hazard_id: 0,
+        room_name: room_name.to_string(),
+        kind,
+        position,
+        radius,
+        cycle,
+        cycle_started_at: ctx.timestamp,
+        is_active: false,
+        last_fired_cycle: None,
+    })
+}
+
+// --- Periodic Hazard Reducers ---
+
+#[spacetimedb::reducer]
+pub fn place_periodic_hazard(
+    ctx: &ReducerContext,
+    room_name: String,
+    kind: PeriodicHazardKind,
+    position: Vector3,
+    radius: f32,
+    mut cycle: HazardCycle,
+) -> Result<(), String> {
+    let room = room::require_room_owner(ctx, &room_name)?;
+    if !map::is_on_map(ctx, room.map_id, &position) {
+        return Err("Hazards must be placed on the room's map".to_string());
+    }
+    if radius <= 0.0 || radius > PERIODIC_HAZARD_MAX_RADIUS {
+        return Err(format!("Radius must be between 0 and {}", PERIODIC_HAZARD_MAX_RADIUS));
+    }
+    if cycle.active_ms < PERIODIC_HAZARD_MIN_ACTIVE_MS || cycle.active_ms > cycle.period_ms {
+        return Err(format!(
+            "The active window must last at least {} ms and fit in the period",
+            PERIODIC_HAZARD_MIN_ACTIVE_MS
+        ));
+    }
+    cycle.phase_offset_ms %= cycle.period_ms;
+    spawn_periodic_hazard(ctx, &room_name, kind, position, radius, cycle);
+    Ok(())
+}
+
+pub fn clear_room(ctx: &ReducerContext, room_name: &str) {
+    let hazards: Vec<u64> = ctx.db.periodic_hazard().room_name().filter(room_name).map(|h| h.hazard_id).collect();
+    for hazard_id in hazards {
+        ctx.db.periodic_hazard().hazard_id().delete(hazard_id);
+    }
+}
+
+#[spacetimedb::reducer]
+pub fn remove_periodic_hazard(ctx: &ReducerContext, hazard_id: u64) -> Result<(), String> {
+    let Some(hazard) = ctx.db.periodic_hazard().hazard_id().find(hazard_id) else {
+        return Err("Hazard not found".to_string());
+    };
+    room::require_room_owner(ctx, &hazard.room_name)?;
+    ctx.db.periodic_hazard().hazard_id().delete(hazard_id);
+    Ok(())
+}
@@ -0,0 +1,135 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - heightmap.rs
+ *
+ * Rolling terrain: a chunk (chunk.rs) can carry a heightmap on top of its
+ * flat tiles, for hills and slopes the box-shaped GameTiles can't express.
+ *
+ * Key components:
+ * - ChunkHeightmap table (public): A resolution x resolution grid of height
+ *   samples (one byte each, row-major along x) spanning the chunk's
+ *   footprint edge to edge. A sample's height is
+ *   base_height + byte / 255 * height_scale. Chunks without a heightmap are
+ *   flat. Clients see the heightmaps of the chunks they view (RLS, like
+ *   tiles)
+ * - height_at: Bilinear sample of the terrain height at a position, if its
+ *   chunk has a heightmap. Player movement (player_logic.rs) and prop
+ *   settling (prop.rs) stand entities on it
+ * - set_chunk_heightmap / clear_chunk_heightmap: Room-owner reducers
+ *
+ * Related files:
+ * - chunk.rs: Chunk footprints; deleting a map's chunks deletes their
+ *   heightmaps
+ */
+
+use spacetimedb::{client_visibility_filter, Filter, ReducerContext, Table};
+
+use crate::chunk::{self, world_chunk as _};
+use crate::common::{Vector3, CHUNK_SIZE_TILES, MAX_HEIGHTMAP_RESOLUTION, MAX_HEIGHTMAP_SCALE, TILE_SIZE};
+use crate::room;
+
+#[spacetimedb::table(name = chunk_heightmap, public, index(name = map_chunk, btree(columns = [map_id, chunk_x, chunk_z])))]
+#[derive(Clone)]
+pub struct ChunkHeightmap {
+    #[primary_key]
+    pub chunk_id: u64,
+    pub map_id: u32,
+    pub chunk_x: i32,
+    pub chunk_z: i32,
+    pub resolution: u32, // Samples per side
+    pub base_height: f32,
+    pub height_scale: f32,
+    pub heights: Vec<u8>,
+}
+
+#[client_visibility_filter]
+const PLAYER_SEES_HEIGHTMAPS_OF_VIEWED_CHUNKS: Filter = Filter::Sql(
+    "SELECT chunk_heightmap.* FROM chunk_heightmap JOIN chunk_view ON chunk_heightmap.chunk_id = chunk_view.chunk_id WHERE chunk_view.identity = :sender",
+);
+
+// World-space width of a chunk, and the x (or z) where chunk 0 begins
+const CHUNK_WIDTH: f32 = CHUNK_SIZE_TILES as f32 * TILE_SIZE;
+const CHUNK_ORIGIN: f32 = -TILE_SIZE * 0.5;
+
+impl ChunkHeightmap {
+    fn sample(&self, i: u32, j: u32) -> f32 {
+        let byte = self.heights[(j * self.resolution + i) as usize];
+        self.base_height + byte as f32 / 255.0 * self.height_scale
+    }
+
+    // Bilinear height at the chunk-local fractions (u, v), each 0..1
+    fn height(&self, u: f32, v: f32) -> f32 {
+        let last = (self.resolution - 1) as f32;
+        let (x, z) = (u.clamp(0.0, 1.0) * last, v.clamp(0.0, 1.0) * last);
+        let (i0, j0) = (x.floor() as u32, z.floor() as u32);
+        let (i1, j1) = ((i0 + 1).min(self.resolution - 1), (j0 + 1).min(self.resolution - 1));
+        let (fx, fz) = (x - i0 as f32, z - j0 as f32);
+        let near = self.sample(i0, j0) * (1.0 - fx) + self.sample(i1, j0) * fx;
+        let far = self.sample(i0, j1) * (1.0 - fx) + self.sample(i1, j1) * fx;
+        near * (1.0 - fz) + far * fz
+    }
+}
+
+// Terrain height at `position` on the map, if its chunk has a heightmap
+pub fn height_at(ctx: &ReducerContext, map_id: u32, position: &Vector3) -> Option<f32> {
+    let (chunk_x, chunk_z) = chunk::chunk_coords_of(position);
+    let heightmap = ctx.db.chunk_heightmap().map_chunk().filter((map_id, chunk_x, chunk_z)).next()?;
+    let u = (position.x - CHUNK_ORIGIN - chunk_x as f32 * CHUNK_WIDTH) / CHUNK_WIDTH;
+    let v = (position.z - CHUNK_ORIGIN - chunk_z as f32 * CHUNK_WIDTH) / CHUNK_WIDTH;
+    Some(heightmap.height(u, v))
+}
+
+pub fn clear_chunk(ctx: &ReducerContext, chunk_id: u64) {
+    ctx.db.chunk_heightmap().chunk_id().delete(chunk_id);
+}
+
+// --- Heightmap Reducers ---
+
+#[spacetimedb::reducer]
+pub fn set_chunk_heightmap(
+    ctx: &ReducerContext,
+    chunk_id: u64,
+    resolution: u32,
+    base_height: f32,
+    height_scale: f32,
+    heights: Vec<u8>,
+) -> Result<(), String> {
+    let Some(chunk) = ctx.db.world_chunk().chunk_id().find(chunk_id) else {
+        return Err("Chunk not found".to_string());
+    };
+    room::require_room_owner(ctx, &chunk.room_name)?;
+    if !(2..=MAX_HEIGHTMAP_RESOLUTION).contains(&resolution) {
+        return Err(format!("Heightmaps have 2-{} samples per side", MAX_HEIGHTMAP_RESOLUTION));
+    }
+    if heights.len() != (resolution * resolution) as usize {
+        return Err(format!("Expected {} height samples", resolution * resolution));
+    }
+    if !(0.0..=MAX_HEIGHTMAP_SCALE).contains(&height_scale) {
+        return Err(format!("Height scale must be between 0 and {}", MAX_HEIGHTMAP_SCALE));
+    }
+    let heightmap = ChunkHeightmap {
+        chunk_id,
+        map_id: chunk.map_id,
+        chunk_x: chunk.chunk_x,
+        chunk_z: chunk.chunk_z,
+        resolution,
+        base_height,
+        height_scale,
+        heights,
+    };
+    if ctx.db.chunk_heightmap().chunk_id().find(chunk_id).is_some() {
+        ctx.db.chunk_heightmap().chunk_id().update(heightmap);
+    } else {
+        ctx.db.chunk_heightmap().insert(heightmap);
+    }
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn clear_chunk_heightmap(ctx: &ReducerContext, chunk_id: u64) -> Result<(), String> {
+    let Some(chunk) = ctx.db.world_chunk().chunk_id().find(chunk_id) else {
+        return Err("Chunk not found".to_string());
+    };
+    room::require_room_owner(ctx, &chunk.room_name)?;
+    clear_chunk(ctx, chunk_id);
+    Ok(())
+}
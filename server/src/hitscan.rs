@@ -0,0 +1,204 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - hitscan.rs
+ *
+ * Instant-hit weapons with lag compensation, so players on high ping can
+ * still land shots on what they saw on screen.
+ *
+ * Key components:
+ *
+ * 1. Position History:
+ *    - PositionHistory table: A small ring buffer per player
+ *      (POSITION_HISTORY_SLOTS rows, one sample per
+ *      POSITION_HISTORY_INTERVAL_MS) written as input moves them
+ *    - record_position: Called from update_player_input; overwrites the slot
+ *      for the current interval, at most once per interval
+ *    - clear_history: Called on teleports (respawn, room change) and
+ *      disconnect so shots never rewind across a jump
+ *    - rewound_position: Interpolates where a player was at a past timestamp
+ *    - latest_sample: The newest sample, used by objective.rs to time when a
+ *      player crossed into an objective between two inputs
+ *
+ * 2. Firing:
+ *    - fire_hitscan: The shooter reports their aim and latency. The server
+ *      clamps the latency to MAX_LAG_COMPENSATION_MS, raycasts through the
+ *      spatial index (with enough slack for how far targets could have moved)
+ *      and re-tests players at their rewound positions. The nearest target
+ *      along the ray takes the hit.
+ *
+ * Related files:
+ *    - spatial.rs: query_ray broad phase and segment_hit
+ *    - combat.rs: apply_damage
+ *    - lib.rs: Records history on input
+ */
+
+use spacetimedb::{Identity, ReducerContext, Table, Timestamp};
+use std::time::Duration;
+
+use crate::combat;
+use crate::common::{
+    forward_from_yaw, CombatEventKind, EntityId, Vector3, ATTACK_NOISE_RADIUS, HITSCAN_COOLDOWN_MS,
+    HITSCAN_DAMAGE, HITSCAN_HIT_RADIUS, HITSCAN_RANGE, MAX_LAG_COMPENSATION_MS, PLAYER_SPEED,
+    POSITION_HISTORY_INTERVAL_MS, POSITION_HISTORY_SLOTS, SPRINT_MULTIPLIER,
+};
+use crate::config;
+use crate::noise;
+use crate::loadout::{self, Ability};
+use crate::player as _;
+use crate::spatial::{self, lerp, segment_hit, SpatialEntity};
+
+#[spacetimedb::table(name = position_history)]
+#[derive(Clone)]
+pub struct PositionHistory {
+    #[primary_key]
+    #[auto_inc]
+    pub history_id: u64,
+    #[index(btree)]
+    pub identity: Identity,
+    pub slot: u8,
+    pub position: Vector3,
+    pub recorded_at: Timestamp,
+}
+
+fn interval_of(timestamp: Timestamp) -> i64 {
+    timestamp.to_micros_since_unix_epoch() / (POSITION_HISTORY_INTERVAL_MS * 1000)
+}
+
+// Sample a player's position into their ring buffer
+pub fn record_position(ctx: &ReducerContext, identity: Identity, position: &Vector3) {
+    let interval = interval_of(ctx.timestamp);
+    let slot = interval.rem_euclid(POSITION_HISTORY_SLOTS) as u8;
+    match ctx.db.position_history().identity().filter(identity).find(|h| h.slot == slot) {
+        // Already sampled this interval
+        Some(row) if interval_of(row.recorded_at) == interval => {}
+        Some(mut row) => {
+            row.position = position.clone();
+            row.recorded_at = ctx.timestamp;
+            ctx.db.position_history().history_id().update(row);
+        }
+        None => {
+            ctx.db.position_history().insert(PositionHistory {
+                history_id: 0,
+                identity,
+                slot,
+                position: position.clone(),
+                recorded_at: ctx.timestamp,
+            });
+        }
+    }
+}
+
+pub fn clear_history(ctx: &ReducerContext, identity: Identity) {
+    let rows: Vec<u64> = ctx.db.position_history().identity().filter(identity).map(|h| h.history_id).collect();
+    for history_id in rows {
+        ctx.db.position_history().history_id().delete(history_id);
+    }
+}
+
+// The newest (time, position) sample of a player, if any
+pub fn latest_sample(ctx: &ReducerContext, identity: Identity) -> Option<(Timestamp, Vector3)> {
+    ctx.db.position_history().identity().filter(identity)
+        .max_by_key(|h| h.recorded_at)
+        .map(|h| (h.recorded_at, h.position))
+}
+
+// Where `identity` was at `at`, interpolated between the samples around it.
+// Falls back to `current` when there's no older sample to rewind to.
+pub fn rewound_position(ctx: &ReducerContext, identity: Identity, at: Timestamp, current: &Vector3) -> Vector3 {
+    let mut before: Option<PositionHistory> = None;
+    let mut after: Option<(Timestamp, Vector3)> = None;
+    for sample in ctx.db.position_history().identity().filter(identity) {
+        if sample.recorded_at <= at {
+            if before.as_ref().is_none_or(|b| sample.recorded_at > b.recorded_at) {
+                before = Some(sample);
+            }
+        } else if after.as_ref().is_none_or(|(t, _)| sample.recorded_at < *t) {
+            after = Some((sample.recorded_at, sample.position));
+        }
+    }
+    let Some(before) = before else {
+        return current.clone();
+    };
+    // Newer than every sample: blend towards where the player is now
+    let (after_at, after_position) = after.unwrap_or((ctx.timestamp, current.clone()));
+    let span = after_at.duration_since(before.recorded_at).unwrap_or_default().as_secs_f32();
+    if span <= 0.0 {
+        return before.position;
+    }
+    let t = at.duration_since(before.recorded_at).unwrap_or_default().as_secs_f32() / span;
+    lerp(&before.position, &after_position, t.clamp(0.0, 1.0))
+}
+
+// Fire the hitscan weapon along `aim` (ground-plane direction; zero = facing).
+// `latency_ms` is the shooter's reported one-way delay, used to rewind targets.
+#[spacetimedb::reducer]
+pub fn fire_hitscan(ctx: &ReducerContext, aim: Vector3, latency_ms: u32) -> Result<(), String> {
+    if !config::combat_enabled(ctx) {
+        return Err("Combat is disabled".to_string());
+    }
+    let Some(mut shooter) = ctx.db.player().identity().find(ctx.sender) else {
+        return Err("Player not found".to_string());
+    };
+    if shooter.is_dead {
+        return Err("Dead players can't shoot".to_string());
+    }
+    if shooter.staggered_until.is_some_and(|until| until > ctx.timestamp) {
+        return Err("Staggered".to_string());
+    }
+    if !loadout::ability_allowed(ctx, &shooter.room_name, Ability::Hitscan) {
+        return Err("Hitscan weapons are disabled in this room".to_string());
+    }
+    let cooldown = Duration::from_millis(HITSCAN_COOLDOWN_MS);
+    if shooter.last_shot_at.is_some_and(|at| at + cooldown > ctx.timestamp) {
+        return Err("Weapon is on cooldown".to_string());
+    }
+    shooter.last_shot_at = Some(ctx.timestamp);
+    ctx.db.player().identity().update(shooter.clone());
+
+    let direction = if aim.x * aim.x + aim.z * aim.z < 1e-6 { forward_from_yaw(shooter.rotation.y) } else { aim };
+    let latency = Duration::from_millis(latency_ms.min(MAX_LAG_COMPENSATION_MS) as u64);
+    let shot_at = ctx.timestamp.checked_sub_duration(latency).unwrap_or(ctx.timestamp);
+    let source = EntityId::Player(shooter.identity);
+
+    // Broad phase on current cells, wide enough for how far a target could
+    // have moved since shot_at; the narrow phase uses rewound positions
+    let slack = PLAYER_SPEED * SPRINT_MULTIPLIER * latency.as_secs_f32();
+    let candidates = spatial::query_ray(
+        ctx,
+        &shooter.room_name,
+        &shooter.position,
+        &direction,
+        HITSCAN_RANGE,
+        HITSCAN_HIT_RADIUS + slack,
+    );
+    let length = (direction.x * direction.x + direction.z * direction.z).sqrt();
+    let end = Vector3 {
+        x: shooter.position.x + direction.x / length * HITSCAN_RANGE,
+        y: shooter.position.y,
+        z: shooter.position.z + direction.z / length * HITSCAN_RANGE,
+    };
+
+    let mut best: Option<(f32, EntityId)> = None;
+    for hit in candidates {
+        let Some(target) = hit.entity.combatant() else {
+            continue;
+        };
+        if target == source {
+            continue;
+        }
+        let position = match &hit.entity {
+            SpatialEntity::Player(identity) => rewound_position(ctx, *identity, shot_at, &hit.position),
+            _ => hit.position,
+        };
+        if let Some(t) = segment_hit(&shooter.position, &end, &position, HITSCAN_HIT_RADIUS) {
+            if best.as_ref().is_none_or(|(best_t, _)| t < *best_t) {
+                best = Some((t, target));
+            }
+        }
+    }
+
+    noise::emit_noise(ctx, &shooter.room_name, source.clone(), &shooter.position, ATTACK_NOISE_RADIUS);
+    if let Some((_, target)) = best {
+        combat::apply_damage(ctx, &source, &target, HITSCAN_DAMAGE, CombatEventKind::Hitscan);
+    }
+    Ok(())
+}
@@ -0,0 +1,317 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - inventory.rs
+ *
+ * Player inventories: a fixed number of slots, each holding one stack of a
+ * single item definition.
+ *
+ * Key components:
+ * - InventoryItem table (public): One stack in one slot. Players only see
+ *   their own items (RLS). Stacks never exceed the definition's max_stack
+ *   and a player has at most INVENTORY_SLOTS of them. A rolled item
+ *   (item_instance.rs) sits alone in its slot and never stacks
+ * - add_item: Tops up existing stacks, then fills free slots; a rolled item
+ *   takes a free slot. Fails without writing anything when the items don't
+ *   fit (loot, pickups and containers leave the item where it was)
+ * - has_item / count_item: What a player holds (keys for locked doors and
+ *   containers, selling to vendors)
+ * - remove_item / remove_from_stack: Take items out (by item, smallest
+ *   plain stacks first, or from one stack) and unequip the item if that was
+ *   the last of it. remove_item destroys rolled items it takes
+ * - move_item: Moves a stack to another slot. Onto a stack of the same item
+ *   it merges as much as fits; onto a different or rolled item the two swap
+ * - split_stack: Moves part of a stack into an empty slot
+ * - drop_item: Puts items on the ground as a loot drop anyone can take.
+ *   Dropping the last copy of an equipped item unequips it
+ * - use_item: Weapons and armor are equipped (equipment.rs); consumables
+ *   take effect (consumable.rs) and one comes off the stack
+ *
+ * Related files:
+ * - item.rs: Item definitions and grant_item
+ * - item_instance.rs: Rolled items
+ * - equipment.rs: Equipped items
+ * - loot.rs: Dropped items
+ * - door.rs / container.rs: Keys
+ */
+
+use spacetimedb::{client_visibility_filter, Filter, Identity, ReducerContext, Table};
+
+use crate::common::INVENTORY_SLOTS;
+use crate::consumable;
+use crate::equipment;
+use crate::item::{item_def as _, ItemDef, ItemKind};
+use crate::item_instance;
+use crate::loot;
+use crate::player as _;
+use crate::PlayerData;
+
+#[spacetimedb::table(name = inventory_item, public)]
+#[derive(Clone)]
+pub struct InventoryItem {
+    #[primary_key]
+    #[auto_inc]
+    pub item_id: u64,
+    #[index(btree)]
+    pub owner: Identity,
+    pub slot: u32, // 0..INVENTORY_SLOTS
+    pub item_def_id: u64,
+    pub quantity: u32,
+    pub instance_id: Option<u64>, // Rolled items only, always one per slot
+}
+
+#[client_visibility_filter]
+const OWNER_SEES_INVENTORY: Filter = Filter::Sql(
+    "SELECT * FROM inventory_item WHERE owner = :sender",
+);
+
+// A definition deleted since the stack was made holds one per slot
+fn max_stack(ctx: &ReducerContext, item_def_id: u64) -> u32 {
+    ctx.db.item_def().item_def_id().find(item_def_id).map_or(1, |def| def.max_stack.max(1))
+}
+
+fn free_slots(ctx: &ReducerContext, owner: Identity) -> Vec<u32> {
+    let used: Vec<u32> = ctx.db.inventory_item().owner().filter(owner).map(|i| i.slot).collect();
+    (0..INVENTORY_SLOTS).filter(|slot| !used.contains(slot)).collect()
+}
+
+fn item_in_slot(ctx: &ReducerContext, owner: Identity, slot: u32) -> Option<InventoryItem> {
+    ctx.db.inventory_item().owner().filter(owner).find(|i| i.slot == slot)
+}
+
+// The sender's stack `item_id`
+fn owned_item(ctx: &ReducerContext, item_id: u64) -> Result<InventoryItem, String> {
+    match ctx.db.inventory_item().item_id().find(item_id) {
+        Some(item) if item.owner == ctx.sender => Ok(item),
+        _ => Err("Item not found".to_string()),
+    }
+}
+
+fn check_slot(slot: u32) -> Result<(), String> {
+    if slot >= INVENTORY_SLOTS {
+        return Err(format!("Inventory slots go from 0 to {}", INVENTORY_SLOTS - 1));
+    }
+    Ok(())
+}
+
+// Take `quantity` off a stack, deleting it when it runs out
+fn take_from_stack(ctx: &ReducerContext, mut item: InventoryItem, quantity: u32) {
+    if quantity >= item.quantity {
+        ctx.db.inventory_item().item_id().delete(item.item_id);
+    } else {
+        item.quantity -= quantity;
+        ctx.db.inventory_item().item_id().update(item);
+    }
+}
+
+pub fn has_item(ctx: &ReducerContext, owner: Identity, item_def_id: u64) -> bool {
+    ctx.db.inventory_item().owner().filter(owner).any(|i| i.item_def_id == item_def_id)
+}
+
+// Whether the player still carries that rolled item, or (for None) any of
+// the definition
+pub fn holds(ctx: &ReducerContext, owner: Identity, item_def_id: u64, instance_id: Option<u64>) -> bool {
+    ctx.db.inventory_item().owner().filter(owner)
+        .any(|i| i.item_def_id == item_def_id && (instance_id.is_none() || i.instance_id == instance_id))
+}
+
+pub fn count_item(ctx: &ReducerContext, owner: Identity, item_def_id: u64) -> u32 {
+    ctx.db.inventory_item().owner().filter(owner)
+        .filter(|i| i.item_def_id == item_def_id)
+        .map(|i| i.quantity)
+        .sum()
+}
+
+// Take `quantity` of an item out of the player's inventory, smallest plain
+// stacks first and rolled items last. Fails without writing anything if
+// they carry fewer
+pub fn remove_item(ctx: &ReducerContext, player: &PlayerData, item_def_id: u64, quantity: u32) -> Result<(), String> {
+    if count_item(ctx, player.identity, item_def_id) < quantity {
+        return Err("You don't have enough of that item".to_string());
+    }
+    let mut stacks: Vec<InventoryItem> = ctx.db.inventory_item().owner().filter(player.identity)
+        .filter(|i| i.item_def_id == item_def_id)
+        .collect();
+    stacks.sort_by_key(|i| (i.instance_id.is_some(), i.quantity));
+    let mut remaining = quantity;
+    for stack in stacks {
+        if remaining == 0 {
+            break;
+        }
+        let taken = remaining.min(stack.quantity);
+        remaining -= taken;
+        item_instance::discard(ctx, stack.instance_id);
+        take_from_stack(ctx, stack, taken);
+    }
+    equipment::unequip_if_gone(ctx, player, item_def_id);
+    Ok(())
+}
+
+// Take `quantity` off one of the player's stacks. Returns the stack's item
+// and, for a rolled item, its instance (which the caller now holds)
+pub fn remove_from_stack(
+    ctx: &ReducerContext,
+    player: &PlayerData,
+    item_id: u64,
+    quantity: u32,
+) -> Result<(u64, Option<u64>), String> {
+    let item = match ctx.db.inventory_item().item_id().find(item_id) {
+        Some(item) if item.owner == player.identity => item,
+        _ => return Err("Item not found".to_string()),
+    };
+    if quantity == 0 || quantity > item.quantity {
+        return Err(format!("Pick between 1 and {} of those", item.quantity));
+    }
+    let (item_def_id, instance_id) = (item.item_def_id, item.instance_id);
+    take_from_stack(ctx, item, quantity);
+    equipment::unequip_if_gone(ctx, player, item_def_id);
+    Ok((item_def_id, instance_id))
+}
+
+pub fn add_item(
+    ctx: &ReducerContext,
+    owner: Identity,
+    def: &ItemDef,
+    quantity: u32,
+    instance_id: Option<u64>,
+) -> Result<(), String> {
+    if instance_id.is_some() {
+        let Some(slot) = free_slots(ctx, owner).first().copied() else {
+            return Err("Your inventory is full".to_string());
+        };
+        ctx.db.inventory_item().insert(InventoryItem {
+            item_id: 0,
+            owner,
+            slot,
+            item_def_id: def.item_def_id,
+            quantity: 1,
+            instance_id,
+        });
+        return Ok(());
+    }
+    let max_stack = def.max_stack.max(1);
+    let stacks: Vec<InventoryItem> = ctx.db.inventory_item().owner().filter(owner)
+        .filter(|i| i.item_def_id == def.item_def_id && i.instance_id.is_none() && i.quantity < max_stack)
+        .collect();
+    let free = free_slots(ctx, owner);
+    let room: u64 = stacks.iter().map(|i| (max_stack - i.quantity) as u64).sum::<u64>()
+        + free.len() as u64 * max_stack as u64;
+    if room < quantity as u64 {
+        return Err("Your inventory is full".to_string());
+    }
+
+    let mut remaining = quantity;
+    for mut stack in stacks {
+        if remaining == 0 {
+            break;
+        }
+        let added = remaining.min(max_stack - stack.quantity);
+        stack.quantity += added;
+        remaining -= added;
+        ctx.db.inventory_item().item_id().update(stack);
+    }
+    for slot in free {
+        if remaining == 0 {
+            break;
+        }
+        let added = remaining.min(max_stack);
+        remaining -= added;
+        ctx.db.inventory_item().insert(InventoryItem {
+            item_id: 0,
+            owner,
+            slot,
+            item_def_id: def.item_def_id,
+            quantity: added,
+            instance_id: None,
+        });
+    }
+    Ok(())
+}
+
+// --- Inventory Reducers ---
+
+#[spacetimedb::reducer]
+pub fn move_item(ctx: &ReducerContext, item_id: u64, to_slot: u32) -> Result<(), String> {
+    let mut item = owned_item(ctx, item_id)?;
+    check_slot(to_slot)?;
+    if item.slot == to_slot {
+        return Ok(());
+    }
+    match item_in_slot(ctx, ctx.sender, to_slot) {
+        None => {
+            item.slot = to_slot;
+            ctx.db.inventory_item().item_id().update(item);
+        }
+        Some(mut target)
+            if target.item_def_id == item.item_def_id && target.instance_id.is_none() && item.instance_id.is_none() =>
+        {
+            let moved = item.quantity.min(max_stack(ctx, item.item_def_id).saturating_sub(target.quantity));
+            if moved == 0 {
+                return Err("That stack is full".to_string());
+            }
+            target.quantity += moved;
+            ctx.db.inventory_item().item_id().update(target);
+            take_from_stack(ctx, item, moved);
+        }
+        Some(mut target) => {
+            target.slot = item.slot;
+            item.slot = to_slot;
+            ctx.db.inventory_item().item_id().update(target);
+            ctx.db.inventory_item().item_id().update(item);
+        }
+    }
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn split_stack(ctx: &ReducerContext, item_id: u64, quantity: u32, to_slot: u32) -> Result<(), String> {
+    let item = owned_item(ctx, item_id)?;
+    check_slot(to_slot)?;
+    if quantity == 0 || quantity >= item.quantity {
+        return Err("Split off at least one item and leave at least one behind".to_string());
+    }
+    if item_in_slot(ctx, ctx.sender, to_slot).is_some() {
+        return Err("That slot is not empty".to_string());
+    }
+    ctx.db.inventory_item().insert(InventoryItem {
+        item_id: 0,
+        owner: ctx.sender,
+        slot: to_slot,
+        item_def_id: item.item_def_id,
+        quantity,
+        instance_id: None, // Rolled items are single and can't be split
+    });
+    take_from_stack(ctx, item, quantity);
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn drop_item(ctx: &ReducerContext, item_id: u64, quantity: u32) -> Result<(), String> {
+    let Some(player) = ctx.db.player().identity().find(ctx.sender) else {
+        return Err("Player not found".to_string());
+    };
+    if player.is_dead {
+        return Err("Dead players can't drop items".to_string());
+    }
+    let (item_def_id, instance_id) = remove_from_stack(ctx, &player, item_id, quantity)?;
+    loot::spawn_drop(ctx, &player.room_name, player.position.clone(), item_def_id, quantity, instance_id, None);
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn use_item(ctx: &ReducerContext, item_id: u64) -> Result<(), String> {
+    let Some(player) = ctx.db.player().identity().find(ctx.sender) else {
+        return Err("Player not found".to_string());
+    };
+    let item = owned_item(ctx, item_id)?;
+    let Some(def) = ctx.db.item_def().item_def_id().find(item.item_def_id) else {
+        return Err("Item not found".to_string());
+    };
+    match def.kind {
+        ItemKind::Weapon | ItemKind::Armor => equipment::equip(ctx, &player, &def, item.instance_id),
+        ItemKind::Consumable => {
+            consumable::consume(ctx, &player, &def)?;
+            take_from_stack(ctx, item, 1);
+            Ok(())
+        }
+        ItemKind::Misc => Err(format!("{} can't be used", def.name)),
+    }
+}
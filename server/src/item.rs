@@ -0,0 +1,254 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - item.rs
+ *
+ * Item definitions. Melee combat reads its damage, reach and attack speed
+ * from the equipped weapon's definition.
+ *
+ * Key components:
+ * - ItemDef table (public): Designer-authored item definitions. Weapons carry
+ *   damage, range and attack_cooldown_ms (attack speed); max_stack is how
+ *   many fit in one inventory slot (inventory.rs). Misc items (keys,
+ *   materials) only sit in the inventory. Weapons and armor name their
+ *   equip_slot, an optional class restriction, a required level and the
+ *   cosmetic_id clients draw when it is worn (equipment.rs). Consumables
+ *   carry their effects (consumable.rs). `value` is the admin-set vendor
+ *   price (shop.rs); 0 keeps an item out of vendor shops. Misc materials
+ *   name the profession whose resource nodes may yield them (gathered_by)
+ * - seed_item_defs: Inserts the starter weapons, potions, food, a key and
+ *   gathering materials (gathering.rs) in init
+ * - equipped_weapon: Resolves a player's current weapon definition, with
+ *   the affixes of a rolled weapon applied (item_instance.rs)
+ * - grant_item: Puts a picked-up item in a player's inventory (loot.rs,
+ *   pickup.rs, container.rs). A weapon picked up with nothing equipped is
+ *   equipped right away
+ * - upsert_item_def: Admin reducer for tuning or adding items
+ *
+ * Related files:
+ * - equipment.rs: Equipped items
+ * - item_instance.rs: Rolled copies with rarity and affixes
+ * - combat.rs: resolve_melee_attack uses the weapon's stats
+ * - lib.rs: Enforces the weapon's attack cooldown on attack input
+ */
+
+use spacetimedb::{Identity, ReducerContext, SpacetimeType, Table};
+
+use crate::admin;
+use crate::common::MAX_CONSUMABLE_EFFECT_SECS;
+use crate::equipment;
+use crate::gathering::Profession;
+use crate::inventory;
+use crate::item_instance;
+use crate::loadout;
+use crate::PlayerData;
+
+// The weapon used when a player has nothing equipped
+pub const UNARMED_WEAPON: &str = "Fists";
+
+#[derive(SpacetimeType, Clone, Copy, Debug, PartialEq)]
+pub enum ItemKind {
+    Weapon,
+    Armor,
+    Consumable,
+    Misc,
+}
+
+#[derive(SpacetimeType, Clone, Copy, Debug, PartialEq)]
+pub enum ConsumableEffectKind {
+    Heal, // Instant
+    RestoreMana, // Instant
+    HealOverTime, // amount per second
+    ManaOverTime, // amount per second
+    Haste, // Faster movement (status.rs Hasted); amount unused
+}
+
+#[derive(SpacetimeType, Clone, Debug, PartialEq)]
+pub struct ConsumableEffect {
+    pub kind: ConsumableEffectKind,
+    pub amount: i32,
+    pub duration_secs: u32, // Over-time effects and buffs only
+}
+
+#[derive(SpacetimeType, Clone, Copy, Debug, PartialEq)]
+pub enum EquipSlot {
+    Weapon,
+    Head,
+    Chest,
+    Hands,
+    Legs,
+    Feet,
+    Back,
+    Trinket,
+}
+
+#[spacetimedb::table(name = item_def, public)]
+#[derive(Clone)]
+pub struct ItemDef {
+    #[primary_key]
+    #[auto_inc]
+    pub item_def_id: u64,
+    #[unique]
+    pub name: String,
+    pub kind: ItemKind,
+    pub damage: i32,
+    pub range: f32,
+    pub attack_cooldown_ms: u64,
+    pub max_stack: u32, // Per inventory slot
+    pub equip_slot: Option<EquipSlot>, // None = can't be equipped
+    pub required_class: Option<String>,
+    pub required_level: u32,
+    pub cosmetic_id: String, // Model drawn while equipped
+    pub effects: Vec<ConsumableEffect>, // Consumables only
+    pub uses_potion_cooldown: bool, // Shares the potion cooldown (consumable.rs)
+    pub value: u64, // Vendor price in gold (shop.rs); 0 = vendors won't deal in it
+    pub gathered_by: Option<Profession>, // Materials only (gathering.rs)
+}
+
+// A definition with no combat stats, equip slot or effects
+fn plain_def(name: &str, kind: ItemKind, max_stack: u32) -> ItemDef {
+    ItemDef {
+        item_def_id: 0,
+        name: name.to_string(),
+        kind,
+        damage: 0,
+        range: 0.0,
+        attack_cooldown_ms: 0,
+        max_stack,
+        equip_slot: None,
+        required_class: None,
+        required_level: 1,
+        cosmetic_id: String::new(),
+        effects: Vec::new(),
+        uses_potion_cooldown: false,
+        value: 0,
+        gathered_by: None,
+    }
+}
+
+fn effect(kind: ConsumableEffectKind, amount: i32, duration_secs: u32) -> ConsumableEffect {
+    ConsumableEffect { kind, amount, duration_secs }
+}
+
+pub fn seed_item_defs(ctx: &ReducerContext) {
+    if ctx.db.item_def().count() > 0 {
+        return;
+    }
+    let weapons = [
+        (UNARMED_WEAPON, 10, 2.5, 500, 0),
+        ("Dagger", 8, 2.0, 250, 20),
+        ("Sword", 18, 3.0, 700, 60),
+        ("Spear", 14, 4.0, 800, 50),
+        ("Warhammer", 30, 2.8, 1400, 120),
+    ];
+    for (name, damage, range, cooldown_ms, value) in weapons {
+        ctx.db.item_def().insert(ItemDef {
+            damage,
+            range,
+            attack_cooldown_ms: cooldown_ms,
+            equip_slot: Some(EquipSlot::Weapon),
+            cosmetic_id: name.to_lowercase(),
+            value,
+            ..plain_def(name, ItemKind::Weapon, 1)
+        });
+    }
+    let consumables = [
+        ("Health Potion", effect(ConsumableEffectKind::Heal, 50, 0), true, 25),
+        ("Mana Potion", effect(ConsumableEffectKind::RestoreMana, 50, 0), true, 25),
+        ("Swiftness Draught", effect(ConsumableEffectKind::Haste, 0, 15), true, 40),
+        ("Bread", effect(ConsumableEffectKind::HealOverTime, 5, 10), false, 5),
+    ];
+    for (name, effect, uses_potion_cooldown, value) in consumables {
+        ctx.db.item_def().insert(ItemDef {
+            effects: vec![effect],
+            uses_potion_cooldown,
+            value,
+            ..plain_def(name, ItemKind::Consumable, 10)
+        });
+    }
+    ctx.db.item_def().insert(plain_def("Rusty Key", ItemKind::Misc, 20));
+    let materials = [
+        ("Copper Ore", Profession::Mining, 4),
+        ("Iron Ore", Profession::Mining, 8),
+        ("Silverleaf", Profession::Herbalism, 4),
+        ("Mageroyal", Profession::Herbalism, 8),
+    ];
+    for (material, profession, value) in materials {
+        ctx.db.item_def().insert(ItemDef {
+            value,
+            gathered_by: Some(profession),
+            ..plain_def(material, ItemKind::Misc, 50)
+        });
+    }
+}
+
+// The weapon a player fights with: their equipped weapon, or bare hands
+pub fn equipped_weapon(ctx: &ReducerContext, identity: Identity) -> Option<ItemDef> {
+    equipment::equipped(ctx, identity, EquipSlot::Weapon)
+        .and_then(|equipped| {
+            let def = ctx.db.item_def().item_def_id().find(equipped.item_def_id)?;
+            Some(item_instance::apply_affixes(ctx, def, equipped.instance_id))
+        })
+        .or_else(|| ctx.db.item_def().name().find(UNARMED_WEAPON.to_string()))
+}
+
+// Hand picked-up items to a player. Fails if they don't fit in the
+// inventory. A first weapon is equipped on the spot if the player meets its
+// requirements (and the room's loadout doesn't lock weapons)
+pub fn grant_item(
+    ctx: &ReducerContext,
+    player: &PlayerData,
+    def: &ItemDef,
+    quantity: u32,
+    instance_id: Option<u64>,
+) -> Result<(), String> {
+    inventory::add_item(ctx, player.identity, def, quantity, instance_id)?;
+    let unarmed = equipment::equipped(ctx, player.identity, EquipSlot::Weapon).is_none();
+    if def.kind == ItemKind::Weapon && unarmed && !loadout::weapon_locked(ctx, &player.room_name) {
+        let _ = equipment::equip(ctx, player, def, instance_id);
+    }
+    Ok(())
+}
+
+// --- Item Reducers ---
+
+#[spacetimedb::reducer]
+pub fn upsert_item_def(ctx: &ReducerContext, def: ItemDef) -> Result<(), String> {
+    admin::require_admin(ctx)?;
+    if def.name.is_empty() {
+        return Err("Item name must not be empty".to_string());
+    }
+    if def.kind == ItemKind::Weapon && (def.damage < 0 || def.range <= 0.0) {
+        return Err("Weapon damage must be >= 0 and range > 0".to_string());
+    }
+    if def.max_stack == 0 {
+        return Err("Items must stack at least 1 per slot".to_string());
+    }
+    let slot_fits = match def.kind {
+        ItemKind::Weapon => def.equip_slot == Some(EquipSlot::Weapon),
+        ItemKind::Armor => def.equip_slot.is_some_and(|slot| slot != EquipSlot::Weapon),
+        ItemKind::Consumable | ItemKind::Misc => def.equip_slot.is_none(),
+    };
+    if !slot_fits {
+        return Err("Weapons go in the weapon slot, armor in another slot and other items in none".to_string());
+    }
+    if def.gathered_by.is_some() && def.kind != ItemKind::Misc {
+        return Err("Only misc items can be gathering materials".to_string());
+    }
+    if (def.kind == ItemKind::Consumable) == def.effects.is_empty() {
+        return Err("Consumables need at least one effect, and only consumables have effects".to_string());
+    }
+    for effect in &def.effects {
+        let instant = matches!(effect.kind, ConsumableEffectKind::Heal | ConsumableEffectKind::RestoreMana);
+        if effect.amount < 0 || (!instant && !(1..=MAX_CONSUMABLE_EFFECT_SECS).contains(&effect.duration_secs)) {
+            return Err(format!(
+                "Effect amounts must be >= 0 and timed effects last 1-{} seconds",
+                MAX_CONSUMABLE_EFFECT_SECS
+            ));
+        }
+    }
+    if def.item_def_id != 0 && ctx.db.item_def().item_def_id().find(def.item_def_id).is_some() {
+        ctx.db.item_def().item_def_id().update(def);
+    } else {
+        ctx.db.item_def().insert(ItemDef { item_def_id: 0, ..def });
+    }
+    Ok(())
+}
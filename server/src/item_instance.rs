@@ -0,0 +1,148 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - item_instance.rs
+ *
+ * Rolled items: a copy of an equippable item definition with its own rarity
+ * and affixes, so two swords from the same boss need not be the same sword.
+ *
+ * Key components:
+ * - ItemInstance table (public): The definition it was rolled from, its
+ *   Rarity, its Affixes and its durability (durability.rs). Instances don't
+ *   record an owner; whatever holds the instance_id (an inventory slot, a
+ *   drop, a bank slot, an escrow) does. Instanced items never stack
+ * - roll_instance: Rolls a rarity (RARITY_WEIGHTS) and, for weapons, one
+ *   distinct affix per rarity tier above Common with values that grow with
+ *   the tier. Called when loot is generated (loot.rs roll_loot). Armor has
+ *   no stats to modify yet, so it only rolls a rarity
+ * - apply_affixes: The definition with an instance's affixes applied. Combat
+ *   reads the equipped weapon through it (item.rs equipped_weapon)
+ * - discard: Deletes an instance when the item leaves the game (drops
+ *   decaying, vendor sales)
+ *
+ * Related files:
+ * - item.rs: The definitions instances are rolled from
+ * - loot.rs / container.rs: Where instances are rolled
+ * - durability.rs: Wear and repairs
+ * - inventory.rs / equipment.rs: Where they are carried and worn
+ */
+
+use spacetimedb::rand::Rng;
+use spacetimedb::{ReducerContext, SpacetimeType, Table, Timestamp};
+
+use crate::common::{
+    AFFIX_ATTACK_SPEED_PER_TIER, AFFIX_DAMAGE_PER_TIER, AFFIX_REACH_PER_TIER, ITEM_MAX_DURABILITY, RARITY_WEIGHTS,
+};
+use crate::item::{ItemDef, ItemKind};
+
+#[derive(SpacetimeType, Clone, Copy, Debug, PartialEq)]
+pub enum Rarity {
+    Common,
+    Uncommon,
+    Rare,
+    Epic,
+    Legendary,
+}
+
+const RARITIES: [Rarity; 5] = [Rarity::Common, Rarity::Uncommon, Rarity::Rare, Rarity::Epic, Rarity::Legendary];
+
+#[derive(SpacetimeType, Clone, Copy, Debug, PartialEq)]
+pub enum AffixKind {
+    Damage, // Flat weapon damage
+    AttackSpeed, // Percent off the attack cooldown
+    Reach, // Percent more weapon range
+}
+
+const AFFIX_KINDS: [AffixKind; 3] = [AffixKind::Damage, AffixKind::AttackSpeed, AffixKind::Reach];
+
+#[derive(SpacetimeType, Clone, Debug, PartialEq)]
+pub struct Affix {
+    pub kind: AffixKind,
+    pub value: i32,
+}
+
+#[spacetimedb::table(name = item_instance, public)]
+#[derive(Clone)]
+pub struct ItemInstance {
+    #[primary_key]
+    #[auto_inc]
+    pub instance_id: u64,
+    pub item_def_id: u64,
+    pub rarity: Rarity,
+    pub affixes: Vec<Affix>,
+    pub durability: u32, // 0 = broken
+    pub max_durability: u32,
+    pub created_at: Timestamp,
+}
+
+// Common is tier 0, Legendary tier 4
+fn tier(rarity: Rarity) -> u32 {
+    RARITIES.iter().position(|r| *r == rarity).unwrap_or(0) as u32
+}
+
+fn roll_rarity(rng: &mut impl Rng) -> Rarity {
+    let total: u32 = RARITY_WEIGHTS.iter().sum();
+    let mut roll = rng.gen_range(0..total);
+    for (rarity, weight) in RARITIES.iter().zip(RARITY_WEIGHTS) {
+        if roll < weight {
+            return *rarity;
+        }
+        roll -= weight;
+    }
+    Rarity::Common
+}
+
+// Each affix rolls between the tier's (low, high) range per tier
+fn roll_value(rng: &mut impl Rng, kind: AffixKind, tier: u32) -> i32 {
+    let (low, high) = match kind {
+        AffixKind::Damage => AFFIX_DAMAGE_PER_TIER,
+        AffixKind::AttackSpeed => AFFIX_ATTACK_SPEED_PER_TIER,
+        AffixKind::Reach => AFFIX_REACH_PER_TIER,
+    };
+    rng.gen_range(low * tier as i32..=high * tier as i32)
+}
+
+// Roll a new instance of `def`
+pub fn roll_instance(ctx: &ReducerContext, def: &ItemDef) -> ItemInstance {
+    let mut rng = ctx.rng();
+    let rarity = roll_rarity(&mut rng);
+    let tier = tier(rarity);
+    let mut affixes = Vec::new();
+    if def.kind == ItemKind::Weapon {
+        let mut kinds = AFFIX_KINDS.to_vec();
+        while affixes.len() < tier as usize && !kinds.is_empty() {
+            let kind = kinds.swap_remove(rng.gen_range(0..kinds.len()));
+            affixes.push(Affix { kind, value: roll_value(&mut rng, kind, tier) });
+        }
+    }
+    ctx.db.item_instance().insert(ItemInstance {
+        instance_id: 0,
+        item_def_id: def.item_def_id,
+        rarity,
+        affixes,
+        durability: ITEM_MAX_DURABILITY,
+        max_durability: ITEM_MAX_DURABILITY,
+        created_at: ctx.timestamp,
+    })
+}
+
+pub fn apply_affixes(ctx: &ReducerContext, mut def: ItemDef, instance_id: Option<u64>) -> ItemDef {
+    let Some(instance) = instance_id.and_then(|id| ctx.db.item_instance().instance_id().find(id)) else {
+        return def;
+    };
+    for affix in &instance.affixes {
+        match affix.kind {
+            AffixKind::Damage => def.damage += affix.value,
+            AffixKind::AttackSpeed => {
+                let percent = 100 - affix.value.clamp(0, 90) as u64;
+                def.attack_cooldown_ms = def.attack_cooldown_ms * percent / 100;
+            }
+            AffixKind::Reach => def.range *= 1.0 + affix.value as f32 / 100.0,
+        }
+    }
+    def
+}
+
+pub fn discard(ctx: &ReducerContext, instance_id: Option<u64>) {
+    if let Some(instance_id) = instance_id {
+        ctx.db.item_instance().instance_id().delete(instance_id);
+    }
+}
@@ -0,0 +1,94 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - jobs.rs
+ *
+ * Dead-letter handling for scheduled work. Scheduled reducers run their
+ * per-item work through run_job; a failure is captured in the failed_job
+ * table with its payload and error instead of being logged and lost, and an
+ * admin can retry it later.
+ *
+ * Key components:
+ * - JobPayload: Everything needed to re-run one unit of scheduled work
+ * - FailedJob table (private): Payload, last error, attempt count
+ * - run_job: Executes a payload, dead-lettering it on failure. Work must check
+ *   everything that can fail before it writes, since the reducer still commits
+ * - retry_failed_job / discard_failed_job: Admin reducers
+ *
+ * Related files:
+ * - spawn.rs: Respawns run as jobs
+ * - admin.rs: require_admin
+ */
+
+use spacetimedb::{Identity, ReducerContext, SpacetimeType, Table, Timestamp};
+
+use crate::admin;
+use crate::spawn;
+
+#[derive(SpacetimeType, Clone, Debug, PartialEq)]
+pub enum JobPayload {
+    Respawn(Identity),
+}
+
+#[spacetimedb::table(name = failed_job)]
+#[derive(Clone)]
+pub struct FailedJob {
+    #[primary_key]
+    #[auto_inc]
+    pub job_id: u64,
+    pub payload: JobPayload,
+    pub error: String,
+    pub attempts: u32,
+    pub first_failed_at: Timestamp,
+    pub last_failed_at: Timestamp,
+}
+
+fn execute(ctx: &ReducerContext, payload: &JobPayload) -> Result<(), String> {
+    match payload {
+        JobPayload::Respawn(identity) => spawn::revive_player(ctx, *identity),
+    }
+}
+
+pub fn run_job(ctx: &ReducerContext, payload: JobPayload) {
+    if let Err(error) = execute(ctx, &payload) {
+        spacetimedb::log::warn!("Job {:?} failed: {}", payload, error);
+        ctx.db.failed_job().insert(FailedJob {
+            job_id: 0,
+            payload,
+            error,
+            attempts: 1,
+            first_failed_at: ctx.timestamp,
+            last_failed_at: ctx.timestamp,
+        });
+    }
+}
+
+// Re-run a dead-lettered job. Succeeds (and records the new error) even if
+// the job fails again, so the attempt is kept.
+#[spacetimedb::reducer]
+pub fn retry_failed_job(ctx: &ReducerContext, job_id: u64) -> Result<(), String> {
+    admin::require_admin(ctx)?;
+    let Some(mut job) = ctx.db.failed_job().job_id().find(job_id) else {
+        return Err("Job not found".to_string());
+    };
+    match execute(ctx, &job.payload) {
+        Ok(()) => {
+            spacetimedb::log::info!("Job {} ({:?}) succeeded on retry", job_id, job.payload);
+            ctx.db.failed_job().job_id().delete(job_id);
+        }
+        Err(error) => {
+            job.error = error;
+            job.attempts += 1;
+            job.last_failed_at = ctx.timestamp;
+            ctx.db.failed_job().job_id().update(job);
+        }
+    }
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn discard_failed_job(ctx: &ReducerContext, job_id: u64) -> Result<(), String> {
+    admin::require_admin(ctx)?;
+    if !ctx.db.failed_job().job_id().delete(job_id) {
+        return Err("Job not found".to_string());
+    }
+    Ok(())
+}
@@ -1,4 +1,4 @@
-/**
+/*!
  * Vibe Coding Starter Pack: 3D Multiplayer - lib.rs
  * 
  * Main entry point for the SpacetimeDB module. This file contains:
@@ -6,14 +6,19 @@
  * 1. Database Schema:
  *    - PlayerData: Active player information
  *    - LoggedOutPlayerData: Persistent data for disconnected players
- *    - GameTickSchedule: Periodic update scheduling
+ *    - GameTickSchedule: Periodic update scheduling, one row per room
+ *    - Room / Npc / CombatLogEntry / DpsMeter: Defined in their own modules
  * 
  * 2. Reducer Functions (Server Endpoints):
  *    - init: Module initialization and game tick scheduling
  *    - identity_connected/disconnected: Connection lifecycle management
  *    - register_player: Player registration with username and character class
- *    - update_player_input: Processes player movement and state updates
- *    - game_tick: Periodic update for game state (scheduled)
+ *    - update_player_input: Processes player movement and state updates (and melee/spell attacks)
+ *    - equip_item / unequip_item: Equipment slots (see equipment.rs)
+ *    - set_team: Chooses the player's team (used by friendly-fire rules)
+ *    - fire_hitscan: Lag-compensated instant-hit shot (see hitscan.rs)
+ *    - game_tick: Periodic update for one room's game state (scheduled per room)
+ *    - respawn_player: Revives dead players (scheduled, see spawn.rs)
  * 
  * 3. Table Structure:
  *    - All tables use Identity as primary keys where appropriate
@@ -22,28 +27,197 @@
  * When modifying:
  *    - Table changes require regenerating TypeScript bindings
  *    - Add `public` tag to tables that need client access
+ *    - Add a btree index to any column filtered on a hot path (per tick or per
+ *      input) instead of scanning with iter()
  *    - New reducers should follow naming convention and error handling patterns
  *    - Game logic should be placed in separate modules (like player_logic.rs)
- *    - Extend game_tick for gameplay systems that need periodic updates
+ *    - Extend game_tick for gameplay systems that need periodic updates; keep
+ *      every step scoped to the ticking room (filter by the room_name index)
  * 
  * Related files:
  *    - common.rs: Shared data structures used in table definitions
  *    - player_logic.rs: Player movement and state update calculations
+ *    - room.rs: Rooms and the default hub room
+ *    - npc.rs: Non-player entities such as training dummies
+ *    - combat.rs: Damage pipeline, combat log and DPS meters
+ *    - spawn.rs: Spawn points and respawn scheduling
+ *    - admin.rs: Admin identities for operator-only reducers
+ *    - npc_ai.rs: Data-driven AI personalities and the NPC AI tick
+ *    - noise.rs: Noise events that let NPCs hear players
+ *    - perception.rs: NPC vision cones and detection meters
+ *    - steering.rs: Separation post-pass so NPCs don't stack
+ *    - projectile.rs: Pooled projectiles with batched grid collision
+ *    - spatial.rs: Broad-phase grid index used for proximity queries
+ *    - hitscan.rs: Lag-compensated hitscan weapon and position history
+ *    - status.rs: Timed status effects (heal-over-time)
+ *    - support.rs: Healing and the Paladin heal spell
+ *    - vote.rs: Per-room vote counters
+ *    - item.rs: Item definitions and equipped weapons
+ *    - squish.rs: Admin season power squish (with dry-run report)
+ *    - experiment.rs: A/B experiment flags with identity bucketing
+ *    - combo.rs: Class melee combo chains and combo events
+ *    - config.rs: Server config and per-subsystem feature flags
+ *    - jobs.rs: Dead-letter table and admin retry for failed scheduled work
+ *    - destructible.rs: Destructible props that take damage like any combatant
+ *    - map.rs: Map tile sets and live room map migration
+ *    - trap.rs: Player-placed traps with per-class limits
+ *    - party.rs: Parties and leader-driven party travel between rooms
+ *    - duel.rs: Opt-in 1v1 duels in non-PvP rooms
+ *    - hazard.rs: Damaging terrain tiles (burns, slows) and periodic hazards
+ *    - observer.rs: Read-only summary tables for ops dashboards
+ *    - notification.rs: Per-player notification inbox
+ *    - revive.rs: Channelled revives of dead teammates
+ *    - scoreboard.rs: Per-room combat totals for scoreboards
+ *    - loadout.rs: Per-room starting weapons and allowed abilities
+ *    - spawner.rs: NPC spawners that keep a room's population alive
+ *    - campaign.rs: Co-op campaign checkpoints saved and resumed by party leaders
+ *    - pathfinding.rs: Budgeted grid A* for chasing NPCs
+ *    - boss.rs: Phased boss encounters with participant loot
+ *    - objective.rs: Objective zones with photo-finish tie-breaking
+ *    - dialogue.rs: Branching NPC conversations
+ *    - shop.rs: Vendor NPCs and their listings
+ *    - patrol.rs: Shared NPC patrol routes with pauses
+ *    - critter.rs: Ambient wildlife that wanders and flees
+ *    - loot.rs: Loot tables and item drops
+ *    - escort.rs: Escorting NPCs to a destination
+ *    - faction.rs: Factions and the hostility matrix
+ *    - npc_script.rs: Data-driven NPC behavior scripts
+ *    - population.rs: NPC health/damage scaling by room population
+ *    - pet.rs: Taming NPCs into pets
+ *    - safe_zone.rs: PvP-free zones enforced by guard NPCs
+ *    - npc_persistent.rs: Notable NPCs restored across module restarts
+ *    - chunk.rs: Chunked world streaming of game tiles
+ *    - mapgen.rs: Seeded procedural maps for rooms
+ *    - build.rs: Placing and removing terrain blocks at runtime
+ *    - door.rs: Doors and the switches that open them
+ *    - pickup.rs: Respawning collectibles placed in the world
+ *    - weather.rs: Per-room weather and its effect on movement and NPC vision
+ *    - region.rs: Named areas and region_enter announcements
+ *    - prop.rs: Pushable physics props (crates, barrels)
+ *    - container.rs: Lootable chests, shared or rolled per player
+ *    - teleporter.rs: Pads that send players elsewhere in the room
+ *    - snapshot.rs: Saving and restoring a room's built world
+ *    - heightmap.rs: Optional per-chunk heightmaps for rolling terrain
+ *    - light.rs: Light sources, with street lamps that follow the day/night cycle
+ *    - decoration.rs: Cosmetic furniture placed by room owners
+ *    - protection.rs: Ownership of player-placed objects, with grief reports
+ *    - sound.rs: Positional sound emitters and gameplay audio cues
+ *    - checkpoint.rs: Checkpoints that catch falls and hazard deaths
+ *    - theme.rs: Data-driven seasonal themes with a scheduled rollover
+ *    - minimap.rs: Per-chunk terrain flags for drawing a minimap
+ *    - gc.rs: Scheduled cleanup of rows whose room or parent row is gone
+ *    - inventory.rs: Player inventories with slots and item stacks
+ *    - equipment.rs: Equipment slots with class and level requirements
+ *    - progression.rs: Character levels
+ *    - wallet.rs: Player gold with a transaction log
+ *    - trade.rs: Player-to-player trades with escrow
+ *    - consumable.rs: Potions and food and their effects
+ *    - bank.rs: Per-player bank storage reached at bankers
+ *    - market.rs: Auction house listings with escrow and expiry
+ *    - item_instance.rs: Rolled items with rarity and affixes
+ *    - durability.rs: Gear wear, breaking and blacksmith repairs
+ *    - gathering.rs: Mining and herbalism skills and resource nodes
+ *    - loot_roll.rs: Need / greed rolls on party loot
+ *    - wardrobe.rs: Cosmetic unlocks and the appearance players show
  */
 
 // Declare modules
 mod common;
 mod player_logic;
+mod room;
+mod npc;
+mod combat;
+mod spawn;
+mod admin;
+mod npc_ai;
+mod noise;
+mod perception;
+mod steering;
+mod projectile;
+mod spatial;
+mod hitscan;
+mod status;
+mod support;
+mod vote;
+mod item;
+mod squish;
+mod experiment;
+mod combo;
+mod config;
+mod jobs;
+mod destructible;
+mod map;
+mod trap;
+mod party;
+mod duel;
+mod hazard;
+mod observer;
+mod notification;
+mod revive;
+mod scoreboard;
+mod loadout;
+mod spawner;
+mod campaign;
+mod pathfinding;
+mod boss;
+mod objective;
+mod dialogue;
+mod shop;
+mod patrol;
+mod critter;
+mod loot;
+mod escort;
+mod faction;
+mod npc_script;
+mod population;
+mod pet;
+mod safe_zone;
+mod npc_persistent;
+mod chunk;
+mod mapgen;
+mod build;
+mod door;
+mod pickup;
+mod weather;
+mod region;
+mod prop;
+mod container;
+mod teleporter;
+mod snapshot;
+mod heightmap;
+mod light;
+mod decoration;
+mod protection;
+mod sound;
+mod checkpoint;
+mod theme;
+mod minimap;
+mod gc;
+mod inventory;
+mod equipment;
+mod progression;
+mod wallet;
+mod trade;
+mod consumable;
+mod bank;
+mod market;
+mod item_instance;
+mod durability;
+mod gathering;
+mod loot_roll;
+mod wardrobe;
 
 use spacetimedb::{ReducerContext, Identity, Table, Timestamp, ScheduleAt};
 use std::time::Duration; // Import standard Duration
 
 // Use items from common module (structs are needed for table definitions)
-use crate::common::{Vector3, InputState};
+use crate::common::{Vector3, TileHazard, InputState, ACTION_ATTACK, ACTION_CAST_SPELL, ACTION_JUMP, EntityId, DEFAULT_HUB_ROOM, NO_TEAM, MAX_TEAM, JUMP_NOISE_RADIUS, MAX_BLOCK_STAMINA, MAX_STAMINA, MELEE_STAMINA_COST, PLAYER_COLORS};
+use crate::spatial::SpatialEntity;
+use crate::loadout::Ability;
 
 // --- Schema Definitions ---
 
-#[spacetimedb::table(name = game_tile, public)]
+#[spacetimedb::table(name = game_tile, public, index(name = map_cell, btree(columns = [map_id, grid_cell])))]
 #[derive(Clone)]
 pub struct GameTile {
     #[primary_key]
@@ -51,6 +225,14 @@ pub struct GameTile {
     tile_id: u64,
     position: Vector3,
     size: Vector3,
+    map_id: u32, // Tile set this tile belongs to (see map.rs)
+    #[index(btree)]
+    room_name: String, // Room instance the tile belongs to; empty for authored map sources (see map.rs)
+    #[index(btree)]
+    chunk_id: u64, // World chunk the tile is streamed with (see chunk.rs)
+    hazard: Option<TileHazard>, // Damaging terrain (see hazard.rs)
+    grid_cell: i64, // Spatial grid cell of the tile's center (see spatial.rs)
+    placed_by: Option<Identity>, // Player who built the block (see build.rs); None for map terrain
 }
 
 #[spacetimedb::table(name = player, public)]
@@ -74,8 +256,30 @@ pub struct PlayerData {
     last_input_seq: u32,
     input: InputState,
     color: String,
+    #[index(btree)]
     has_voted: bool,
     current_vote: String,
+    #[index(btree)]
+    room_name: String,
+    is_dead: bool,
+    team: u8,
+    is_blocking: bool,
+    block_stamina: i32,
+    max_block_stamina: i32,
+    stamina: i32,
+    max_stamina: i32,
+    block_started_at: Option<Timestamp>,
+    staggered_until: Option<Timestamp>,
+    last_damager: Option<EntityId>,
+    last_damaged_at: Option<Timestamp>,
+    last_shot_at: Option<Timestamp>, // Hitscan cooldown
+    last_attack_at: Option<Timestamp>, // Melee cooldown (weapon attack speed)
+    last_potion_at: Option<Timestamp>, // Shared potion cooldown (consumable.rs)
+    invulnerable_until: Timestamp, // I-frames after respawn and big hits
+    combo_stage: u8, // Next step of the class combo chain (combo.rs)
+    combo_expires_at: Option<Timestamp>,
+    current_region: Option<u64>, // Named region the player is in (region.rs)
+    last_checkpoint: Option<u64>, // Checkpoint falls and hazard deaths return to (checkpoint.rs)
 }
 
 #[spacetimedb::table(name = logged_out_player)]
@@ -100,6 +304,25 @@ pub struct GameTickSchedule {
     #[auto_inc]
     scheduled_id: u64,
     scheduled_at: ScheduleAt,
+    #[unique]
+    room_name: String,
+}
+
+// Give a room its own tick schedule (no-op if it already has one)
+pub fn schedule_game_tick(ctx: &ReducerContext, room_name: &str) {
+    if ctx.db.game_tick_schedule().room_name().find(room_name.to_string()).is_some() {
+        return;
+    }
+    let loop_duration = Duration::from_secs(1);
+    let schedule = GameTickSchedule {
+        scheduled_id: 0,
+        scheduled_at: ScheduleAt::Interval(loop_duration.into()),
+        room_name: room_name.to_string(),
+    };
+    match ctx.db.game_tick_schedule().try_insert(schedule) {
+        Ok(row) => spacetimedb::log::info!("Game tick for room '{}' scheduled. ID: {}", room_name, row.scheduled_id),
+        Err(e) => spacetimedb::log::error!("FAILED to insert game tick schedule for room '{}': {}", room_name, e),
+    }
 }
 
 // --- Lifecycle Reducers ---
@@ -107,43 +330,20 @@ pub struct GameTickSchedule {
 #[spacetimedb::reducer(init)]
 pub fn init(ctx: &ReducerContext) -> Result<(), String> {
     spacetimedb::log::info!("[INIT] Initializing Vibe Multiplayer module...");
-    if ctx.db.game_tick_schedule().count() == 0 {
-        spacetimedb::log::info!("[INIT] Scheduling initial game tick (every 1 second)...");
-        let loop_duration = Duration::from_secs(1);
-        let schedule = GameTickSchedule {
-            scheduled_id: 0,
-            scheduled_at: ScheduleAt::Interval(loop_duration.into()),
-        };
-        match ctx.db.game_tick_schedule().try_insert(schedule) {
-            Ok(row) => spacetimedb::log::info!("[INIT] Game tick schedule inserted successfully. ID: {}", row.scheduled_id),
-            Err(e) => spacetimedb::log::error!("[INIT] FAILED to insert game tick schedule: {}", e),
-        }
-    }
-
-    // Initialize game tiles if none exist
-    if ctx.db.game_tile().count() == 0 {
-        spacetimedb::log::info!("[INIT] Creating initial game tiles...");
-        
-        let tiles = vec![
-            (-20..=20).flat_map(|x| {
-                (-20..=20).map(move |z| {
-                    GameTile {
-                        tile_id: 0,
-                        position: Vector3 { x: x as f32 * 10.0, y: 0.0, z: z as f32 * 10.0 },
-                        size: Vector3 { x: 10.0, y: 1.0, z: 10.0 },
-                    }
-                })
-            }).collect::<Vec<_>>(),
-        ].into_iter().flatten();
-
-        for tile in tiles {
-            if let Err(e) = ctx.db.game_tile().try_insert(tile) {
-                spacetimedb::log::error!("[INIT] Failed to insert tile: {}", e);
-            }
-        }
-        
-        spacetimedb::log::info!("[INIT] Game tiles created successfully");
-    }
+    admin::seed_admin(ctx);
+    config::seed_server_config(ctx);
+    combat::schedule_log_pruning(ctx);
+    observer::schedule_refresh(ctx);
+    weather::schedule_weather(ctx);
+    theme::schedule_themes(ctx);
+    gc::schedule_gc(ctx);
+    npc_ai::seed_personalities(ctx);
+    faction::seed_factions(ctx);
+    item::seed_item_defs(ctx);
+    mapgen::seed_map_templates(ctx);
+    // Creating a room also schedules its game tick (every 1 second)
+    room::ensure_default_rooms(ctx);
+    npc_persistent::restore_persistent_npcs(ctx);
 
     Ok(())
 }
@@ -176,6 +376,20 @@ pub fn identity_disconnected(ctx: &ReducerContext) {
         };
         ctx.db.logged_out_player().insert(logged_out_player);
         ctx.db.player().identity().delete(player_identity);
+        room::on_player_left(ctx, &player.room_name, player.has_voted);
+        combat::clear_dps_meter(ctx, player_identity);
+        scoreboard::clear_player(ctx, player_identity);
+        combat::clear_threat_from_player(ctx, player_identity);
+        spatial::untrack(ctx, &SpatialEntity::Player(player_identity));
+        hitscan::clear_history(ctx, player_identity);
+        status::clear_player(ctx, player_identity);
+        party::on_player_disconnected(ctx, player_identity);
+        dialogue::clear_player(ctx, player_identity);
+        chunk::clear_player(ctx, player_identity);
+        build::clear_player(ctx, player_identity);
+        teleporter::clear_player(ctx, player_identity);
+        trade::on_player_disconnected(ctx, &player);
+        equipment::clear_loadout_items(ctx, player_identity);
     } else {
         spacetimedb::log::warn!("Disconnect by player {} not found in active player table.", player_identity);
         if let Some(mut logged_out_player) = ctx.db.logged_out_player().identity().find(player_identity) {
@@ -203,24 +417,22 @@ pub fn register_player(ctx: &ReducerContext, username: String, character_class:
         return;
     }
 
-    // Assign color and position based on current player count
-    let player_count = ctx.db.player().iter().count();
-    let colors = ["cyan", "magenta", "yellow", "lightgreen", "white", "orange"];
-    let assigned_color = colors[player_count % colors.len()].to_string();
-    let spawn_position = Vector3 { x: (player_count as f32 * 5.0) - 2.5, y: 1.0, z: 0.0 };
+    // Keep the color the player picked (wardrobe.rs), or assign one based on
+    // current player count; new players appear at one of the hub's spawn
+    // points
+    let player_count = ctx.db.player().count() as usize;
+    let assigned_color = wardrobe::chosen_color(ctx, player_identity)
+        .unwrap_or_else(|| PLAYER_COLORS[player_count % PLAYER_COLORS.len()].to_string());
+    let spawn_position = spawn::select_spawn_point(ctx, DEFAULT_HUB_ROOM, player_identity);
 
     if let Some(logged_out_player) = ctx.db.logged_out_player().identity().find(player_identity) {
         spacetimedb::log::info!("Player {} is rejoining.", player_identity);
-        let default_input = InputState {
-            forward: false, backward: false, left: false, right: false,
-            sprint: false, jump: false, attack: false, cast_spell: false,
-            sequence: 0
-        };
+        let default_input = InputState::idle();
         let rejoining_player = PlayerData {
             identity: logged_out_player.identity,
             username: logged_out_player.username.clone(),
             character_class: logged_out_player.character_class.clone(),
-            position: spawn_position,
+            position: spawn_position.clone(),
             rotation: logged_out_player.rotation.clone(),
             health: logged_out_player.health,
             max_health: logged_out_player.max_health,
@@ -236,21 +448,42 @@ pub fn register_player(ctx: &ReducerContext, username: String, character_class:
             color: assigned_color,
             has_voted: false,
             current_vote: String::new(),
+            room_name: DEFAULT_HUB_ROOM.to_string(),
+            is_dead: logged_out_player.health <= 0,
+            team: NO_TEAM,
+            is_blocking: false,
+            block_stamina: MAX_BLOCK_STAMINA,
+            max_block_stamina: MAX_BLOCK_STAMINA,
+            stamina: MAX_STAMINA,
+            max_stamina: MAX_STAMINA,
+            block_started_at: None,
+            staggered_until: None,
+            last_damager: None,
+            last_damaged_at: None,
+            last_shot_at: None,
+            last_attack_at: None,
+            last_potion_at: None,
+            invulnerable_until: ctx.timestamp,
+            combo_stage: 0,
+            combo_expires_at: None,
+            current_region: None,
+            last_checkpoint: None,
         };
+        let rejoined_dead = rejoining_player.is_dead;
         ctx.db.player().insert(rejoining_player);
         ctx.db.logged_out_player().identity().delete(player_identity);
+        if rejoined_dead {
+            // The original respawn schedule was dropped while they were offline
+            spawn::schedule_respawn(ctx, player_identity);
+        }
     } else {
         spacetimedb::log::info!("Registering new player {}.", player_identity);
-        let default_input = InputState {
-            forward: false, backward: false, left: false, right: false,
-            sprint: false, jump: false, attack: false, cast_spell: false,
-            sequence: 0
-        };
+        let default_input = InputState::idle();
         ctx.db.player().insert(PlayerData {
             identity: player_identity,
             username,
             character_class,
-            position: spawn_position,
+            position: spawn_position.clone(),
             rotation: Vector3 { x: 0.0, y: 0.0, z: 0.0 },
             health: 100,
             max_health: 100,
@@ -266,8 +499,31 @@ pub fn register_player(ctx: &ReducerContext, username: String, character_class:
             color: assigned_color,
             has_voted: false,
             current_vote: String::new(),
+            room_name: DEFAULT_HUB_ROOM.to_string(),
+            is_dead: false,
+            team: NO_TEAM,
+            is_blocking: false,
+            block_stamina: MAX_BLOCK_STAMINA,
+            max_block_stamina: MAX_BLOCK_STAMINA,
+            stamina: MAX_STAMINA,
+            max_stamina: MAX_STAMINA,
+            block_started_at: None,
+            staggered_until: None,
+            last_damager: None,
+            last_damaged_at: None,
+            last_shot_at: None,
+            last_attack_at: None,
+            last_potion_at: None,
+            invulnerable_until: ctx.timestamp,
+            combo_stage: 0,
+            combo_expires_at: None,
+            current_region: None,
+            last_checkpoint: None,
         });
     }
+    spatial::track(ctx, SpatialEntity::Player(player_identity), DEFAULT_HUB_ROOM, &spawn_position);
+    room::on_player_joined(ctx, DEFAULT_HUB_ROOM, false);
+    loadout::apply_loadout(ctx, player_identity, DEFAULT_HUB_ROOM);
 }
 
 #[spacetimedb::reducer]
@@ -279,21 +535,121 @@ pub fn update_player_input(
     client_animation: String,
 ) {
     if let Some(mut player) = ctx.db.player().identity().find(ctx.sender) {
-        player_logic::update_input_state(&mut player, input, client_rot, client_animation);
-        ctx.db.player().identity().update(player);
+        if player.is_dead {
+            return;
+        }
+        let was_attacking = player.input.has(ACTION_ATTACK);
+        let was_casting = player.input.has(ACTION_CAST_SPELL);
+        let was_jumping = player.input.has(ACTION_JUMP);
+        let was_blocking = player.is_blocking;
+        player_logic::update_input_state(ctx, &mut player, input, client_rot, client_animation);
+        if player.is_blocking && !was_blocking {
+            player.block_started_at = Some(ctx.timestamp);
+        }
+        // Staggered players (e.g. after being parried) can't start new attacks
+        let staggered = player.staggered_until.is_some_and(|until| until > ctx.timestamp);
+        let combat_enabled = config::combat_enabled(ctx);
+        // Swings are limited by stamina and the equipped weapon's attack speed
+        let weapon = item::equipped_weapon(ctx, player.identity);
+        let weapon_ready = weapon.as_ref().is_some_and(|w| {
+            let cooldown = Duration::from_millis(w.attack_cooldown_ms);
+            player.last_attack_at.is_none_or(|at| at + cooldown <= ctx.timestamp)
+        });
+        let started_attack = player.input.has(ACTION_ATTACK) && !was_attacking && !staggered && combat_enabled
+            && player.stamina >= MELEE_STAMINA_COST && weapon_ready
+            && loadout::ability_allowed(ctx, &player.room_name, Ability::Melee);
+        if started_attack {
+            player.stamina -= MELEE_STAMINA_COST;
+            player.last_attack_at = Some(ctx.timestamp);
+        }
+        let started_cast = player.input.has(ACTION_CAST_SPELL) && !was_casting && !staggered && combat_enabled
+            && loadout::ability_allowed(ctx, &player.room_name, Ability::Spell);
+        let started_jump = player.input.has(ACTION_JUMP) && !was_jumping;
+        ctx.db.player().identity().update(player.clone());
+        objective::check_completions(ctx, &player);
+        hitscan::record_position(ctx, player.identity, &player.position);
+        if let Some(weapon) = weapon.as_ref().filter(|_| started_attack) {
+            combat::resolve_melee_attack(ctx, &player, weapon);
+        }
+        if started_cast {
+            combat::resolve_spell_cast(ctx, &player);
+        }
+        if started_jump {
+            // Jumps are short, so the landing noise is emitted at take-off
+            noise::emit_noise(ctx, &player.room_name, EntityId::Player(player.identity), &player.position, JUMP_NOISE_RADIUS);
+        }
     } else {
         spacetimedb::log::warn!("Player {} tried to update input but is not active.", ctx.sender);
     }
 }
 
+// Each room ticks independently from its own schedule row, so the work per
+// tick only touches that room's rows (everything is filtered by room_name)
 #[spacetimedb::reducer(update)]
-pub fn game_tick(ctx: &ReducerContext, _tick_info: GameTickSchedule) {
-    // Just use a simple log message without timestamp conversion
+pub fn game_tick(ctx: &ReducerContext, tick_info: GameTickSchedule) {
+    let room_name = tick_info.room_name.as_str();
+    let Some(room) = room::find_room(ctx, room_name) else {
+        spacetimedb::log::warn!("Dropping game tick for missing room '{}'.", room_name);
+        ctx.db.game_tick_schedule().scheduled_id().delete(tick_info.scheduled_id);
+        return;
+    };
     let delta_time = 1.0; // Fixed 1-second tick for simplicity
-    
-    player_logic::update_players_logic(ctx, delta_time);
-    
-    spacetimedb::log::debug!("Game tick completed");
+    observer::record_tick(ctx, room_name);
+    let room = room::advance_time_of_day(ctx, room, delta_time);
+    light::update_lights(ctx, &room);
+
+    player_logic::update_players_logic(ctx, room_name, delta_time);
+    teleporter::tick_teleporters(ctx, room_name);
+    chunk::stream_chunks(ctx, &room);
+    region::track_regions(ctx, room_name);
+    checkpoint::track_checkpoints(ctx, &room);
+    let combat_enabled = config::combat_enabled(ctx);
+    if combat_enabled {
+        status::tick_status_effects(ctx, room_name, delta_time);
+    }
+    noise::emit_movement_noise(ctx, room_name);
+    npc_ai::update_npcs(ctx, &room, delta_time);
+    critter::update_critters(ctx, &room);
+    escort::tick_escorts(ctx, room_name, delta_time);
+    pet::tick_pets(ctx, room_name, delta_time);
+    prop::step_props(ctx, room_name, room.map_id, delta_time as f32);
+    minimap::rebuild_dirty(ctx, &room);
+    spawner::tick_spawners(ctx, &room);
+    if combat_enabled {
+        boss::tick_bosses(ctx, room_name);
+        projectile::step_projectiles(ctx, room_name, delta_time);
+        combat::decay_threat(ctx, room_name, delta_time);
+        combat::update_dps_meters(ctx, room_name);
+        trap::tick_traps(ctx, room_name);
+        duel::tick_duels(ctx, room_name);
+        hazard::apply_tile_hazards(ctx, &room, delta_time);
+        hazard::tick_periodic_hazards(ctx, room_name);
+        revive::tick_revives(ctx, room_name);
+    }
+    objective::resolve_objective_claims(ctx, room_name);
+    combat::prune_aoe_events(ctx, room_name);
+    combo::prune_combo_events(ctx, room_name);
+    noise::prune_noise_events(ctx, room_name);
+    region::prune_region_events(ctx, room_name);
+    protection::prune_grief_reports(ctx, room_name);
+    sound::prune_sound_emitters(ctx, room_name);
+    trade::tick_trades(ctx, room_name);
+
+    spacetimedb::log::debug!("Game tick completed for room '{}'", room_name);
+}
+
+#[spacetimedb::reducer]
+pub fn set_team(ctx: &ReducerContext, team: u8) -> Result<(), String> {
+    if team > MAX_TEAM {
+        return Err(format!("Team must be between {} and {}", NO_TEAM, MAX_TEAM));
+    }
+    if let Some(mut player) = ctx.db.player().identity().find(ctx.sender) {
+        player.team = team;
+        ctx.db.player().identity().update(player);
+        Ok(())
+    } else {
+        Err("Player not found".to_string())
+    }
 }
 
 #[spacetimedb::reducer]
@@ -301,13 +657,16 @@ pub fn submit_vote(ctx: &ReducerContext, vote: String) -> Result<(), String> {
     let identity = ctx.sender;
     
     // Validate vote
-    let valid_votes = vec!["S", "M", "L", "XL"];
+    let valid_votes = ["S", "M", "L", "XL"];
     if !valid_votes.contains(&vote.as_str()) {
         return Err("Invalid vote. Must be one of: S, M, L, XL".to_string());
     }
 
     // Update player's vote
     if let Some(mut player) = ctx.db.player().identity().find(identity) {
+        if !player.has_voted {
+            vote::adjust_counts(ctx, &player.room_name, 0, 1);
+        }
         player.current_vote = vote;
         player.has_voted = true;
         ctx.db.player().identity().update(player);
@@ -319,13 +678,14 @@ pub fn submit_vote(ctx: &ReducerContext, vote: String) -> Result<(), String> {
 
 #[spacetimedb::reducer]
 pub fn reset_votes(ctx: &ReducerContext) -> Result<(), String> {
-    // Reset all players' votes
-    for player_id in ctx.db.player().iter().map(|p| p.identity).collect::<Vec<_>>() {
+    // Reset the votes of everyone who has voted
+    for player_id in ctx.db.player().has_voted().filter(true).map(|p| p.identity).collect::<Vec<_>>() {
         if let Some(mut player) = ctx.db.player().identity().find(player_id) {
             player.current_vote = String::new();
             player.has_voted = false;
             ctx.db.player().identity().update(player);
         }
     }
+    vote::reset_all(ctx);
     Ok(())
 }
@@ -0,0 +1,118 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - light.rs
+ *
+ * Light sources placed in rooms, kept on the server so every client lights
+ * the scene the same way.
+ *
+ * Key components:
+ * - LightSource table (public): Position, color (any CSS color the client
+ *   understands), radius, whether it flickers and whether it is on.
+ *   `night_only` lights are street lamps: the room's clock switches them
+ * - update_lights: Called from game_tick. Turns night-only lights on at dusk
+ *   and off at dawn (room.rs is_night); only lights whose state changes are
+ *   written
+ * - place_light / remove_light / set_light_on: Room-owner reducers.
+ *   set_light_on only applies to lights that aren't night-only
+ *
+ * Related files:
+ * - room.rs: time_of_day / is_night
+ * - room.rs: close_room clears a room's lights
+ */
+
+use spacetimedb::{ReducerContext, Table};
+
+use crate::common::{Vector3, MAX_LIGHTS_PER_ROOM, MAX_LIGHT_COLOR_LEN, MAX_LIGHT_RADIUS};
+use crate::room::{self, Room};
+
+#[spacetimedb::table(name = light_source, public)]
+#[derive(Clone)]
+pub struct LightSource {
+    #[primary_key]
+    #[auto_inc]
+    pub light_id: u64,
+    #[index(btree)]
+    pub room_name: String,
+    pub position: Vector3,
+    pub color: String,
+    pub radius: f32,
+    pub flicker: bool,
+    pub night_only: bool, // Switched by the day/night cycle
+    pub is_on: bool,
+}
+
+pub fn update_lights(ctx: &ReducerContext, room: &Room) {
+    let night = room::is_night(room);
+    let switched: Vec<LightSource> = ctx.db.light_source().room_name().filter(&room.room_name)
+        .filter(|light| light.night_only && light.is_on != night)
+        .collect();
+    for mut light in switched {
+        light.is_on = night;
+        ctx.db.light_source().light_id().update(light);
+    }
+}
+
+pub fn clear_room(ctx: &ReducerContext, room_name: &str) {
+    let lights: Vec<u64> = ctx.db.light_source().room_name().filter(room_name).map(|l| l.light_id).collect();
+    for light_id in lights {
+        ctx.db.light_source().light_id().delete(light_id);
+    }
+}
+
+// --- Light Reducers ---
+
+#[spacetimedb::reducer]
+pub fn place_light(
+    ctx: &ReducerContext,
+    room_name: String,
+    position: Vector3,
+    color: String,
+    radius: f32,
+    flicker: bool,
+    night_only: bool,
+) -> Result<(), String> {
+    let room = room::require_room_owner(ctx, &room_name)?;
+    if ctx.db.light_source().room_name().filter(&room_name).count() >= MAX_LIGHTS_PER_ROOM {
+        return Err(format!("A room can have at most {} lights", MAX_LIGHTS_PER_ROOM));
+    }
+    if color.is_empty() || color.len() > MAX_LIGHT_COLOR_LEN {
+        return Err(format!("Light colors must be 1-{} characters", MAX_LIGHT_COLOR_LEN));
+    }
+    if radius <= 0.0 || radius > MAX_LIGHT_RADIUS {
+        return Err(format!("Light radius must be between 0 and {}", MAX_LIGHT_RADIUS));
+    }
+    ctx.db.light_source().insert(LightSource {
+        light_id: 0,
+        room_name,
+        position,
+        color,
+        radius,
+        flicker,
+        night_only,
+        is_on: !night_only || room::is_night(&room),
+    });
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn remove_light(ctx: &ReducerContext, light_id: u64) -> Result<(), String> {
+    let Some(light) = ctx.db.light_source().light_id().find(light_id) else {
+        return Err("Light not found".to_string());
+    };
+    room::require_room_owner(ctx, &light.room_name)?;
+    ctx.db.light_source().light_id().delete(light_id);
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn set_light_on(ctx: &ReducerContext, light_id: u64, on: bool) -> Result<(), String> {
+    let Some(mut light) = ctx.db.light_source().light_id().find(light_id) else {
+        return Err("Light not found".to_string());
+    };
+    room::require_room_owner(ctx, &light.room_name)?;
+    if light.night_only {
+        return Err("This light follows the day/night cycle".to_string());
+    }
+    light.is_on = on;
+    ctx.db.light_source().light_id().update(light);
+    Ok(())
+}
@@ -0,0 +1,132 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - loadout.rs
+ *
+ * Per-room starting loadouts, so room owners can build scenarios such as
+ * "snipers only" or "swords only" without a new game mode.
+ *
+ * Key components:
+ * - RoomLoadout table (public): The weapon granted on spawn, whether players
+ *   may swap it, and which abilities (melee, spells, hitscan) are usable in
+ *   the room. Rooms without a row play with the normal rules
+ * - apply_loadout: Takes back whatever an earlier loadout granted, then
+ *   equips the room's weapon. Called when a player registers, enters a room
+ *   (room.rs move_player_to_room, so leaving a room or its closing returns
+ *   the weapon) and respawns, and for everyone present when the owner
+ *   changes the loadout. Granted weapons are marked from_loadout
+ *   (equipment.rs) and also taken back on disconnect
+ * - ability_allowed: Checked before melee swings, spell casts and hitscan shots
+ * - set_room_loadout / clear_room_loadout: Room-owner reducers
+ *
+ * Related files:
+ * - item.rs: Weapon definitions
+ * - equipment.rs: The equipped weapon slot
+ * - lib.rs / hitscan.rs: Ability checks
+ * - room.rs / spawn.rs: Room entry and respawn
+ */
+
+use spacetimedb::{Identity, ReducerContext, Table};
+
+use crate::equipment;
+use crate::item::{item_def as _, EquipSlot, ItemKind};
+use crate::player as _;
+use crate::room;
+
+#[spacetimedb::table(name = room_loadout, public)]
+#[derive(Clone)]
+pub struct RoomLoadout {
+    #[primary_key]
+    pub room_name: String,
+    pub weapon: Option<u64>, // item_def_id granted on spawn; None = keep your own
+    pub lock_weapon: bool,   // Players can't equip anything else while in the room
+    pub melee_enabled: bool,
+    pub spells_enabled: bool,
+    pub hitscan_enabled: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Ability {
+    Melee,
+    Spell,
+    Hitscan,
+}
+
+pub fn ability_allowed(ctx: &ReducerContext, room_name: &str, ability: Ability) -> bool {
+    let Some(loadout) = ctx.db.room_loadout().room_name().find(room_name.to_string()) else {
+        return true;
+    };
+    match ability {
+        Ability::Melee => loadout.melee_enabled,
+        Ability::Spell => loadout.spells_enabled,
+        Ability::Hitscan => loadout.hitscan_enabled,
+    }
+}
+
+// Whether the room's loadout forbids changing weapons
+pub fn weapon_locked(ctx: &ReducerContext, room_name: &str) -> bool {
+    ctx.db.room_loadout().room_name().find(room_name.to_string()).is_some_and(|l| l.lock_weapon)
+}
+
+// Grant the room's starting weapon (if it has one) to a player, in place
+// of anything another room's loadout granted
+pub fn apply_loadout(ctx: &ReducerContext, identity: Identity, room_name: &str) {
+    equipment::clear_loadout_items(ctx, identity);
+    let weapon = ctx.db.room_loadout().room_name().find(room_name.to_string())
+        .and_then(|loadout| loadout.weapon)
+        .and_then(|item_def_id| ctx.db.item_def().item_def_id().find(item_def_id));
+    if let Some(def) = weapon {
+        equipment::set_slot(ctx, identity, EquipSlot::Weapon, Some(&def), None, true);
+    }
+}
+
+// --- Loadout Reducers ---
+
+#[spacetimedb::reducer]
+pub fn set_room_loadout(
+    ctx: &ReducerContext,
+    room_name: String,
+    weapon: Option<u64>,
+    lock_weapon: bool,
+    melee_enabled: bool,
+    spells_enabled: bool,
+    hitscan_enabled: bool,
+) -> Result<(), String> {
+    room::require_room_owner(ctx, &room_name)?;
+    if let Some(item_def_id) = weapon {
+        let Some(def) = ctx.db.item_def().item_def_id().find(item_def_id) else {
+            return Err("Item not found".to_string());
+        };
+        if def.kind != ItemKind::Weapon {
+            return Err(format!("{} is not a weapon", def.name));
+        }
+    }
+    let loadout = RoomLoadout {
+        room_name: room_name.clone(),
+        weapon,
+        lock_weapon,
+        melee_enabled,
+        spells_enabled,
+        hitscan_enabled,
+    };
+    if ctx.db.room_loadout().room_name().find(room_name.clone()).is_some() {
+        ctx.db.room_loadout().room_name().update(loadout);
+    } else {
+        ctx.db.room_loadout().insert(loadout);
+    }
+    // Players already in the room get the new weapon right away
+    let present: Vec<Identity> = ctx.db.player().room_name().filter(&room_name).map(|p| p.identity).collect();
+    for identity in present {
+        apply_loadout(ctx, identity, &room_name);
+    }
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn clear_room_loadout(ctx: &ReducerContext, room_name: String) -> Result<(), String> {
+    room::require_room_owner(ctx, &room_name)?;
+    ctx.db.room_loadout().room_name().delete(room_name.clone());
+    let present: Vec<Identity> = ctx.db.player().room_name().filter(&room_name).map(|p| p.identity).collect();
+    for identity in present {
+        equipment::clear_loadout_items(ctx, identity);
+    }
+    Ok(())
+}
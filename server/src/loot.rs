@@ -0,0 +1,384 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - loot.rs
+ *
+ * Loot tables and the item drops they leave on the ground.
+ *
+ * Key components:
+ * - LootTable table (public): A named table rolled `rolls` times per drop,
+ *   plus gold for whoever the drop belongs to (wallet.rs)
+ * - LootEntry table (public): One weighted outcome of a table. Entries
+ *   without an item are "nothing" rolls
+ * - NpcLoot table (public): Which loot table an NPC drops when it dies
+ * - DroppedItem table (public): Items lying in a room, taken from within
+ *   `pickup_radius`. Drops from a kill belong to the killer and their party
+ *   until `owned_until`, then anyone can take them
+ * - DropDecaySchedule table: One-shot schedule row per drop; decay_drop
+ *   removes the drop (and its rolled item) at its `expires_at` if nobody
+ *   took it
+ * - roll_loot: Rolls a table with ctx.rng(). Every equippable item comes
+ *   out as its own rolled instance with a rarity and affixes
+ *   (item_instance.rs). Also fills containers, see container.rs
+ * - drop_loot: Spawns a roll's drops (called when NPCs die and destructibles
+ *   break). A party's drops go to need / greed rolls (loot_roll.rs)
+ * - spawn_drop: Puts items on the ground (drop_loot, and players dropping
+ *   items from their inventory, see inventory.rs)
+ * - pickup_item: Takes a drop within its pickup_radius into the inventory. A
+ *   drop that doesn't fit stays on the ground
+ * - upsert_loot_table / set_loot_entries (admin) / set_npc_loot_table
+ *   (room owner): Authoring
+ *
+ * Related files:
+ * - combat.rs: NPC deaths drop loot
+ * - destructible.rs: Broken props drop loot
+ * - party.rs: The killer's party shares the ownership window
+ * - item.rs: Item definitions and grant_item
+ * - item_instance.rs: Rarity and affixes
+ */
+
+use spacetimedb::rand::Rng;
+use spacetimedb::{Identity, ReducerContext, ScheduleAt, SpacetimeType, Table, Timestamp};
+use std::time::Duration;
+
+use crate::admin;
+use crate::common::{
+    Vector3, LOOT_DROP_LIFETIME_SECS, LOOT_OWNERSHIP_SECS, LOOT_SCATTER_RADIUS, MAX_LOOT_ENTRIES, MAX_LOOT_ROLLS,
+    PICKUP_RANGE,
+};
+use crate::item::{self, item_def as _};
+use crate::item_instance;
+use crate::loot_roll;
+use crate::npc::npc as _;
+use crate::party;
+use crate::player as _;
+use crate::room;
+use crate::wallet::{self, WalletReason};
+
+#[derive(SpacetimeType, Clone, Debug, PartialEq)]
+pub struct LootEntrySpec {
+    pub item_def_id: Option<u64>, // None = the roll drops nothing
+    pub weight: u32,
+    pub quantity: u32,
+}
+
+#[spacetimedb::table(name = loot_table, public)]
+#[derive(Clone)]
+pub struct LootTable {
+    #[primary_key]
+    #[auto_inc]
+    pub loot_table_id: u64,
+    pub name: String,
+    pub rolls: u32,
+    pub gold: u64, // Paid straight into the owner's wallet; ownerless drops pay none
+}
+
+#[spacetimedb::table(name = loot_entry, public)]
+#[derive(Clone)]
+pub struct LootEntry {
+    #[primary_key]
+    #[auto_inc]
+    pub entry_id: u64,
+    #[index(btree)]
+    pub loot_table_id: u64,
+    pub item_def_id: Option<u64>,
+    pub weight: u32,
+    pub quantity: u32,
+}
+
+#[spacetimedb::table(name = npc_loot, public)]
+#[derive(Clone)]
+pub struct NpcLoot {
+    #[primary_key]
+    pub npc_id: u64,
+    pub loot_table_id: u64,
+}
+
+#[spacetimedb::table(name = dropped_item, public)]
+#[derive(Clone)]
+pub struct DroppedItem {
+    #[primary_key]
+    #[auto_inc]
+    pub dropped_item_id: u64,
+    #[index(btree)]
+    pub room_name: String,
+    pub item_def_id: u64,
+    pub quantity: u32,
+    pub instance_id: Option<u64>, // Rolled items (item_instance.rs)
+    pub position: Vector3,
+    pub pickup_radius: f32,
+    pub owner: Option<Identity>, // None = free for all
+    pub owner_party: Option<u64>,
+    pub owned_until: Timestamp,
+    pub expires_at: Timestamp,
+}
+
+#[spacetimedb::table(name = drop_decay_schedule, scheduled(decay_drop))]
+pub struct DropDecaySchedule {
+    #[primary_key]
+    #[auto_inc]
+    pub scheduled_id: u64,
+    pub scheduled_at: ScheduleAt,
+    #[index(btree)]
+    pub dropped_item_id: u64,
+}
+
+// Pick one entry of the table, weighted by `weight`
+fn roll_entry<'a>(rng: &mut impl Rng, entries: &'a [LootEntry]) -> Option<&'a LootEntry> {
+    let total: u64 = entries.iter().map(|e| e.weight as u64).sum();
+    if total == 0 {
+        return None;
+    }
+    let mut roll = rng.gen_range(0..total);
+    for entry in entries {
+        if roll < entry.weight as u64 {
+            return Some(entry);
+        }
+        roll -= entry.weight as u64;
+    }
+    None
+}
+
+// Roll `loot_table_id` once per `rolls`: the (item_def_id, quantity) pairs
+// it yields. Unknown tables yield nothing
+fn roll_table(ctx: &ReducerContext, loot_table_id: u64) -> Vec<(u64, u32)> {
+    let Some(table) = ctx.db.loot_table().loot_table_id().find(loot_table_id) else {
+        return Vec::new();
+    };
+    let entries: Vec<LootEntry> = ctx.db.loot_entry().loot_table_id().filter(loot_table_id).collect();
+    let mut rng = ctx.rng();
+    (0..table.rolls)
+        .filter_map(|_| roll_entry(&mut rng, &entries))
+        .filter_map(|entry| entry.item_def_id.map(|item_def_id| (item_def_id, entry.quantity.max(1))))
+        .collect()
+}
+
+// Roll `loot_table_id` into (item_def_id, quantity, instance_id) drops.
+// Equippable items are split into one rolled instance each
+pub fn roll_loot(ctx: &ReducerContext, loot_table_id: u64) -> Vec<(u64, u32, Option<u64>)> {
+    let mut loot = Vec::new();
+    for (item_def_id, quantity) in roll_table(ctx, loot_table_id) {
+        match ctx.db.item_def().item_def_id().find(item_def_id).filter(|def| def.equip_slot.is_some()) {
+            Some(def) => {
+                for _ in 0..quantity {
+                    let instance = item_instance::roll_instance(ctx, &def);
+                    loot.push((item_def_id, 1, Some(instance.instance_id)));
+                }
+            }
+            None => loot.push((item_def_id, quantity, None)),
+        }
+    }
+    loot
+}
+
+// Put items on the ground. A drop with an owner is theirs (and their
+// party's) for LOOT_OWNERSHIP_SECS
+pub fn spawn_drop(
+    ctx: &ReducerContext,
+    room_name: &str,
+    position: Vector3,
+    item_def_id: u64,
+    quantity: u32,
+    instance_id: Option<u64>,
+    owner: Option<Identity>,
+) {
+    let owner_party = owner.and_then(|identity| party::party_of(ctx, identity));
+    let owned_until = if owner.is_some() { ctx.timestamp + Duration::from_secs(LOOT_OWNERSHIP_SECS) } else { ctx.timestamp };
+    let expires_at = ctx.timestamp + Duration::from_secs(LOOT_DROP_LIFETIME_SECS);
+    let drop = ctx.db.dropped_item().insert(DroppedItem {
+        dropped_item_id: 0,
+        room_name: room_name.to_string(),
+        item_def_id,
+        quantity,
+        instance_id,
+        position,
+        pickup_radius: PICKUP_RANGE,
+        owner,
+        owner_party,
+        owned_until,
+        expires_at,
+    });
+    ctx.db.drop_decay_schedule().insert(DropDecaySchedule {
+        scheduled_id: 0,
+        scheduled_at: ScheduleAt::Time(expires_at),
+        dropped_item_id: drop.dropped_item_id,
+    });
+}
+
+fn delete_drop(ctx: &ReducerContext, dropped_item_id: u64) {
+    ctx.db.dropped_item().dropped_item_id().delete(dropped_item_id);
+    let schedules: Vec<u64> = ctx.db.drop_decay_schedule().dropped_item_id().filter(dropped_item_id)
+        .map(|s| s.scheduled_id)
+        .collect();
+    for scheduled_id in schedules {
+        ctx.db.drop_decay_schedule().scheduled_id().delete(scheduled_id);
+    }
+}
+
+// Roll `loot_table_id` and scatter the drops around `position`. `owner` gets
+// the first pick for LOOT_OWNERSHIP_SECS, and the table's gold. If their
+// party has other members in the room, the party rolls on each item instead
+// (loot_roll.rs)
+pub fn drop_loot(ctx: &ReducerContext, room_name: &str, position: &Vector3, loot_table_id: u64, owner: Option<Identity>) {
+    let gold = ctx.db.loot_table().loot_table_id().find(loot_table_id).map_or(0, |t| t.gold);
+    if let Some(owner) = owner.filter(|_| gold > 0) {
+        if let Err(e) = wallet::earn(ctx, owner, gold, WalletReason::Loot) {
+            spacetimedb::log::warn!("[LOOT] {} missed {} gold: {}", owner, gold, e);
+        }
+    }
+    let party_id = owner.and_then(|identity| party::party_of(ctx, identity));
+    let eligible: Vec<Identity> = party_id.map_or(Vec::new(), |party_id| {
+        party::members_of(ctx, party_id).into_iter()
+            .map(|m| m.identity)
+            .filter(|identity| ctx.db.player().identity().find(*identity).is_some_and(|p| p.room_name == room_name))
+            .collect()
+    });
+    let mut rng = ctx.rng();
+    for (item_def_id, quantity, instance_id) in roll_loot(ctx, loot_table_id) {
+        let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+        let distance = rng.gen_range(0.0..LOOT_SCATTER_RADIUS);
+        let scattered = Vector3 {
+            x: position.x + angle.cos() * distance,
+            y: position.y,
+            z: position.z + angle.sin() * distance,
+        };
+        match party_id.filter(|_| eligible.len() > 1) {
+            Some(party_id) => {
+                let drop = (item_def_id, quantity, instance_id);
+                loot_roll::start(ctx, room_name, scattered, party_id, eligible.clone(), drop);
+            }
+            None => spawn_drop(ctx, room_name, scattered, item_def_id, quantity, instance_id, owner),
+        }
+    }
+}
+
+// Called when an NPC dies: drop its loot table (if any) at its position
+pub fn on_npc_killed(ctx: &ReducerContext, npc_id: u64, room_name: &str, position: &Vector3, killer: Option<Identity>) {
+    if let Some(npc_loot) = ctx.db.npc_loot().npc_id().find(npc_id) {
+        drop_loot(ctx, room_name, position, npc_loot.loot_table_id, killer);
+        ctx.db.npc_loot().npc_id().delete(npc_id);
+    }
+}
+
+pub fn clear_npc(ctx: &ReducerContext, npc_id: u64) {
+    ctx.db.npc_loot().npc_id().delete(npc_id);
+}
+
+pub fn clear_room(ctx: &ReducerContext, room_name: &str) {
+    let drops: Vec<DroppedItem> = ctx.db.dropped_item().room_name().filter(room_name).collect();
+    for drop in drops {
+        item_instance::discard(ctx, drop.instance_id);
+        delete_drop(ctx, drop.dropped_item_id);
+    }
+}
+
+fn can_take(ctx: &ReducerContext, drop: &DroppedItem, identity: Identity) -> bool {
+    if drop.owned_until <= ctx.timestamp || drop.owner.is_none_or(|owner| owner == identity) {
+        return true;
+    }
+    drop.owner_party.is_some_and(|party_id| party::party_of(ctx, identity) == Some(party_id))
+}
+
+// --- Loot Reducers ---
+
+#[spacetimedb::reducer]
+pub fn decay_drop(ctx: &ReducerContext, schedule: DropDecaySchedule) -> Result<(), String> {
+    if ctx.sender != ctx.identity() {
+        return Err("decay_drop may only be invoked by the scheduler".to_string());
+    }
+    if let Some(drop) = ctx.db.dropped_item().dropped_item_id().find(schedule.dropped_item_id) {
+        item_instance::discard(ctx, drop.instance_id);
+        ctx.db.dropped_item().dropped_item_id().delete(drop.dropped_item_id);
+    }
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn pickup_item(ctx: &ReducerContext, dropped_item_id: u64) -> Result<(), String> {
+    let Some(player) = ctx.db.player().identity().find(ctx.sender) else {
+        return Err("Player not found".to_string());
+    };
+    let Some(drop) = ctx.db.dropped_item().dropped_item_id().find(dropped_item_id) else {
+        return Err("Item not found".to_string());
+    };
+    if player.is_dead {
+        return Err("Dead players can't pick up items".to_string());
+    }
+    if player.room_name != drop.room_name || player.position.distance_xz(&drop.position) > drop.pickup_radius {
+        return Err("Too far away".to_string());
+    }
+    if !can_take(ctx, &drop, ctx.sender) {
+        return Err("This loot belongs to someone else for now".to_string());
+    }
+    let Some(def) = ctx.db.item_def().item_def_id().find(drop.item_def_id) else {
+        delete_drop(ctx, dropped_item_id);
+        return Err("Item not found".to_string());
+    };
+    item::grant_item(ctx, &player, &def, drop.quantity, drop.instance_id)?;
+    delete_drop(ctx, dropped_item_id);
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn upsert_loot_table(ctx: &ReducerContext, table: LootTable) -> Result<(), String> {
+    admin::require_admin(ctx)?;
+    if table.name.is_empty() {
+        return Err("Loot table name must not be empty".to_string());
+    }
+    if table.rolls > MAX_LOOT_ROLLS {
+        return Err(format!("A loot table can roll at most {} times", MAX_LOOT_ROLLS));
+    }
+    if table.loot_table_id != 0 && ctx.db.loot_table().loot_table_id().find(table.loot_table_id).is_some() {
+        ctx.db.loot_table().loot_table_id().update(table);
+    } else {
+        ctx.db.loot_table().insert(LootTable { loot_table_id: 0, ..table });
+    }
+    Ok(())
+}
+
+// Replace all entries of a loot table
+#[spacetimedb::reducer]
+pub fn set_loot_entries(ctx: &ReducerContext, loot_table_id: u64, entries: Vec<LootEntrySpec>) -> Result<(), String> {
+    admin::require_admin(ctx)?;
+    if ctx.db.loot_table().loot_table_id().find(loot_table_id).is_none() {
+        return Err("Loot table not found".to_string());
+    }
+    if entries.len() > MAX_LOOT_ENTRIES {
+        return Err(format!("A loot table can have at most {} entries", MAX_LOOT_ENTRIES));
+    }
+    let unknown_item = entries.iter()
+        .filter_map(|e| e.item_def_id)
+        .any(|item_def_id| ctx.db.item_def().item_def_id().find(item_def_id).is_none());
+    if unknown_item {
+        return Err("An entry drops an item that doesn't exist".to_string());
+    }
+    let old: Vec<u64> = ctx.db.loot_entry().loot_table_id().filter(loot_table_id).map(|e| e.entry_id).collect();
+    for entry_id in old {
+        ctx.db.loot_entry().entry_id().delete(entry_id);
+    }
+    for spec in entries {
+        ctx.db.loot_entry().insert(LootEntry {
+            entry_id: 0,
+            loot_table_id,
+            item_def_id: spec.item_def_id,
+            weight: spec.weight,
+            quantity: spec.quantity,
+        });
+    }
+    Ok(())
+}
+
+// Room owner: choose what an NPC drops on death (or nothing with None)
+#[spacetimedb::reducer]
+pub fn set_npc_loot_table(ctx: &ReducerContext, npc_id: u64, loot_table_id: Option<u64>) -> Result<(), String> {
+    let Some(npc) = ctx.db.npc().npc_id().find(npc_id) else {
+        return Err("NPC not found".to_string());
+    };
+    room::require_room_owner(ctx, &npc.room_name)?;
+    ctx.db.npc_loot().npc_id().delete(npc_id);
+    if let Some(loot_table_id) = loot_table_id {
+        if ctx.db.loot_table().loot_table_id().find(loot_table_id).is_none() {
+            return Err("Loot table not found".to_string());
+        }
+        ctx.db.npc_loot().insert(NpcLoot { npc_id, loot_table_id });
+    }
+    Ok(())
+}
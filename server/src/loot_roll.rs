@@ -0,0 +1,202 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - loot_roll.rs
+ *
+ * Need / greed rolls for loot dropped for a party, so the quickest clicker
+ * doesn't take everything.
+ *
+ * Key components:
+ * - LootRoll table (public): One item up for rolls, held here instead of on
+ *   the ground, with the party members who were in the room when it
+ *   dropped. Started by loot.rs drop_loot when the killer's party has
+ *   another member in the room
+ * - LootRollVote table (public): Each eligible member's Need, Greed or Pass.
+ *   Need is only allowed on gear the voter's class can wear
+ * - roll_on_loot: Player reducer. The roll resolves once every eligible
+ *   member voted, or after LOOT_ROLL_SECS (LootRollSchedule driving
+ *   resolve_loot_roll)
+ * - Resolution: Need beats Greed; among the winning choice everyone rolls
+ *   1-100 with ctx.rng() and the highest roll wins. The item goes into the
+ *   winner's inventory, or at the winner's name on the ground if it doesn't
+ *   fit or they're gone. If nobody wants it, it drops free for all
+ *
+ * Related files:
+ * - loot.rs: drop_loot and spawn_drop
+ * - party.rs: Who is eligible
+ * - notification.rs: Winners are notified
+ * - room.rs: close_room clears a room's rolls
+ */
+
+use spacetimedb::rand::Rng;
+use spacetimedb::{Identity, ReducerContext, ScheduleAt, SpacetimeType, Table, Timestamp};
+use std::time::Duration;
+
+use crate::common::{Vector3, LOOT_ROLL_SECS};
+use crate::inventory;
+use crate::item::item_def as _;
+use crate::item_instance;
+use crate::loot;
+use crate::notification::{self, NotificationKind};
+use crate::player as _;
+
+#[derive(SpacetimeType, Clone, Copy, Debug, PartialEq)]
+pub enum RollChoice {
+    Need,
+    Greed,
+    Pass,
+}
+
+#[spacetimedb::table(name = loot_roll, public)]
+#[derive(Clone)]
+pub struct LootRoll {
+    #[primary_key]
+    #[auto_inc]
+    pub roll_id: u64,
+    #[index(btree)]
+    pub room_name: String,
+    pub party_id: u64,
+    pub item_def_id: u64,
+    pub quantity: u32,
+    pub instance_id: Option<u64>, // Rolled items (item_instance.rs)
+    pub position: Vector3, // Where it dropped
+    pub eligible: Vec<Identity>,
+    pub expires_at: Timestamp,
+}
+
+#[spacetimedb::table(name = loot_roll_vote, public)]
+#[derive(Clone)]
+pub struct LootRollVote {
+    #[primary_key]
+    #[auto_inc]
+    pub vote_id: u64,
+    #[index(btree)]
+    pub roll_id: u64,
+    pub identity: Identity,
+    pub choice: RollChoice,
+}
+
+#[spacetimedb::table(name = loot_roll_schedule, scheduled(resolve_loot_roll))]
+pub struct LootRollSchedule {
+    #[primary_key]
+    #[auto_inc]
+    pub scheduled_id: u64,
+    pub scheduled_at: ScheduleAt,
+    #[index(btree)]
+    pub roll_id: u64,
+}
+
+// Put an item up for rolls among `eligible`
+pub fn start(
+    ctx: &ReducerContext,
+    room_name: &str,
+    position: Vector3,
+    party_id: u64,
+    eligible: Vec<Identity>,
+    (item_def_id, quantity, instance_id): (u64, u32, Option<u64>),
+) {
+    let expires_at = ctx.timestamp + Duration::from_secs(LOOT_ROLL_SECS);
+    let roll = ctx.db.loot_roll().insert(LootRoll {
+        roll_id: 0,
+        room_name: room_name.to_string(),
+        party_id,
+        item_def_id,
+        quantity,
+        instance_id,
+        position,
+        eligible,
+        expires_at,
+    });
+    ctx.db.loot_roll_schedule().insert(LootRollSchedule {
+        scheduled_id: 0,
+        scheduled_at: ScheduleAt::Time(expires_at),
+        roll_id: roll.roll_id,
+    });
+}
+
+fn delete_roll(ctx: &ReducerContext, roll_id: u64) {
+    let votes: Vec<u64> = ctx.db.loot_roll_vote().roll_id().filter(roll_id).map(|v| v.vote_id).collect();
+    for vote_id in votes {
+        ctx.db.loot_roll_vote().vote_id().delete(vote_id);
+    }
+    let schedules: Vec<u64> = ctx.db.loot_roll_schedule().roll_id().filter(roll_id).map(|s| s.scheduled_id).collect();
+    for scheduled_id in schedules {
+        ctx.db.loot_roll_schedule().scheduled_id().delete(scheduled_id);
+    }
+    ctx.db.loot_roll().roll_id().delete(roll_id);
+}
+
+// Pick the winner, hand over the item and close the roll
+fn resolve(ctx: &ReducerContext, roll: LootRoll) {
+    let votes: Vec<LootRollVote> = ctx.db.loot_roll_vote().roll_id().filter(roll.roll_id).collect();
+    let choice = [RollChoice::Need, RollChoice::Greed].into_iter().find(|c| votes.iter().any(|v| v.choice == *c));
+    let mut rng = ctx.rng();
+    let winner = choice.and_then(|choice| {
+        votes.iter()
+            .filter(|v| v.choice == choice)
+            .map(|v| (rng.gen_range(1..=100u32), v.identity))
+            .max_by_key(|(score, _)| *score)
+    });
+    delete_roll(ctx, roll.roll_id);
+
+    let Some((score, winner)) = winner else {
+        loot::spawn_drop(ctx, &roll.room_name, roll.position, roll.item_def_id, roll.quantity, roll.instance_id, None);
+        return;
+    };
+    let Some(def) = ctx.db.item_def().item_def_id().find(roll.item_def_id) else {
+        item_instance::discard(ctx, roll.instance_id);
+        return;
+    };
+    let received = ctx.db.player().identity().find(winner).is_some()
+        && inventory::add_item(ctx, winner, &def, roll.quantity, roll.instance_id).is_ok();
+    if !received {
+        let (item_def_id, quantity, instance_id) = (roll.item_def_id, roll.quantity, roll.instance_id);
+        loot::spawn_drop(ctx, &roll.room_name, roll.position, item_def_id, quantity, instance_id, Some(winner));
+    }
+    notification::notify(ctx, winner, NotificationKind::LootWon, format!("You won {} with a roll of {}", def.name, score));
+}
+
+pub fn clear_room(ctx: &ReducerContext, room_name: &str) {
+    let rolls: Vec<LootRoll> = ctx.db.loot_roll().room_name().filter(room_name).collect();
+    for roll in rolls {
+        item_instance::discard(ctx, roll.instance_id);
+        delete_roll(ctx, roll.roll_id);
+    }
+}
+
+// --- Loot Roll Reducers ---
+
+#[spacetimedb::reducer]
+pub fn roll_on_loot(ctx: &ReducerContext, roll_id: u64, choice: RollChoice) -> Result<(), String> {
+    let Some(roll) = ctx.db.loot_roll().roll_id().find(roll_id).filter(|r| r.eligible.contains(&ctx.sender)) else {
+        return Err("Loot roll not found".to_string());
+    };
+    if ctx.db.loot_roll_vote().roll_id().filter(roll_id).any(|v| v.identity == ctx.sender) {
+        return Err("You already rolled on that".to_string());
+    }
+    if choice == RollChoice::Need {
+        let Some(player) = ctx.db.player().identity().find(ctx.sender) else {
+            return Err("Player not found".to_string());
+        };
+        let wearable = ctx.db.item_def().item_def_id().find(roll.item_def_id).is_some_and(|def| {
+            def.equip_slot.is_some() && def.required_class.is_none_or(|class| class == player.character_class)
+        });
+        if !wearable {
+            return Err("You can only roll Need on gear you can wear".to_string());
+        }
+    }
+    ctx.db.loot_roll_vote().insert(LootRollVote { vote_id: 0, roll_id, identity: ctx.sender, choice });
+    if ctx.db.loot_roll_vote().roll_id().filter(roll_id).count() >= roll.eligible.len() {
+        resolve(ctx, roll);
+    }
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn resolve_loot_roll(ctx: &ReducerContext, schedule: LootRollSchedule) -> Result<(), String> {
+    if ctx.sender != ctx.identity() {
+        return Err("resolve_loot_roll may only be invoked by the scheduler".to_string());
+    }
+    if let Some(roll) = ctx.db.loot_roll().roll_id().find(schedule.roll_id) {
+        resolve(ctx, roll);
+    }
+    Ok(())
+}
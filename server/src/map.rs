@@ -0,0 +1,220 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - map.rs
+ *
+ * Maps are tile sets identified by map_id; every room points at one. Live
+ * content updates author a new map and migrate running rooms onto it.
+ *
+ * Every room plays on its own instance of a map: tiles carry the room_name
+ * they belong to, and each instance has a map_id of its own, so tile lookups
+ * by map_id never see another room's world. Authored maps (add_map_tile) are
+ * only sources with an empty room_name; migrating a room onto one copies its
+ * tiles into a fresh instance (mapgen::instance_map). The hub room's
+ * instance is the streamed DEFAULT_MAP_ID (chunk.rs).
+ *
+ * Key components:
+ * - tile_at / is_on_map: The tile a position stands on, if any
+ * - add_map_tile / set_tile_hazard: Admin reducers for authoring map tiles
+ *   and their hazards
+ * - migrate_room_map: Admin reducer that moves a live room to another map
+ *   (move_room_to_map, also used by mapgen.rs). It runs as a single
+ *   transaction, so the room is effectively paused while it happens and
+ *   clients never observe a half-migrated room:
+ *   1. Points the room at its new tile set
+ *   2. Re-seeds spawn points that are valid on the new map (generated maps
+ *      bring their own)
+ *   3. Removes destructible props that no longer stand on a tile
+ *   4. Relocates living players to the best spawn point, clearing their
+ *      lag-compensation history so shots don't rewind across the move
+ *   5. Deletes the room's previous tile set
+ * - delete_map / delete_room_tiles: Remove a tile set (replaced instances,
+ *   room teardown)
+ *
+ * Related files:
+ * - lib.rs: GameTile (map_id, room_name, chunk_id, grid_cell)
+ * - chunk.rs: DEFAULT_MAP_ID is streamed; instanced tiles join a chunk
+ * - mapgen.rs: Generated and copied instances (GeneratedMap)
+ * - room.rs: Room.map_id
+ * - spatial.rs: has_line_of_sight tests against the room's map
+ * - spawn.rs: reseed_spawn_points / set_spawn_points
+ */
+
+use spacetimedb::{ReducerContext, Table};
+use std::collections::HashSet;
+
+use crate::admin;
+use crate::chunk::{self, world_chunk as _};
+use crate::common::{TileHazard, Vector3, DEFAULT_MAP_ID, GENERATED_MAP_ID_BASE};
+use crate::destructible::destructible as _;
+use crate::door;
+use crate::hitscan;
+use crate::mapgen::{self, generated_map as _};
+use crate::minimap;
+use crate::player as _;
+use crate::player_logic;
+use crate::room::{self, room as _};
+use crate::spatial::{self, SpatialEntity};
+use crate::spawn;
+use crate::{game_tile, GameTile, PlayerData};
+
+// Ids of authored maps: not the hub's streamed map, not an instance
+fn is_authored_id(map_id: u32) -> bool {
+    map_id != DEFAULT_MAP_ID && map_id < GENERATED_MAP_ID_BASE
+}
+
+// Whether an authored map has tiles rooms can be migrated onto
+pub fn map_exists(ctx: &ReducerContext, map_id: u32) -> bool {
+    is_authored_id(map_id) && ctx.db.game_tile().map_cell().filter(map_id).next().is_some()
+}
+
+// The map tile whose footprint contains `position`, if any
+pub fn tile_at(ctx: &ReducerContext, map_id: u32, position: &Vector3) -> Option<GameTile> {
+    spatial::grid_cells_in_radius(position, 0.0).into_iter()
+        .flat_map(|cell| ctx.db.game_tile().map_cell().filter((map_id, cell)))
+        .find(|tile| {
+            (position.x - tile.position.x).abs() <= tile.size.x * 0.5
+                && (position.z - tile.position.z).abs() <= tile.size.z * 0.5
+        })
+}
+
+// Whether `position` is above a tile of the map
+pub fn is_on_map(ctx: &ReducerContext, map_id: u32, position: &Vector3) -> bool {
+    tile_at(ctx, map_id, position).is_some()
+}
+
+// Delete a room instance's tiles, chunks and instance record
+pub fn delete_map(ctx: &ReducerContext, map_id: u32) {
+    let tiles: Vec<u64> = ctx.db.game_tile().map_cell().filter(map_id).map(|t| t.tile_id).collect();
+    for tile_id in tiles {
+        ctx.db.game_tile().tile_id().delete(tile_id);
+    }
+    chunk::delete_map_chunks(ctx, map_id);
+    ctx.db.generated_map().map_id().delete(map_id);
+}
+
+// Room teardown: every tile set the room owns (including chunks that never
+// got tiles)
+pub fn delete_room_tiles(ctx: &ReducerContext, room_name: &str) {
+    let map_ids: HashSet<u32> = ctx.db.game_tile().room_name().filter(room_name).map(|t| t.map_id)
+        .chain(ctx.db.world_chunk().room_name().filter(room_name).map(|c| c.map_id))
+        .collect();
+    for map_id in map_ids {
+        delete_map(ctx, map_id);
+    }
+}
+
+#[spacetimedb::reducer]
+pub fn add_map_tile(
+    ctx: &ReducerContext,
+    map_id: u32,
+    position: Vector3,
+    size: Vector3,
+    hazard: Option<TileHazard>,
+) -> Result<(), String> {
+    admin::require_admin(ctx)?;
+    if !is_authored_id(map_id) {
+        return Err(format!("Authored maps use ids below {} other than {}", GENERATED_MAP_ID_BASE, DEFAULT_MAP_ID));
+    }
+    if size.x <= 0.0 || size.y <= 0.0 || size.z <= 0.0 {
+        return Err("Tile size must be positive".to_string());
+    }
+    validate_hazard(hazard.as_ref())?;
+    ctx.db.game_tile().insert(GameTile {
+        tile_id: 0,
+        map_id,
+        room_name: String::new(),
+        chunk_id: 0, // Sources aren't streamed; their instances are
+        hazard,
+        grid_cell: spatial::grid_cell_of(&position),
+        position,
+        size,
+        placed_by: None,
+    });
+    Ok(())
+}
+
+fn validate_hazard(hazard: Option<&TileHazard>) -> Result<(), String> {
+    if hazard.is_some_and(|h| h.damage_per_sec < 0) {
+        return Err("Hazard damage must be >= 0".to_string());
+    }
+    Ok(())
+}
+
+// Turn an existing tile into hazardous terrain (or back with None)
+#[spacetimedb::reducer]
+pub fn set_tile_hazard(ctx: &ReducerContext, tile_id: u64, hazard: Option<TileHazard>) -> Result<(), String> {
+    admin::require_admin(ctx)?;
+    validate_hazard(hazard.as_ref())?;
+    let Some(mut tile) = ctx.db.game_tile().tile_id().find(tile_id) else {
+        return Err("Tile not found".to_string());
+    };
+    tile.hazard = hazard;
+    minimap::mark_dirty(ctx, tile.chunk_id);
+    ctx.db.game_tile().tile_id().update(tile);
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn migrate_room_map(ctx: &ReducerContext, room_name: String, new_map_id: u32) -> Result<(), String> {
+    admin::require_admin(ctx)?;
+    let Some(room) = room::find_room(ctx, &room_name) else {
+        return Err("Room not found".to_string());
+    };
+    if !map_exists(ctx, new_map_id) {
+        return Err(format!("Map {} has no tiles", new_map_id));
+    }
+    let current_source = ctx.db.generated_map().map_id().find(room.map_id).and_then(|m| m.source_map_id);
+    if current_source == Some(new_map_id) {
+        return Err("Room is already on that map".to_string());
+    }
+    let instance_id = mapgen::instance_map(ctx, &room_name, new_map_id);
+    move_room_to_map(ctx, &room_name, instance_id, None)
+}
+
+// Moves a room onto a new instance the caller has built (see the module
+// docs). `spawn_positions` replaces the room's spawn points; None re-seeds
+// the default ones
+pub fn move_room_to_map(
+    ctx: &ReducerContext,
+    room_name: &str,
+    new_map_id: u32,
+    spawn_points: Option<Vec<(Vector3, u8)>>,
+) -> Result<(), String> {
+    let Some(mut room) = room::find_room(ctx, room_name) else {
+        return Err("Room not found".to_string());
+    };
+    let old_map_id = room.map_id;
+    room.map_id = new_map_id;
+    ctx.db.room().room_name().update(room);
+
+    match spawn_points {
+        Some(points) => spawn::set_spawn_points(ctx, room_name, points),
+        None => spawn::reseed_spawn_points(ctx, room_name, new_map_id),
+    }
+    door::rebuild_tiles(ctx, room_name);
+
+    let stranded_props: Vec<u64> = ctx.db.destructible().room_name().filter(room_name)
+        .filter(|d| !is_on_map(ctx, new_map_id, &d.position))
+        .map(|d| d.destructible_id)
+        .collect();
+    for destructible_id in &stranded_props {
+        ctx.db.destructible().destructible_id().delete(*destructible_id);
+        spatial::untrack(ctx, &SpatialEntity::Destructible(*destructible_id));
+    }
+
+    let players: Vec<PlayerData> = ctx.db.player().room_name().filter(room_name)
+        .filter(|p| !p.is_dead)
+        .collect();
+    for mut player in players {
+        let spawn_position = spawn::select_spawn_point(ctx, room_name, player.identity);
+        player_logic::set_player_position(ctx, &mut player, spawn_position);
+        hitscan::clear_history(ctx, player.identity);
+        ctx.db.player().identity().update(player);
+    }
+    delete_map(ctx, old_map_id);
+
+    spacetimedb::log::info!(
+        "Migrated room '{}' from map {} to map {} ({} props removed).",
+        room_name, old_map_id, new_map_id, stranded_props.len()
+    );
+    Ok(())
+}
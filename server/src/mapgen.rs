@@ -0,0 +1,299 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - mapgen.rs
+ *
+ * Procedural maps: every non-hub room gets its own layout, built from a seed
+ * and a map template, instead of sharing the streamed default map.
+ *
+ * Key components:
+ * - MapTemplate table (public): A named recipe for layouts: its shape, size
+ *   and how dense platforms, obstacles, hazards and props are. init seeds
+ *   "arena", "maze", "island" and "town"; admins can add more
+ *   (upsert_map_template)
+ * - MapShape: Square ground, a round Island, or a Maze whose walls are
+ *   carved with a depth-first search
+ * - GeneratedMap table (public): A room's own tile set (map instance, see
+ *   map.rs): either generated from a seed and template, or copied from an
+ *   authored map (source_map_id, instance_map). Instances get ids from
+ *   GENERATED_MAP_ID_BASE up so they never collide with authored maps
+ * - generate_room_map: Lays out the template with a seeded RNG (the same
+ *   seed and template always give the same layout), moves the room onto it
+ *   with its own spawn points (map::move_room_to_map) and replaces the
+ *   room's props. The area within MAPGEN_CLEAR_TILES of the origin stays
+ *   free of walls, obstacles and hazards. The room's previous tile set is
+ *   deleted
+ * - generate_map: Room-owner reducer. create_room stamps the template the
+ *   creator picked (DEFAULT_MAP_TEMPLATE if none) with a random seed
+ *
+ * Related files:
+ * - map.rs: move_room_to_map / delete_map
+ * - chunk.rs: Generated tiles join their room's chunks
+ * - destructible.rs: Props
+ * - hazard.rs: Hazard tiles burn players standing on them
+ */
+
+use spacetimedb::rand::rngs::StdRng;
+use spacetimedb::rand::{Rng, SeedableRng};
+use spacetimedb::{ReducerContext, SpacetimeType, Table, Timestamp};
+
+use crate::admin;
+use crate::chunk;
+use crate::common::{
+    HazardEffect, TileHazard, Vector3, GENERATED_MAP_ID_BASE, MAPGEN_CLEAR_TILES, MAPGEN_HAZARD_DPS,
+    MAPGEN_MAX_HALF_EXTENT_TILES, MAPGEN_MAX_PROPS, MAPGEN_PROP_HEALTH, MAPGEN_SPAWN_POINTS, NO_TEAM, TILE_SIZE,
+};
+use crate::destructible;
+use crate::map;
+use crate::room;
+use crate::spatial;
+use crate::{game_tile, GameTile};
+
+#[derive(SpacetimeType, Clone, Copy, Debug, PartialEq)]
+pub enum MapShape {
+    Square,
+    Island,
+    Maze,
+}
+
+#[spacetimedb::table(name = map_template, public)]
+#[derive(Clone)]
+pub struct MapTemplate {
+    #[primary_key]
+    pub name: String,
+    pub description: String,
+    pub shape: MapShape,
+    pub half_extent_tiles: i32,
+    // Per-tile chances of each feature outside the cleared spawn area
+    pub platform_chance: f32,
+    pub obstacle_chance: f32,
+    pub hazard_chance: f32,
+    pub prop_count: u32,
+}
+
+#[spacetimedb::table(name = generated_map, public)]
+#[derive(Clone)]
+pub struct GeneratedMap {
+    #[primary_key]
+    pub map_id: u32,
+    #[index(btree)]
+    pub room_name: String,
+    pub seed: u64,
+    pub template: String, // Empty for copies of authored maps and snapshots
+    pub source_map_id: Option<u32>,
+    pub created_at: Timestamp,
+}
+
+// Where the room's spawn points and props go on a freshly laid out map
+struct Layout {
+    spawn_points: Vec<Vector3>,
+    props: Vec<Vector3>,
+}
+
+pub fn seed_map_templates(ctx: &ReducerContext) {
+    if ctx.db.map_template().count() > 0 {
+        return;
+    }
+    let templates = [
+        ("arena", "A compact fighting pit with platforms", MapShape::Square, 10, 0.12, 0.04, 0.03, 4),
+        ("maze", "Winding corridors between high walls", MapShape::Maze, 12, 0.0, 0.0, 0.02, 6),
+        ("island", "A round island with scattered cover", MapShape::Island, 14, 0.05, 0.04, 0.0, 4),
+        ("town", "Streets between buildings, full of crates", MapShape::Square, 16, 0.03, 0.15, 0.0, 12),
+    ];
+    for (name, description, shape, half_extent_tiles, platform_chance, obstacle_chance, hazard_chance, prop_count) in templates {
+        ctx.db.map_template().insert(MapTemplate {
+            name: name.to_string(),
+            description: description.to_string(),
+            shape,
+            half_extent_tiles,
+            platform_chance,
+            obstacle_chance,
+            hazard_chance,
+            prop_count,
+        });
+    }
+}
+
+fn next_map_id(ctx: &ReducerContext) -> u32 {
+    ctx.db.generated_map().iter().map(|m| m.map_id + 1).max().unwrap_or(GENERATED_MAP_ID_BASE)
+}
+
+fn insert_tile(ctx: &ReducerContext, room_name: &str, map_id: u32, position: Vector3, size: Vector3, hazard: Option<TileHazard>) {
+    ctx.db.game_tile().insert(GameTile {
+        tile_id: 0,
+        map_id,
+        room_name: room_name.to_string(),
+        chunk_id: chunk::chunk_id_at(ctx, room_name, map_id, &position),
+        hazard,
+        grid_cell: spatial::grid_cell_of(&position),
+        position,
+        size,
+        placed_by: None,
+    });
+}
+
+// Maze walls over the (2 * half_extent + 1)^2 tile grid: cells sit on odd
+// grid coordinates and a depth-first search knocks down the walls between them
+fn carve_maze(rng: &mut StdRng, half_extent: i32) -> Vec<Vec<bool>> {
+    let size = (2 * half_extent + 1) as usize;
+    let mut wall = vec![vec![true; size]; size];
+    let mut stack = vec![(1usize, 1usize)];
+    wall[1][1] = false;
+    while let Some(&(x, z)) = stack.last() {
+        let neighbours: Vec<(usize, usize)> = [(2i32, 0i32), (-2, 0), (0, 2), (0, -2)].iter()
+            .map(|(dx, dz)| (x as i32 + dx, z as i32 + dz))
+            .filter(|&(nx, nz)| nx > 0 && nz > 0 && (nx as usize) < size - 1 && (nz as usize) < size - 1)
+            .map(|(nx, nz)| (nx as usize, nz as usize))
+            .filter(|&(nx, nz)| wall[nx][nz])
+            .collect();
+        if neighbours.is_empty() {
+            stack.pop();
+            continue;
+        }
+        let (nx, nz) = neighbours[rng.gen_range(0..neighbours.len())];
+        wall[(x + nx) / 2][(z + nz) / 2] = false;
+        wall[nx][nz] = false;
+        stack.push((nx, nz));
+    }
+    wall
+}
+
+fn build_layout(ctx: &ReducerContext, room_name: &str, map_id: u32, seed: u64, template: &MapTemplate) -> Layout {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let half_extent = template.half_extent_tiles;
+    let maze = (template.shape == MapShape::Maze).then(|| carve_maze(&mut rng, half_extent));
+    let mut open_tiles = Vec::new();
+    for ix in -half_extent..=half_extent {
+        for iz in -half_extent..=half_extent {
+            let distance_sq = ix * ix + iz * iz;
+            if template.shape == MapShape::Island && distance_sq > half_extent * half_extent {
+                continue;
+            }
+            let (x, z) = (ix as f32 * TILE_SIZE, iz as f32 * TILE_SIZE);
+            let cleared = distance_sq <= MAPGEN_CLEAR_TILES * MAPGEN_CLEAR_TILES;
+            // Roll every feature for every tile so the layout only depends on the seed
+            let (hazard_roll, obstacle_roll, platform_roll) = (rng.gen::<f32>(), rng.gen::<f32>(), rng.gen::<f32>());
+            let platform_height = rng.gen_range(3.0..6.0);
+            let maze_wall = maze.as_ref().is_some_and(|w| w[(ix + half_extent) as usize][(iz + half_extent) as usize]);
+
+            let hazard = (!cleared && hazard_roll < template.hazard_chance)
+                .then_some(TileHazard { damage_per_sec: MAPGEN_HAZARD_DPS, effect: HazardEffect::Burn });
+            let is_hazard = hazard.is_some();
+            let ground_size = Vector3 { x: TILE_SIZE, y: 1.0, z: TILE_SIZE };
+            insert_tile(ctx, room_name, map_id, Vector3 { x, y: 0.0, z }, ground_size.clone(), hazard);
+
+            if !cleared && (maze_wall || obstacle_roll < template.obstacle_chance) {
+                // A wall block standing on the ground tile
+                insert_tile(ctx, room_name, map_id, Vector3 { x, y: 2.5, z }, Vector3 { x: TILE_SIZE, y: 4.0, z: TILE_SIZE }, None);
+            } else if !cleared && platform_roll < template.platform_chance {
+                insert_tile(ctx, room_name, map_id, Vector3 { x, y: platform_height, z }, ground_size, None);
+            } else if !is_hazard {
+                open_tiles.push(Vector3 { x, y: 1.0, z });
+            }
+        }
+    }
+
+    let mut pick = |count: usize| {
+        let mut picked = Vec::new();
+        while picked.len() < count && !open_tiles.is_empty() {
+            let index = rng.gen_range(0..open_tiles.len());
+            picked.push(open_tiles.swap_remove(index));
+        }
+        picked
+    };
+    let spawn_points = pick(MAPGEN_SPAWN_POINTS);
+    let props = pick(template.prop_count as usize);
+    Layout { spawn_points, props }
+}
+
+pub fn generate_room_map(ctx: &ReducerContext, room_name: &str, seed: u64, template_name: &str) -> Result<u32, String> {
+    let Some(template) = ctx.db.map_template().name().find(template_name.to_string()) else {
+        return Err(format!("Unknown map template '{}'", template_name));
+    };
+    let map_id = next_map_id(ctx);
+    let layout = build_layout(ctx, room_name, map_id, seed, &template);
+    ctx.db.generated_map().insert(GeneratedMap {
+        map_id,
+        room_name: room_name.to_string(),
+        seed,
+        template: template.name.clone(),
+        source_map_id: None,
+        created_at: ctx.timestamp,
+    });
+    let spawn_points = layout.spawn_points.into_iter().map(|position| (position, NO_TEAM)).collect();
+    map::move_room_to_map(ctx, room_name, map_id, Some(spawn_points))?;
+
+    destructible::clear_room(ctx, room_name);
+    let crate_size = Vector3 { x: 1.0, y: 1.0, z: 1.0 };
+    for position in layout.props {
+        destructible::spawn_destructible(ctx, room_name, Vector3 { y: 0.5, ..position }, crate_size.clone(), MAPGEN_PROP_HEALTH, None);
+    }
+
+    spacetimedb::log::info!("Generated map {} for room '{}' (seed {}, template '{}').", map_id, room_name, seed, template.name);
+    Ok(map_id)
+}
+
+// Copy an authored map's tiles into a new instance for the room
+pub fn instance_map(ctx: &ReducerContext, room_name: &str, source_map_id: u32) -> u32 {
+    let sources: Vec<(Vector3, Vector3, Option<TileHazard>)> = ctx.db.game_tile().map_cell().filter(source_map_id)
+        .map(|tile| (tile.position, tile.size, tile.hazard))
+        .collect();
+    instance_tiles(ctx, room_name, sources, Some(source_map_id))
+}
+
+// A new instance for the room made of the given (position, size, hazard)
+// tiles (copied maps, world snapshots)
+pub fn instance_tiles(
+    ctx: &ReducerContext,
+    room_name: &str,
+    tiles: Vec<(Vector3, Vector3, Option<TileHazard>)>,
+    source_map_id: Option<u32>,
+) -> u32 {
+    let map_id = next_map_id(ctx);
+    for (position, size, hazard) in tiles {
+        insert_tile(ctx, room_name, map_id, position, size, hazard);
+    }
+    ctx.db.generated_map().insert(GeneratedMap {
+        map_id,
+        room_name: room_name.to_string(),
+        seed: 0,
+        template: String::new(),
+        source_map_id,
+        created_at: ctx.timestamp,
+    });
+    map_id
+}
+
+// --- Map Generation Reducers ---
+
+#[spacetimedb::reducer]
+pub fn generate_map(ctx: &ReducerContext, room_name: String, seed: u64, template: String) -> Result<(), String> {
+    let room = room::require_room_owner(ctx, &room_name)?;
+    if room.is_hub {
+        return Err("Hub rooms stay on the default map".to_string());
+    }
+    generate_room_map(ctx, &room_name, seed, &template)?;
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn upsert_map_template(ctx: &ReducerContext, template: MapTemplate) -> Result<(), String> {
+    admin::require_admin(ctx)?;
+    if template.name.is_empty() {
+        return Err("Template name must not be empty".to_string());
+    }
+    if template.half_extent_tiles < MAPGEN_CLEAR_TILES || template.half_extent_tiles > MAPGEN_MAX_HALF_EXTENT_TILES {
+        return Err(format!("Half extent must be between {} and {} tiles", MAPGEN_CLEAR_TILES, MAPGEN_MAX_HALF_EXTENT_TILES));
+    }
+    let chances = [template.platform_chance, template.obstacle_chance, template.hazard_chance];
+    if chances.iter().any(|c| !(0.0..=1.0).contains(c)) {
+        return Err("Feature chances must be between 0 and 1".to_string());
+    }
+    if template.prop_count > MAPGEN_MAX_PROPS {
+        return Err(format!("A template can place at most {} props", MAPGEN_MAX_PROPS));
+    }
+    if ctx.db.map_template().name().find(template.name.clone()).is_some() {
+        ctx.db.map_template().name().update(template);
+    } else {
+        ctx.db.map_template().insert(template);
+    }
+    Ok(())
+}
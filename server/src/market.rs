@@ -0,0 +1,206 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - market.rs
+ *
+ * The auction house: players list items for a fixed price and anyone can
+ * buy them, online or not.
+ *
+ * Key components:
+ * - MarketListing table (public): An item stack (or rolled item) for sale,
+ *   its total price and when it expires. Listed items leave the seller's inventory into
+ *   escrow, so a seller can't sell, trade or drop what a buyer is paying
+ *   for, and unlisting hands back exactly what was listed
+ * - MarketClaim table (public): Items waiting for their owner after their
+ *   listing expired. Players only see their own claims (RLS)
+ * - list_item: Escrows an inventory stack and charges a non-refundable fee
+ *   of MARKET_FEE_PERCENT of the price (at least 1 gold)
+ * - buy_listing: The buyer pays the price (wallet.rs), the seller is paid
+ *   the same, and the items go into the buyer's inventory (it fails if they
+ *   don't fit)
+ * - cancel_listing: The seller takes the items back into their inventory
+ * - MarketExpirySchedule table: One-shot schedule row per listing;
+ *   expire_listing moves unsold items into a claim after MARKET_LISTING_SECS
+ * - collect_claim: Moves a claim into the inventory
+ * - All trading reducers need config.rs trading_enabled
+ *
+ * Related files:
+ * - inventory.rs / wallet.rs: What changes hands
+ * - trade.rs: Direct trades between two players
+ */
+
+use spacetimedb::{client_visibility_filter, Filter, Identity, ReducerContext, ScheduleAt, Table, Timestamp};
+use std::time::Duration;
+
+use crate::common::{MARKET_FEE_PERCENT, MARKET_LISTING_SECS, MAX_MARKET_LISTINGS_PER_PLAYER};
+use crate::config;
+use crate::inventory;
+use crate::item::item_def as _;
+use crate::player as _;
+use crate::wallet::{self, WalletReason};
+
+#[spacetimedb::table(name = market_listing, public)]
+#[derive(Clone)]
+pub struct MarketListing {
+    #[primary_key]
+    #[auto_inc]
+    pub listing_id: u64,
+    #[index(btree)]
+    pub seller: Identity,
+    #[index(btree)]
+    pub item_def_id: u64,
+    pub quantity: u32,
+    pub instance_id: Option<u64>, // Rolled items (item_instance.rs)
+    pub price: u64, // For the whole stack
+    pub listed_at: Timestamp,
+    pub expires_at: Timestamp,
+}
+
+#[spacetimedb::table(name = market_claim, public)]
+#[derive(Clone)]
+pub struct MarketClaim {
+    #[primary_key]
+    #[auto_inc]
+    pub claim_id: u64,
+    #[index(btree)]
+    pub owner: Identity,
+    pub item_def_id: u64,
+    pub quantity: u32,
+    pub instance_id: Option<u64>,
+    pub created_at: Timestamp,
+}
+
+#[spacetimedb::table(name = market_expiry_schedule, scheduled(expire_listing))]
+pub struct MarketExpirySchedule {
+    #[primary_key]
+    #[auto_inc]
+    pub scheduled_id: u64,
+    pub scheduled_at: ScheduleAt,
+    #[index(btree)]
+    pub listing_id: u64,
+}
+
+#[client_visibility_filter]
+const OWNER_SEES_MARKET_CLAIMS: Filter = Filter::Sql(
+    "SELECT * FROM market_claim WHERE owner = :sender",
+);
+
+fn require_trading(ctx: &ReducerContext) -> Result<(), String> {
+    if !config::current(ctx).trading_enabled {
+        return Err("Trading is disabled".to_string());
+    }
+    Ok(())
+}
+
+fn listing_fee(price: u64) -> u64 {
+    ((price as u128 * MARKET_FEE_PERCENT as u128 / 100) as u64).max(1)
+}
+
+// Take a listing off the market, along with its expiry schedule
+fn delete_listing(ctx: &ReducerContext, listing_id: u64) {
+    ctx.db.market_listing().listing_id().delete(listing_id);
+    let schedules: Vec<u64> = ctx.db.market_expiry_schedule().listing_id().filter(listing_id)
+        .map(|s| s.scheduled_id)
+        .collect();
+    for scheduled_id in schedules {
+        ctx.db.market_expiry_schedule().scheduled_id().delete(scheduled_id);
+    }
+}
+
+// --- Market Reducers ---
+
+// List `quantity` of an inventory stack for `price` gold in total
+#[spacetimedb::reducer]
+pub fn list_item(ctx: &ReducerContext, item_id: u64, quantity: u32, price: u64) -> Result<(), String> {
+    require_trading(ctx)?;
+    let Some(player) = ctx.db.player().identity().find(ctx.sender) else {
+        return Err("Player not found".to_string());
+    };
+    if price == 0 {
+        return Err("Price must be at least 1 gold".to_string());
+    }
+    if ctx.db.market_listing().seller().filter(ctx.sender).count() >= MAX_MARKET_LISTINGS_PER_PLAYER {
+        return Err(format!("You can have at most {} listings", MAX_MARKET_LISTINGS_PER_PLAYER));
+    }
+    wallet::spend(ctx, ctx.sender, listing_fee(price), WalletReason::MarketFee)?;
+    let (item_def_id, instance_id) = inventory::remove_from_stack(ctx, &player, item_id, quantity)?;
+    let expires_at = ctx.timestamp + Duration::from_secs(MARKET_LISTING_SECS);
+    let listing = ctx.db.market_listing().insert(MarketListing {
+        listing_id: 0,
+        seller: ctx.sender,
+        item_def_id,
+        quantity,
+        instance_id,
+        price,
+        listed_at: ctx.timestamp,
+        expires_at,
+    });
+    ctx.db.market_expiry_schedule().insert(MarketExpirySchedule {
+        scheduled_id: 0,
+        scheduled_at: ScheduleAt::Time(expires_at),
+        listing_id: listing.listing_id,
+    });
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn buy_listing(ctx: &ReducerContext, listing_id: u64) -> Result<(), String> {
+    require_trading(ctx)?;
+    let Some(listing) = ctx.db.market_listing().listing_id().find(listing_id) else {
+        return Err("Listing not found".to_string());
+    };
+    if listing.seller == ctx.sender {
+        return Err("You can't buy your own listing".to_string());
+    }
+    let Some(def) = ctx.db.item_def().item_def_id().find(listing.item_def_id) else {
+        return Err("Item not found".to_string());
+    };
+    wallet::spend(ctx, ctx.sender, listing.price, WalletReason::MarketPurchase)?;
+    wallet::earn(ctx, listing.seller, listing.price, WalletReason::MarketSale)?;
+    inventory::add_item(ctx, ctx.sender, &def, listing.quantity, listing.instance_id)?;
+    delete_listing(ctx, listing_id);
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn cancel_listing(ctx: &ReducerContext, listing_id: u64) -> Result<(), String> {
+    let Some(listing) = ctx.db.market_listing().listing_id().find(listing_id).filter(|l| l.seller == ctx.sender) else {
+        return Err("Listing not found".to_string());
+    };
+    if let Some(def) = ctx.db.item_def().item_def_id().find(listing.item_def_id) {
+        inventory::add_item(ctx, ctx.sender, &def, listing.quantity, listing.instance_id)?;
+    }
+    delete_listing(ctx, listing_id);
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn expire_listing(ctx: &ReducerContext, schedule: MarketExpirySchedule) -> Result<(), String> {
+    if ctx.sender != ctx.identity() {
+        return Err("expire_listing may only be invoked by the scheduler".to_string());
+    }
+    let Some(listing) = ctx.db.market_listing().listing_id().find(schedule.listing_id) else {
+        return Ok(());
+    };
+    ctx.db.market_listing().listing_id().delete(listing.listing_id);
+    ctx.db.market_claim().insert(MarketClaim {
+        claim_id: 0,
+        owner: listing.seller,
+        item_def_id: listing.item_def_id,
+        quantity: listing.quantity,
+        instance_id: listing.instance_id,
+        created_at: ctx.timestamp,
+    });
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn collect_claim(ctx: &ReducerContext, claim_id: u64) -> Result<(), String> {
+    let Some(claim) = ctx.db.market_claim().claim_id().find(claim_id).filter(|c| c.owner == ctx.sender) else {
+        return Err("Claim not found".to_string());
+    };
+    let Some(def) = ctx.db.item_def().item_def_id().find(claim.item_def_id) else {
+        return Err("Item not found".to_string());
+    };
+    inventory::add_item(ctx, ctx.sender, &def, claim.quantity, claim.instance_id)?;
+    ctx.db.market_claim().claim_id().delete(claim_id);
+    Ok(())
+}
@@ -0,0 +1,174 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - minimap.rs
+ *
+ * A compact per-chunk summary of the terrain, so clients can draw a minimap
+ * of the whole room without subscribing to every tile row.
+ *
+ * Key components:
+ * - MinimapChunk table (public): One row per world chunk (chunk.rs) with a
+ *   byte of MINIMAP_* flags per tile cell, CHUNK_SIZE_TILES^2 of them in
+ *   row-major order along x:
+ *   - MINIMAP_WALKABLE: There is ground at the cell
+ *   - MINIMAP_HAZARD: The ground does damage (hazard.rs)
+ *   - MINIMAP_WATER: The ground slows players (water, mud)
+ *   - MINIMAP_OBSTACLE: A closed door, a breakable prop or a resting
+ *     physics prop stands on the cell
+ *   Players see the minimap of their own room (RLS)
+ * - mark_dirty / mark_dirty_at: Called wherever the world changes (new
+ *   chunks, built or removed blocks, doors, hazards, props settling or being
+ *   pushed, breakable props appearing or breaking)
+ * - rebuild_dirty: Called from game_tick. Recomputes the room's dirty
+ *   chunks, so a burst of changes costs one rebuild per chunk
+ *
+ * Related files:
+ * - chunk.rs: Chunks, whose deletion deletes their minimaps
+ * - map.rs: tile_at
+ */
+
+use spacetimedb::{client_visibility_filter, Filter, ReducerContext, Table, Timestamp};
+use std::collections::HashSet;
+
+use crate::chunk::{self, world_chunk as _, WorldChunk};
+use crate::common::{
+    HazardEffect, Vector3, CHUNK_SIZE_TILES, MINIMAP_HAZARD, MINIMAP_OBSTACLE, MINIMAP_WALKABLE, MINIMAP_WATER,
+    TILE_SIZE,
+};
+use crate::destructible::{destructible as _, Destructible};
+use crate::door;
+use crate::map;
+use crate::prop::{prop as _, Prop};
+use crate::room::{self, Room};
+
+#[spacetimedb::table(name = minimap_chunk, public)]
+#[derive(Clone)]
+pub struct MinimapChunk {
+    #[primary_key]
+    pub chunk_id: u64,
+    #[index(btree)]
+    pub room_name: String,
+    pub chunk_x: i32,
+    pub chunk_z: i32,
+    pub cells: Vec<u8>,
+    pub updated_at: Timestamp,
+}
+
+#[spacetimedb::table(name = minimap_dirty)]
+pub struct MinimapDirty {
+    #[primary_key]
+    pub chunk_id: u64,
+    #[index(btree)]
+    pub room_name: String,
+}
+
+#[client_visibility_filter]
+const PLAYER_SEES_OWN_ROOM_MINIMAP: Filter = Filter::Sql(
+    "SELECT minimap_chunk.* FROM minimap_chunk JOIN player ON minimap_chunk.room_name = player.room_name WHERE player.identity = :sender",
+);
+
+pub fn mark_dirty(ctx: &ReducerContext, chunk_id: u64) {
+    if ctx.db.minimap_dirty().chunk_id().find(chunk_id).is_some() {
+        return;
+    }
+    // Tiles of authored map sources have no chunk
+    let Some(chunk) = ctx.db.world_chunk().chunk_id().find(chunk_id) else {
+        return;
+    };
+    ctx.db.minimap_dirty().insert(MinimapDirty { chunk_id, room_name: chunk.room_name });
+}
+
+pub fn mark_dirty_at(ctx: &ReducerContext, room_name: &str, position: &Vector3) {
+    let Some(room) = room::find_room(ctx, room_name) else {
+        return;
+    };
+    if let Some(chunk) = chunk::chunk_at(ctx, room.map_id, position) {
+        mark_dirty(ctx, chunk.chunk_id);
+    }
+}
+
+// Everything standing on the room's ground that build_cells looks at
+struct Obstacles {
+    door_tiles: HashSet<u64>,
+    props: Vec<Prop>, // Resting ones; moving props mark their chunks when they settle
+    destructibles: Vec<Destructible>,
+}
+
+// Whether a box centered at `center` with `size` covers the point
+fn covers(center: &Vector3, size: &Vector3, point: &Vector3) -> bool {
+    (point.x - center.x).abs() <= size.x * 0.5 && (point.z - center.z).abs() <= size.z * 0.5
+}
+
+fn build_cells(ctx: &ReducerContext, room: &Room, chunk: &WorldChunk, obstacles: &Obstacles) -> Vec<u8> {
+    let mut cells = Vec::with_capacity((CHUNK_SIZE_TILES * CHUNK_SIZE_TILES) as usize);
+    for dz in 0..CHUNK_SIZE_TILES {
+        for dx in 0..CHUNK_SIZE_TILES {
+            let center = Vector3 {
+                x: (chunk.chunk_x * CHUNK_SIZE_TILES + dx) as f32 * TILE_SIZE,
+                y: 0.0,
+                z: (chunk.chunk_z * CHUNK_SIZE_TILES + dz) as f32 * TILE_SIZE,
+            };
+            let mut flags = 0;
+            if let Some(tile) = map::tile_at(ctx, room.map_id, &center) {
+                if obstacles.door_tiles.contains(&tile.tile_id) {
+                    flags |= MINIMAP_OBSTACLE;
+                } else {
+                    flags |= MINIMAP_WALKABLE;
+                }
+                if let Some(hazard) = &tile.hazard {
+                    if hazard.damage_per_sec > 0 {
+                        flags |= MINIMAP_HAZARD;
+                    }
+                    if hazard.effect == HazardEffect::Slow {
+                        flags |= MINIMAP_WATER;
+                    }
+                }
+            }
+            let obstructed = obstacles.props.iter().any(|p| covers(&p.position, &p.size, &center))
+                || obstacles.destructibles.iter().any(|d| covers(&d.position, &d.size, &center));
+            if obstructed {
+                flags |= MINIMAP_OBSTACLE;
+            }
+            cells.push(flags);
+        }
+    }
+    cells
+}
+
+pub fn rebuild_dirty(ctx: &ReducerContext, room: &Room) {
+    let dirty: Vec<u64> = ctx.db.minimap_dirty().room_name().filter(&room.room_name).map(|d| d.chunk_id).collect();
+    if dirty.is_empty() {
+        return;
+    }
+    let obstacles = Obstacles {
+        door_tiles: door::door_tile_ids(ctx, &room.room_name),
+        props: ctx.db.prop().room_name().filter(&room.room_name).filter(|p| p.at_rest).collect(),
+        destructibles: ctx.db.destructible().room_name().filter(&room.room_name).collect(),
+    };
+    for chunk_id in dirty {
+        ctx.db.minimap_dirty().chunk_id().delete(chunk_id);
+        let Some(chunk) = ctx.db.world_chunk().chunk_id().find(chunk_id) else {
+            continue;
+        };
+        // Chunks of a tile set the room has since left
+        if chunk.map_id != room.map_id {
+            continue;
+        }
+        let minimap = MinimapChunk {
+            chunk_id,
+            room_name: room.room_name.clone(),
+            chunk_x: chunk.chunk_x,
+            chunk_z: chunk.chunk_z,
+            cells: build_cells(ctx, room, &chunk, &obstacles),
+            updated_at: ctx.timestamp,
+        };
+        if ctx.db.minimap_chunk().chunk_id().find(chunk_id).is_some() {
+            ctx.db.minimap_chunk().chunk_id().update(minimap);
+        } else {
+            ctx.db.minimap_chunk().insert(minimap);
+        }
+    }
+}
+
+pub fn clear_chunk(ctx: &ReducerContext, chunk_id: u64) {
+    ctx.db.minimap_chunk().chunk_id().delete(chunk_id);
+    ctx.db.minimap_dirty().chunk_id().delete(chunk_id);
+}
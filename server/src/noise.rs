@@ -0,0 +1,96 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - noise.rs
+ *
+ * Sound propagation for stealth gameplay. Loud actions leave short-lived
+ * noise events that NPCs can hear even without seeing the source.
+ *
+ * Key components:
+ * - NoiseEvent table: Position + audible radius of a recent noise
+ * - emit_noise: Record a noise (attacks, spells, explosions, jumps)
+ * - emit_movement_noise: Called from game_tick; sprinting players are loud
+ * - latest_heard_noise: Most recent noise audible from a position, used by
+ *   npc_ai.rs to send idle NPCs to investigate the last-heard position
+ * - heard_players: Players whose noises a listener is within earshot of,
+ *   used by perception.rs to fill detection meters by hearing
+ * - prune_noise_events: Drops events older than NOISE_EVENT_TTL_SECS
+ *
+ * Related files:
+ * - common.rs: Noise radii per action
+ * - npc_ai.rs: Investigation behavior
+ */
+
+use spacetimedb::{Identity, ReducerContext, Table, Timestamp};
+use std::time::Duration;
+
+use crate::common::{EntityId, Vector3, NOISE_EVENT_TTL_SECS, SPRINT_NOISE_RADIUS};
+use crate::player as _;
+
+#[spacetimedb::table(name = noise_event, public)]
+#[derive(Clone)]
+pub struct NoiseEvent {
+    #[primary_key]
+    #[auto_inc]
+    pub event_id: u64,
+    #[index(btree)]
+    pub room_name: String,
+    pub source: EntityId,
+    pub position: Vector3,
+    pub radius: f32,
+    pub timestamp: Timestamp,
+}
+
+pub fn emit_noise(ctx: &ReducerContext, room_name: &str, source: EntityId, position: &Vector3, radius: f32) {
+    ctx.db.noise_event().insert(NoiseEvent {
+        event_id: 0,
+        room_name: room_name.to_string(),
+        source,
+        position: position.clone(),
+        radius,
+        timestamp: ctx.timestamp,
+    });
+}
+
+pub fn emit_movement_noise(ctx: &ReducerContext, room_name: &str) {
+    let sprinting: Vec<_> = ctx.db.player().room_name().filter(room_name)
+        .filter(|p| p.is_running && !p.is_dead)
+        .collect();
+    for player in sprinting {
+        emit_noise(ctx, room_name, EntityId::Player(player.identity), &player.position, SPRINT_NOISE_RADIUS);
+    }
+}
+
+// Position of the most recent noise in the room that a listener at
+// `listener` is within earshot of
+pub fn latest_heard_noise(ctx: &ReducerContext, room_name: &str, listener: &Vector3) -> Option<Vector3> {
+    ctx.db.noise_event().room_name().filter(room_name)
+        .filter(|event| listener.distance_xz(&event.position) <= event.radius)
+        .max_by_key(|event| event.timestamp)
+        .map(|event| event.position)
+}
+
+// Players behind the recent noises a listener at `listener` is within
+// earshot of (each player once)
+pub fn heard_players(ctx: &ReducerContext, room_name: &str, listener: &Vector3) -> Vec<Identity> {
+    let mut heard = Vec::new();
+    for event in ctx.db.noise_event().room_name().filter(room_name) {
+        if let EntityId::Player(identity) = event.source {
+            if listener.distance_xz(&event.position) <= event.radius && !heard.contains(&identity) {
+                heard.push(identity);
+            }
+        }
+    }
+    heard
+}
+
+pub fn prune_noise_events(ctx: &ReducerContext, room_name: &str) {
+    let Some(cutoff) = ctx.timestamp.checked_sub_duration(Duration::from_secs(NOISE_EVENT_TTL_SECS)) else {
+        return;
+    };
+    let expired: Vec<u64> = ctx.db.noise_event().room_name().filter(room_name)
+        .filter(|event| event.timestamp < cutoff)
+        .map(|event| event.event_id)
+        .collect();
+    for event_id in expired {
+        ctx.db.noise_event().event_id().delete(event_id);
+    }
+}
@@ -0,0 +1,109 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - notification.rs
+ *
+ * A unified per-player notification inbox fed by every subsystem.
+ *
+ * Key components:
+ * - NotificationKind: What happened (party invites, duel challenges, room
+ *   bans, ...); new subsystems add their own kind
+ * - Notification table (public, filtered per recipient): Message, creation
+ *   time and read state. Clients only ever receive their own notifications
+ * - notify: Subsystems call this; each inbox is capped at MAX_NOTIFICATIONS,
+ *   evicting the oldest entries
+ * - mark_read / mark_all_read / dismiss: Recipient-only reducers
+ *
+ * Related files:
+ * - party.rs / duel.rs / room.rs / boss.rs / escort.rs / trade.rs /
+ *   durability.rs / loot_roll.rs: Current notification sources
+ */
+
+use spacetimedb::{client_visibility_filter, Filter, Identity, ReducerContext, SpacetimeType, Table, Timestamp};
+
+use crate::common::MAX_NOTIFICATIONS;
+
+#[derive(SpacetimeType, Clone, Copy, Debug, PartialEq)]
+pub enum NotificationKind {
+    PartyInvite,
+    DuelChallenge,
+    RoomBan,
+    BossDefeated,
+    EscortFinished,
+    TradeOffer,
+    ItemBroken,
+    LootWon,
+}
+
+#[spacetimedb::table(name = notification, public)]
+#[derive(Clone)]
+pub struct Notification {
+    #[primary_key]
+    #[auto_inc]
+    pub notification_id: u64,
+    #[index(btree)]
+    pub recipient: Identity,
+    pub kind: NotificationKind,
+    pub message: String,
+    pub is_read: bool,
+    pub created_at: Timestamp,
+}
+
+#[client_visibility_filter]
+const RECIPIENT_SEES_OWN_NOTIFICATIONS: Filter = Filter::Sql(
+    "SELECT * FROM notification WHERE recipient = :sender",
+);
+
+pub fn notify(ctx: &ReducerContext, recipient: Identity, kind: NotificationKind, message: String) {
+    // Make room: auto_inc ids grow over time, so the lowest ids are the oldest
+    let mut existing: Vec<u64> = ctx.db.notification().recipient().filter(recipient)
+        .map(|n| n.notification_id)
+        .collect();
+    if existing.len() >= MAX_NOTIFICATIONS {
+        existing.sort_unstable();
+        for notification_id in &existing[..=existing.len() - MAX_NOTIFICATIONS] {
+            ctx.db.notification().notification_id().delete(*notification_id);
+        }
+    }
+    ctx.db.notification().insert(Notification {
+        notification_id: 0,
+        recipient,
+        kind,
+        message,
+        is_read: false,
+        created_at: ctx.timestamp,
+    });
+}
+
+fn own_notification(ctx: &ReducerContext, notification_id: u64) -> Result<Notification, String> {
+    ctx.db.notification().notification_id().find(notification_id)
+        .filter(|n| n.recipient == ctx.sender)
+        .ok_or_else(|| "Notification not found".to_string())
+}
+
+#[spacetimedb::reducer]
+pub fn mark_read(ctx: &ReducerContext, notification_id: u64) -> Result<(), String> {
+    let mut notification = own_notification(ctx, notification_id)?;
+    if !notification.is_read {
+        notification.is_read = true;
+        ctx.db.notification().notification_id().update(notification);
+    }
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn mark_all_read(ctx: &ReducerContext) -> Result<(), String> {
+    let unread: Vec<Notification> = ctx.db.notification().recipient().filter(ctx.sender)
+        .filter(|n| !n.is_read)
+        .collect();
+    for mut notification in unread {
+        notification.is_read = true;
+        ctx.db.notification().notification_id().update(notification);
+    }
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn dismiss(ctx: &ReducerContext, notification_id: u64) -> Result<(), String> {
+    own_notification(ctx, notification_id)?;
+    ctx.db.notification().notification_id().delete(notification_id);
+    Ok(())
+}
@@ -0,0 +1,258 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - npc.rs
+ *
+ * Non-player entities that live inside a room.
+ *
+ * Key components:
+ * - Npc table: Name, position, health and kind of every NPC. Names are
+ *   generated from seed lists (generate_name)
+ * - NpcKind: What an NPC is; decides how the damage pipeline treats it
+ * - NpcState: The AI state machine's current state (idle, patrol,
+ *   investigate, chase, attack, return, flee), written by npc_ai.rs and
+ *   critter.rs so clients can pick animations and alert markers
+ * - spawn_training_dummies: Places a row of training dummies in hub rooms
+ * - require_in_range: Proximity check for talking to / trading with an NPC
+ * - spawn_npc / set_npc_personality / set_npc_patrol / set_npc_leash:
+ *   Room-owner reducers for placing hostile NPCs, choosing their AI
+ *   personality, overriding its leash range and giving them a patrol route (up to MAX_PATROL_POINTS waypoints, walked in a loop).
+ *   Shared routes with pauses live in patrol.rs
+ *
+ * Training dummies are invulnerable-but-damageable: hits against them are
+ * resolved and logged like any other hit, but their health never drops.
+ * This lets players measure their damage output on the DPS meter.
+ *
+ * Related files:
+ * - combat.rs: Applies damage to NPCs and records it in the combat log
+ * - npc_ai.rs: Per-tick behavior of hostile NPCs
+ * - room.rs: Spawns dummies when a hub room is created
+ */
+
+use spacetimedb::rand::Rng;
+use spacetimedb::{ReducerContext, SpacetimeType, Table, Timestamp};
+
+use crate::common::{Vector3, INTERACT_RANGE};
+use crate::faction;
+use crate::npc_ai;
+use crate::population;
+use crate::player as _;
+use crate::room;
+use crate::spatial::{self, SpatialEntity};
+
+#[derive(SpacetimeType, Clone, Copy, Debug, PartialEq)]
+pub enum NpcKind {
+    TrainingDummy,
+    Monster, // Runs the combat AI; its faction decides whom it attacks
+    Critter, // Ambient wildlife (critter.rs)
+}
+
+#[derive(SpacetimeType, Clone, Copy, Debug, PartialEq)]
+pub enum NpcState {
+    Idle,
+    Patrol,
+    Investigate,
+    Chase,
+    Attack,
+    Return, // Walking home after losing a target (or fleeing)
+    Flee,   // Critter running away from a player
+}
+
+#[spacetimedb::table(name = npc, public)]
+#[derive(Clone)]
+pub struct Npc {
+    #[primary_key]
+    #[auto_inc]
+    pub npc_id: u64,
+    #[index(btree)]
+    pub room_name: String,
+    pub name: String,
+    pub kind: NpcKind,
+    pub position: Vector3,
+    pub rotation: Vector3,
+    pub health: i32,
+    pub max_health: i32,
+    pub base_max_health: i32, // Before room population scaling (population.rs)
+    pub home_position: Vector3,
+    pub state: NpcState,
+    pub patrol_points: Vec<Vector3>, // Empty = stand guard at home_position
+    pub patrol_index: u32,           // Waypoint currently walked to
+    pub patrol_route_id: Option<u64>, // Shared route (patrol.rs); overrides patrol_points
+    pub patrol_paused_until: Option<Timestamp>, // Waiting at a route waypoint
+    pub personality: String, // Empty = use the room difficulty's default
+    pub faction: String,     // Who this NPC fights (faction.rs)
+    pub investigate_position: Option<Vector3>, // Last-heard noise the NPC is walking to
+    pub staggered_until: Option<Timestamp>,   // Set when a player parries this NPC
+    pub leash_radius: Option<f32>,            // None = the personality's leash
+    pub resetting: bool,                      // Broke its leash; walking home
+    pub reset_invulnerable_until: Option<Timestamp>,
+    pub last_ai_update: Timestamp,            // Drives the coarse LOD update cadence
+}
+
+impl Npc {
+    // Invulnerable NPCs still get hit (and logged) but never lose health
+    pub fn is_invulnerable(&self) -> bool {
+        self.kind == NpcKind::TrainingDummy
+    }
+
+    // Hostile NPCs count as enemies (e.g. when choosing a spawn point)
+    pub fn is_hostile(&self) -> bool {
+        match self.kind {
+            NpcKind::TrainingDummy | NpcKind::Critter => false,
+            NpcKind::Monster => true,
+        }
+    }
+}
+
+const TRAINING_DUMMY_COUNT: i32 = 3;
+const TRAINING_DUMMY_HEALTH: i32 = 1000;
+const MONSTER_HEALTH: i32 = 60;
+const CRITTER_HEALTH: i32 = 5;
+const MAX_PATROL_POINTS: usize = 16;
+
+// Seed lists for generated names: monsters get a made-up name from two
+// syllables, critters a species
+const NAME_SYLLABLES_START: [&str; 8] = ["Gor", "Mal", "Thra", "Ves", "Kor", "Zul", "Bram", "Eld"];
+const NAME_SYLLABLES_END: [&str; 8] = ["ak", "eth", "ion", "uk", "ara", "os", "ric", "wen"];
+const CRITTER_SPECIES: [&str; 4] = ["Rabbit", "Hare", "Sparrow", "Finch"];
+
+pub fn generate_name(ctx: &ReducerContext, kind: NpcKind) -> String {
+    let mut rng = ctx.rng();
+    match kind {
+        NpcKind::TrainingDummy => "Training Dummy".to_string(),
+        NpcKind::Monster => format!(
+            "{}{}",
+            NAME_SYLLABLES_START[rng.gen_range(0..NAME_SYLLABLES_START.len())],
+            NAME_SYLLABLES_END[rng.gen_range(0..NAME_SYLLABLES_END.len())],
+        ),
+        NpcKind::Critter => CRITTER_SPECIES[rng.gen_range(0..CRITTER_SPECIES.len())].to_string(),
+    }
+}
+
+fn base_health(kind: NpcKind) -> i32 {
+    match kind {
+        NpcKind::TrainingDummy => TRAINING_DUMMY_HEALTH,
+        NpcKind::Monster => MONSTER_HEALTH,
+        NpcKind::Critter => CRITTER_HEALTH,
+    }
+}
+
+pub fn spawn_npc_at(ctx: &ReducerContext, room_name: &str, kind: NpcKind, position: Vector3, personality: String) -> Npc {
+    let mut npc = Npc {
+        npc_id: 0,
+        room_name: room_name.to_string(),
+        name: generate_name(ctx, kind),
+        kind,
+        position: position.clone(),
+        rotation: Vector3 { x: 0.0, y: 0.0, z: 0.0 },
+        health: 0,
+        max_health: 0,
+        base_max_health: 0,
+        home_position: position,
+        state: NpcState::Idle,
+        patrol_points: Vec::new(),
+        patrol_index: 0,
+        patrol_route_id: None,
+        patrol_paused_until: None,
+        personality,
+        faction: faction::default_faction(kind).to_string(),
+        investigate_position: None,
+        staggered_until: None,
+        leash_radius: None,
+        resetting: false,
+        reset_invulnerable_until: None,
+        last_ai_update: ctx.timestamp,
+    };
+    population::set_base_health(ctx, &mut npc, base_health(kind));
+    let npc = ctx.db.npc().insert(npc);
+    spatial::track(ctx, SpatialEntity::Npc(npc.npc_id), room_name, &npc.position);
+    npc
+}
+
+// The NPC, if the calling player is alive, in its room and within
+// INTERACT_RANGE (talking, trading)
+pub fn require_in_range(ctx: &ReducerContext, npc_id: u64) -> Result<Npc, String> {
+    let Some(player) = ctx.db.player().identity().find(ctx.sender) else {
+        return Err("Player not found".to_string());
+    };
+    let Some(npc) = ctx.db.npc().npc_id().find(npc_id) else {
+        return Err("NPC not found".to_string());
+    };
+    if player.is_dead {
+        return Err("Dead players can't interact".to_string());
+    }
+    if player.room_name != npc.room_name || player.position.distance_xz(&npc.position) > INTERACT_RANGE {
+        return Err("Too far away".to_string());
+    }
+    Ok(npc)
+}
+
+pub fn spawn_training_dummies(ctx: &ReducerContext, room_name: &str) {
+    for i in 0..TRAINING_DUMMY_COUNT {
+        let position = Vector3 { x: (i as f32 * 4.0) - 4.0, y: 1.0, z: -10.0 };
+        spawn_npc_at(ctx, room_name, NpcKind::TrainingDummy, position, String::new());
+    }
+    spacetimedb::log::info!("Spawned {} training dummies in room '{}'.", TRAINING_DUMMY_COUNT, room_name);
+}
+
+// --- NPC Reducers ---
+
+#[spacetimedb::reducer]
+pub fn spawn_npc(
+    ctx: &ReducerContext,
+    room_name: String,
+    kind: NpcKind,
+    position: Vector3,
+    personality: String,
+) -> Result<(), String> {
+    room::require_room_owner(ctx, &room_name)?;
+    if !personality.is_empty() && !npc_ai::personality_exists(ctx, &personality) {
+        return Err(format!("Unknown AI personality '{}'", personality));
+    }
+    let npc = spawn_npc_at(ctx, &room_name, kind, position, personality);
+    spacetimedb::log::info!("Spawned NPC {} ({:?}) in room '{}'.", npc.npc_id, kind, room_name);
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn set_npc_personality(ctx: &ReducerContext, npc_id: u64, personality: String) -> Result<(), String> {
+    let Some(mut npc) = ctx.db.npc().npc_id().find(npc_id) else {
+        return Err("NPC not found".to_string());
+    };
+    room::require_room_owner(ctx, &npc.room_name)?;
+    if !personality.is_empty() && !npc_ai::personality_exists(ctx, &personality) {
+        return Err(format!("Unknown AI personality '{}'", personality));
+    }
+    npc.personality = personality;
+    ctx.db.npc().npc_id().update(npc);
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn set_npc_patrol(ctx: &ReducerContext, npc_id: u64, patrol_points: Vec<Vector3>) -> Result<(), String> {
+    let Some(mut npc) = ctx.db.npc().npc_id().find(npc_id) else {
+        return Err("NPC not found".to_string());
+    };
+    room::require_room_owner(ctx, &npc.room_name)?;
+    if patrol_points.len() > MAX_PATROL_POINTS {
+        return Err(format!("A patrol route can have at most {} points", MAX_PATROL_POINTS));
+    }
+    npc.patrol_points = patrol_points;
+    npc.patrol_index = 0;
+    ctx.db.npc().npc_id().update(npc);
+    Ok(())
+}
+
+// Override how far the NPC may be dragged from home before it resets (None =
+// the personality's leash_radius)
+#[spacetimedb::reducer]
+pub fn set_npc_leash(ctx: &ReducerContext, npc_id: u64, leash_radius: Option<f32>) -> Result<(), String> {
+    let Some(mut npc) = ctx.db.npc().npc_id().find(npc_id) else {
+        return Err("NPC not found".to_string());
+    };
+    room::require_room_owner(ctx, &npc.room_name)?;
+    if leash_radius.is_some_and(|r| !r.is_finite() || r <= 0.0) {
+        return Err("Leash radius must be a positive number".to_string());
+    }
+    npc.leash_radius = leash_radius;
+    ctx.db.npc().npc_id().update(npc);
+    Ok(())
+}
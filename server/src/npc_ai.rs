@@ -0,0 +1,482 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - npc_ai.rs
+ *
+ * Per-tick behavior for hostile NPCs, driven by data rather than code.
+ *
+ * Key components:
+ *
+ * 1. AI Personalities:
+ *    - AiPersonality table: Named parameter sets (aggro radius, leash, speed,
+ *      damage, retaliation, flanking angle, flee threshold)
+ *    - seed_personalities: Inserts passive / defensive / aggressive / flanker in init
+ *    - upsert_ai_personality: Admin reducer so designers can tune or add sets.
+ *      Distances and speed must be finite and non-negative, the flee
+ *      threshold a fraction of 0-1
+ *
+ * 2. Personality Resolution:
+ *    - An NPC's own `personality` wins; an empty value falls back to the
+ *      default personality of its room's difficulty
+ *
+ * 3. AI Tick:
+ *    - update_npcs: Called from each room's game_tick. Picks a target (highest threat first,
+ *      see the threat table in combat.rs, then the player the NPC has fully detected through its vision cone
+ *      and hearing, see perception.rs), approaches it (from the
+ *      side/back for flankers), attacks in range, and returns home when leashed
+ *      or below the flee threshold. Chasing NPCs route around obstacles with
+ *      the room's budgeted path planner (pathfinding.rs)
+ *    - Factions (faction.rs): an NPC only spots players if its faction is
+ *      hostile to FACTION_PLAYERS, and without a player target it attacks
+ *      the nearest visible NPC of a hostile faction (guards vs monsters)
+ *    - Leash resets: an NPC dragged past its leash (Npc.leash_radius, else
+ *      the personality's) in a fight drops all threat and detection, evades
+ *      damage for NPC_RESET_INVULNERABLE_MS and paths home regenerating
+ *      health, ignoring players until it arrives
+ *    - Staggered NPCs (parried by a player) skip their turn; escorted NPCs
+ *      (escort.rs) are moved by their escort instead
+ *    - Without a target, NPCs that hear a noise (noise.rs) walk to the
+ *      last-heard position to investigate before heading home
+ *    - Otherwise they run their kind's behavior script (npc_script.rs), if
+ *      there is one, or walk their patrol route in a loop (a shared route from
+ *      patrol.rs, pausing at its waypoints, or set_npc_patrol's points), or
+ *      return home and stand idle
+ *    - NPC damage scales with the room's player count (population.rs)
+ *    - Each update records the resulting NpcState (Idle / Patrol /
+ *      Investigate / Chase / Attack / Return) on the NPC row
+ *    - Simulation LOD: only NPCs within AI_LOD_NEAR_DISTANCE of a player (and
+ *      at most AI_LOD_MAX_FULL_PER_ROOM of them, nearest first) run the full
+ *      AI each tick. The rest get a coarse movement-only update every
+ *      AI_LOD_COARSE_INTERVAL_SECS, keeping game_tick within budget for hordes
+ *    - After all NPCs in a room have moved, steering.rs keeps each spawner's
+ *      group together and separates any that ended up overlapping each other
+ *      or players
+ *
+ * Related files:
+ *    - npc.rs: Npc table and spawning
+ *    - room.rs: RoomDifficulty -> default personality
+ *    - combat.rs: NPC attacks go through apply_damage like player attacks
+ */
+
+use spacetimedb::{ReducerContext, Table};
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::admin;
+use crate::combat;
+use crate::escort;
+use crate::faction::{FactionMatrix, FACTION_PLAYERS};
+use crate::common::{
+    forward_from_yaw, CombatEventKind, EntityId, Vector3, AI_LOD_COARSE_INTERVAL_SECS,
+    AI_LOD_MAX_FULL_PER_ROOM, AI_LOD_NEAR_DISTANCE, NPC_RESET_INVULNERABLE_MS, NPC_RESET_REGEN_PER_SEC,
+    THREAT_ON_DETECTION,
+};
+use crate::noise;
+use crate::npc::{npc as _, Npc, NpcState};
+use crate::npc_script::{self, NpcScript};
+use crate::pathfinding::PathPlanner;
+use crate::patrol;
+use crate::perception;
+use crate::player as _;
+use crate::population::PopulationScale;
+use crate::room::Room;
+use crate::spatial::{self, SpatialEntity};
+use crate::steering;
+use crate::weather;
+
+pub const PERSONALITY_PASSIVE: &str = "passive";
+pub const PERSONALITY_DEFENSIVE: &str = "defensive";
+pub const PERSONALITY_AGGRESSIVE: &str = "aggressive";
+pub const PERSONALITY_FLANKER: &str = "flanker";
+
+#[spacetimedb::table(name = ai_personality, public)]
+#[derive(Clone)]
+pub struct AiPersonality {
+    #[primary_key]
+    pub name: String,
+    pub aggro_radius: f32,         // Sight range of the vision cone; 0 = never initiates combat
+    pub leash_radius: f32,         // Max distance from home before giving up the chase
+    pub move_speed: f32,
+    pub attack_range: f32,
+    pub attack_damage: i32,
+    pub retaliates: bool,          // Target the highest-threat player even outside sight range
+    pub flank_angle: f32,          // Radians around the target to approach from (0 = head on)
+    pub flee_health_fraction: f32, // Retreat home below this fraction of max health
+}
+
+pub fn seed_personalities(ctx: &ReducerContext) {
+    if ctx.db.ai_personality().count() > 0 {
+        return;
+    }
+    let defaults = [
+        (PERSONALITY_PASSIVE, 0.0, 10.0, 3.0, 2.0, 4, true, 0.0, 0.3),
+        (PERSONALITY_DEFENSIVE, 6.0, 12.0, 4.0, 2.0, 6, true, 0.0, 0.2),
+        (PERSONALITY_AGGRESSIVE, 15.0, 30.0, 5.0, 2.0, 8, true, 0.0, 0.0),
+        (PERSONALITY_FLANKER, 12.0, 25.0, 6.0, 2.0, 7, true, std::f32::consts::FRAC_PI_2 * 1.5, 0.1),
+    ];
+    for (name, aggro, leash, speed, range, damage, retaliates, flank, flee) in defaults {
+        ctx.db.ai_personality().insert(AiPersonality {
+            name: name.to_string(),
+            aggro_radius: aggro,
+            leash_radius: leash,
+            move_speed: speed,
+            attack_range: range,
+            attack_damage: damage,
+            retaliates,
+            flank_angle: flank,
+            flee_health_fraction: flee,
+        });
+    }
+}
+
+pub fn personality_exists(ctx: &ReducerContext, name: &str) -> bool {
+    ctx.db.ai_personality().name().find(name.to_string()).is_some()
+}
+
+#[spacetimedb::reducer]
+pub fn upsert_ai_personality(ctx: &ReducerContext, personality: AiPersonality) -> Result<(), String> {
+    admin::require_admin(ctx)?;
+    if personality.name.is_empty() {
+        return Err("Personality name must not be empty".to_string());
+    }
+    let distances = [
+        personality.aggro_radius,
+        personality.leash_radius,
+        personality.move_speed,
+        personality.attack_range,
+    ];
+    if distances.iter().any(|d| !d.is_finite() || *d < 0.0) {
+        return Err("Radii, speed and range must be non-negative numbers".to_string());
+    }
+    if !personality.flank_angle.is_finite() {
+        return Err("Flank angle must be a number".to_string());
+    }
+    if !(0.0..=1.0).contains(&personality.flee_health_fraction) {
+        return Err("flee_health_fraction must be between 0 and 1".to_string());
+    }
+    if ctx.db.ai_personality().name().find(personality.name.clone()).is_some() {
+        ctx.db.ai_personality().name().update(personality);
+    } else {
+        ctx.db.ai_personality().insert(personality);
+    }
+    Ok(())
+}
+
+// Step from `from` towards `to` by at most `max_step`, on the ground plane
+pub fn move_towards(from: &Vector3, to: &Vector3, max_step: f32) -> Vector3 {
+    let distance = from.distance_xz(to);
+    if distance <= max_step || distance < 0.001 {
+        return Vector3 { x: to.x, y: from.y, z: to.z };
+    }
+    let t = max_step / distance;
+    Vector3 { x: from.x + (to.x - from.x) * t, y: from.y, z: from.z + (to.z - from.z) * t }
+}
+
+// Yaw that makes an entity at `from` face `to` (inverse of forward_from_yaw)
+pub fn yaw_towards(from: &Vector3, to: &Vector3) -> f32 {
+    (-(to.x - from.x)).atan2(-(to.z - from.z))
+}
+
+// Without a target or a noise to check: walk the patrol route (the shared
+// route if assigned, pausing at its waypoints, else the NPC's own points), or
+// head home (always home while fleeing) and idle there
+fn walk_route(ctx: &ReducerContext, npc: &mut Npc, max_step: f32, fleeing: bool) {
+    let route = npc.patrol_route_id.map(|id| patrol::route_waypoints(ctx, id)).unwrap_or_default();
+    let waypoints: Vec<(Vector3, u64)> = if route.is_empty() {
+        npc.patrol_points.iter().map(|p| (p.clone(), 0)).collect()
+    } else {
+        route.into_iter().map(|w| (w.position, w.pause_ms)).collect()
+    };
+    if !fleeing && !waypoints.is_empty() {
+        if npc.patrol_paused_until.is_some_and(|until| until > ctx.timestamp) {
+            npc.state = NpcState::Idle;
+            return;
+        }
+        npc.patrol_paused_until = None;
+        let index = npc.patrol_index as usize % waypoints.len();
+        let (waypoint, pause_ms) = waypoints[index].clone();
+        npc.state = NpcState::Patrol;
+        npc.rotation.y = yaw_towards(&npc.position, &waypoint);
+        npc.position = move_towards(&npc.position, &waypoint, max_step);
+        if npc.position.distance_xz(&waypoint) < 0.5 {
+            npc.patrol_index = ((index + 1) % waypoints.len()) as u32;
+            if pause_ms > 0 {
+                npc.patrol_paused_until = Some(ctx.timestamp + Duration::from_millis(pause_ms));
+            }
+        }
+    } else if npc.position.distance_xz(&npc.home_position) > 0.1 {
+        let home = npc.home_position.clone();
+        npc.state = NpcState::Return;
+        npc.rotation.y = yaw_towards(&npc.position, &home);
+        npc.position = move_towards(&npc.position, &home, max_step);
+    } else {
+        npc.state = NpcState::Idle;
+    }
+}
+
+// Run the AI for every hostile NPC in one room (called from that room's game_tick)
+pub fn update_npcs(ctx: &ReducerContext, room: &Room, delta_time: f64) {
+    if room.current_player_count == 0 {
+        return;
+    }
+    let personalities: HashMap<String, AiPersonality> = ctx.db.ai_personality().iter()
+        .map(|p| (p.name.clone(), p))
+        .collect();
+
+    let default_personality = room.difficulty.default_personality();
+    let player_positions: Vec<Vector3> = ctx.db.player().room_name().filter(&room.room_name)
+        .filter(|p| !p.is_dead)
+        .map(|p| p.position)
+        .collect();
+
+    // Closest NPCs first so they win the full-AI budget. Only ids are kept:
+    // NPCs fight each other, so an earlier turn can hurt or kill a later one
+    let mut npcs: Vec<(f32, u64)> = ctx.db.npc().room_name().filter(&room.room_name)
+        .filter(|n| n.is_hostile() && !escort::is_escorted(ctx, n.npc_id))
+        .map(|n| {
+            let nearest = player_positions.iter()
+                .map(|p| n.position.distance_xz(p))
+                .fold(f32::INFINITY, f32::min);
+            (nearest, n.npc_id)
+        })
+        .collect();
+    npcs.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let shared = SharedTickData {
+        factions: FactionMatrix::load(ctx),
+        scripts: npc_script::load_scripts(ctx),
+        map_id: room.map_id,
+        scale: PopulationScale::for_players(room.current_player_count),
+        vision_multiplier: weather::current(ctx, &room.room_name).vision_multiplier(),
+    };
+    let mut planner = PathPlanner::new(ctx, room);
+    let mut full_updates = 0;
+    for (nearest_player, npc_id) in npcs {
+        let Some(npc) = ctx.db.npc().npc_id().find(npc_id) else {
+            continue;
+        };
+        if npc.staggered_until.is_some_and(|until| until > ctx.timestamp) {
+            continue;
+        }
+        let name = if npc.personality.is_empty() { default_personality } else { npc.personality.as_str() };
+        let Some(personality) = personalities.get(name) else {
+            spacetimedb::log::warn!("NPC {} has unknown personality '{}'", npc.npc_id, name);
+            continue;
+        };
+        if nearest_player <= AI_LOD_NEAR_DISTANCE && full_updates < AI_LOD_MAX_FULL_PER_ROOM {
+            full_updates += 1;
+            update_npc(ctx, npc, personality, &shared, &mut planner, delta_time as f32);
+        } else {
+            let elapsed = ctx.timestamp.duration_since(npc.last_ai_update).unwrap_or_default();
+            if elapsed.as_secs_f32() >= AI_LOD_COARSE_INTERVAL_SECS {
+                update_npc_coarse(ctx, npc, personality, elapsed.as_secs_f32());
+            }
+        }
+    }
+    steering::apply_cohesion(ctx, &room.room_name);
+    steering::apply_separation(ctx, &room.room_name);
+}
+
+// Read once per room tick and shared by every NPC update
+struct SharedTickData {
+    factions: FactionMatrix,
+    scripts: Vec<NpcScript>,
+    map_id: u32,
+    scale: PopulationScale, // Room population scaling of NPC damage
+    vision_multiplier: f32, // Weather (fog) shortens sight ranges
+}
+
+// Closest NPC within `range` whose faction is hostile to `npc`'s and that it
+// can see
+fn nearest_hostile_npc(ctx: &ReducerContext, npc: &Npc, factions: &FactionMatrix, map_id: u32, range: f32) -> Option<Npc> {
+    if range <= 0.0 {
+        return None;
+    }
+    spatial::query_radius(ctx, &npc.room_name, &npc.position, range).into_iter()
+        .filter_map(|hit| match hit.entity {
+            SpatialEntity::Npc(other_id) if other_id != npc.npc_id => {
+                ctx.db.npc().npc_id().find(other_id).map(|other| (hit.distance, other))
+            }
+            _ => None,
+        })
+        .filter(|(_, other)| factions.is_hostile(&npc.faction, &other.faction))
+        .filter(|(_, other)| spatial::has_line_of_sight(ctx, map_id, &npc.position, &other.position))
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, other)| other)
+}
+
+// Leash broken: forget every target and walk home (see step_reset)
+fn start_reset(ctx: &ReducerContext, npc: &mut Npc) {
+    npc.resetting = true;
+    npc.reset_invulnerable_until = Some(ctx.timestamp + Duration::from_millis(NPC_RESET_INVULNERABLE_MS));
+    npc.investigate_position = None;
+    combat::clear_threat_on_npc(ctx, npc.npc_id);
+    perception::clear_npc(ctx, npc.npc_id);
+}
+
+// Walk towards `next` while regenerating health; back to full health and
+// normal behavior once home
+fn step_reset(npc: &mut Npc, next: &Vector3, max_step: f32, delta_time: f32) {
+    let regen = (npc.max_health as f32 * NPC_RESET_REGEN_PER_SEC * delta_time).ceil() as i32;
+    npc.health = (npc.health + regen).min(npc.max_health);
+    npc.state = NpcState::Return;
+    npc.rotation.y = yaw_towards(&npc.position, next);
+    npc.position = move_towards(&npc.position, next, max_step);
+    if npc.position.distance_xz(&npc.home_position) < 0.5 {
+        npc.resetting = false;
+        npc.health = npc.max_health;
+        npc.state = NpcState::Idle;
+    }
+}
+
+fn save(ctx: &ReducerContext, mut npc: Npc) {
+    npc.last_ai_update = ctx.timestamp;
+    spatial::track(ctx, SpatialEntity::Npc(npc.npc_id), &npc.room_name, &npc.position);
+    ctx.db.npc().npc_id().update(npc);
+}
+
+fn update_npc(
+    ctx: &ReducerContext,
+    mut npc: Npc,
+    personality: &AiPersonality,
+    shared: &SharedTickData,
+    planner: &mut PathPlanner,
+    delta_time: f32,
+) {
+    let health_fraction = npc.health as f32 / npc.max_health.max(1) as f32;
+    let fleeing = health_fraction < personality.flee_health_fraction;
+    let home = npc.home_position.clone();
+    let leash = npc.leash_radius.unwrap_or(personality.leash_radius);
+    let max_step = personality.move_speed * delta_time;
+
+    // Only a fight can drag an NPC past its leash; patrols may roam further
+    let fighting = matches!(npc.state, NpcState::Chase | NpcState::Attack);
+    if !npc.resetting && fighting && npc.position.distance_xz(&home) > leash {
+        start_reset(ctx, &mut npc);
+    }
+    if npc.resetting {
+        let next = planner.waypoint(ctx, npc.npc_id, &npc.position, &home);
+        step_reset(&mut npc, &next, max_step, delta_time);
+        save(ctx, npc);
+        return;
+    }
+
+    // Meters keep filling/decaying even while fleeing or fighting. NPCs whose
+    // faction isn't hostile to players never spot them (but still retaliate)
+    let hostile_to_players = shared.factions.is_hostile(&npc.faction, FACTION_PLAYERS);
+    let sight_range = if hostile_to_players { personality.aggro_radius * shared.vision_multiplier } else { 0.0 };
+    let detected = perception::update_detection(ctx, &npc, shared.map_id, sight_range, delta_time);
+    let threat_target = if personality.retaliates && !fleeing {
+        combat::highest_threat_player(ctx, npc.npc_id, &npc.room_name)
+    } else {
+        None
+    };
+    if let Some(attacker) = &threat_target {
+        perception::alert(ctx, npc.npc_id, attacker.identity);
+    }
+    let player_target = threat_target.or_else(|| {
+        if fleeing {
+            return None;
+        }
+        let spotted = detected.and_then(|identity| ctx.db.player().identity().find(identity))?;
+        // Engage: a threat entry keeps the NPC on this player even if it loses sight
+        combat::add_threat(ctx, npc.npc_id, spotted.identity, THREAT_ON_DETECTION);
+        Some(spotted)
+    });
+    // Never chase past the leash
+    let player_target = player_target.filter(|p| home.distance_xz(&p.position) <= leash);
+    let target = match player_target {
+        Some(player) => Some((EntityId::Player(player.identity), player.position, player.rotation.y)),
+        None if !fleeing => nearest_hostile_npc(ctx, &npc, &shared.factions, shared.map_id, personality.aggro_radius)
+            .filter(|other| home.distance_xz(&other.position) <= leash)
+            .map(|other| (EntityId::Npc(other.npc_id), other.position, other.rotation.y)),
+        None => None,
+    };
+    if target.is_some() {
+        npc.investigate_position = None;
+    }
+
+    match target {
+        Some((target_id, target_position, target_yaw)) => {
+            let distance = npc.position.distance_xz(&target_position);
+            if distance <= personality.attack_range {
+                npc.state = NpcState::Attack;
+                npc.rotation.y = yaw_towards(&npc.position, &target_position);
+                combat::apply_damage(
+                    ctx,
+                    &EntityId::Npc(npc.npc_id),
+                    &target_id,
+                    shared.scale.scale_damage(personality.attack_damage),
+                    CombatEventKind::Melee,
+                );
+            } else {
+                npc.state = NpcState::Chase;
+                // Flankers aim for a point rotated around the target's facing
+                let approach = if personality.flank_angle != 0.0 {
+                    let offset = forward_from_yaw(target_yaw + personality.flank_angle);
+                    let reach = personality.attack_range * 0.8;
+                    Vector3 {
+                        x: target_position.x + offset.x * reach,
+                        y: target_position.y,
+                        z: target_position.z + offset.z * reach,
+                    }
+                } else {
+                    target_position.clone()
+                };
+                let next = planner.waypoint(ctx, npc.npc_id, &npc.position, &approach);
+                npc.rotation.y = yaw_towards(&npc.position, &next);
+                npc.position = move_towards(&npc.position, &next, max_step);
+            }
+        }
+        None => {
+            if !fleeing {
+                if let Some(heard) = noise::latest_heard_noise(ctx, &npc.room_name, &npc.position) {
+                    if home.distance_xz(&heard) <= leash {
+                        npc.investigate_position = Some(heard);
+                    }
+                }
+            }
+            if fleeing {
+                npc.investigate_position = None;
+            }
+            if let Some(goal) = npc.investigate_position.clone() {
+                npc.state = NpcState::Investigate;
+                npc.rotation.y = yaw_towards(&npc.position, &goal);
+                npc.position = move_towards(&npc.position, &goal, max_step);
+                if npc.position.distance_xz(&goal) < 0.5 {
+                    npc.investigate_position = None;
+                }
+            } else if fleeing || !npc_script::run_script(
+                ctx,
+                &mut npc,
+                &shared.scripts,
+                max_step,
+                personality.attack_range,
+                shared.scale.scale_damage(personality.attack_damage),
+            ) {
+                walk_route(ctx, &mut npc, max_step, fleeing);
+            }
+        }
+    }
+
+    save(ctx, npc);
+}
+
+// Cheap update for NPCs far from every player: no perception, threat or
+// attacks, just drift towards the current investigation point or home using
+// all the time elapsed since the last update
+fn update_npc_coarse(ctx: &ReducerContext, mut npc: Npc, personality: &AiPersonality, elapsed: f32) {
+    let max_step = personality.move_speed * elapsed;
+    if npc.resetting {
+        let home = npc.home_position.clone();
+        step_reset(&mut npc, &home, max_step, elapsed);
+    } else if let Some(goal) = npc.investigate_position.clone() {
+        npc.state = NpcState::Investigate;
+        npc.rotation.y = yaw_towards(&npc.position, &goal);
+        npc.position = move_towards(&npc.position, &goal, max_step);
+        if npc.position.distance_xz(&goal) < 0.5 {
+            npc.investigate_position = None;
+        }
+    } else {
+        walk_route(ctx, &mut npc, max_step, false);
+    }
+    save(ctx, npc);
+}
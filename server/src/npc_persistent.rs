@@ -0,0 +1,137 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - npc_persistent.rs
+ *
+ * Notable NPCs (vendors, quest givers) that keep their identity across
+ * module restarts.
+ *
+ * Key components:
+ * - NpcPersistent table (public): A snapshot of a notable NPC's name, kind,
+ *   room, home position, personality and faction, linked to the NPC that
+ *   currently embodies it
+ * - restore_persistent_npcs: Called in init. Respawns every notable NPC whose
+ *   NPC row is gone (and whose room still exists) and relinks the snapshot
+ * - set_npc_persistent / set_npc_name: Room-owner reducers. Renaming a
+ *   notable NPC also updates its snapshot
+ *
+ * Related files:
+ * - npc.rs: spawn_npc_at / generate_name
+ * - lib.rs: init
+ */
+
+use spacetimedb::{ReducerContext, Table};
+
+use crate::common::{Vector3, MAX_NPC_NAME_LEN};
+use crate::npc::{self, npc as _, Npc, NpcKind};
+use crate::room;
+
+#[spacetimedb::table(name = npc_persistent, public)]
+#[derive(Clone)]
+pub struct NpcPersistent {
+    #[primary_key]
+    #[auto_inc]
+    pub persistent_id: u64,
+    #[unique]
+    pub npc_id: u64,
+    #[index(btree)]
+    pub room_name: String,
+    pub name: String,
+    pub kind: NpcKind,
+    pub home_position: Vector3,
+    pub personality: String,
+    pub faction: String,
+}
+
+fn snapshot(npc: &Npc, persistent_id: u64) -> NpcPersistent {
+    NpcPersistent {
+        persistent_id,
+        npc_id: npc.npc_id,
+        room_name: npc.room_name.clone(),
+        name: npc.name.clone(),
+        kind: npc.kind,
+        home_position: npc.home_position.clone(),
+        personality: npc.personality.clone(),
+        faction: npc.faction.clone(),
+    }
+}
+
+pub fn restore_persistent_npcs(ctx: &ReducerContext) {
+    let missing: Vec<NpcPersistent> = ctx.db.npc_persistent().iter()
+        .filter(|p| ctx.db.npc().npc_id().find(p.npc_id).is_none())
+        .collect();
+    for persistent in missing {
+        if room::find_room(ctx, &persistent.room_name).is_none() {
+            spacetimedb::log::warn!("[INIT] Notable NPC '{}' lost its room '{}'.", persistent.name, persistent.room_name);
+            continue;
+        }
+        let mut npc = npc::spawn_npc_at(
+            ctx,
+            &persistent.room_name,
+            persistent.kind,
+            persistent.home_position.clone(),
+            persistent.personality.clone(),
+        );
+        npc.name = persistent.name.clone();
+        npc.faction = persistent.faction.clone();
+        ctx.db.npc_persistent().persistent_id().update(NpcPersistent { npc_id: npc.npc_id, ..persistent });
+        ctx.db.npc().npc_id().update(npc);
+    }
+}
+
+// Mark a freshly spawned NPC as notable (world snapshots restoring one)
+pub fn persist(ctx: &ReducerContext, npc: &Npc) {
+    ctx.db.npc_persistent().insert(snapshot(npc, 0));
+}
+
+pub fn is_persistent(ctx: &ReducerContext, npc_id: u64) -> bool {
+    ctx.db.npc_persistent().npc_id().find(npc_id).is_some()
+}
+
+// The room is gone for good: its notable NPCs are no longer restored
+pub fn clear_room(ctx: &ReducerContext, room_name: &str) {
+    let rows: Vec<u64> = ctx.db.npc_persistent().room_name().filter(room_name).map(|p| p.persistent_id).collect();
+    for persistent_id in rows {
+        ctx.db.npc_persistent().persistent_id().delete(persistent_id);
+    }
+}
+
+// --- Persistence Reducers ---
+
+#[spacetimedb::reducer]
+pub fn set_npc_persistent(ctx: &ReducerContext, npc_id: u64, persistent: bool) -> Result<(), String> {
+    let Some(npc) = ctx.db.npc().npc_id().find(npc_id) else {
+        return Err("NPC not found".to_string());
+    };
+    room::require_room_owner(ctx, &npc.room_name)?;
+    let existing = ctx.db.npc_persistent().npc_id().find(npc_id);
+    match (existing, persistent) {
+        (Some(row), true) => {
+            ctx.db.npc_persistent().persistent_id().update(snapshot(&npc, row.persistent_id));
+        }
+        (Some(_), false) => {
+            ctx.db.npc_persistent().npc_id().delete(npc_id);
+        }
+        (None, true) => {
+            ctx.db.npc_persistent().insert(snapshot(&npc, 0));
+        }
+        (None, false) => {}
+    }
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn set_npc_name(ctx: &ReducerContext, npc_id: u64, name: String) -> Result<(), String> {
+    let Some(mut npc) = ctx.db.npc().npc_id().find(npc_id) else {
+        return Err("NPC not found".to_string());
+    };
+    room::require_room_owner(ctx, &npc.room_name)?;
+    let name = name.trim().to_string();
+    if name.is_empty() || name.len() > MAX_NPC_NAME_LEN {
+        return Err(format!("NPC names must be 1-{} characters", MAX_NPC_NAME_LEN));
+    }
+    npc.name = name;
+    if let Some(row) = ctx.db.npc_persistent().npc_id().find(npc_id) {
+        ctx.db.npc_persistent().persistent_id().update(snapshot(&npc, row.persistent_id));
+    }
+    ctx.db.npc().npc_id().update(npc);
+    Ok(())
+}
@@ -0,0 +1,190 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - npc_script.rs
+ *
+ * Designer-authored idle behaviors: short scripts of move / emote / wait /
+ * attack steps, stored as data and interpreted by the AI tick, so new
+ * behaviors don't need a module rebuild.
+ *
+ * Key components:
+ * - ScriptStep: MoveTo (an offset from the NPC's home), Emote, Wait and
+ *   Attack (hit the nearest living player within attack range, if any)
+ * - NpcScript table (public): A named script for one NpcKind (at most one
+ *   script per kind). Scripts loop back to the first step after the last
+ * - NpcScriptState table (public): Each NPC's step, wait timer and current
+ *   emote, so clients can play the emote
+ * - run_script: Called by npc_ai.rs in place of the patrol route when the
+ *   NPC has nothing to fight or investigate. Distant NPCs on the coarse LOD
+ *   update don't run scripts
+ * - upsert_npc_script / delete_npc_script: Admin reducers
+ *
+ * Related files:
+ * - npc_ai.rs: Calls run_script
+ * - combat.rs: Attack steps go through apply_damage; dead NPCs are cleared
+ */
+
+use spacetimedb::{ReducerContext, SpacetimeType, Table, Timestamp};
+use std::time::Duration;
+
+use crate::admin;
+use crate::combat;
+use crate::common::{CombatEventKind, EntityId, Vector3, MAX_SCRIPT_STEPS};
+use crate::npc::{Npc, NpcKind, NpcState};
+use crate::npc_ai::{move_towards, yaw_towards};
+use crate::player as _;
+
+#[derive(SpacetimeType, Clone, Debug, PartialEq)]
+pub enum ScriptStep {
+    MoveTo(Vector3), // Offset from the NPC's home position
+    Emote(String),
+    Wait(u64), // Milliseconds
+    Attack,
+}
+
+#[spacetimedb::table(name = npc_script, public)]
+#[derive(Clone)]
+pub struct NpcScript {
+    #[primary_key]
+    #[auto_inc]
+    pub script_id: u64,
+    #[unique]
+    pub name: String,
+    pub kind: NpcKind,
+    pub steps: Vec<ScriptStep>,
+}
+
+#[spacetimedb::table(name = npc_script_state, public)]
+#[derive(Clone)]
+pub struct NpcScriptState {
+    #[primary_key]
+    pub npc_id: u64,
+    pub script_id: u64,
+    pub step_index: u32,
+    pub wait_until: Option<Timestamp>,
+    pub emote: Option<String>,
+    pub emote_at: Option<Timestamp>,
+}
+
+// Every script, read once per AI tick
+pub fn load_scripts(ctx: &ReducerContext) -> Vec<NpcScript> {
+    ctx.db.npc_script().iter().collect()
+}
+
+pub fn clear_npc(ctx: &ReducerContext, npc_id: u64) {
+    ctx.db.npc_script_state().npc_id().delete(npc_id);
+}
+
+// Advance `npc` through the script for its kind. Returns false if its kind
+// has no script, so the caller falls back to the patrol route
+pub fn run_script(
+    ctx: &ReducerContext,
+    npc: &mut Npc,
+    scripts: &[NpcScript],
+    max_step: f32,
+    attack_range: f32,
+    attack_damage: i32,
+) -> bool {
+    let Some(script) = scripts.iter().find(|s| s.kind == npc.kind && !s.steps.is_empty()) else {
+        return false;
+    };
+    let mut state = match ctx.db.npc_script_state().npc_id().find(npc.npc_id) {
+        Some(state) if state.script_id == script.script_id => state,
+        _ => NpcScriptState {
+            npc_id: npc.npc_id,
+            script_id: script.script_id,
+            step_index: 0,
+            wait_until: None,
+            emote: None,
+            emote_at: None,
+        },
+    };
+    let index = state.step_index as usize % script.steps.len();
+    let mut done = true;
+    npc.state = NpcState::Idle;
+    match &script.steps[index] {
+        ScriptStep::MoveTo(offset) => {
+            let goal = Vector3 {
+                x: npc.home_position.x + offset.x,
+                y: npc.position.y,
+                z: npc.home_position.z + offset.z,
+            };
+            npc.state = NpcState::Patrol;
+            npc.rotation.y = yaw_towards(&npc.position, &goal);
+            npc.position = move_towards(&npc.position, &goal, max_step);
+            done = npc.position.distance_xz(&goal) < 0.5;
+        }
+        ScriptStep::Emote(name) => {
+            state.emote = Some(name.clone());
+            state.emote_at = Some(ctx.timestamp);
+        }
+        ScriptStep::Wait(ms) => {
+            let until = *state.wait_until.get_or_insert(ctx.timestamp + Duration::from_millis(*ms));
+            done = until <= ctx.timestamp;
+        }
+        ScriptStep::Attack => {
+            let nearest = ctx.db.player().room_name().filter(&npc.room_name)
+                .filter(|p| !p.is_dead && npc.position.distance_xz(&p.position) <= attack_range)
+                .min_by(|a, b| npc.position.distance_xz(&a.position).total_cmp(&npc.position.distance_xz(&b.position)));
+            if let Some(player) = nearest {
+                npc.state = NpcState::Attack;
+                npc.rotation.y = yaw_towards(&npc.position, &player.position);
+                combat::apply_damage(
+                    ctx,
+                    &EntityId::Npc(npc.npc_id),
+                    &EntityId::Player(player.identity),
+                    attack_damage,
+                    CombatEventKind::Melee,
+                );
+            }
+        }
+    }
+    if done {
+        state.step_index = ((index + 1) % script.steps.len()) as u32;
+        state.wait_until = None;
+    }
+    if ctx.db.npc_script_state().npc_id().find(npc.npc_id).is_some() {
+        ctx.db.npc_script_state().npc_id().update(state);
+    } else {
+        ctx.db.npc_script_state().insert(state);
+    }
+    true
+}
+
+// --- Script Reducers ---
+
+#[spacetimedb::reducer]
+pub fn upsert_npc_script(ctx: &ReducerContext, script: NpcScript) -> Result<(), String> {
+    admin::require_admin(ctx)?;
+    if script.name.is_empty() {
+        return Err("Script name must not be empty".to_string());
+    }
+    if script.steps.len() > MAX_SCRIPT_STEPS {
+        return Err(format!("A script can have at most {} steps", MAX_SCRIPT_STEPS));
+    }
+    let taken = ctx.db.npc_script().iter().any(|s| s.kind == script.kind && s.script_id != script.script_id);
+    if taken {
+        return Err(format!("{:?} NPCs already have a script", script.kind));
+    }
+    if script.script_id != 0 && ctx.db.npc_script().script_id().find(script.script_id).is_some() {
+        ctx.db.npc_script().script_id().update(script);
+    } else {
+        ctx.db.npc_script().insert(NpcScript { script_id: 0, ..script });
+    }
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn delete_npc_script(ctx: &ReducerContext, script_id: u64) -> Result<(), String> {
+    admin::require_admin(ctx)?;
+    if ctx.db.npc_script().script_id().find(script_id).is_none() {
+        return Err("Script not found".to_string());
+    }
+    ctx.db.npc_script().script_id().delete(script_id);
+    let running: Vec<u64> = ctx.db.npc_script_state().iter()
+        .filter(|s| s.script_id == script_id)
+        .map(|s| s.npc_id)
+        .collect();
+    for npc_id in running {
+        ctx.db.npc_script_state().npc_id().delete(npc_id);
+    }
+    Ok(())
+}
@@ -0,0 +1,232 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - objective.rs
+ *
+ * Objective zones (flag captures, race finish lines) with deterministic
+ * photo-finish ordering, so simultaneous completions always resolve the
+ * same way and every decision can be audited from the stored data.
+ *
+ * Key components:
+ * - Objective table (public): A circular zone in a room. A FlagCapture has a
+ *   single winner and closes once it has one; a RaceFinish ranks everyone
+ *   who reaches it
+ * - ObjectiveClaim table (private): Written from update_player_input when a
+ *   player's move ends inside an open zone. completed_at is a sub-tick time:
+ *   the moment the segment from the player's newest lag-compensation sample
+ *   (hitscan.rs) to their new position crosses into the zone
+ * - resolve_objective_claims: Called from game_tick. Appends the tick's
+ *   claims per objective to match_result, ordered by, in turn:
+ *   1. completed_at (sub-tick crossing time)
+ *   2. received_at (when the server processed the input)
+ *   3. identity bytes, as a last resort that never ties
+ * - MatchResult table (public): Placement, both timestamps and decided_by,
+ *   the rule that separated this entry from the one placed before it
+ * - create_objective: Room-owner reducer
+ *
+ * Related files:
+ * - hitscan.rs: latest_sample
+ * - lib.rs: update_player_input / game_tick
+ */
+
+use spacetimedb::{Identity, ReducerContext, SpacetimeType, Table, Timestamp};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use crate::common::{Vector3, MAX_OBJECTIVE_RADIUS};
+use crate::hitscan;
+use crate::room;
+use crate::PlayerData;
+
+#[derive(SpacetimeType, Clone, Copy, Debug, PartialEq)]
+pub enum ObjectiveKind {
+    FlagCapture,
+    RaceFinish,
+}
+
+// Which ordering rule placed a result after the previous one
+#[derive(SpacetimeType, Clone, Copy, Debug, PartialEq)]
+pub enum TieBreak {
+    None, // First place of its batch
+    SubTickTime,
+    ReceivedAt,
+    Identity,
+}
+
+#[spacetimedb::table(name = objective, public)]
+#[derive(Clone)]
+pub struct Objective {
+    #[primary_key]
+    #[auto_inc]
+    pub objective_id: u64,
+    #[index(btree)]
+    pub room_name: String,
+    pub kind: ObjectiveKind,
+    pub position: Vector3,
+    pub radius: f32,
+    pub is_open: bool,
+    pub created_at: Timestamp,
+}
+
+#[spacetimedb::table(name = objective_claim)]
+#[derive(Clone)]
+pub struct ObjectiveClaim {
+    #[primary_key]
+    #[auto_inc]
+    pub claim_id: u64,
+    #[index(btree)]
+    pub room_name: String,
+    pub objective_id: u64,
+    pub identity: Identity,
+    pub completed_at: Timestamp,
+    pub received_at: Timestamp,
+}
+
+#[spacetimedb::table(name = match_result, public)]
+#[derive(Clone)]
+pub struct MatchResult {
+    #[primary_key]
+    #[auto_inc]
+    pub result_id: u64,
+    #[index(btree)]
+    pub objective_id: u64,
+    pub room_name: String,
+    pub identity: Identity,
+    pub placement: u32, // 1-based
+    pub completed_at: Timestamp,
+    pub received_at: Timestamp,
+    pub decided_by: TieBreak,
+    pub resolved_at: Timestamp,
+}
+
+// Fraction along `from` -> `to` where the segment first enters the circle
+// (ground plane). Callers guarantee `to` is inside.
+fn entry_fraction(from: &Vector3, to: &Vector3, center: &Vector3, radius: f32) -> f32 {
+    let (dx, dz) = (to.x - from.x, to.z - from.z);
+    let (fx, fz) = (from.x - center.x, from.z - center.z);
+    let a = dx * dx + dz * dz;
+    if a < 1e-6 {
+        return 1.0;
+    }
+    let b = 2.0 * (fx * dx + fz * dz);
+    let c = fx * fx + fz * fz - radius * radius;
+    let discriminant = (b * b - 4.0 * a * c).max(0.0);
+    ((-b - discriminant.sqrt()) / (2.0 * a)).clamp(0.0, 1.0)
+}
+
+fn already_completed(ctx: &ReducerContext, objective: &Objective, identity: Identity) -> bool {
+    ctx.db.match_result().objective_id().filter(objective.objective_id).any(|r| r.identity == identity)
+        || ctx.db.objective_claim().room_name().filter(&objective.room_name)
+            .any(|c| c.objective_id == objective.objective_id && c.identity == identity)
+}
+
+// Called on input, before the new position is sampled into the
+// lag-compensation history
+pub fn check_completions(ctx: &ReducerContext, player: &PlayerData) {
+    if player.is_dead {
+        return;
+    }
+    let objectives: Vec<Objective> = ctx.db.objective().room_name().filter(&player.room_name)
+        .filter(|o| o.is_open && o.position.distance_xz(&player.position) <= o.radius)
+        .collect();
+    for objective in objectives {
+        if already_completed(ctx, &objective, player.identity) {
+            continue;
+        }
+        let completed_at = match hitscan::latest_sample(ctx, player.identity) {
+            Some((sampled_at, from)) if from.distance_xz(&objective.position) > objective.radius => {
+                let t = entry_fraction(&from, &player.position, &objective.position, objective.radius);
+                let span = ctx.timestamp.duration_since(sampled_at).unwrap_or_default();
+                sampled_at + span.mul_f32(t)
+            }
+            Some((sampled_at, _)) => sampled_at,
+            None => ctx.timestamp,
+        };
+        ctx.db.objective_claim().insert(ObjectiveClaim {
+            claim_id: 0,
+            room_name: objective.room_name.clone(),
+            objective_id: objective.objective_id,
+            identity: player.identity,
+            completed_at,
+            received_at: ctx.timestamp,
+        });
+    }
+}
+
+// The ordering of two claims and the rule that decided it
+fn compare_claims(a: &ObjectiveClaim, b: &ObjectiveClaim) -> (Ordering, TieBreak) {
+    let by_time = a.completed_at.cmp(&b.completed_at);
+    if by_time != Ordering::Equal {
+        return (by_time, TieBreak::SubTickTime);
+    }
+    let by_received = a.received_at.cmp(&b.received_at);
+    if by_received != Ordering::Equal {
+        return (by_received, TieBreak::ReceivedAt);
+    }
+    (a.identity.to_byte_array().cmp(&b.identity.to_byte_array()), TieBreak::Identity)
+}
+
+pub fn resolve_objective_claims(ctx: &ReducerContext, room_name: &str) {
+    let claims: Vec<ObjectiveClaim> = ctx.db.objective_claim().room_name().filter(room_name).collect();
+    if claims.is_empty() {
+        return;
+    }
+    let mut by_objective: BTreeMap<u64, Vec<ObjectiveClaim>> = BTreeMap::new();
+    for claim in claims {
+        ctx.db.objective_claim().claim_id().delete(claim.claim_id);
+        by_objective.entry(claim.objective_id).or_default().push(claim);
+    }
+    for (objective_id, mut batch) in by_objective {
+        let Some(mut objective) = ctx.db.objective().objective_id().find(objective_id) else {
+            continue;
+        };
+        if !objective.is_open {
+            continue;
+        }
+        batch.sort_by(|a, b| compare_claims(a, b).0);
+        let placed_before = ctx.db.match_result().objective_id().filter(objective_id).count() as u32;
+        for (i, claim) in batch.iter().enumerate() {
+            let decided_by = if i == 0 { TieBreak::None } else { compare_claims(&batch[i - 1], claim).1 };
+            ctx.db.match_result().insert(MatchResult {
+                result_id: 0,
+                objective_id,
+                room_name: room_name.to_string(),
+                identity: claim.identity,
+                placement: placed_before + i as u32 + 1,
+                completed_at: claim.completed_at,
+                received_at: claim.received_at,
+                decided_by,
+                resolved_at: ctx.timestamp,
+            });
+        }
+        if objective.kind == ObjectiveKind::FlagCapture {
+            spacetimedb::log::info!("Objective {} captured by {}.", objective_id, batch[0].identity);
+            objective.is_open = false;
+            ctx.db.objective().objective_id().update(objective);
+        }
+    }
+}
+
+// --- Objective Reducers ---
+
+#[spacetimedb::reducer]
+pub fn create_objective(
+    ctx: &ReducerContext,
+    room_name: String,
+    kind: ObjectiveKind,
+    position: Vector3,
+    radius: f32,
+) -> Result<(), String> {
+    room::require_room_owner(ctx, &room_name)?;
+    if radius <= 0.0 || radius > MAX_OBJECTIVE_RADIUS {
+        return Err(format!("Radius must be between 0 and {}", MAX_OBJECTIVE_RADIUS));
+    }
+    ctx.db.objective().insert(Objective {
+        objective_id: 0,
+        room_name,
+        kind,
+        position,
+        radius,
+        is_open: true,
+        created_at: ctx.timestamp,
+    });
+    Ok(())
+}
@@ -0,0 +1,186 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - observer.rs
+ *
+ * Read-only summary tables for external dashboards, so ops tooling can
+ * subscribe to a handful of small rows instead of the gameplay tables.
+ *
+ * Key components:
+ * - ServerStats table (single row): Players online / logged out, rooms
+ *   active, duels in progress and queue lengths (pending duel challenges,
+ *   pending party invites, dead-lettered jobs). Refreshed every
+ *   OBSERVER_REFRESH_SECS by refresh_observer_stats
+ * - RoomTickStats table: Tick health per room (tick count, last tick time and
+ *   the interval since the previous tick, which grows when the host lags).
+ *   Written by record_tick at the start of every game_tick
+ * - DashboardViewer table (private): Identities allowed to read the summary
+ *   tables, granted by admins (grant_dashboard_access). The client visibility
+ *   filters below only show the summary rows to those identities
+ *
+ * Related files:
+ * - lib.rs: init schedules the refresh; game_tick calls record_tick
+ * - admin.rs: require_admin
+ */
+
+use spacetimedb::{client_visibility_filter, Filter, Identity, ReducerContext, ScheduleAt, Table, Timestamp};
+use std::time::Duration;
+
+use crate::admin;
+use crate::common::OBSERVER_REFRESH_SECS;
+use crate::duel::{duel, DuelState};
+use crate::jobs::failed_job;
+use crate::party::party_invite;
+use crate::room::room;
+use crate::{logged_out_player, player};
+
+// Summary rows and viewer grants share this scope, which is what the
+// visibility filters join on
+const OBSERVER_SCOPE: &str = "ops";
+
+#[spacetimedb::table(name = server_stats, public)]
+#[derive(Clone)]
+pub struct ServerStats {
+    #[primary_key]
+    pub scope: String,
+    pub players_online: u64,
+    pub players_logged_out: u64,
+    pub rooms_total: u32,
+    pub rooms_active: u32, // Rooms with at least one player
+    pub duels_in_progress: u32,
+    pub pending_duel_challenges: u32,
+    pub pending_party_invites: u64,
+    pub failed_jobs: u64,
+    pub refreshed_at: Timestamp,
+}
+
+#[spacetimedb::table(name = room_tick_stats, public)]
+#[derive(Clone)]
+pub struct RoomTickStats {
+    #[primary_key]
+    pub room_name: String,
+    pub scope: String,
+    pub tick_count: u64,
+    pub last_tick_at: Timestamp,
+    pub last_interval_ms: u64, // Time since the previous tick (nominally 1000)
+}
+
+#[spacetimedb::table(name = dashboard_viewer)]
+pub struct DashboardViewer {
+    #[primary_key]
+    pub identity: Identity,
+    pub scope: String,
+    pub granted_at: Timestamp,
+}
+
+#[client_visibility_filter]
+const DASHBOARD_SEES_SERVER_STATS: Filter = Filter::Sql(
+    "SELECT server_stats.* FROM server_stats
+     JOIN dashboard_viewer ON server_stats.scope = dashboard_viewer.scope
+     WHERE dashboard_viewer.identity = :sender",
+);
+
+#[client_visibility_filter]
+const DASHBOARD_SEES_ROOM_TICK_STATS: Filter = Filter::Sql(
+    "SELECT room_tick_stats.* FROM room_tick_stats
+     JOIN dashboard_viewer ON room_tick_stats.scope = dashboard_viewer.scope
+     WHERE dashboard_viewer.identity = :sender",
+);
+
+#[spacetimedb::table(name = observer_refresh_schedule, scheduled(refresh_observer_stats))]
+pub struct ObserverRefreshSchedule {
+    #[primary_key]
+    #[auto_inc]
+    pub scheduled_id: u64,
+    pub scheduled_at: ScheduleAt,
+}
+
+pub fn schedule_refresh(ctx: &ReducerContext) {
+    if ctx.db.observer_refresh_schedule().count() > 0 {
+        return;
+    }
+    ctx.db.observer_refresh_schedule().insert(ObserverRefreshSchedule {
+        scheduled_id: 0,
+        scheduled_at: ScheduleAt::Interval(Duration::from_secs(OBSERVER_REFRESH_SECS).into()),
+    });
+}
+
+// Tick health for one room; called at the start of its game_tick
+pub fn record_tick(ctx: &ReducerContext, room_name: &str) {
+    match ctx.db.room_tick_stats().room_name().find(room_name.to_string()) {
+        Some(mut stats) => {
+            let interval = ctx.timestamp.duration_since(stats.last_tick_at).unwrap_or_default();
+            stats.tick_count += 1;
+            stats.last_interval_ms = interval.as_millis() as u64;
+            stats.last_tick_at = ctx.timestamp;
+            ctx.db.room_tick_stats().room_name().update(stats);
+        }
+        None => {
+            ctx.db.room_tick_stats().insert(RoomTickStats {
+                room_name: room_name.to_string(),
+                scope: OBSERVER_SCOPE.to_string(),
+                tick_count: 1,
+                last_tick_at: ctx.timestamp,
+                last_interval_ms: 0,
+            });
+        }
+    }
+}
+
+#[spacetimedb::reducer]
+pub fn refresh_observer_stats(ctx: &ReducerContext, _schedule: ObserverRefreshSchedule) -> Result<(), String> {
+    if ctx.sender != ctx.identity() {
+        return Err("refresh_observer_stats may only be invoked by the scheduler".to_string());
+    }
+    let (mut rooms_total, mut rooms_active) = (0, 0);
+    for room in ctx.db.room().iter() {
+        rooms_total += 1;
+        if room.current_player_count > 0 {
+            rooms_active += 1;
+        }
+    }
+    let (mut duels_in_progress, mut pending_duel_challenges) = (0, 0);
+    for duel in ctx.db.duel().iter() {
+        match duel.state {
+            DuelState::Pending => pending_duel_challenges += 1,
+            DuelState::Countdown | DuelState::Active => duels_in_progress += 1,
+            DuelState::Finished => {}
+        }
+    }
+    let stats = ServerStats {
+        scope: OBSERVER_SCOPE.to_string(),
+        players_online: ctx.db.player().count(),
+        players_logged_out: ctx.db.logged_out_player().count(),
+        rooms_total,
+        rooms_active,
+        duels_in_progress,
+        pending_duel_challenges,
+        pending_party_invites: ctx.db.party_invite().count(),
+        failed_jobs: ctx.db.failed_job().count(),
+        refreshed_at: ctx.timestamp,
+    };
+    if ctx.db.server_stats().scope().find(OBSERVER_SCOPE.to_string()).is_some() {
+        ctx.db.server_stats().scope().update(stats);
+    } else {
+        ctx.db.server_stats().insert(stats);
+    }
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn grant_dashboard_access(ctx: &ReducerContext, identity: Identity) -> Result<(), String> {
+    admin::require_admin(ctx)?;
+    if ctx.db.dashboard_viewer().identity().find(identity).is_none() {
+        ctx.db.dashboard_viewer().insert(DashboardViewer {
+            identity,
+            scope: OBSERVER_SCOPE.to_string(),
+            granted_at: ctx.timestamp,
+        });
+    }
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn revoke_dashboard_access(ctx: &ReducerContext, identity: Identity) -> Result<(), String> {
+    admin::require_admin(ctx)?;
+    ctx.db.dashboard_viewer().identity().delete(identity);
+    Ok(())
+}
@@ -0,0 +1,248 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - party.rs
+ *
+ * Parties: small groups of players led by one member.
+ *
+ * Key components:
+ * - Party table (public): The party and its leader
+ * - PartyMember table (public): One row per member (a player is in at most
+ *   one party)
+ * - PartyInvite table (public): Pending invites, accepted by the invitee
+ * - create_party / invite_to_party / accept_party_invite / leave_party:
+ *   Party management. Leadership passes to the longest-standing member when
+ *   the leader leaves; the last member leaving disbands the party
+ * - party_travel: The leader moves every online member to another room at
+ *   once. Every member is validated (bans, capacity, the password given by
+ *   the leader) before anyone moves, so a failure leaves the party where it
+ *   was. Members land on a tight ring around the leader's spawn point
+ * - on_player_disconnected: Disconnecting players leave their party
+ *
+ * Related files:
+ * - room.rs: Access checks and move_player_to_room
+ * - lib.rs: identity_disconnected
+ */
+
+use spacetimedb::{Identity, ReducerContext, Table, Timestamp};
+
+use crate::common::{Vector3, MAX_PARTY_SIZE, PARTY_SPAWN_SPACING};
+use crate::map;
+use crate::notification::{self, NotificationKind};
+use crate::player as _;
+use crate::room;
+use crate::spawn;
+use crate::PlayerData;
+
+#[spacetimedb::table(name = party, public)]
+#[derive(Clone)]
+pub struct Party {
+    #[primary_key]
+    #[auto_inc]
+    pub party_id: u64,
+    pub leader: Identity,
+    pub created_at: Timestamp,
+}
+
+#[spacetimedb::table(name = party_member, public)]
+#[derive(Clone)]
+pub struct PartyMember {
+    #[primary_key]
+    pub identity: Identity,
+    #[index(btree)]
+    pub party_id: u64,
+    pub joined_at: Timestamp,
+}
+
+#[spacetimedb::table(name = party_invite, public)]
+#[derive(Clone)]
+pub struct PartyInvite {
+    #[primary_key]
+    #[auto_inc]
+    pub invite_id: u64,
+    #[index(btree)]
+    pub party_id: u64,
+    #[index(btree)]
+    pub invitee: Identity,
+    pub invited_by: Identity,
+    pub invited_at: Timestamp,
+}
+
+pub fn party_of(ctx: &ReducerContext, identity: Identity) -> Option<u64> {
+    ctx.db.party_member().identity().find(identity).map(|m| m.party_id)
+}
+
+pub fn members_of(ctx: &ReducerContext, party_id: u64) -> Vec<PartyMember> {
+    let mut members: Vec<PartyMember> = ctx.db.party_member().party_id().filter(party_id).collect();
+    members.sort_by_key(|m| m.joined_at);
+    members
+}
+
+// The caller's party, if they lead it
+pub fn require_party_leader(ctx: &ReducerContext) -> Result<u64, String> {
+    let Some(party_id) = party_of(ctx, ctx.sender) else {
+        return Err("Not in a party".to_string());
+    };
+    if ctx.db.party().party_id().find(party_id).is_none_or(|p| p.leader != ctx.sender) {
+        return Err("Only the party leader can do that".to_string());
+    }
+    Ok(party_id)
+}
+
+// Remove a player from their party, handing over or disbanding as needed
+fn remove_member(ctx: &ReducerContext, identity: Identity) {
+    let Some(member) = ctx.db.party_member().identity().find(identity) else {
+        return;
+    };
+    ctx.db.party_member().identity().delete(identity);
+    let party_id = member.party_id;
+    let Some(mut party) = ctx.db.party().party_id().find(party_id) else {
+        return;
+    };
+    let remaining = members_of(ctx, party_id);
+    match remaining.first() {
+        None => {
+            ctx.db.party().party_id().delete(party_id);
+            let invites: Vec<u64> = ctx.db.party_invite().party_id().filter(party_id).map(|i| i.invite_id).collect();
+            for invite_id in invites {
+                ctx.db.party_invite().invite_id().delete(invite_id);
+            }
+        }
+        Some(next) if party.leader == identity => {
+            party.leader = next.identity;
+            ctx.db.party().party_id().update(party);
+        }
+        Some(_) => {}
+    }
+}
+
+pub fn on_player_disconnected(ctx: &ReducerContext, identity: Identity) {
+    remove_member(ctx, identity);
+}
+
+// Positions around `center` for the party: the leader on the spawn point,
+// everyone else on a small ring, falling back to the spawn point itself where
+// the ring leaves the map
+fn party_spawn_positions(ctx: &ReducerContext, map_id: u32, center: &Vector3, count: usize) -> Vec<Vector3> {
+    (0..count)
+        .map(|i| {
+            if i == 0 {
+                return center.clone();
+            }
+            let angle = i as f32 / (count - 1) as f32 * std::f32::consts::TAU;
+            let position = Vector3 {
+                x: center.x + angle.cos() * PARTY_SPAWN_SPACING,
+                y: center.y,
+                z: center.z + angle.sin() * PARTY_SPAWN_SPACING,
+            };
+            if map::is_on_map(ctx, map_id, &position) { position } else { center.clone() }
+        })
+        .collect()
+}
+
+// --- Party Reducers ---
+
+#[spacetimedb::reducer]
+pub fn create_party(ctx: &ReducerContext) -> Result<(), String> {
+    if ctx.db.player().identity().find(ctx.sender).is_none() {
+        return Err("Player not found".to_string());
+    }
+    if party_of(ctx, ctx.sender).is_some() {
+        return Err("Already in a party".to_string());
+    }
+    let party = ctx.db.party().insert(Party { party_id: 0, leader: ctx.sender, created_at: ctx.timestamp });
+    ctx.db.party_member().insert(PartyMember { identity: ctx.sender, party_id: party.party_id, joined_at: ctx.timestamp });
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn invite_to_party(ctx: &ReducerContext, target: Identity) -> Result<(), String> {
+    let Some(party_id) = party_of(ctx, ctx.sender) else {
+        return Err("Not in a party".to_string());
+    };
+    if ctx.db.player().identity().find(target).is_none() {
+        return Err("Target player not found".to_string());
+    }
+    if party_of(ctx, target).is_some() {
+        return Err("Target is already in a party".to_string());
+    }
+    if members_of(ctx, party_id).len() >= MAX_PARTY_SIZE {
+        return Err("Party is full".to_string());
+    }
+    if ctx.db.party_invite().invitee().filter(target).any(|i| i.party_id == party_id) {
+        return Ok(());
+    }
+    ctx.db.party_invite().insert(PartyInvite {
+        invite_id: 0,
+        party_id,
+        invitee: target,
+        invited_by: ctx.sender,
+        invited_at: ctx.timestamp,
+    });
+    let inviter = ctx.db.player().identity().find(ctx.sender).map(|p| p.username).unwrap_or_default();
+    notification::notify(ctx, target, NotificationKind::PartyInvite, format!("{} invited you to their party", inviter));
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn accept_party_invite(ctx: &ReducerContext, party_id: u64) -> Result<(), String> {
+    let Some(invite) = ctx.db.party_invite().invitee().filter(ctx.sender).find(|i| i.party_id == party_id) else {
+        return Err("No invite from that party".to_string());
+    };
+    ctx.db.party_invite().invite_id().delete(invite.invite_id);
+    if ctx.db.party().party_id().find(party_id).is_none() {
+        return Err("That party no longer exists".to_string());
+    }
+    if party_of(ctx, ctx.sender).is_some() {
+        return Err("Already in a party".to_string());
+    }
+    if members_of(ctx, party_id).len() >= MAX_PARTY_SIZE {
+        return Err("Party is full".to_string());
+    }
+    ctx.db.party_member().insert(PartyMember { identity: ctx.sender, party_id, joined_at: ctx.timestamp });
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn leave_party(ctx: &ReducerContext) -> Result<(), String> {
+    if party_of(ctx, ctx.sender).is_none() {
+        return Err("Not in a party".to_string());
+    }
+    remove_member(ctx, ctx.sender);
+    Ok(())
+}
+
+// Leader-only: move every online member to `room_name` together
+#[spacetimedb::reducer]
+pub fn party_travel(ctx: &ReducerContext, room_name: String, password: Option<String>) -> Result<(), String> {
+    let party_id = require_party_leader(ctx)?;
+    let Some(room) = room::find_room(ctx, &room_name) else {
+        return Err("Room not found".to_string());
+    };
+
+    // Leader first, so they get the spawn point itself
+    let mut travellers: Vec<PlayerData> = members_of(ctx, party_id).into_iter()
+        .filter_map(|m| ctx.db.player().identity().find(m.identity))
+        .filter(|p| p.room_name != room_name)
+        .collect();
+    travellers.sort_by_key(|p| p.identity != ctx.sender);
+    if travellers.is_empty() {
+        return Ok(());
+    }
+
+    // Validate everyone before moving anyone
+    for player in &travellers {
+        if room::is_banned(ctx, &room_name, player.identity) {
+            return Err(format!("{} is banned from '{}'", player.username, room_name));
+        }
+    }
+    room::check_password(ctx, &room, password.as_deref())?;
+    room::check_capacity(&room, travellers.len() as u32)?;
+
+    let center = spawn::select_spawn_point(ctx, &room_name, ctx.sender);
+    let positions = party_spawn_positions(ctx, room.map_id, &center, travellers.len());
+    let count = travellers.len();
+    for (player, position) in travellers.into_iter().zip(positions) {
+        room::move_player_to_room(ctx, player, &room_name, position);
+    }
+    spacetimedb::log::info!("Party {} ({} players) travelled to room '{}'.", party_id, count, room_name);
+    Ok(())
+}
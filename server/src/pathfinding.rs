@@ -0,0 +1,240 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - pathfinding.rs
+ *
+ * Grid A* so chasing NPCs route around obstacles instead of walking straight
+ * into them.
+ *
+ * Key components:
+ * - Navigation grid: The ground is split into PATH_CELL_SIZE squares. A cell
+ *   is walkable when its center stands on a tile of the room's map and is
+ *   not inside a destructible prop or closed door; hazardous tiles are
+ *   walkable but cost PATH_HAZARD_PENALTY extra so NPCs avoid lava when
+ *   there's another way
+ * - PathPlanner: Created once per room per tick by npc_ai.rs. waypoint()
+ *   returns the point an NPC should walk towards next:
+ *   - a cached path (NpcPath table) while the goal hasn't moved more than
+ *     PATH_REPLAN_DISTANCE
+ *   - the goal itself when the straight line to it is walkable
+ *   - otherwise a fresh A* search, if the room still has path budget this
+ *     tick (PATH_SEARCHES_PER_TICK searches of at most PATH_MAX_EXPANSIONS
+ *     cells each). Without budget, or without a path, the NPC walks straight
+ *     at the goal as before
+ * - NpcPath table (private): Remaining waypoints per NPC, dropped when the
+ *   NPC dies (clear_npc)
+ *
+ * Related files:
+ * - npc_ai.rs: Uses the planner for chase movement
+ * - map.rs: tile_at
+ * - destructible.rs: Props that block movement
+ * - door.rs: Closed doors block movement too
+ */
+
+use spacetimedb::{ReducerContext, Table};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+use crate::common::{
+    Vector3, PATH_CELL_SIZE, PATH_HAZARD_PENALTY, PATH_MAX_EXPANSIONS, PATH_REPLAN_DISTANCE, PATH_SEARCHES_PER_TICK,
+};
+use crate::destructible::destructible as _;
+use crate::door;
+use crate::map;
+use crate::room::Room;
+
+#[spacetimedb::table(name = npc_path)]
+pub struct NpcPath {
+    #[primary_key]
+    pub npc_id: u64,
+    pub goal: Vector3,
+    pub waypoints: Vec<Vector3>,
+}
+
+type Cell = (i32, i32);
+
+// Distance at which a waypoint counts as reached
+const WAYPOINT_REACHED: f32 = 0.5;
+
+fn cell_of(position: &Vector3) -> Cell {
+    ((position.x / PATH_CELL_SIZE).floor() as i32, (position.z / PATH_CELL_SIZE).floor() as i32)
+}
+
+fn cell_center(cell: Cell, y: f32) -> Vector3 {
+    Vector3 {
+        x: (cell.0 as f32 + 0.5) * PATH_CELL_SIZE,
+        y,
+        z: (cell.1 as f32 + 0.5) * PATH_CELL_SIZE,
+    }
+}
+
+// Octile distance between cells: the exact cost of an unobstructed 8-way path
+fn heuristic(a: Cell, b: Cell) -> f32 {
+    let dx = (a.0 - b.0).abs() as f32;
+    let dz = (a.1 - b.1).abs() as f32;
+    dx.max(dz) + (std::f32::consts::SQRT_2 - 1.0) * dx.min(dz)
+}
+
+// Open-set entry ordered so BinaryHeap pops the lowest estimated cost first
+struct Open {
+    estimate: f32,
+    cell: Cell,
+}
+
+impl PartialEq for Open {
+    fn eq(&self, other: &Self) -> bool {
+        self.estimate == other.estimate
+    }
+}
+
+impl Eq for Open {}
+
+impl PartialOrd for Open {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Open {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.estimate.total_cmp(&self.estimate)
+    }
+}
+
+pub struct PathPlanner {
+    map_id: u32,
+    searches_left: u32,
+    obstacles: Vec<(Vector3, Vector3)>, // Destructible and closed door (center, size) boxes
+    // Extra cost of entering a cell (None = blocked), shared by every search this tick
+    cell_costs: HashMap<Cell, Option<f32>>,
+}
+
+impl PathPlanner {
+    pub fn new(ctx: &ReducerContext, room: &Room) -> Self {
+        let mut obstacles: Vec<(Vector3, Vector3)> = ctx.db.destructible().room_name().filter(&room.room_name)
+            .map(|d| (d.position, d.size))
+            .collect();
+        obstacles.extend(door::closed_door_boxes(ctx, &room.room_name));
+        PathPlanner {
+            map_id: room.map_id,
+            searches_left: PATH_SEARCHES_PER_TICK,
+            obstacles,
+            cell_costs: HashMap::new(),
+        }
+    }
+
+    fn cell_cost(&mut self, ctx: &ReducerContext, cell: Cell) -> Option<f32> {
+        if let Some(cost) = self.cell_costs.get(&cell) {
+            return *cost;
+        }
+        let center = cell_center(cell, 0.0);
+        let blocked = self.obstacles.iter().any(|(position, size)| {
+            (center.x - position.x).abs() <= size.x * 0.5 && (center.z - position.z).abs() <= size.z * 0.5
+        });
+        let cost = if blocked {
+            None
+        } else {
+            map::tile_at(ctx, self.map_id, &center)
+                .map(|tile| if tile.hazard.is_some() { PATH_HAZARD_PENALTY } else { 0.0 })
+        };
+        self.cell_costs.insert(cell, cost);
+        cost
+    }
+
+    // Whether every cell along the segment is walkable and hazard-free
+    fn straight_line_clear(&mut self, ctx: &ReducerContext, from: &Vector3, to: &Vector3) -> bool {
+        let steps = (from.distance_xz(to) / (PATH_CELL_SIZE * 0.5)).ceil().max(1.0) as usize;
+        (0..=steps).all(|i| {
+            let t = i as f32 / steps as f32;
+            let point = Vector3 { x: from.x + (to.x - from.x) * t, y: from.y, z: from.z + (to.z - from.z) * t };
+            self.cell_cost(ctx, cell_of(&point)) == Some(0.0)
+        })
+    }
+
+    // A* from `from` to `goal`; waypoints are cell centers ending at the goal
+    fn search(&mut self, ctx: &ReducerContext, from: &Vector3, goal: &Vector3) -> Option<Vec<Vector3>> {
+        let (start, target) = (cell_of(from), cell_of(goal));
+        self.cell_cost(ctx, target)?;
+        let mut open = BinaryHeap::new();
+        let mut best: HashMap<Cell, f32> = HashMap::new();
+        let mut came_from: HashMap<Cell, Cell> = HashMap::new();
+        best.insert(start, 0.0);
+        open.push(Open { estimate: heuristic(start, target), cell: start });
+
+        let mut expansions = 0;
+        while let Some(Open { cell, .. }) = open.pop() {
+            if cell == target {
+                let mut cells = vec![cell];
+                while let Some(previous) = came_from.get(cells.last()?) {
+                    cells.push(*previous);
+                }
+                cells.pop(); // The NPC is already in the start cell
+                cells.reverse();
+                let mut waypoints: Vec<Vector3> = cells.into_iter().map(|c| cell_center(c, from.y)).collect();
+                // Finish on the goal itself rather than its cell center
+                waypoints.pop();
+                waypoints.push(goal.clone());
+                return Some(waypoints);
+            }
+            expansions += 1;
+            if expansions > PATH_MAX_EXPANSIONS {
+                return None;
+            }
+            let cost_here = best[&cell];
+            for dx in -1..=1 {
+                for dz in -1..=1 {
+                    if dx == 0 && dz == 0 {
+                        continue;
+                    }
+                    let next = (cell.0 + dx, cell.1 + dz);
+                    let Some(penalty) = self.cell_cost(ctx, next) else {
+                        continue;
+                    };
+                    // No cutting corners past blocked cells
+                    if dx != 0 && dz != 0
+                        && (self.cell_cost(ctx, (cell.0 + dx, cell.1)).is_none()
+                            || self.cell_cost(ctx, (cell.0, cell.1 + dz)).is_none())
+                    {
+                        continue;
+                    }
+                    let step = if dx != 0 && dz != 0 { std::f32::consts::SQRT_2 } else { 1.0 };
+                    let cost = cost_here + step + penalty;
+                    if best.get(&next).is_none_or(|known| cost < *known) {
+                        best.insert(next, cost);
+                        came_from.insert(next, cell);
+                        open.push(Open { estimate: cost + heuristic(next, target), cell: next });
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    // The point an NPC at `from` should walk towards to reach `goal`
+    pub fn waypoint(&mut self, ctx: &ReducerContext, npc_id: u64, from: &Vector3, goal: &Vector3) -> Vector3 {
+        if let Some(mut path) = ctx.db.npc_path().npc_id().find(npc_id) {
+            if path.goal.distance_xz(goal) <= PATH_REPLAN_DISTANCE {
+                while path.waypoints.first().is_some_and(|w| w.distance_xz(from) < WAYPOINT_REACHED) {
+                    path.waypoints.remove(0);
+                }
+                if let Some(next) = path.waypoints.first().cloned() {
+                    ctx.db.npc_path().npc_id().update(path);
+                    return next;
+                }
+            }
+            ctx.db.npc_path().npc_id().delete(npc_id);
+        }
+        if self.straight_line_clear(ctx, from, goal) || self.searches_left == 0 {
+            return goal.clone();
+        }
+        self.searches_left -= 1;
+        let Some(waypoints) = self.search(ctx, from, goal) else {
+            return goal.clone();
+        };
+        let next = waypoints.first().cloned().unwrap_or_else(|| goal.clone());
+        ctx.db.npc_path().insert(NpcPath { npc_id, goal: goal.clone(), waypoints });
+        next
+    }
+}
+
+pub fn clear_npc(ctx: &ReducerContext, npc_id: u64) {
+    ctx.db.npc_path().npc_id().delete(npc_id);
+}
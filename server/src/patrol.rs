@@ -0,0 +1,145 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - patrol.rs
+ *
+ * Shared patrol routes that NPCs walk when they have nothing better to do.
+ *
+ * Key components:
+ * - PatrolRoute table (public): A named route
+ * - PatrolWaypoint table (public): The route's stops, walked in `sequence`
+ *   order and looped. pause_ms makes the NPC stand still at the stop before
+ *   moving on
+ * - upsert_patrol_route / set_patrol_waypoints / delete_patrol_route:
+ *   Admin authoring reducers
+ * - assign_patrol_route: Room owner puts an NPC on a route (or takes it off
+ *   with None). A route overrides the NPC's own patrol_points
+ *
+ * Related files:
+ * - npc.rs: Npc.patrol_route_id / patrol_paused_until
+ * - npc_ai.rs: walk_route follows the waypoints
+ */
+
+use spacetimedb::{ReducerContext, SpacetimeType, Table};
+
+use crate::admin;
+use crate::common::{Vector3, MAX_PATROL_PAUSE_MS, MAX_PATROL_WAYPOINTS};
+use crate::npc::npc as _;
+use crate::room;
+
+#[derive(SpacetimeType, Clone, Debug, PartialEq)]
+pub struct WaypointSpec {
+    pub position: Vector3,
+    pub pause_ms: u64,
+}
+
+#[spacetimedb::table(name = patrol_route, public)]
+#[derive(Clone)]
+pub struct PatrolRoute {
+    #[primary_key]
+    #[auto_inc]
+    pub route_id: u64,
+    pub name: String,
+}
+
+#[spacetimedb::table(name = patrol_waypoint, public)]
+#[derive(Clone)]
+pub struct PatrolWaypoint {
+    #[primary_key]
+    #[auto_inc]
+    pub waypoint_id: u64,
+    #[index(btree)]
+    pub route_id: u64,
+    pub sequence: u32,
+    pub position: Vector3,
+    pub pause_ms: u64,
+}
+
+// The route's waypoints in walking order (empty if the route is gone)
+pub fn route_waypoints(ctx: &ReducerContext, route_id: u64) -> Vec<PatrolWaypoint> {
+    let mut waypoints: Vec<PatrolWaypoint> = ctx.db.patrol_waypoint().route_id().filter(route_id).collect();
+    waypoints.sort_by_key(|w| w.sequence);
+    waypoints
+}
+
+fn delete_waypoints(ctx: &ReducerContext, route_id: u64) {
+    let waypoint_ids: Vec<u64> = ctx.db.patrol_waypoint().route_id().filter(route_id).map(|w| w.waypoint_id).collect();
+    for waypoint_id in waypoint_ids {
+        ctx.db.patrol_waypoint().waypoint_id().delete(waypoint_id);
+    }
+}
+
+// --- Patrol Reducers ---
+
+#[spacetimedb::reducer]
+pub fn upsert_patrol_route(ctx: &ReducerContext, route: PatrolRoute) -> Result<(), String> {
+    admin::require_admin(ctx)?;
+    if route.name.is_empty() {
+        return Err("Route name must not be empty".to_string());
+    }
+    if route.route_id != 0 && ctx.db.patrol_route().route_id().find(route.route_id).is_some() {
+        ctx.db.patrol_route().route_id().update(route);
+    } else {
+        ctx.db.patrol_route().insert(PatrolRoute { route_id: 0, ..route });
+    }
+    Ok(())
+}
+
+// Replace all of a route's waypoints; they are walked in the given order
+#[spacetimedb::reducer]
+pub fn set_patrol_waypoints(ctx: &ReducerContext, route_id: u64, waypoints: Vec<WaypointSpec>) -> Result<(), String> {
+    admin::require_admin(ctx)?;
+    if ctx.db.patrol_route().route_id().find(route_id).is_none() {
+        return Err("Patrol route not found".to_string());
+    }
+    if waypoints.len() > MAX_PATROL_WAYPOINTS {
+        return Err(format!("A patrol route can have at most {} waypoints", MAX_PATROL_WAYPOINTS));
+    }
+    if waypoints.iter().any(|w| w.pause_ms > MAX_PATROL_PAUSE_MS) {
+        return Err(format!("Waypoint pauses can last at most {} ms", MAX_PATROL_PAUSE_MS));
+    }
+    delete_waypoints(ctx, route_id);
+    for (sequence, spec) in waypoints.into_iter().enumerate() {
+        ctx.db.patrol_waypoint().insert(PatrolWaypoint {
+            waypoint_id: 0,
+            route_id,
+            sequence: sequence as u32,
+            position: spec.position,
+            pause_ms: spec.pause_ms,
+        });
+    }
+    Ok(())
+}
+
+// Deleting a route takes every NPC walking it off the route
+#[spacetimedb::reducer]
+pub fn delete_patrol_route(ctx: &ReducerContext, route_id: u64) -> Result<(), String> {
+    admin::require_admin(ctx)?;
+    if ctx.db.patrol_route().route_id().find(route_id).is_none() {
+        return Err("Patrol route not found".to_string());
+    }
+    delete_waypoints(ctx, route_id);
+    ctx.db.patrol_route().route_id().delete(route_id);
+    let walkers: Vec<_> = ctx.db.npc().iter().filter(|n| n.patrol_route_id == Some(route_id)).collect();
+    for mut npc in walkers {
+        npc.patrol_route_id = None;
+        npc.patrol_index = 0;
+        npc.patrol_paused_until = None;
+        ctx.db.npc().npc_id().update(npc);
+    }
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn assign_patrol_route(ctx: &ReducerContext, npc_id: u64, route_id: Option<u64>) -> Result<(), String> {
+    let Some(mut npc) = ctx.db.npc().npc_id().find(npc_id) else {
+        return Err("NPC not found".to_string());
+    };
+    room::require_room_owner(ctx, &npc.room_name)?;
+    if route_id.is_some_and(|id| ctx.db.patrol_route().route_id().find(id).is_none()) {
+        return Err("Patrol route not found".to_string());
+    }
+    npc.patrol_route_id = route_id;
+    npc.patrol_index = 0;
+    npc.patrol_paused_until = None;
+    ctx.db.npc().npc_id().update(npc);
+    Ok(())
+}
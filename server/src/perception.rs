@@ -0,0 +1,204 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - perception.rs
+ *
+ * How NPCs notice players: facing-based vision cones feeding per-player
+ * detection meters, instead of omniscient radius aggro.
+ *
+ * Key components:
+ * - NpcDetection table (public): One row per (NPC, player) pair with a
+ *   detection level in [0, 1] and a derived DetectionState, so clients can
+ *   show awareness indicators ("?" while suspicious, "!" once alerted)
+ * - update_detection: Called by the AI tick for each hostile NPC. Nearby
+ *   players (found through the spatial index) inside the vision cone with a
+ *   clear line of sight (spatial::has_line_of_sight), or bumping into the
+ *   NPC, fill the meter at a rate scaled by distance, the player's stealth
+ *   and the room's light level. Players the NPC hears (sprinting, fighting,
+ *   see noise.rs) fill it more slowly and at most up to HEARING_MAX_LEVEL;
+ *   everyone else decays. Returns the alerted player with the fullest meter.
+ * - light_level: Ambient light of a room in [0, 1]
+ *
+ * Related files:
+ * - npc_ai.rs: Uses update_detection to choose targets
+ * - common.rs: Vision cone and detection tuning constants
+ */
+
+use spacetimedb::{Identity, ReducerContext, SpacetimeType, Table, Timestamp};
+
+use crate::common::{
+    forward_from_yaw, DETECTION_ALERT_THRESHOLD, DETECTION_DECAY_PER_SEC, DETECTION_FILL_PER_SEC,
+    DETECTION_SUSPICIOUS_THRESHOLD, HEARING_FILL_PER_SEC, HEARING_MAX_LEVEL, PROXIMITY_DETECTION_RADIUS,
+    VISION_CONE_COS,
+};
+use crate::noise;
+use crate::npc::Npc;
+use crate::player as _;
+use crate::spatial::{self, SpatialEntity};
+use crate::PlayerData;
+
+#[derive(SpacetimeType, Clone, Copy, Debug, PartialEq)]
+pub enum DetectionState {
+    Unaware,
+    Suspicious,
+    Alerted,
+}
+
+#[spacetimedb::table(name = npc_detection, public)]
+#[derive(Clone)]
+pub struct NpcDetection {
+    #[primary_key]
+    #[auto_inc]
+    pub detection_id: u64,
+    #[index(btree)]
+    pub npc_id: u64,
+    pub player_identity: Identity,
+    pub level: f32,
+    pub state: DetectionState,
+    pub updated_at: Timestamp,
+}
+
+fn state_for(level: f32) -> DetectionState {
+    if level >= DETECTION_ALERT_THRESHOLD {
+        DetectionState::Alerted
+    } else if level >= DETECTION_SUSPICIOUS_THRESHOLD {
+        DetectionState::Suspicious
+    } else {
+        DetectionState::Unaware
+    }
+}
+
+// Ambient light of a room in [0, 1]. Rooms don't have a day/night cycle yet,
+// so everything is lit like full daylight.
+pub fn light_level(_ctx: &ReducerContext, _room_name: &str) -> f32 {
+    1.0
+}
+
+// How conspicuous a player is: sprinting draws the eye, standing still doesn't
+fn visibility_factor(player: &PlayerData) -> f32 {
+    if player.is_running {
+        1.5
+    } else if player.is_moving {
+        1.0
+    } else {
+        0.5
+    }
+}
+
+// Whether the NPC can currently see the player within `sight_range`: inside
+// the vision cone with nothing in between, or close enough to bump into
+fn can_see(ctx: &ReducerContext, npc: &Npc, player: &PlayerData, map_id: u32, sight_range: f32) -> bool {
+    let distance = npc.position.distance_xz(&player.position);
+    if distance <= PROXIMITY_DETECTION_RADIUS {
+        return true;
+    }
+    if distance > sight_range {
+        return false;
+    }
+    let forward = forward_from_yaw(npc.rotation.y);
+    let dot = ((player.position.x - npc.position.x) * forward.x
+        + (player.position.z - npc.position.z) * forward.z) / distance;
+    dot >= VISION_CONE_COS && spatial::has_line_of_sight(ctx, map_id, &npc.position, &player.position)
+}
+
+// Raise (or start) `player`'s meter by `gain`, never past `cap`; the row is
+// marked as touched this tick so the decay pass skips it
+fn raise(ctx: &ReducerContext, existing: &mut Vec<NpcDetection>, npc_id: u64, player: Identity, gain: f32, cap: f32) {
+    match existing.iter_mut().find(|d| d.player_identity == player) {
+        Some(row) => {
+            row.level = row.level.max((row.level + gain).min(cap));
+            row.updated_at = ctx.timestamp;
+        }
+        None => existing.push(NpcDetection {
+            detection_id: 0,
+            npc_id,
+            player_identity: player,
+            level: gain.min(cap),
+            state: DetectionState::Unaware,
+            updated_at: ctx.timestamp,
+        }),
+    }
+}
+
+// Advance every detection meter for `npc` by one tick and return the alerted
+// player with the highest level, if any
+pub fn update_detection(ctx: &ReducerContext, npc: &Npc, map_id: u32, sight_range: f32, delta_time: f32) -> Option<Identity> {
+    let light = light_level(ctx, &npc.room_name);
+    let mut existing: Vec<NpcDetection> = ctx.db.npc_detection().npc_id().filter(npc.npc_id).collect();
+
+    if sight_range > 0.0 {
+        let nearby = spatial::query_radius(ctx, &npc.room_name, &npc.position, sight_range.max(PROXIMITY_DETECTION_RADIUS));
+        for hit in nearby {
+            let SpatialEntity::Player(identity) = hit.entity else {
+                continue;
+            };
+            let Some(player) = ctx.db.player().identity().find(identity) else {
+                continue;
+            };
+            if !can_see(ctx, npc, &player, map_id, sight_range) {
+                continue;
+            }
+            let distance = npc.position.distance_xz(&player.position);
+            let closeness = 1.0 - (distance / sight_range).clamp(0.0, 1.0);
+            let gain = DETECTION_FILL_PER_SEC * delta_time * (0.25 + closeness) * visibility_factor(&player) * light;
+            raise(ctx, &mut existing, npc.npc_id, player.identity, gain, 1.0);
+        }
+        for identity in noise::heard_players(ctx, &npc.room_name, &npc.position) {
+            let seen = existing.iter().any(|d| d.player_identity == identity && d.updated_at == ctx.timestamp);
+            if !seen {
+                raise(ctx, &mut existing, npc.npc_id, identity, HEARING_FILL_PER_SEC * delta_time, HEARING_MAX_LEVEL);
+            }
+        }
+    }
+
+    let mut best: Option<(f32, Identity)> = None;
+    for mut row in existing {
+        if row.updated_at != ctx.timestamp {
+            row.level -= DETECTION_DECAY_PER_SEC * delta_time;
+        }
+        if row.level <= 0.0 {
+            if row.detection_id != 0 {
+                ctx.db.npc_detection().detection_id().delete(row.detection_id);
+            }
+            continue;
+        }
+        row.state = state_for(row.level);
+        if row.state == DetectionState::Alerted && best.is_none_or(|(level, _)| row.level > level) {
+            best = Some((row.level, row.player_identity));
+        }
+        if row.detection_id == 0 {
+            ctx.db.npc_detection().insert(row);
+        } else {
+            ctx.db.npc_detection().detection_id().update(row);
+        }
+    }
+    best.map(|(_, identity)| identity)
+}
+
+// Mark `player` as fully detected by `npc` (e.g. after being attacked by them)
+pub fn alert(ctx: &ReducerContext, npc_id: u64, player: Identity) {
+    let existing = ctx.db.npc_detection().npc_id().filter(npc_id).find(|d| d.player_identity == player);
+    match existing {
+        Some(mut row) => {
+            row.level = 1.0;
+            row.state = DetectionState::Alerted;
+            row.updated_at = ctx.timestamp;
+            ctx.db.npc_detection().detection_id().update(row);
+        }
+        None => {
+            ctx.db.npc_detection().insert(NpcDetection {
+                detection_id: 0,
+                npc_id,
+                player_identity: player,
+                level: 1.0,
+                state: DetectionState::Alerted,
+                updated_at: ctx.timestamp,
+            });
+        }
+    }
+}
+
+pub fn clear_npc(ctx: &ReducerContext, npc_id: u64) {
+    let rows: Vec<u64> = ctx.db.npc_detection().npc_id().filter(npc_id).map(|d| d.detection_id).collect();
+    for detection_id in rows {
+        ctx.db.npc_detection().detection_id().delete(detection_id);
+    }
+}
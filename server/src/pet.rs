@@ -0,0 +1,140 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - pet.rs
+ *
+ * Taming NPCs into pets that follow their owner around.
+ *
+ * Key components:
+ * - Pet table (public): A tamed creature bound to one player (one pet per
+ *   player), with the kind, health and position it had as an NPC
+ * - tame_npc: Player reducer. Only classes that can_tame may try; the NPC
+ *   must be in interaction range (npc::require_in_range) and not a training
+ *   dummy, boss or escort. The chance grows from TAME_MIN_CHANCE at full
+ *   health to TAME_MAX_CHANCE at no health, so weakening a creature first
+ *   pays off. On success the NPC row becomes a pet row; on failure a
+ *   hostile NPC turns on the tamer (TAME_FAIL_THREAT)
+ * - tick_pets: Called from game_tick. Pets trail their online owner at
+ *   PET_FOLLOW_DISTANCE and follow them into other rooms
+ * - release_pet: Owner reducer
+ *
+ * Related files:
+ * - npc.rs: The NPCs being tamed
+ * - critter.rs: Wildlife, the easiest pets
+ * - combat.rs: forget_npc clears the NPC from every other system
+ */
+
+use spacetimedb::rand::Rng;
+use spacetimedb::{Identity, ReducerContext, Table, Timestamp};
+
+use crate::boss;
+use crate::combat;
+use crate::common::{
+    Vector3, CLASS_WIZARD, PET_FOLLOW_DISTANCE, PET_MOVE_SPEED, TAME_FAIL_THREAT, TAME_MAX_CHANCE, TAME_MIN_CHANCE,
+};
+use crate::escort;
+use crate::loot;
+use crate::npc::{self, npc as _, NpcKind};
+use crate::npc_ai::{move_towards, yaw_towards};
+use crate::player as _;
+
+#[spacetimedb::table(name = pet, public)]
+#[derive(Clone)]
+pub struct Pet {
+    #[primary_key]
+    #[auto_inc]
+    pub pet_id: u64,
+    #[unique]
+    pub owner: Identity,
+    pub kind: NpcKind,
+    #[index(btree)]
+    pub room_name: String,
+    pub position: Vector3,
+    pub rotation: Vector3,
+    pub health: i32,
+    pub max_health: i32,
+    pub tamed_at: Timestamp,
+}
+
+// Wizards are the only class with a way with beasts so far
+pub fn can_tame(character_class: &str) -> bool {
+    matches!(character_class, CLASS_WIZARD)
+}
+
+// Chance to tame a creature at `health_fraction` of its max health
+fn tame_chance(health_fraction: f32) -> f32 {
+    TAME_MIN_CHANCE + (TAME_MAX_CHANCE - TAME_MIN_CHANCE) * (1.0 - health_fraction.clamp(0.0, 1.0))
+}
+
+pub fn tick_pets(ctx: &ReducerContext, room_name: &str, delta_time: f64) {
+    let pets: Vec<Pet> = ctx.db.pet().room_name().filter(room_name).collect();
+    let max_step = PET_MOVE_SPEED * delta_time as f32;
+    for mut pet in pets {
+        // Pets of offline owners wait where they are
+        let Some(owner) = ctx.db.player().identity().find(pet.owner) else {
+            continue;
+        };
+        if owner.room_name != pet.room_name {
+            pet.room_name = owner.room_name.clone();
+            pet.position = owner.position.clone();
+        } else if pet.position.distance_xz(&owner.position) > PET_FOLLOW_DISTANCE {
+            pet.rotation.y = yaw_towards(&pet.position, &owner.position);
+            pet.position = move_towards(&pet.position, &owner.position, max_step);
+        } else {
+            continue;
+        }
+        ctx.db.pet().pet_id().update(pet);
+    }
+}
+
+// --- Pet Reducers ---
+
+#[spacetimedb::reducer]
+pub fn tame_npc(ctx: &ReducerContext, npc_id: u64) -> Result<(), String> {
+    let Some(player) = ctx.db.player().identity().find(ctx.sender) else {
+        return Err("Player not found".to_string());
+    };
+    if !can_tame(&player.character_class) {
+        return Err(format!("{}s can't tame creatures", player.character_class));
+    }
+    if ctx.db.pet().owner().find(ctx.sender).is_some() {
+        return Err("You already have a pet".to_string());
+    }
+    let target = npc::require_in_range(ctx, npc_id)?;
+    if target.kind == NpcKind::TrainingDummy || boss::is_boss(ctx, npc_id) || escort::is_escorted(ctx, npc_id) {
+        return Err("That creature can't be tamed".to_string());
+    }
+
+    let health_fraction = target.health as f32 / target.max_health.max(1) as f32;
+    if ctx.rng().gen::<f32>() >= tame_chance(health_fraction) {
+        if target.is_hostile() {
+            combat::add_threat(ctx, npc_id, ctx.sender, TAME_FAIL_THREAT);
+        }
+        spacetimedb::log::info!("Player {} failed to tame NPC {}.", ctx.sender, npc_id);
+        return Ok(());
+    }
+
+    ctx.db.npc().npc_id().delete(npc_id);
+    combat::forget_npc(ctx, npc_id);
+    loot::clear_npc(ctx, npc_id);
+    ctx.db.pet().insert(Pet {
+        pet_id: 0,
+        owner: ctx.sender,
+        kind: target.kind,
+        room_name: target.room_name,
+        position: target.position,
+        rotation: target.rotation,
+        health: target.health,
+        max_health: target.max_health,
+        tamed_at: ctx.timestamp,
+    });
+    spacetimedb::log::info!("Player {} tamed NPC {}.", ctx.sender, npc_id);
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn release_pet(ctx: &ReducerContext) -> Result<(), String> {
+    if ctx.db.pet().owner().find(ctx.sender).is_none() {
+        return Err("You don't have a pet".to_string());
+    }
+    ctx.db.pet().owner().delete(ctx.sender);
+    Ok(())
+}
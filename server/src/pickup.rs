@@ -0,0 +1,159 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - pickup.rs
+ *
+ * Collectibles placed in a room's world. Unlike loot drops they are part of
+ * the level: taking one only hides it until it respawns.
+ *
+ * Key components:
+ * - WorldPickup table (public): An item lying at a fixed position, with how
+ *   long it takes to come back once collected. Clients hide pickups that
+ *   aren't `available`
+ * - collect_pickup: Player reducer. Takes an available pickup within
+ *   PICKUP_RANGE and grants its item (item::grant_item). The reducer isn't
+ *   called pickup_item because loot drops already use that name
+ * - PickupRespawnSchedule table: One-shot schedule rows driving
+ *   respawn_pickup, which makes the pickup available again
+ * - place_pickup / remove_pickup: Room-owner reducers. Pickups are
+ *   consumables or misc items (not gear or gathering materials) and respawn
+ *   after MIN_PICKUP_RESPAWN_SECS-MAX_PICKUP_RESPAWN_SECS, so a room owner
+ *   can't turn one into an item farm
+ *
+ * Related files:
+ * - item.rs: Item definitions and grant_item
+ * - loot.rs: Dropped items, which vanish once taken
+ * - room.rs: close_room clears a room's pickups
+ */
+
+use spacetimedb::{ReducerContext, ScheduleAt, Table};
+use std::time::Duration;
+
+use crate::common::{Vector3, MAX_PICKUP_RESPAWN_SECS, MIN_PICKUP_RESPAWN_SECS, PICKUP_RANGE};
+use crate::item::{self, item_def as _, ItemKind};
+use crate::player as _;
+use crate::room;
+
+#[spacetimedb::table(name = world_pickup, public)]
+#[derive(Clone)]
+pub struct WorldPickup {
+    #[primary_key]
+    #[auto_inc]
+    pub pickup_id: u64,
+    #[index(btree)]
+    pub room_name: String,
+    pub item_def_id: u64,
+    pub position: Vector3,
+    pub respawn_secs: u64,
+    pub available: bool,
+}
+
+#[spacetimedb::table(name = pickup_respawn_schedule, scheduled(respawn_pickup))]
+pub struct PickupRespawnSchedule {
+    #[primary_key]
+    #[auto_inc]
+    pub scheduled_id: u64,
+    pub scheduled_at: ScheduleAt,
+    #[index(btree)]
+    pub pickup_id: u64,
+}
+
+fn cancel_respawn(ctx: &ReducerContext, pickup_id: u64) {
+    let schedules: Vec<u64> = ctx.db.pickup_respawn_schedule().pickup_id().filter(pickup_id).map(|s| s.scheduled_id).collect();
+    for scheduled_id in schedules {
+        ctx.db.pickup_respawn_schedule().scheduled_id().delete(scheduled_id);
+    }
+}
+
+fn delete_pickup(ctx: &ReducerContext, pickup_id: u64) {
+    cancel_respawn(ctx, pickup_id);
+    ctx.db.world_pickup().pickup_id().delete(pickup_id);
+}
+
+pub fn clear_room(ctx: &ReducerContext, room_name: &str) {
+    let pickups: Vec<u64> = ctx.db.world_pickup().room_name().filter(room_name).map(|p| p.pickup_id).collect();
+    for pickup_id in pickups {
+        delete_pickup(ctx, pickup_id);
+    }
+}
+
+// --- Pickup Reducers ---
+
+#[spacetimedb::reducer]
+pub fn collect_pickup(ctx: &ReducerContext, pickup_id: u64) -> Result<(), String> {
+    let Some(player) = ctx.db.player().identity().find(ctx.sender) else {
+        return Err("Player not found".to_string());
+    };
+    let Some(mut pickup) = ctx.db.world_pickup().pickup_id().find(pickup_id) else {
+        return Err("Pickup not found".to_string());
+    };
+    if player.is_dead {
+        return Err("Dead players can't pick up items".to_string());
+    }
+    if !pickup.available {
+        return Err("Someone already took that".to_string());
+    }
+    if player.room_name != pickup.room_name || player.position.distance_xz(&pickup.position) > PICKUP_RANGE {
+        return Err("Too far away".to_string());
+    }
+    let Some(def) = ctx.db.item_def().item_def_id().find(pickup.item_def_id) else {
+        return Err("Item not found".to_string());
+    };
+    item::grant_item(ctx, &player, &def, 1, None)?;
+
+    ctx.db.pickup_respawn_schedule().insert(PickupRespawnSchedule {
+        scheduled_id: 0,
+        scheduled_at: ScheduleAt::Time(ctx.timestamp + Duration::from_secs(pickup.respawn_secs)),
+        pickup_id,
+    });
+    pickup.available = false;
+    ctx.db.world_pickup().pickup_id().update(pickup);
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn respawn_pickup(ctx: &ReducerContext, schedule: PickupRespawnSchedule) -> Result<(), String> {
+    if ctx.sender != ctx.identity() {
+        return Err("respawn_pickup may only be invoked by the scheduler".to_string());
+    }
+    if let Some(mut pickup) = ctx.db.world_pickup().pickup_id().find(schedule.pickup_id) {
+        pickup.available = true;
+        ctx.db.world_pickup().pickup_id().update(pickup);
+    }
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn place_pickup(ctx: &ReducerContext, room_name: String, item_def_id: u64, position: Vector3, respawn_secs: u64) -> Result<(), String> {
+    room::require_room_owner(ctx, &room_name)?;
+    let Some(def) = ctx.db.item_def().item_def_id().find(item_def_id) else {
+        return Err("Item not found".to_string());
+    };
+    let placeable = matches!(def.kind, ItemKind::Consumable | ItemKind::Misc) && def.gathered_by.is_none();
+    if !placeable {
+        return Err("Only consumables and misc items can be placed as pickups".to_string());
+    }
+    if !(MIN_PICKUP_RESPAWN_SECS..=MAX_PICKUP_RESPAWN_SECS).contains(&respawn_secs) {
+        return Err(format!(
+            "Pickups respawn after {}-{} seconds",
+            MIN_PICKUP_RESPAWN_SECS, MAX_PICKUP_RESPAWN_SECS
+        ));
+    }
+    ctx.db.world_pickup().insert(WorldPickup {
+        pickup_id: 0,
+        room_name,
+        item_def_id,
+        position,
+        respawn_secs,
+        available: true,
+    });
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn remove_pickup(ctx: &ReducerContext, pickup_id: u64) -> Result<(), String> {
+    let Some(pickup) = ctx.db.world_pickup().pickup_id().find(pickup_id) else {
+        return Err("Pickup not found".to_string());
+    };
+    room::require_room_owner(ctx, &pickup.room_name)?;
+    delete_pickup(ctx, pickup_id);
+    Ok(())
+}
@@ -1,4 +1,4 @@
-/**
+/*!
  * Vibe Coding Starter Pack: 3D Multiplayer - player_logic.rs
  * 
  * This file contains the core movement and player state update logic.
@@ -15,11 +15,17 @@
  *    - update_input_state: Updates player state based on client input
  *    - Handles position, animation, and derived state (is_moving, is_running)
  *    - Translates raw input to game state
+ *    - Rooted players (status.rs) have their movement keys ignored; slowed
+ *      players move at SLOW_MOVE_MULTIPLIER speed
+ *    - set_player_position: Moves a player and keeps their spatial index entry in sync
  * 
  * 3. Game Tick:
- *    - update_players_logic: Placeholder for periodic player updates
- *    - Currently empty as players are updated directly through input
- *    - Can be extended for server-side simulation (AI, physics, etc.)
+ *    - update_players_logic: Periodic player updates
+ *    - Movement itself is applied directly through input
+ *    - Regenerates block stamina for players who aren't blocking
+ *    - Drains stamina while sprinting and regenerates it otherwise; at zero
+ *      stamina update_input_state ignores the sprint key (force-walk). The
+ *      regen rate is scaled by the stamina_regen experiment (experiment.rs)
  * 
  * Extension points:
  *    - Add terrain logic for realistic height adjustments
@@ -34,16 +40,26 @@
 
 use spacetimedb::ReducerContext;
 // Import common structs and constants
-use crate::common::{Vector3, InputState, PLAYER_SPEED, SPRINT_MULTIPLIER};
+use crate::common::{
+    Vector3, InputState, ACTION_ATTACK, ACTION_BLOCK, ACTION_CAST_SPELL, ACTION_SPRINT, PLAYER_SPEED, SPRINT_MULTIPLIER, BLOCK_STAMINA_REGEN_PER_SEC, SPRINT_STAMINA_DRAIN_PER_SEC,
+    SLOW_MOVE_MULTIPLIER, HASTE_MOVE_MULTIPLIER, STAMINA_REGEN_PER_SEC,
+};
+use crate::door;
+use crate::heightmap;
+use crate::experiment::{self, STAMINA_REGEN_EXPERIMENT};
+use crate::prop;
+use crate::room;
+use crate::spatial::{self, SpatialEntity};
+use crate::status::{self, StatusKind};
+use crate::weather;
 // Import the PlayerData struct definition (assuming it's in lib.rs or common.rs)
 use crate::PlayerData;
+use crate::player as _;
 
 // Corrected movement logic based on reversed feedback
 pub fn calculate_new_position(position: &Vector3, rotation: &Vector3, input: &InputState, delta_time: f32) -> Vector3 {
-    let has_movement_input = input.forward || input.backward || input.left || input.right;
-
-    if has_movement_input {
-        let speed = if input.sprint { PLAYER_SPEED * SPRINT_MULTIPLIER } else { PLAYER_SPEED };
+    if input.is_moving() {
+        let speed = if input.has(ACTION_SPRINT) { PLAYER_SPEED * SPRINT_MULTIPLIER } else { PLAYER_SPEED };
 
         // Create basis vectors for movement (forward/right vectors from camera)
         // -Z is forward in Three.js coordinates 
@@ -71,33 +87,15 @@ pub fn calculate_new_position(position: &Vector3, rotation: &Vector3, input: &In
             z: -right.x * sin_yaw + right.z * cos_yaw,
         };
         
-        // Accumulate movement along these basis vectors
-        let mut direction = Vector3 { x: 0.0, y: 0.0, z: 0.0 };
-        
-        // Now correctly apply input to match Three.js coordinate system
-        if input.forward {
-            direction.x += rotated_forward.x;  // Add forward direction
-            direction.z += rotated_forward.z;
-        }
-        if input.backward {
-            direction.x -= rotated_forward.x;  // Subtract forward direction
-            direction.z -= rotated_forward.z;
-        }
-        if input.right {
-            direction.x += rotated_right.x;    // Add right direction
-            direction.z += rotated_right.z;
-        }
-        if input.left {
-            direction.x -= rotated_right.x;    // Subtract right direction
-            direction.z -= rotated_right.z;
-        }
-        
-        // Normalize for consistent speed in all directions
-        let magnitude = (direction.x.powi(2) + direction.z.powi(2)).sqrt();
-        if magnitude > 0.01 {
-            direction.x /= magnitude;
-            direction.z /= magnitude;
-        }
+        // Combine the basis vectors by the move axis (y = forward, x = right).
+        // move_vector is capped at length 1, so diagonals aren't faster and
+        // partial stick deflection moves proportionally slower
+        let axis = input.move_vector();
+        let mut direction = Vector3 {
+            x: rotated_forward.x * axis.y + rotated_right.x * axis.x,
+            y: 0.0,
+            z: rotated_forward.z * axis.y + rotated_right.z * axis.x,
+        };
         
         // Apply speed and delta time
         direction.x *= speed * delta_time;
@@ -111,7 +109,7 @@ pub fn calculate_new_position(position: &Vector3, rotation: &Vector3, input: &In
         // For terrain, you could implement height logic here if needed
         // Example: new_position.y = calculate_terrain_height(new_position.x, new_position.z);
         
-        return new_position;
+        new_position
     } else {
         // No movement input, return current position
         position.clone()
@@ -122,19 +120,33 @@ pub fn calculate_new_position(position: &Vector3, rotation: &Vector3, input: &In
 // You could implement server-side animation logic here if needed
 // For example:
 // pub fn determine_animation(input: &InputState) -> String {
-//     let is_moving = input.forward || input.backward || input.left || input.right;
-//     if input.attack { return "attack1".to_string(); }
-//     if input.jump { return "jump".to_string(); }
-//     if is_moving {
-//         if input.sprint { "run-forward".to_string() }
+//     if input.has(ACTION_ATTACK) { return "attack1".to_string(); }
+//     if input.has(ACTION_JUMP) { return "jump".to_string(); }
+//     if input.is_moving() {
+//         if input.has(ACTION_SPRINT) { "run-forward".to_string() }
 //         else { "walk-forward".to_string() }
 //     } else {
 //         "idle".to_string()
 //     }
 // }
 
+// Move a player, keeping their spatial index entry (spatial.rs) in sync.
+// The player's room_name must already be set.
+pub fn set_player_position(ctx: &ReducerContext, player: &mut PlayerData, position: Vector3) {
+    spatial::track(ctx, SpatialEntity::Player(player.identity), &player.room_name, &position);
+    player.position = position;
+}
+
 // Update player state based on input
-pub fn update_input_state(player: &mut PlayerData, input: InputState, client_rot: Vector3, client_animation: String) {
+pub fn update_input_state(ctx: &ReducerContext, player: &mut PlayerData, mut input: InputState, client_rot: Vector3, client_animation: String) {
+    // Out of stamina: force-walk regardless of the sprint key
+    if player.stamina <= 0 {
+        input.clear(ACTION_SPRINT);
+    }
+    // Rooted (e.g. by a snare trap): no movement until it wears off
+    if status::has_status(ctx, player.identity, StatusKind::Rooted) {
+        input.stop_moving();
+    }
     // Calculate movement & animation based on RECEIVED input
     let delta_time_estimate: f32 = 1.0 / 60.0; // Estimate client frame delta
     let new_position = calculate_new_position(
@@ -144,21 +156,87 @@ pub fn update_input_state(player: &mut PlayerData, input: InputState, client_rot
         delta_time_estimate
     );
 
+    // Slowed (e.g. by hazardous terrain): cover less ground per input
+    let new_position = if status::has_status(ctx, player.identity, StatusKind::Slowed) {
+        spatial::lerp(&player.position, &new_position, SLOW_MOVE_MULTIPLIER)
+    } else {
+        new_position
+    };
+
+    // Hasted (swiftness potions, see consumable.rs): cover more
+    let new_position = if status::has_status(ctx, player.identity, StatusKind::Hasted) {
+        spatial::lerp(&player.position, &new_position, HASTE_MOVE_MULTIPLIER)
+    } else {
+        new_position
+    };
+
+    // Rain and storms (weather.rs) make for heavier going
+    let move_multiplier = weather::current(ctx, &player.room_name).move_multiplier();
+    let new_position = if move_multiplier < 1.0 {
+        spatial::lerp(&player.position, &new_position, move_multiplier)
+    } else {
+        new_position
+    };
+
+    // Rolling terrain (heightmap.rs): stand on the ground where there is some
+    let mut new_position = new_position;
+    let ground = room::find_room(ctx, &player.room_name)
+        .and_then(|room| heightmap::height_at(ctx, room.map_id, &new_position));
+    if let Some(ground) = ground {
+        new_position.y = ground;
+    }
+
+    // Closed doors (door.rs) stop the player at the doorway; physics props
+    // (prop.rs) get pushed along unless they're stuck
+    let blocked = door::blocks(ctx, &player.room_name, &new_position)
+        || !prop::push_props(ctx, &player.room_name, &player.position, &new_position);
+    let new_position = if blocked {
+        player.position.clone()
+    } else {
+        new_position
+    };
+
     // Update player state
-    player.position = new_position;
+    set_player_position(ctx, player, new_position);
     player.rotation = client_rot;
     player.current_animation = client_animation;
     player.input = input.clone(); // Store the input that caused this state
     player.last_input_seq = input.sequence;
-    player.is_moving = input.forward || input.backward || input.left || input.right;
-    player.is_running = player.is_moving && input.sprint;
-    player.is_attacking = input.attack;
-    player.is_casting = input.cast_spell;
+    player.is_moving = input.is_moving();
+    player.is_running = player.is_moving && input.has(ACTION_SPRINT);
+    player.is_attacking = input.has(ACTION_ATTACK);
+    player.is_casting = input.has(ACTION_CAST_SPELL);
+    player.is_blocking = input.has(ACTION_BLOCK) && player.block_stamina > 0;
 }
 
-// Update players logic (called from game_tick)
-pub fn update_players_logic(_ctx: &ReducerContext, _delta_time: f64) {
-    // In the simplified starter pack, we don't need to do anything in the game tick
-    // for players as they're updated directly through the update_player_input reducer
-    // This function is a placeholder for future expansion
+// Update the players in one room (called from that room's game_tick)
+pub fn update_players_logic(ctx: &ReducerContext, room_name: &str, delta_time: f64) {
+    // Movement is applied directly through the update_player_input reducer;
+    // the tick only handles resources that drain or recover over time.
+    let dt = delta_time as f32;
+    let block_regen = (BLOCK_STAMINA_REGEN_PER_SEC * dt).round() as i32;
+    let sprint_drain = (SPRINT_STAMINA_DRAIN_PER_SEC * dt).round() as i32;
+    let players: Vec<PlayerData> = ctx.db.player().room_name().filter(room_name)
+        .filter(|p| !p.is_dead)
+        .collect();
+    for mut player in players {
+        let (block_stamina, stamina) = (player.block_stamina, player.stamina);
+        if !player.is_blocking {
+            player.block_stamina = (player.block_stamina + block_regen).min(player.max_block_stamina);
+        }
+        if player.is_running {
+            player.stamina = (player.stamina - sprint_drain).max(0);
+            if player.stamina == 0 {
+                // Forced back to walking until the next input arrives
+                player.is_running = false;
+            }
+        } else if player.stamina < player.max_stamina {
+            let rate = experiment::tuning(ctx, STAMINA_REGEN_EXPERIMENT, player.identity, 1.0);
+            let stamina_regen = (STAMINA_REGEN_PER_SEC * rate * dt).round() as i32;
+            player.stamina = (player.stamina + stamina_regen).min(player.max_stamina);
+        }
+        if player.block_stamina != block_stamina || player.stamina != stamina {
+            ctx.db.player().identity().update(player);
+        }
+    }
 }
@@ -0,0 +1,81 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - population.rs
+ *
+ * Dynamic NPC difficulty: NPC health and damage scale with the number of
+ * players in the room, so solo players and full rooms both get reasonable
+ * encounters.
+ *
+ * Key components:
+ * - PopulationScale: Health and damage multipliers for a player count. The
+ *   first player plays at 1.0; every extra player (up to
+ *   POPULATION_SCALE_MAX_PLAYERS) adds NPC_HEALTH_PER_EXTRA_PLAYER /
+ *   NPC_DAMAGE_PER_EXTRA_PLAYER
+ * - set_base_health: Gives an NPC its unscaled max health and applies the
+ *   room's current scale (spawning, bosses)
+ * - rescale_room: Called by room.rs whenever a player joins or leaves.
+ *   Every NPC in the room gets max_health = base_max_health * scale, keeping
+ *   its current health fraction
+ * - Damage is scaled when NPCs attack (npc_ai.rs, npc_script.rs)
+ *
+ * Related files:
+ * - room.rs: on_player_joined / on_player_left
+ * - npc.rs: Npc.base_max_health
+ */
+
+use spacetimedb::ReducerContext;
+
+use crate::common::{NPC_DAMAGE_PER_EXTRA_PLAYER, NPC_HEALTH_PER_EXTRA_PLAYER, POPULATION_SCALE_MAX_PLAYERS};
+use crate::npc::{npc as _, Npc};
+use crate::room;
+
+#[derive(Clone, Copy, Debug)]
+pub struct PopulationScale {
+    pub health: f32,
+    pub damage: f32,
+}
+
+impl PopulationScale {
+    pub fn for_players(player_count: u32) -> Self {
+        let extra = player_count.clamp(1, POPULATION_SCALE_MAX_PLAYERS) - 1;
+        PopulationScale {
+            health: 1.0 + extra as f32 * NPC_HEALTH_PER_EXTRA_PLAYER,
+            damage: 1.0 + extra as f32 * NPC_DAMAGE_PER_EXTRA_PLAYER,
+        }
+    }
+
+    pub fn for_room(ctx: &ReducerContext, room_name: &str) -> Self {
+        let player_count = room::find_room(ctx, room_name).map_or(0, |r| r.current_player_count);
+        Self::for_players(player_count)
+    }
+
+    pub fn scale_damage(&self, damage: i32) -> i32 {
+        (damage as f32 * self.damage).round() as i32
+    }
+}
+
+// Set max health to `scale` times the NPC's base, keeping the health fraction
+// (living NPCs keep at least 1 health)
+fn apply(npc: &mut Npc, scale: PopulationScale) {
+    let fraction = npc.health as f32 / npc.max_health.max(1) as f32;
+    npc.max_health = ((npc.base_max_health as f32 * scale.health).round() as i32).max(1);
+    let health = (fraction * npc.max_health as f32).round() as i32;
+    npc.health = if npc.health > 0 { health.max(1) } else { 0 };
+}
+
+// Full health at `base_max_health`, scaled for the NPC's room. The caller
+// saves the row
+pub fn set_base_health(ctx: &ReducerContext, npc: &mut Npc, base_max_health: i32) {
+    npc.base_max_health = base_max_health;
+    npc.max_health = base_max_health;
+    npc.health = base_max_health;
+    apply(npc, PopulationScale::for_room(ctx, &npc.room_name));
+}
+
+pub fn rescale_room(ctx: &ReducerContext, room_name: &str, player_count: u32) {
+    let scale = PopulationScale::for_players(player_count);
+    let npcs: Vec<Npc> = ctx.db.npc().room_name().filter(room_name).collect();
+    for mut npc in npcs {
+        apply(&mut npc, scale);
+        ctx.db.npc().npc_id().update(npc);
+    }
+}
@@ -0,0 +1,49 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - progression.rs
+ *
+ * Character levels. There is no experience system yet, so levels only move
+ * when an admin sets them; equipment level requirements read them.
+ *
+ * Key components:
+ * - CharacterLevel table (public): A player's level. Players without a row
+ *   are level 1
+ * - level_of: Resolves a player's level
+ * - set_player_level: Admin reducer
+ *
+ * Related files:
+ * - equipment.rs: required_level checks
+ */
+
+use spacetimedb::{Identity, ReducerContext, Table};
+
+use crate::admin;
+use crate::common::MAX_PLAYER_LEVEL;
+
+#[spacetimedb::table(name = character_level, public)]
+#[derive(Clone)]
+pub struct CharacterLevel {
+    #[primary_key]
+    pub identity: Identity,
+    pub level: u32,
+}
+
+pub fn level_of(ctx: &ReducerContext, identity: Identity) -> u32 {
+    ctx.db.character_level().identity().find(identity).map_or(1, |l| l.level)
+}
+
+// --- Progression Reducers ---
+
+#[spacetimedb::reducer]
+pub fn set_player_level(ctx: &ReducerContext, identity: Identity, level: u32) -> Result<(), String> {
+    admin::require_admin(ctx)?;
+    if level == 0 || level > MAX_PLAYER_LEVEL {
+        return Err(format!("Levels go from 1 to {}", MAX_PLAYER_LEVEL));
+    }
+    let row = CharacterLevel { identity, level };
+    if ctx.db.character_level().identity().find(identity).is_some() {
+        ctx.db.character_level().identity().update(row);
+    } else {
+        ctx.db.character_level().insert(row);
+    }
+    Ok(())
+}
@@ -0,0 +1,200 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - projectile.rs
+ *
+ * Server-simulated projectiles (e.g. the Wizard fireball).
+ *
+ * Key components:
+ *
+ * 1. Pooling:
+ *    - Projectile rows are never deleted. When a projectile hits or expires
+ *      it is marked inactive, and spawn_projectile reuses an inactive row
+ *      (found through the `active` index) before inserting a new one. This
+ *      keeps insert/delete churn down in heavy firefights.
+ *
+ * 2. Batched Stepping:
+ *    - step_projectiles: Called from each room's game_tick. Each active
+ *      projectile's swept path for this tick is bucketed by the spatial grid
+ *      cells it overlaps (spatial.rs). Every cell is then queried exactly
+ *      once and its players and NPCs are tested against all projectiles in
+ *      that bucket.
+ *    - The earliest hit along the path wins. Explosive projectiles detonate
+ *      through apply_aoe (also when their lifetime runs out); others deal
+ *      direct damage.
+ *
+ * Related files:
+ *    - combat.rs: apply_damage / apply_aoe and the Wizard spell that fires these
+ *    - spatial.rs: Grid cells and membership rows used for the broad phase
+ *    - common.rs: Projectile constants
+ */
+
+use spacetimedb::{ReducerContext, Table, Timestamp};
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::combat;
+use crate::common::{CombatEventKind, EntityId, Vector3, PROJECTILE_HIT_RADIUS};
+use crate::spatial::{self, lerp, segment_hit, SpatialEntity};
+
+#[spacetimedb::table(name = projectile, public)]
+#[derive(Clone)]
+pub struct Projectile {
+    #[primary_key]
+    #[auto_inc]
+    pub projectile_id: u64,
+    #[index(btree)]
+    pub active: bool,
+    #[index(btree)]
+    pub room_name: String,
+    pub owner: EntityId,
+    pub position: Vector3,
+    pub velocity: Vector3,
+    pub damage: i32,
+    pub explosion_radius: f32, // 0 = direct hit only
+    pub knockback: f32,
+    pub expires_at: Timestamp,
+}
+
+// Parameters for a new projectile; position/velocity are in world units
+pub struct ProjectileSpec {
+    pub damage: i32,
+    pub explosion_radius: f32,
+    pub knockback: f32,
+    pub lifetime: Duration,
+}
+
+pub fn spawn_projectile(
+    ctx: &ReducerContext,
+    owner: EntityId,
+    room_name: &str,
+    origin: Vector3,
+    velocity: Vector3,
+    spec: ProjectileSpec,
+) {
+    let mut row = Projectile {
+        projectile_id: 0,
+        active: true,
+        room_name: room_name.to_string(),
+        owner,
+        position: origin,
+        velocity,
+        damage: spec.damage,
+        explosion_radius: spec.explosion_radius,
+        knockback: spec.knockback,
+        expires_at: ctx.timestamp + spec.lifetime,
+    };
+    // Reuse a pooled row if one is free
+    let row = match ctx.db.projectile().active().filter(false).next() {
+        Some(free) => {
+            row.projectile_id = free.projectile_id;
+            ctx.db.projectile().projectile_id().update(row)
+        }
+        None => ctx.db.projectile().insert(row),
+    };
+    spatial::track(ctx, SpatialEntity::Projectile(row.projectile_id), &row.room_name, &row.position);
+}
+
+// Advance the active projectiles of one room (called from that room's game_tick)
+pub fn step_projectiles(ctx: &ReducerContext, room_name: &str, delta_time: f64) {
+    let dt = delta_time as f32;
+    let projectiles: Vec<Projectile> = ctx.db.projectile().room_name().filter(room_name)
+        .filter(|p| p.active)
+        .collect();
+    if projectiles.is_empty() {
+        return;
+    }
+
+    // Swept path of every projectile this tick
+    let paths: Vec<(Vector3, Vector3)> = projectiles.iter()
+        .map(|p| {
+            let end = Vector3 {
+                x: p.position.x + p.velocity.x * dt,
+                y: p.position.y + p.velocity.y * dt,
+                z: p.position.z + p.velocity.z * dt,
+            };
+            (p.position.clone(), end)
+        })
+        .collect();
+
+    // Bucket projectiles by every grid cell their path (plus hit radius) overlaps
+    let mut buckets: HashMap<i64, Vec<usize>> = HashMap::new();
+    for (index, (start, end)) in paths.iter().enumerate() {
+        for cell in spatial::grid_cells_along_ray(start, end, PROJECTILE_HIT_RADIUS) {
+            buckets.entry(cell).or_default().push(index);
+        }
+    }
+
+    // Earliest hit per projectile: (t, target)
+    let mut hits: Vec<Option<(f32, EntityId)>> = vec![None; projectiles.len()];
+    let mut consider = |index: usize, target: EntityId, position: &Vector3| {
+        let projectile = &projectiles[index];
+        if target == projectile.owner {
+            return;
+        }
+        let (start, end) = &paths[index];
+        if let Some(t) = segment_hit(start, end, position, PROJECTILE_HIT_RADIUS) {
+            if hits[index].as_ref().is_none_or(|(best, _)| t < *best) {
+                hits[index] = Some((t, target));
+            }
+        }
+    };
+
+    for (cell, indices) in &buckets {
+        for (entity, position) in spatial::entities_in_cells(ctx, room_name, &[*cell]) {
+            let Some(target) = entity.combatant() else {
+                continue;
+            };
+            for &index in indices {
+                consider(index, target.clone(), &position);
+            }
+        }
+    }
+
+    for (index, mut projectile) in projectiles.into_iter().enumerate() {
+        let (start, end) = &paths[index];
+        match hits[index].take() {
+            Some((t, target)) => {
+                let impact = lerp(start, end, t);
+                if projectile.explosion_radius > 0.0 {
+                    combat::apply_aoe(
+                        ctx,
+                        &projectile.owner,
+                        &projectile.room_name,
+                        &impact,
+                        projectile.explosion_radius,
+                        projectile.damage,
+                        projectile.knockback,
+                    );
+                } else {
+                    combat::apply_damage(ctx, &projectile.owner, &target, projectile.damage, CombatEventKind::Projectile);
+                }
+                projectile.position = impact;
+                projectile.active = false;
+            }
+            None => {
+                projectile.position = end.clone();
+                if projectile.expires_at <= ctx.timestamp {
+                    // Explosives go off where they run out of range
+                    if projectile.explosion_radius > 0.0 {
+                        combat::apply_aoe(
+                            ctx,
+                            &projectile.owner,
+                            &projectile.room_name,
+                            &projectile.position,
+                            projectile.explosion_radius,
+                            projectile.damage,
+                            projectile.knockback,
+                        );
+                    }
+                    projectile.active = false;
+                }
+            }
+        }
+        let entity = SpatialEntity::Projectile(projectile.projectile_id);
+        if projectile.active {
+            spatial::track(ctx, entity, &projectile.room_name, &projectile.position);
+        } else {
+            spatial::untrack(ctx, &entity);
+        }
+        ctx.db.projectile().projectile_id().update(projectile);
+    }
+}
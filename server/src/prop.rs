@@ -0,0 +1,202 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - prop.rs
+ *
+ * Physics props: loose crates and barrels players can shove around. (Not to
+ * be confused with the breakable props of destructible.rs, which stay put.)
+ *
+ * Key components:
+ * - Prop table (public): A box with a velocity. Props at rest are skipped by
+ *   the simulation until something pushes them
+ * - push_props: Part of the player movement collision pass
+ *   (player_logic.rs). A player walking into a prop slides it along by
+ *   PROP_PUSH_FACTOR of their step; if the prop can't move (another prop or a
+ *   closed door is in the way) the player is stopped instead
+ * - step_props: Called from game_tick. Applies gravity and ground friction
+ *   and settles props on the highest tile top (or heightmap terrain) beneath
+ *   them. Props that fall
+ *   below PROP_KILL_Y (off the map) are removed
+ * - place_prop / remove_prop: The room owner places props any time, other
+ *   players while build_mode is on. Only the player who placed a prop (or
+ *   the room owner) can remove it (protection.rs)
+ *
+ * Related files:
+ * - player_logic.rs: update_input_state calls push_props
+ * - door.rs: Closed doors stop props too
+ * - room.rs: close_room clears a room's props
+ */
+
+use spacetimedb::{Identity, ReducerContext, SpacetimeType, Table};
+
+use crate::common::{
+    Vector3, MAX_PROPS_PER_ROOM, PLAYER_BODY_RADIUS, PROP_FRICTION, PROP_GRAVITY, PROP_KILL_Y, PROP_PUSH_FACTOR,
+    PROP_REST_SPEED,
+};
+use crate::door;
+use crate::heightmap;
+use crate::minimap;
+use crate::protection::{self, PlacedObject};
+use crate::room;
+use crate::spatial;
+use crate::game_tile;
+
+#[derive(SpacetimeType, Clone, Copy, Debug, PartialEq)]
+pub enum PropKind {
+    Crate,
+    Barrel,
+}
+
+impl PropKind {
+    pub fn size(self) -> Vector3 {
+        match self {
+            PropKind::Crate => Vector3 { x: 1.0, y: 1.0, z: 1.0 },
+            PropKind::Barrel => Vector3 { x: 0.8, y: 1.2, z: 0.8 },
+        }
+    }
+}
+
+#[spacetimedb::table(name = prop, public)]
+#[derive(Clone)]
+pub struct Prop {
+    #[primary_key]
+    #[auto_inc]
+    pub prop_id: u64,
+    #[index(btree)]
+    pub room_name: String,
+    pub kind: PropKind,
+    pub position: Vector3, // Center of the box
+    pub size: Vector3,
+    pub velocity: Vector3,
+    pub at_rest: bool,
+    pub placed_by: Identity,
+}
+
+// Whether a body of PLAYER_BODY_RADIUS at `position` overlaps the prop
+fn touches(prop: &Prop, position: &Vector3) -> bool {
+    (position.x - prop.position.x).abs() < prop.size.x * 0.5 + PLAYER_BODY_RADIUS
+        && (position.z - prop.position.z).abs() < prop.size.z * 0.5 + PLAYER_BODY_RADIUS
+        && position.y < prop.position.y + prop.size.y * 0.5
+        && position.y + 1.0 > prop.position.y - prop.size.y * 0.5
+}
+
+fn overlaps(a: &Prop, b: &Prop) -> bool {
+    (a.position.x - b.position.x).abs() < (a.size.x + b.size.x) * 0.5
+        && (a.position.z - b.position.z).abs() < (a.size.z + b.size.z) * 0.5
+        && (a.position.y - b.position.y).abs() < (a.size.y + b.size.y) * 0.5
+}
+
+// Top of the highest tile (or rolling terrain) under `position` that the
+// prop could stand on
+fn ground_height(ctx: &ReducerContext, map_id: u32, position: &Vector3, bottom: f32) -> Option<f32> {
+    ctx.db.game_tile().map_cell().filter((map_id, spatial::grid_cell_of(position)))
+        .filter(|tile| {
+            (position.x - tile.position.x).abs() <= tile.size.x * 0.5
+                && (position.z - tile.position.z).abs() <= tile.size.z * 0.5
+        })
+        .map(|tile| tile.position.y + tile.size.y * 0.5)
+        .filter(|top| *top <= bottom + 0.01)
+        .chain(heightmap::height_at(ctx, map_id, position)) // Never sinks into terrain
+        .max_by(|a, b| a.total_cmp(b))
+}
+
+// Slide props out of the way of a player moving from `from` to `to`. Returns
+// false if a prop is stuck, in which case the player must stay at `from`
+pub fn push_props(ctx: &ReducerContext, room_name: &str, from: &Vector3, to: &Vector3) -> bool {
+    let props: Vec<Prop> = ctx.db.prop().room_name().filter(room_name).collect();
+    let step = Vector3 { x: to.x - from.x, y: 0.0, z: to.z - from.z };
+    for mut prop in props.iter().filter(|p| touches(p, to) && !touches(p, from)).cloned() {
+        let resting_at = prop.at_rest.then(|| prop.position.clone());
+        prop.position.x += step.x * PROP_PUSH_FACTOR;
+        prop.position.z += step.z * PROP_PUSH_FACTOR;
+        let stuck = door::blocks(ctx, room_name, &prop.position)
+            || props.iter().any(|other| other.prop_id != prop.prop_id && overlaps(&prop, other));
+        if stuck {
+            return false;
+        }
+        if let Some(position) = resting_at {
+            minimap::mark_dirty_at(ctx, room_name, &position);
+        }
+        prop.at_rest = false;
+        ctx.db.prop().prop_id().update(prop);
+    }
+    true
+}
+
+pub fn step_props(ctx: &ReducerContext, room_name: &str, map_id: u32, delta_time: f32) {
+    let moving: Vec<Prop> = ctx.db.prop().room_name().filter(room_name).filter(|p| !p.at_rest).collect();
+    for mut prop in moving {
+        let half_height = prop.size.y * 0.5;
+        let bottom = prop.position.y - half_height;
+        prop.velocity.y -= PROP_GRAVITY * delta_time;
+        prop.position.x += prop.velocity.x * delta_time;
+        prop.position.z += prop.velocity.z * delta_time;
+        let fallen = bottom + prop.velocity.y * delta_time;
+
+        match ground_height(ctx, map_id, &prop.position, bottom) {
+            Some(top) if fallen <= top => {
+                prop.position.y = top + half_height;
+                prop.velocity.y = 0.0;
+                let friction = (1.0 - PROP_FRICTION * delta_time).max(0.0);
+                prop.velocity.x *= friction;
+                prop.velocity.z *= friction;
+                let speed = (prop.velocity.x * prop.velocity.x + prop.velocity.z * prop.velocity.z).sqrt();
+                prop.at_rest = speed < PROP_REST_SPEED;
+                if prop.at_rest {
+                    minimap::mark_dirty_at(ctx, room_name, &prop.position);
+                }
+            }
+            _ => prop.position.y = fallen + half_height,
+        }
+        if prop.position.y < PROP_KILL_Y {
+            ctx.db.prop().prop_id().delete(prop.prop_id);
+        } else {
+            ctx.db.prop().prop_id().update(prop);
+        }
+    }
+}
+
+pub fn clear_room(ctx: &ReducerContext, room_name: &str) {
+    let props: Vec<u64> = ctx.db.prop().room_name().filter(room_name).map(|p| p.prop_id).collect();
+    for prop_id in props {
+        ctx.db.prop().prop_id().delete(prop_id);
+    }
+}
+
+// --- Prop Reducers ---
+
+#[spacetimedb::reducer]
+pub fn place_prop(ctx: &ReducerContext, room_name: String, kind: PropKind, position: Vector3) -> Result<(), String> {
+    protection::require_placer(ctx, &room_name)?;
+    if ctx.db.prop().room_name().filter(&room_name).count() >= MAX_PROPS_PER_ROOM {
+        return Err(format!("A room can have at most {} props", MAX_PROPS_PER_ROOM));
+    }
+    // Dropped in place: the next tick lets it fall onto the ground
+    ctx.db.prop().insert(Prop {
+        prop_id: 0,
+        room_name,
+        kind,
+        position,
+        size: kind.size(),
+        velocity: Vector3 { x: 0.0, y: 0.0, z: 0.0 },
+        at_rest: false,
+        placed_by: ctx.sender,
+    });
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn remove_prop(ctx: &ReducerContext, prop_id: u64) -> Result<(), String> {
+    let Some(prop) = ctx.db.prop().prop_id().find(prop_id) else {
+        return Err("Prop not found".to_string());
+    };
+    let Some(room) = room::find_room(ctx, &prop.room_name) else {
+        return Err("Room not found".to_string());
+    };
+    if !protection::may_modify(ctx, &room, PlacedObject::Prop, prop_id, Some(prop.placed_by)) {
+        return Ok(());
+    }
+    if prop.at_rest {
+        minimap::mark_dirty_at(ctx, &room.room_name, &prop.position);
+    }
+    ctx.db.prop().prop_id().delete(prop_id);
+    Ok(())
+}
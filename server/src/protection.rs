@@ -0,0 +1,122 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - protection.rs
+ *
+ * Ownership of player-placed world objects. While a room's build_mode is on
+ * anyone in it can place blocks (build.rs), decorations and props; this
+ * keeps them from tearing down each other's work.
+ *
+ * Key components:
+ * - PlacedObject: Which kind of object a check or report is about
+ * - require_placer: Who may place objects in a room: its owner (or an admin)
+ *   anywhere, everyone else only while build_mode is on and they are in the
+ *   room
+ * - may_modify: Whether the sender may move or remove an object. The room
+ *   owner (and admins) may touch anything, other players only their own
+ *   objects and unowned map terrain. A refusal records a grief report
+ * - GriefReport table (public): One row per refused edit, visible to the
+ *   player whose object it was and pruned after GRIEF_REPORT_TTL_SECS
+ *
+ * Related files:
+ * - build.rs / decoration.rs / prop.rs: The protected objects
+ * - room.rs: close_room clears a room's reports
+ */
+
+use spacetimedb::{client_visibility_filter, Filter, Identity, ReducerContext, SpacetimeType, Table, Timestamp};
+use std::time::Duration;
+
+use crate::admin;
+use crate::common::GRIEF_REPORT_TTL_SECS;
+use crate::player as _;
+use crate::room::{self, Room};
+
+#[derive(SpacetimeType, Clone, Copy, Debug, PartialEq)]
+pub enum PlacedObject {
+    Tile,
+    Decoration,
+    Prop,
+}
+
+#[spacetimedb::table(name = grief_report, public)]
+#[derive(Clone)]
+pub struct GriefReport {
+    #[primary_key]
+    #[auto_inc]
+    pub report_id: u64,
+    #[index(btree)]
+    pub room_name: String,
+    pub offender: Identity,
+    pub object_owner: Identity,
+    pub object: PlacedObject,
+    pub object_id: u64,
+    pub timestamp: Timestamp,
+}
+
+#[client_visibility_filter]
+const OWNER_SEES_GRIEF_REPORTS: Filter = Filter::Sql(
+    "SELECT * FROM grief_report WHERE object_owner = :sender",
+);
+
+pub fn require_placer(ctx: &ReducerContext, room_name: &str) -> Result<Room, String> {
+    let Some(room) = room::find_room(ctx, room_name) else {
+        return Err("Room not found".to_string());
+    };
+    if room.owner == Some(ctx.sender) || admin::is_admin(ctx) {
+        return Ok(room);
+    }
+    let in_room = ctx.db.player().identity().find(ctx.sender).is_some_and(|p| p.room_name == room_name);
+    if !room.build_mode || !in_room {
+        return Err("Only the room owner can build here".to_string());
+    }
+    Ok(room)
+}
+
+// Whether the sender may move or remove the object. Callers should end the
+// reducer with Ok(()) on false: an Err would roll back the grief report too
+pub fn may_modify(
+    ctx: &ReducerContext,
+    room: &Room,
+    object: PlacedObject,
+    object_id: u64,
+    placed_by: Option<Identity>,
+) -> bool {
+    let Some(object_owner) = placed_by else {
+        return true; // Map terrain, governed by build_mode alone
+    };
+    if object_owner == ctx.sender || room.owner == Some(ctx.sender) || admin::is_admin(ctx) {
+        return true;
+    }
+    ctx.db.grief_report().insert(GriefReport {
+        report_id: 0,
+        room_name: room.room_name.clone(),
+        offender: ctx.sender,
+        object_owner,
+        object,
+        object_id,
+        timestamp: ctx.timestamp,
+    });
+    spacetimedb::log::warn!(
+        "Player {:?} tried to change {:?} {} owned by {:?} in room '{}'.",
+        ctx.sender, object, object_id, object_owner, room.room_name
+    );
+    false
+}
+
+pub fn prune_grief_reports(ctx: &ReducerContext, room_name: &str) {
+    let Some(cutoff) = ctx.timestamp.checked_sub_duration(Duration::from_secs(GRIEF_REPORT_TTL_SECS)) else {
+        return;
+    };
+    let expired: Vec<u64> = ctx.db.grief_report().room_name().filter(room_name)
+        .filter(|report| report.timestamp < cutoff)
+        .map(|report| report.report_id)
+        .collect();
+    for report_id in expired {
+        ctx.db.grief_report().report_id().delete(report_id);
+    }
+}
+
+pub fn clear_room(ctx: &ReducerContext, room_name: &str) {
+    let reports: Vec<u64> = ctx.db.grief_report().room_name().filter(room_name).map(|r| r.report_id).collect();
+    for report_id in reports {
+        ctx.db.grief_report().report_id().delete(report_id);
+    }
+}
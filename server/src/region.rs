@@ -0,0 +1,147 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - region.rs
+ *
+ * Named areas of a room ("the Dark Forest") that clients announce when a
+ * player walks into them.
+ *
+ * Key components:
+ * - Region table (public): A labeled axis-aligned box in a room. Where
+ *   regions overlap, the smallest one wins, so a village can sit inside a
+ *   forest
+ * - track_regions: Called from game_tick. Updates each player's
+ *   current_region and records a region_enter event when it changes to a
+ *   region (leaving into the open records nothing)
+ * - RegionEnter table (public): The events, pruned after
+ *   REGION_EVENT_TTL_SECS
+ * - place_region / remove_region: Room-owner reducers
+ *
+ * Related files:
+ * - lib.rs: PlayerData.current_region, game_tick
+ * - room.rs: close_room clears a room's regions
+ */
+
+use spacetimedb::{Identity, ReducerContext, Table, Timestamp};
+use std::time::Duration;
+
+use crate::common::{Vector3, MAX_REGION_NAME_LEN, REGION_EVENT_TTL_SECS};
+use crate::player as _;
+use crate::room;
+
+#[spacetimedb::table(name = region, public)]
+#[derive(Clone)]
+pub struct Region {
+    #[primary_key]
+    #[auto_inc]
+    pub region_id: u64,
+    #[index(btree)]
+    pub room_name: String,
+    pub name: String,
+    pub min: Vector3,
+    pub max: Vector3,
+}
+
+#[spacetimedb::table(name = region_enter, public)]
+#[derive(Clone)]
+pub struct RegionEnter {
+    #[primary_key]
+    #[auto_inc]
+    pub event_id: u64,
+    #[index(btree)]
+    pub room_name: String,
+    pub identity: Identity,
+    pub region_id: u64,
+    pub region_name: String,
+    pub timestamp: Timestamp,
+}
+
+impl Region {
+    fn contains(&self, position: &Vector3) -> bool {
+        (self.min.x..=self.max.x).contains(&position.x)
+            && (self.min.y..=self.max.y).contains(&position.y)
+            && (self.min.z..=self.max.z).contains(&position.z)
+    }
+
+    fn volume(&self) -> f32 {
+        (self.max.x - self.min.x) * (self.max.y - self.min.y) * (self.max.z - self.min.z)
+    }
+}
+
+// The most specific region at `position`
+fn region_at<'a>(regions: &'a [Region], position: &Vector3) -> Option<&'a Region> {
+    regions.iter()
+        .filter(|r| r.contains(position))
+        .min_by(|a, b| a.volume().total_cmp(&b.volume()))
+}
+
+pub fn track_regions(ctx: &ReducerContext, room_name: &str) {
+    let regions: Vec<Region> = ctx.db.region().room_name().filter(room_name).collect();
+    for mut player in ctx.db.player().room_name().filter(room_name) {
+        let region = region_at(&regions, &player.position);
+        let region_id = region.map(|r| r.region_id);
+        if player.current_region == region_id {
+            continue;
+        }
+        if let Some(region) = region {
+            ctx.db.region_enter().insert(RegionEnter {
+                event_id: 0,
+                room_name: room_name.to_string(),
+                identity: player.identity,
+                region_id: region.region_id,
+                region_name: region.name.clone(),
+                timestamp: ctx.timestamp,
+            });
+        }
+        player.current_region = region_id;
+        ctx.db.player().identity().update(player);
+    }
+}
+
+pub fn prune_region_events(ctx: &ReducerContext, room_name: &str) {
+    let Some(cutoff) = ctx.timestamp.checked_sub_duration(Duration::from_secs(REGION_EVENT_TTL_SECS)) else {
+        return;
+    };
+    let expired: Vec<u64> = ctx.db.region_enter().room_name().filter(room_name)
+        .filter(|event| event.timestamp < cutoff)
+        .map(|event| event.event_id)
+        .collect();
+    for event_id in expired {
+        ctx.db.region_enter().event_id().delete(event_id);
+    }
+}
+
+pub fn clear_room(ctx: &ReducerContext, room_name: &str) {
+    let regions: Vec<u64> = ctx.db.region().room_name().filter(room_name).map(|r| r.region_id).collect();
+    for region_id in regions {
+        ctx.db.region().region_id().delete(region_id);
+    }
+    let events: Vec<u64> = ctx.db.region_enter().room_name().filter(room_name).map(|e| e.event_id).collect();
+    for event_id in events {
+        ctx.db.region_enter().event_id().delete(event_id);
+    }
+}
+
+// --- Region Reducers ---
+
+#[spacetimedb::reducer]
+pub fn place_region(ctx: &ReducerContext, room_name: String, name: String, min: Vector3, max: Vector3) -> Result<(), String> {
+    room::require_room_owner(ctx, &room_name)?;
+    let name = name.trim().to_string();
+    if name.is_empty() || name.len() > MAX_REGION_NAME_LEN {
+        return Err(format!("Region names must be 1-{} characters", MAX_REGION_NAME_LEN));
+    }
+    if min.x >= max.x || min.y >= max.y || min.z >= max.z {
+        return Err("A region's min corner must be below its max corner".to_string());
+    }
+    ctx.db.region().insert(Region { region_id: 0, room_name, name, min, max });
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn remove_region(ctx: &ReducerContext, region_id: u64) -> Result<(), String> {
+    let Some(region) = ctx.db.region().region_id().find(region_id) else {
+        return Err("Region not found".to_string());
+    };
+    room::require_room_owner(ctx, &region.room_name)?;
+    ctx.db.region().region_id().delete(region_id);
+    Ok(())
+}
@@ -0,0 +1,118 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - revive.rs
+ *
+ * Teammates can bring a dead player back before their respawn timer runs out
+ * by channelling a revive next to them.
+ *
+ * Key components:
+ * - ReviveInProgress table (public): One channel per reviver, with where the
+ *   reviver stood and when the channel completes, so clients can show a
+ *   progress bar
+ * - revive_target: Starts a channel on a dead ally within REVIVE_RANGE
+ * - tick_revives: Called from game_tick. Cancels channels whose reviver moved
+ *   more than REVIVE_MOVE_TOLERANCE, took damage or died, or whose target is
+ *   gone or already up; completes the rest once their time is up, restoring
+ *   the target where they fell at REVIVE_HEALTH_FRACTION of max health
+ *   (their scheduled respawn then finds them alive and does nothing)
+ *
+ * Related files:
+ * - support.rs: is_ally
+ * - spawn.rs: The regular respawn flow
+ */
+
+use spacetimedb::{Identity, ReducerContext, Table, Timestamp};
+use std::time::Duration;
+
+use crate::common::{Vector3, REVIVE_CHANNEL_MS, REVIVE_HEALTH_FRACTION, REVIVE_MOVE_TOLERANCE, REVIVE_RANGE};
+use crate::player as _;
+use crate::player_logic;
+use crate::support;
+
+#[spacetimedb::table(name = revive_in_progress, public)]
+#[derive(Clone)]
+pub struct ReviveInProgress {
+    #[primary_key]
+    pub reviver: Identity,
+    #[index(btree)]
+    pub target: Identity,
+    #[index(btree)]
+    pub room_name: String,
+    pub reviver_position: Vector3,
+    pub started_at: Timestamp,
+    pub completes_at: Timestamp,
+}
+
+#[spacetimedb::reducer]
+pub fn revive_target(ctx: &ReducerContext, target: Identity) -> Result<(), String> {
+    let Some(reviver) = ctx.db.player().identity().find(ctx.sender) else {
+        return Err("Player not found".to_string());
+    };
+    let Some(downed) = ctx.db.player().identity().find(target) else {
+        return Err("Target player not found".to_string());
+    };
+    if reviver.is_dead {
+        return Err("Dead players can't revive others".to_string());
+    }
+    if !downed.is_dead {
+        return Err("Target is not down".to_string());
+    }
+    if target == ctx.sender || !support::is_ally(ctx, &reviver, &downed) {
+        return Err("You can only revive allies".to_string());
+    }
+    if reviver.position.distance_xz(&downed.position) > REVIVE_RANGE {
+        return Err("Too far away to revive".to_string());
+    }
+    if ctx.db.revive_in_progress().target().filter(target).next().is_some() {
+        return Err("Someone is already reviving that player".to_string());
+    }
+    // Starting a new channel replaces the reviver's previous one
+    ctx.db.revive_in_progress().reviver().delete(ctx.sender);
+    ctx.db.revive_in_progress().insert(ReviveInProgress {
+        reviver: ctx.sender,
+        target,
+        room_name: reviver.room_name,
+        reviver_position: reviver.position,
+        started_at: ctx.timestamp,
+        completes_at: ctx.timestamp + Duration::from_millis(REVIVE_CHANNEL_MS),
+    });
+    Ok(())
+}
+
+pub fn tick_revives(ctx: &ReducerContext, room_name: &str) {
+    let channels: Vec<ReviveInProgress> = ctx.db.revive_in_progress().room_name().filter(room_name).collect();
+    for channel in channels {
+        let reviver = ctx.db.player().identity().find(channel.reviver);
+        let downed = ctx.db.player().identity().find(channel.target);
+        let interrupted = match (&reviver, &downed) {
+            (Some(reviver), Some(downed)) => {
+                reviver.is_dead
+                    || !downed.is_dead
+                    || reviver.room_name != channel.room_name
+                    || downed.room_name != channel.room_name
+                    || reviver.position.distance_xz(&channel.reviver_position) > REVIVE_MOVE_TOLERANCE
+                    || reviver.last_damaged_at.is_some_and(|at| at >= channel.started_at)
+            }
+            _ => true,
+        };
+        if interrupted {
+            ctx.db.revive_in_progress().reviver().delete(channel.reviver);
+            continue;
+        }
+        if channel.completes_at > ctx.timestamp {
+            continue;
+        }
+        ctx.db.revive_in_progress().reviver().delete(channel.reviver);
+        if let Some(mut downed) = downed {
+            downed.is_dead = false;
+            downed.health = ((downed.max_health as f32 * REVIVE_HEALTH_FRACTION).round() as i32).max(1);
+            downed.last_damager = None;
+            downed.last_damaged_at = None;
+            downed.current_animation = "idle".to_string();
+            // Re-register them in the spatial index at the spot they fell
+            let position = downed.position.clone();
+            player_logic::set_player_position(ctx, &mut downed, position);
+            spacetimedb::log::info!("Player {} was revived by {}.", downed.identity, channel.reviver);
+            ctx.db.player().identity().update(downed);
+        }
+    }
+}
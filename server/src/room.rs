@@ -0,0 +1,490 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - room.rs
+ *
+ * Rooms partition the world so that players, NPCs and combat only interact
+ * with entities in the same room.
+ *
+ * Key components:
+ * - Room table: One row per room, including whether it is a social hub,
+ *   its combat rules (pvp_enabled, friendly_fire) and NPC difficulty
+ * - ensure_default_rooms: Seeds the default hub room (and its spawn points) during init
+ * - create_room / join_room / configure_room / set_room_difficulty:
+ *   Player-facing room management. A player owns at most
+ *   MAX_ROOMS_PER_OWNER rooms at once
+ * - Access control: max_players capacity, an optional password (kept in the
+ *   private room_password table) and per-room bans (room_ban), managed by the
+ *   owner through set_room_access / ban_from_room / unban_from_room
+ * - move_player_to_room: The one place players change rooms (join_room,
+ *   bans, party travel)
+ * - on_player_joined / on_player_left: Keep current_player_count and the
+ *   room's vote counters (vote.rs) in sync, and rescale the room's NPCs to
+ *   the new population (population.rs)
+ * - Every room gets its own game_tick schedule row when it is created, so
+ *   rooms simulate independently of each other
+ * - Every room gets its own tile set when it is created (the hub streams the
+ *   default map, other rooms generate one, see map.rs)
+ * - Day/night cycle: game_tick advances each room's time_of_day; the owner
+ *   sets how long a day lasts (set_day_length). is_night lets gameplay
+ *   react to the dark
+ * - close_room: Owner reducer that tears an empty, non-hub room down along
+ *   with its tile set and everything placed in it (clear_room_contents,
+ *   which gc.rs also uses for rows outliving their room)
+ *
+ * Related files:
+ * - lib.rs: Assigns players to a room on registration and disconnect; game_tick
+ * - npc.rs: NPCs (e.g. training dummies) are spawned per room
+ * - critter.rs: Ambient critters are scattered over new rooms
+ * - mapgen.rs: New rooms stamp the map template their creator picked
+ * - spawn.rs: Spawn points belong to a room
+ */
+
+use spacetimedb::rand::Rng;
+use spacetimedb::{Identity, ReducerContext, SpacetimeType, Table, Timestamp};
+
+use crate::admin;
+use crate::bank;
+use crate::checkpoint;
+use crate::chunk;
+use crate::container;
+use crate::decoration;
+use crate::destructible;
+use crate::door;
+use crate::gathering;
+use crate::common::{
+    Vector3, DAWN_HOUR, DEFAULT_DAY_LENGTH_SECS, DEFAULT_HUB_ROOM, DEFAULT_MAP_ID, DEFAULT_MAP_TEMPLATE, DUSK_HOUR,
+    INITIAL_CHUNK_RADIUS, MAX_DAY_LENGTH_SECS, MAX_ROOMS_PER_OWNER, MAX_ROOM_NAME_LEN, MIN_DAY_LENGTH_SECS,
+};
+use crate::notification::{self, NotificationKind};
+use crate::npc::{self, npc as _};
+use crate::npc_persistent;
+use crate::pickup;
+use crate::npc_ai::{PERSONALITY_AGGRESSIVE, PERSONALITY_DEFENSIVE, PERSONALITY_PASSIVE};
+use crate::hitscan;
+use crate::light;
+use crate::loadout;
+use crate::loot;
+use crate::loot_roll;
+use crate::map;
+use crate::mapgen;
+use crate::player as _;
+use crate::player_logic;
+use crate::population;
+use crate::prop;
+use crate::protection;
+use crate::region;
+use crate::snapshot;
+use crate::sound;
+use crate::spawn;
+use crate::theme;
+use crate::trade;
+use crate::spawner;
+use crate::vote::{self, room_vote_state as _};
+use crate::PlayerData;
+use crate::combat;
+use crate::critter;
+use crate::hazard;
+use crate::safe_zone;
+use crate::teleporter;
+use crate::trap;
+use crate::weather;
+use crate::game_tick_schedule as _;
+use crate::scoreboard;
+
+// Difficulty picks the default AI personality for NPCs that don't set their own
+#[derive(SpacetimeType, Clone, Copy, Debug, PartialEq)]
+pub enum RoomDifficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl RoomDifficulty {
+    pub fn default_personality(&self) -> &'static str {
+        match self {
+            RoomDifficulty::Easy => PERSONALITY_PASSIVE,
+            RoomDifficulty::Normal => PERSONALITY_DEFENSIVE,
+            RoomDifficulty::Hard => PERSONALITY_AGGRESSIVE,
+        }
+    }
+}
+
+#[spacetimedb::table(name = room, public)]
+#[derive(Clone)]
+pub struct Room {
+    #[primary_key]
+    pub room_name: String,
+    pub is_hub: bool,
+    #[index(btree)]
+    pub owner: Option<Identity>,
+    pub pvp_enabled: bool,
+    pub friendly_fire: bool,
+    pub difficulty: RoomDifficulty,
+    pub map_id: u32, // Tile set the room plays on (see map.rs)
+    pub max_players: u32, // 0 = unlimited
+    pub has_password: bool, // The password itself lives in the private room_password table
+    pub build_mode: bool, // Everyone in the room may edit its terrain (see build.rs)
+    pub time_of_day: f32, // Hours since midnight, 0..24; clients light the scene by it
+    pub day_length_secs: u32, // Real seconds per in-game day
+    pub current_player_count: u32,
+    pub created_at: Timestamp,
+}
+
+#[spacetimedb::table(name = room_password)]
+pub struct RoomPassword {
+    #[primary_key]
+    pub room_name: String,
+    pub password: String,
+}
+
+#[spacetimedb::table(name = room_ban)]
+pub struct RoomBan {
+    #[primary_key]
+    #[auto_inc]
+    pub ban_id: u64,
+    #[index(btree)]
+    pub room_name: String,
+    #[index(btree)]
+    pub identity: Identity,
+    pub banned_at: Timestamp,
+}
+
+pub fn find_room(ctx: &ReducerContext, room_name: &str) -> Option<Room> {
+    ctx.db.room().room_name().find(room_name.to_string())
+}
+
+// The room, if the sender owns it (admins may manage any room)
+pub fn require_room_owner(ctx: &ReducerContext, room_name: &str) -> Result<Room, String> {
+    let Some(room) = find_room(ctx, room_name) else {
+        return Err("Room not found".to_string());
+    };
+    if room.owner != Some(ctx.sender) && !admin::is_admin(ctx) {
+        return Err("Only the room owner can do that".to_string());
+    }
+    Ok(room)
+}
+
+pub fn is_banned(ctx: &ReducerContext, room_name: &str, identity: Identity) -> bool {
+    ctx.db.room_ban().identity().filter(identity).any(|ban| ban.room_name == room_name)
+}
+
+// Whether the room's password (if it has one) matches
+pub fn check_password(ctx: &ReducerContext, room: &Room, password: Option<&str>) -> Result<(), String> {
+    if !room.has_password {
+        return Ok(());
+    }
+    let expected = ctx.db.room_password().room_name().find(room.room_name.clone()).map(|p| p.password);
+    if expected.is_some() && expected.as_deref() == password {
+        Ok(())
+    } else {
+        Err("Wrong room password".to_string())
+    }
+}
+
+// Whether `incoming` more players fit in the room
+pub fn check_capacity(room: &Room, incoming: u32) -> Result<(), String> {
+    if room.max_players > 0 && room.current_player_count + incoming > room.max_players {
+        return Err(format!("Room '{}' is full", room.room_name));
+    }
+    Ok(())
+}
+
+// Move a player into another room at `spawn_position`, keeping both rooms'
+// counters in sync
+pub fn move_player_to_room(ctx: &ReducerContext, mut player: PlayerData, room_name: &str, spawn_position: Vector3) {
+    let identity = player.identity;
+    on_player_left(ctx, &player.room_name, player.has_voted);
+    let has_voted = player.has_voted;
+    player.room_name = room_name.to_string();
+    player_logic::set_player_position(ctx, &mut player, spawn_position);
+    hitscan::clear_history(ctx, identity);
+    ctx.db.player().identity().update(player);
+    on_player_joined(ctx, room_name, has_voted);
+    combat::clear_dps_meter(ctx, identity);
+    scoreboard::clear_player(ctx, identity);
+    // Takes back the old room's loadout weapon before granting the new one
+    loadout::apply_loadout(ctx, identity, room_name);
+}
+
+// Create the default hub room (and its training dummies) if it doesn't exist yet
+pub fn ensure_default_rooms(ctx: &ReducerContext) {
+    if ctx.db.room().room_name().find(DEFAULT_HUB_ROOM.to_string()).is_some() {
+        return;
+    }
+    spacetimedb::log::info!("[INIT] Creating hub room '{}'...", DEFAULT_HUB_ROOM);
+    ctx.db.room().insert(Room {
+        room_name: DEFAULT_HUB_ROOM.to_string(),
+        is_hub: true,
+        owner: None,
+        pvp_enabled: false,
+        friendly_fire: false,
+        difficulty: RoomDifficulty::Normal,
+        map_id: DEFAULT_MAP_ID,
+        max_players: 0,
+        has_password: false,
+        build_mode: false,
+        time_of_day: DAWN_HOUR,
+        day_length_secs: DEFAULT_DAY_LENGTH_SECS,
+        current_player_count: 0,
+        created_at: ctx.timestamp,
+    });
+    // The hub plays on the streamed default map: generate the chunks around
+    // the origin up front so it has ground for its spawn points
+    chunk::generate_around(ctx, DEFAULT_HUB_ROOM, DEFAULT_MAP_ID, &Vector3 { x: 0.0, y: 0.0, z: 0.0 }, INITIAL_CHUNK_RADIUS);
+    spawn::seed_spawn_points(ctx, DEFAULT_HUB_ROOM);
+    npc::spawn_training_dummies(ctx, DEFAULT_HUB_ROOM);
+    critter::spawn_critters(ctx, DEFAULT_HUB_ROOM);
+    destructible::seed_destructibles(ctx, DEFAULT_HUB_ROOM);
+    crate::schedule_game_tick(ctx, DEFAULT_HUB_ROOM);
+}
+
+// `has_voted` carries the player's vote over into the room's vote counters
+pub fn on_player_joined(ctx: &ReducerContext, room_name: &str, has_voted: bool) {
+    if let Some(mut room) = ctx.db.room().room_name().find(room_name.to_string()) {
+        room.current_player_count += 1;
+        let player_count = room.current_player_count;
+        ctx.db.room().room_name().update(room);
+        vote::adjust_counts(ctx, room_name, 1, has_voted as i32);
+        population::rescale_room(ctx, room_name, player_count);
+    } else {
+        spacetimedb::log::warn!("Player joined unknown room '{}'.", room_name);
+    }
+}
+
+pub fn on_player_left(ctx: &ReducerContext, room_name: &str, has_voted: bool) {
+    if let Some(mut room) = ctx.db.room().room_name().find(room_name.to_string()) {
+        room.current_player_count = room.current_player_count.saturating_sub(1);
+        let player_count = room.current_player_count;
+        ctx.db.room().room_name().update(room);
+        vote::adjust_counts(ctx, room_name, -1, -(has_voted as i32));
+        population::rescale_room(ctx, room_name, player_count);
+    }
+}
+
+// Tear down everything placed in or tracking a room, short of the room row
+// itself: close_room, and gc.rs for rows whose room is already gone
+pub fn clear_room_contents(ctx: &ReducerContext, room_name: &str) {
+    let npcs: Vec<u64> = ctx.db.npc().room_name().filter(room_name).map(|n| n.npc_id).collect();
+    for npc_id in npcs {
+        ctx.db.npc().npc_id().delete(npc_id);
+        combat::forget_npc(ctx, npc_id);
+        loot::clear_npc(ctx, npc_id);
+    }
+    npc_persistent::clear_room(ctx, room_name);
+    spawner::clear_room(ctx, room_name);
+    trap::clear_room(ctx, room_name);
+    hazard::clear_room(ctx, room_name);
+    safe_zone::clear_room(ctx, room_name);
+    loot::clear_room(ctx, room_name);
+    loot_roll::clear_room(ctx, room_name);
+    destructible::clear_room(ctx, room_name);
+    door::clear_room(ctx, room_name);
+    pickup::clear_room(ctx, room_name);
+    gathering::clear_room(ctx, room_name);
+    weather::clear_room(ctx, room_name);
+    region::clear_room(ctx, room_name);
+    prop::clear_room(ctx, room_name);
+    container::clear_room(ctx, room_name);
+    teleporter::clear_room(ctx, room_name);
+    snapshot::clear_room(ctx, room_name);
+    light::clear_room(ctx, room_name);
+    decoration::clear_room(ctx, room_name);
+    protection::clear_room(ctx, room_name);
+    sound::clear_room(ctx, room_name);
+    checkpoint::clear_room(ctx, room_name);
+    theme::clear_room(ctx, room_name);
+    trade::clear_room(ctx, room_name);
+    bank::clear_room(ctx, room_name);
+    spawn::set_spawn_points(ctx, room_name, Vec::new());
+    map::delete_room_tiles(ctx, room_name);
+    let bans: Vec<u64> = ctx.db.room_ban().room_name().filter(room_name).map(|b| b.ban_id).collect();
+    for ban_id in bans {
+        ctx.db.room_ban().ban_id().delete(ban_id);
+    }
+    ctx.db.room_password().room_name().delete(room_name.to_string());
+    ctx.db.room_vote_state().room_name().delete(room_name.to_string());
+    ctx.db.game_tick_schedule().room_name().delete(room_name.to_string());
+}
+
+// --- Room Reducers ---
+
+#[spacetimedb::reducer]
+pub fn create_room(ctx: &ReducerContext, room_name: String, map: Option<String>) -> Result<(), String> {
+    let room_name = room_name.trim().to_string();
+    if room_name.is_empty() || room_name.len() > MAX_ROOM_NAME_LEN {
+        return Err(format!("Room name must be 1-{} characters", MAX_ROOM_NAME_LEN));
+    }
+    if find_room(ctx, &room_name).is_some() {
+        return Err("A room with that name already exists".to_string());
+    }
+    if ctx.db.room().owner().filter(Some(ctx.sender)).count() >= MAX_ROOMS_PER_OWNER {
+        return Err(format!("You can own at most {} rooms; close one first", MAX_ROOMS_PER_OWNER));
+    }
+
+    ctx.db.room().insert(Room {
+        room_name: room_name.clone(),
+        is_hub: false,
+        owner: Some(ctx.sender),
+        pvp_enabled: false,
+        friendly_fire: false,
+        difficulty: RoomDifficulty::Normal,
+        map_id: DEFAULT_MAP_ID,
+        max_players: 0,
+        has_password: false,
+        build_mode: false,
+        time_of_day: DAWN_HOUR,
+        day_length_secs: DEFAULT_DAY_LENGTH_SECS,
+        current_player_count: 0,
+        created_at: ctx.timestamp,
+    });
+    let template = map.unwrap_or_else(|| DEFAULT_MAP_TEMPLATE.to_string());
+    mapgen::generate_room_map(ctx, &room_name, ctx.rng().gen(), &template)?;
+    critter::spawn_critters(ctx, &room_name);
+    theme::apply_calendar(ctx, &room_name);
+    crate::schedule_game_tick(ctx, &room_name);
+    spacetimedb::log::info!("Player {} created room '{}'.", ctx.sender, room_name);
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn join_room(ctx: &ReducerContext, room_name: String, password: Option<String>) -> Result<(), String> {
+    let Some(player) = ctx.db.player().identity().find(ctx.sender) else {
+        return Err("Player not found".to_string());
+    };
+    let Some(room) = find_room(ctx, &room_name) else {
+        return Err("Room not found".to_string());
+    };
+    if player.room_name == room_name {
+        return Ok(());
+    }
+    if is_banned(ctx, &room_name, player.identity) {
+        return Err("You are banned from this room".to_string());
+    }
+    check_password(ctx, &room, password.as_deref())?;
+    check_capacity(&room, 1)?;
+
+    let spawn_position = spawn::select_spawn_point(ctx, &room_name, player.identity);
+    move_player_to_room(ctx, player, &room_name, spawn_position);
+    Ok(())
+}
+
+// Owner-only: cap the room's player count (0 = unlimited) and set or clear
+// its password
+#[spacetimedb::reducer]
+pub fn set_room_access(ctx: &ReducerContext, room_name: String, max_players: u32, password: Option<String>) -> Result<(), String> {
+    let mut room = require_room_owner(ctx, &room_name)?;
+    if room.is_hub && (max_players > 0 || password.is_some()) {
+        return Err("The hub room is always open".to_string());
+    }
+    ctx.db.room_password().room_name().delete(room_name.clone());
+    room.has_password = false;
+    if let Some(password) = password.filter(|p| !p.is_empty()) {
+        ctx.db.room_password().insert(RoomPassword { room_name: room_name.clone(), password });
+        room.has_password = true;
+    }
+    room.max_players = max_players;
+    ctx.db.room().room_name().update(room);
+    Ok(())
+}
+
+// Owner-only: ban a player from the room, sending them to the hub if they're in it
+#[spacetimedb::reducer]
+pub fn ban_from_room(ctx: &ReducerContext, room_name: String, identity: Identity) -> Result<(), String> {
+    let room = require_room_owner(ctx, &room_name)?;
+    if room.is_hub {
+        return Err("Players can't be banned from the hub".to_string());
+    }
+    if room.owner == Some(identity) {
+        return Err("The room owner can't be banned".to_string());
+    }
+    if !is_banned(ctx, &room_name, identity) {
+        ctx.db.room_ban().insert(RoomBan { ban_id: 0, room_name: room_name.clone(), identity, banned_at: ctx.timestamp });
+        notification::notify(ctx, identity, NotificationKind::RoomBan, format!("You were banned from room '{}'", room_name));
+    }
+    if let Some(player) = ctx.db.player().identity().find(identity).filter(|p| p.room_name == room_name) {
+        let spawn_position = spawn::select_spawn_point(ctx, DEFAULT_HUB_ROOM, identity);
+        move_player_to_room(ctx, player, DEFAULT_HUB_ROOM, spawn_position);
+    }
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn unban_from_room(ctx: &ReducerContext, room_name: String, identity: Identity) -> Result<(), String> {
+    require_room_owner(ctx, &room_name)?;
+    let bans: Vec<u64> = ctx.db.room_ban().identity().filter(identity)
+        .filter(|ban| ban.room_name == room_name)
+        .map(|ban| ban.ban_id)
+        .collect();
+    for ban_id in bans {
+        ctx.db.room_ban().ban_id().delete(ban_id);
+    }
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn configure_room(
+    ctx: &ReducerContext,
+    room_name: String,
+    pvp_enabled: bool,
+    friendly_fire: bool,
+) -> Result<(), String> {
+    let mut room = require_room_owner(ctx, &room_name)?;
+    room.pvp_enabled = pvp_enabled;
+    room.friendly_fire = friendly_fire;
+    ctx.db.room().room_name().update(room);
+    spacetimedb::log::info!(
+        "Room '{}' configured: pvp_enabled={}, friendly_fire={}",
+        room_name, pvp_enabled, friendly_fire
+    );
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn set_room_difficulty(ctx: &ReducerContext, room_name: String, difficulty: RoomDifficulty) -> Result<(), String> {
+    let mut room = require_room_owner(ctx, &room_name)?;
+    room.difficulty = difficulty;
+    ctx.db.room().room_name().update(room);
+    Ok(())
+}
+
+// Advance the room's clock by `delta_time` seconds (called from game_tick)
+pub fn advance_time_of_day(ctx: &ReducerContext, mut room: Room, delta_time: f64) -> Room {
+    let hours = (delta_time / room.day_length_secs.max(1) as f64 * 24.0) as f32;
+    room.time_of_day = (room.time_of_day + hours).rem_euclid(24.0);
+    ctx.db.room().room_name().update(room.clone());
+    room
+}
+
+// Night runs from DUSK_HOUR to DAWN_HOUR; spawners (spawner.rs) and street
+// lamps (light.rs) key off it
+pub fn is_night(room: &Room) -> bool {
+    room.time_of_day >= DUSK_HOUR || room.time_of_day < DAWN_HOUR
+}
+
+#[spacetimedb::reducer]
+pub fn set_day_length(ctx: &ReducerContext, room_name: String, day_length_secs: u32) -> Result<(), String> {
+    let mut room = require_room_owner(ctx, &room_name)?;
+    if !(MIN_DAY_LENGTH_SECS..=MAX_DAY_LENGTH_SECS).contains(&day_length_secs) {
+        return Err(format!("Days last {}-{} seconds", MIN_DAY_LENGTH_SECS, MAX_DAY_LENGTH_SECS));
+    }
+    room.day_length_secs = day_length_secs;
+    ctx.db.room().room_name().update(room);
+    Ok(())
+}
+
+// Tear down an empty room: its tile set, NPCs, props, placed content and the
+// room itself
+#[spacetimedb::reducer]
+pub fn close_room(ctx: &ReducerContext, room_name: String) -> Result<(), String> {
+    let room = require_room_owner(ctx, &room_name)?;
+    if room.is_hub {
+        return Err("Hub rooms can't be closed".to_string());
+    }
+    if room.current_player_count > 0 {
+        return Err("Only empty rooms can be closed".to_string());
+    }
+
+    clear_room_contents(ctx, &room_name);
+    ctx.db.room().room_name().delete(room_name.clone());
+    spacetimedb::log::info!("Player {} closed room '{}'.", ctx.sender, room_name);
+    Ok(())
+}
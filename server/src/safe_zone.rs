@@ -0,0 +1,124 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - safe_zone.rs
+ *
+ * Safe zones for social hubs: areas where players can't hurt each other,
+ * watched over by guard NPCs.
+ *
+ * Key components:
+ * - SafeZone table (public): A circular region of a room
+ * - SafeZoneGuard table (public): NPCs flagged as guards of a zone
+ * - zone_for_pvp: The safe zone an attempted player-vs-player hit touches
+ *   (attacker or victim inside). apply_damage blocks such hits entirely,
+ *   duels included, and calls guard_zone
+ * - guard_zone: Every guard of the zone gets SAFE_ZONE_GUARD_THREAT on the
+ *   attacker, so the guards' AI retaliates against them (combat.rs threat)
+ * - create_safe_zone / remove_safe_zone / set_zone_guard: Room-owner reducers
+ *
+ * Related files:
+ * - combat.rs: apply_damage
+ * - npc_ai.rs: Guards chase players they have threat on
+ * - faction.rs: FACTION_GUARDS, the natural faction for zone guards
+ */
+
+use spacetimedb::{Identity, ReducerContext, Table};
+
+use crate::combat;
+use crate::common::{Vector3, MAX_SAFE_ZONE_RADIUS, SAFE_ZONE_GUARD_THREAT};
+use crate::npc::npc as _;
+use crate::room;
+use crate::PlayerData;
+
+#[spacetimedb::table(name = safe_zone, public)]
+#[derive(Clone)]
+pub struct SafeZone {
+    #[primary_key]
+    #[auto_inc]
+    pub zone_id: u64,
+    #[index(btree)]
+    pub room_name: String,
+    pub name: String,
+    pub center: Vector3,
+    pub radius: f32,
+}
+
+#[spacetimedb::table(name = safe_zone_guard, public)]
+#[derive(Clone)]
+pub struct SafeZoneGuard {
+    #[primary_key]
+    pub npc_id: u64,
+    #[index(btree)]
+    pub zone_id: u64,
+}
+
+// The safe zone containing the attacker or the victim, if any
+pub fn zone_for_pvp(ctx: &ReducerContext, attacker: &PlayerData, victim: &PlayerData) -> Option<SafeZone> {
+    ctx.db.safe_zone().room_name().filter(&attacker.room_name).find(|zone| {
+        attacker.position.distance_xz(&zone.center) <= zone.radius
+            || victim.position.distance_xz(&zone.center) <= zone.radius
+    })
+}
+
+// Send the zone's guards after a player who started a fight in it
+pub fn guard_zone(ctx: &ReducerContext, zone: &SafeZone, offender: Identity) {
+    for guard in ctx.db.safe_zone_guard().zone_id().filter(zone.zone_id) {
+        combat::add_threat(ctx, guard.npc_id, offender, SAFE_ZONE_GUARD_THREAT);
+    }
+}
+
+pub fn clear_npc(ctx: &ReducerContext, npc_id: u64) {
+    ctx.db.safe_zone_guard().npc_id().delete(npc_id);
+}
+
+fn delete_zone(ctx: &ReducerContext, zone_id: u64) {
+    let guards: Vec<u64> = ctx.db.safe_zone_guard().zone_id().filter(zone_id).map(|g| g.npc_id).collect();
+    for npc_id in guards {
+        ctx.db.safe_zone_guard().npc_id().delete(npc_id);
+    }
+    ctx.db.safe_zone().zone_id().delete(zone_id);
+}
+
+pub fn clear_room(ctx: &ReducerContext, room_name: &str) {
+    let zones: Vec<u64> = ctx.db.safe_zone().room_name().filter(room_name).map(|z| z.zone_id).collect();
+    for zone_id in zones {
+        delete_zone(ctx, zone_id);
+    }
+}
+
+// --- Safe Zone Reducers ---
+
+#[spacetimedb::reducer]
+pub fn create_safe_zone(ctx: &ReducerContext, room_name: String, name: String, center: Vector3, radius: f32) -> Result<(), String> {
+    room::require_room_owner(ctx, &room_name)?;
+    if radius <= 0.0 || radius > MAX_SAFE_ZONE_RADIUS {
+        return Err(format!("Radius must be between 0 and {}", MAX_SAFE_ZONE_RADIUS));
+    }
+    ctx.db.safe_zone().insert(SafeZone { zone_id: 0, room_name, name, center, radius });
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn remove_safe_zone(ctx: &ReducerContext, zone_id: u64) -> Result<(), String> {
+    let Some(zone) = ctx.db.safe_zone().zone_id().find(zone_id) else {
+        return Err("Safe zone not found".to_string());
+    };
+    room::require_room_owner(ctx, &zone.room_name)?;
+    delete_zone(ctx, zone_id);
+    Ok(())
+}
+
+// Flag an NPC as a guard of a zone in its room (or unflag it with None)
+#[spacetimedb::reducer]
+pub fn set_zone_guard(ctx: &ReducerContext, npc_id: u64, zone_id: Option<u64>) -> Result<(), String> {
+    let Some(npc) = ctx.db.npc().npc_id().find(npc_id) else {
+        return Err("NPC not found".to_string());
+    };
+    room::require_room_owner(ctx, &npc.room_name)?;
+    ctx.db.safe_zone_guard().npc_id().delete(npc_id);
+    if let Some(zone_id) = zone_id {
+        if !ctx.db.safe_zone().zone_id().find(zone_id).is_some_and(|z| z.room_name == npc.room_name) {
+            return Err("Safe zone not found in the NPC's room".to_string());
+        }
+        ctx.db.safe_zone_guard().insert(SafeZoneGuard { npc_id, zone_id });
+    }
+    Ok(())
+}
@@ -0,0 +1,120 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - scoreboard.rs
+ *
+ * Running combat totals per player per room, so clients can render a
+ * scoreboard without re-deriving it from the (pruned) combat log.
+ *
+ * Key components:
+ * - RoomCombatStats table (public): Damage dealt / taken, healing done,
+ *   kills, deaths and assists for one player during their current stay in
+ *   one room. The row starts with the player's first combat event in the
+ *   room (session_started_at) and is removed when they leave the room or
+ *   disconnect, so every visit starts a fresh session
+ * - record_damage / record_kill / record_healing: Called by the combat
+ *   pipeline (apply_damage, record_kill, heal_target)
+ *
+ * Related files:
+ * - combat.rs: apply_damage and kill attribution
+ * - support.rs: heal_target
+ * - room.rs / lib.rs: clear_player on room change and disconnect
+ */
+
+use spacetimedb::{Identity, ReducerContext, Table, Timestamp};
+
+use crate::common::EntityId;
+
+#[spacetimedb::table(
+    name = room_combat_stats,
+    public,
+    index(name = room_player, btree(columns = [room_name, identity]))
+)]
+#[derive(Clone)]
+pub struct RoomCombatStats {
+    #[primary_key]
+    #[auto_inc]
+    pub stats_id: u64,
+    pub room_name: String,
+    #[index(btree)]
+    pub identity: Identity,
+    pub damage_dealt: i64,
+    pub damage_taken: i64,
+    pub healing_done: i64,
+    pub kills: u32,
+    pub deaths: u32,
+    pub assists: u32,
+    pub session_started_at: Timestamp,
+    pub updated_at: Timestamp,
+}
+
+// Apply `change` to the player's row in `room_name`, creating it if needed
+fn update_stats(ctx: &ReducerContext, room_name: &str, identity: Identity, change: impl FnOnce(&mut RoomCombatStats)) {
+    let existing = ctx.db.room_combat_stats().room_player().filter((room_name, identity)).next();
+    match existing {
+        Some(mut stats) => {
+            change(&mut stats);
+            stats.updated_at = ctx.timestamp;
+            ctx.db.room_combat_stats().stats_id().update(stats);
+        }
+        None => {
+            let mut stats = RoomCombatStats {
+                stats_id: 0,
+                room_name: room_name.to_string(),
+                identity,
+                damage_dealt: 0,
+                damage_taken: 0,
+                healing_done: 0,
+                kills: 0,
+                deaths: 0,
+                assists: 0,
+                session_started_at: ctx.timestamp,
+                updated_at: ctx.timestamp,
+            };
+            change(&mut stats);
+            ctx.db.room_combat_stats().insert(stats);
+        }
+    }
+}
+
+pub fn record_damage(ctx: &ReducerContext, room_name: &str, source: &EntityId, target: &EntityId, amount: i32) {
+    if amount <= 0 {
+        return;
+    }
+    if let EntityId::Player(identity) = source {
+        update_stats(ctx, room_name, *identity, |s| s.damage_dealt += amount as i64);
+    }
+    if let EntityId::Player(identity) = target {
+        update_stats(ctx, room_name, *identity, |s| s.damage_taken += amount as i64);
+    }
+}
+
+pub fn record_kill(ctx: &ReducerContext, room_name: &str, killer: &EntityId, victim: &EntityId, assists: &[Identity]) {
+    // Killing yourself (e.g. with your own fireball) only counts as a death
+    if let EntityId::Player(identity) = killer {
+        if killer != victim {
+            update_stats(ctx, room_name, *identity, |s| s.kills += 1);
+        }
+    }
+    if let EntityId::Player(identity) = victim {
+        update_stats(ctx, room_name, *identity, |s| s.deaths += 1);
+    }
+    for identity in assists {
+        update_stats(ctx, room_name, *identity, |s| s.assists += 1);
+    }
+}
+
+pub fn record_healing(ctx: &ReducerContext, room_name: &str, source: &EntityId, amount: i32) {
+    if amount <= 0 {
+        return;
+    }
+    if let EntityId::Player(identity) = source {
+        update_stats(ctx, room_name, *identity, |s| s.healing_done += amount as i64);
+    }
+}
+
+// End the player's session: drop their rows in every room
+pub fn clear_player(ctx: &ReducerContext, identity: Identity) {
+    let rows: Vec<u64> = ctx.db.room_combat_stats().identity().filter(identity).map(|s| s.stats_id).collect();
+    for stats_id in rows {
+        ctx.db.room_combat_stats().stats_id().delete(stats_id);
+    }
+}
@@ -0,0 +1,213 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - shop.rs
+ *
+ * Vendor NPCs that buy and sell items.
+ *
+ * Key components:
+ * - NpcShop table (public): Turns an NPC into a vendor. sell_back_fraction
+ *   is the share of an item's value (ItemDef.value) the vendor pays when
+ *   buying it back
+ * - ShopListing table (public): What a vendor sells, at what price and with
+ *   how much stock (None = unlimited)
+ * - create_shop / set_shop_listing / remove_shop_listing: Room-owner
+ *   authoring reducers. Only items with a value can be listed, and never
+ *   below it, so a room owner can't hand out items or mint gold
+ * - buy_item / sell_item: Check the trading feature flag, proximity to the
+ *   vendor (npc::require_in_range), the listing and its stock. Buying
+ *   spends price * quantity from the wallet (wallet.rs) and fails if the
+ *   items don't fit in the inventory. Selling pays sell_back_fraction of
+ *   the item's value and restocks limited listings
+ *
+ * Related files:
+ * - npc.rs: require_in_range
+ * - item.rs: Item definitions
+ * - inventory.rs / wallet.rs: What changes hands
+ * - config.rs: trading_enabled
+ */
+
+use spacetimedb::{ReducerContext, Table};
+
+use crate::common::MAX_SHOP_LISTINGS;
+use crate::config;
+use crate::inventory;
+use crate::item::{self, item_def as _};
+use crate::npc::{self, npc as _};
+use crate::player as _;
+use crate::room;
+use crate::wallet::{self, WalletReason};
+
+#[spacetimedb::table(name = npc_shop, public)]
+#[derive(Clone)]
+pub struct NpcShop {
+    #[primary_key]
+    #[auto_inc]
+    pub shop_id: u64,
+    #[unique]
+    pub npc_id: u64,
+    pub name: String,
+    pub sell_back_fraction: f32,
+}
+
+#[spacetimedb::table(name = shop_listing, public)]
+#[derive(Clone)]
+pub struct ShopListing {
+    #[primary_key]
+    #[auto_inc]
+    pub listing_id: u64,
+    #[index(btree)]
+    pub shop_id: u64,
+    pub item_def_id: u64,
+    pub price: u64,
+    pub stock: Option<u32>, // None = unlimited
+}
+
+// The shop and its owning room, if the caller owns the room
+fn require_shop_owner(ctx: &ReducerContext, shop_id: u64) -> Result<NpcShop, String> {
+    let Some(shop) = ctx.db.npc_shop().shop_id().find(shop_id) else {
+        return Err("Shop not found".to_string());
+    };
+    let Some(vendor) = ctx.db.npc().npc_id().find(shop.npc_id) else {
+        return Err("The vendor is gone".to_string());
+    };
+    room::require_room_owner(ctx, &vendor.room_name)?;
+    Ok(shop)
+}
+
+// Trading must be enabled and the caller next to the shop's vendor
+fn require_open_shop(ctx: &ReducerContext, shop_id: u64) -> Result<NpcShop, String> {
+    if !config::current(ctx).trading_enabled {
+        return Err("Trading is disabled".to_string());
+    }
+    let Some(shop) = ctx.db.npc_shop().shop_id().find(shop_id) else {
+        return Err("Shop not found".to_string());
+    };
+    npc::require_in_range(ctx, shop.npc_id)?;
+    Ok(shop)
+}
+
+pub fn clear_npc(ctx: &ReducerContext, npc_id: u64) {
+    let Some(shop) = ctx.db.npc_shop().npc_id().find(npc_id) else {
+        return;
+    };
+    let listings: Vec<u64> = ctx.db.shop_listing().shop_id().filter(shop.shop_id).map(|l| l.listing_id).collect();
+    for listing_id in listings {
+        ctx.db.shop_listing().listing_id().delete(listing_id);
+    }
+    ctx.db.npc_shop().shop_id().delete(shop.shop_id);
+}
+
+// --- Shop Reducers ---
+
+#[spacetimedb::reducer]
+pub fn create_shop(ctx: &ReducerContext, npc_id: u64, name: String, sell_back_fraction: f32) -> Result<(), String> {
+    let Some(vendor) = ctx.db.npc().npc_id().find(npc_id) else {
+        return Err("NPC not found".to_string());
+    };
+    room::require_room_owner(ctx, &vendor.room_name)?;
+    if ctx.db.npc_shop().npc_id().find(npc_id).is_some() {
+        return Err("That NPC already runs a shop".to_string());
+    }
+    if !(0.0..=1.0).contains(&sell_back_fraction) {
+        return Err("sell_back_fraction must be between 0 and 1".to_string());
+    }
+    ctx.db.npc_shop().insert(NpcShop { shop_id: 0, npc_id, name, sell_back_fraction });
+    Ok(())
+}
+
+// Add a listing, or update the shop's existing listing for the item
+#[spacetimedb::reducer]
+pub fn set_shop_listing(ctx: &ReducerContext, shop_id: u64, item_def_id: u64, price: u64, stock: Option<u32>) -> Result<(), String> {
+    require_shop_owner(ctx, shop_id)?;
+    let Some(def) = ctx.db.item_def().item_def_id().find(item_def_id) else {
+        return Err("Item not found".to_string());
+    };
+    if def.value == 0 {
+        return Err("Vendors don't deal in that item".to_string());
+    }
+    if price < def.value {
+        return Err(format!("{} sells for at least {} gold", def.name, def.value));
+    }
+    let existing = ctx.db.shop_listing().shop_id().filter(shop_id).find(|l| l.item_def_id == item_def_id);
+    match existing {
+        Some(mut listing) => {
+            listing.price = price;
+            listing.stock = stock;
+            ctx.db.shop_listing().listing_id().update(listing);
+        }
+        None => {
+            if ctx.db.shop_listing().shop_id().filter(shop_id).count() >= MAX_SHOP_LISTINGS {
+                return Err(format!("A shop can list at most {} items", MAX_SHOP_LISTINGS));
+            }
+            ctx.db.shop_listing().insert(ShopListing { listing_id: 0, shop_id, item_def_id, price, stock });
+        }
+    }
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn remove_shop_listing(ctx: &ReducerContext, listing_id: u64) -> Result<(), String> {
+    let Some(listing) = ctx.db.shop_listing().listing_id().find(listing_id) else {
+        return Err("Listing not found".to_string());
+    };
+    require_shop_owner(ctx, listing.shop_id)?;
+    ctx.db.shop_listing().listing_id().delete(listing_id);
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn buy_item(ctx: &ReducerContext, listing_id: u64, quantity: u32) -> Result<(), String> {
+    let Some(mut listing) = ctx.db.shop_listing().listing_id().find(listing_id) else {
+        return Err("Listing not found".to_string());
+    };
+    require_open_shop(ctx, listing.shop_id)?;
+    let Some(player) = ctx.db.player().identity().find(ctx.sender) else {
+        return Err("Player not found".to_string());
+    };
+    if quantity == 0 {
+        return Err("Quantity must be at least 1".to_string());
+    }
+    if listing.stock.is_some_and(|stock| stock < quantity) {
+        return Err("Not enough stock".to_string());
+    }
+    let Some(def) = ctx.db.item_def().item_def_id().find(listing.item_def_id) else {
+        return Err("Item not found".to_string());
+    };
+    // The item's value may have been raised since it was listed
+    let Some(cost) = listing.price.max(def.value).checked_mul(quantity as u64) else {
+        return Err("Not enough gold".to_string());
+    };
+    wallet::spend(ctx, ctx.sender, cost, WalletReason::VendorBuy)?;
+    item::grant_item(ctx, &player, &def, quantity, None)?;
+    if let Some(stock) = listing.stock {
+        listing.stock = Some(stock - quantity);
+        ctx.db.shop_listing().listing_id().update(listing);
+    }
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn sell_item(ctx: &ReducerContext, shop_id: u64, item_def_id: u64, quantity: u32) -> Result<(), String> {
+    let shop = require_open_shop(ctx, shop_id)?;
+    let Some(player) = ctx.db.player().identity().find(ctx.sender) else {
+        return Err("Player not found".to_string());
+    };
+    if quantity == 0 {
+        return Err("Quantity must be at least 1".to_string());
+    }
+    let Some(mut listing) = ctx.db.shop_listing().shop_id().filter(shop_id).find(|l| l.item_def_id == item_def_id) else {
+        return Err("This vendor doesn't deal in that item".to_string());
+    };
+    let Some(def) = ctx.db.item_def().item_def_id().find(item_def_id) else {
+        return Err("Item not found".to_string());
+    };
+    inventory::remove_item(ctx, &player, item_def_id, quantity)?;
+    // Paid from the item's value, not the listed price, which the room owner
+    // picks. Float to int casts saturate, so a huge sale can't wrap around
+    let payout = (def.value as f64 * shop.sell_back_fraction as f64 * quantity as f64) as u64;
+    wallet::earn(ctx, ctx.sender, payout, WalletReason::VendorSell)?;
+    if let Some(stock) = listing.stock {
+        listing.stock = Some(stock.saturating_add(quantity));
+        ctx.db.shop_listing().listing_id().update(listing);
+    }
+    Ok(())
+}
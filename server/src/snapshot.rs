@@ -0,0 +1,313 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - snapshot.rs
+ *
+ * Named checkpoints of a room's built world that its owner can restore.
+ *
+ * Key components:
+ * - WorldSnapshot table (public): One checkpoint per (room, name), with the
+ *   room's spawn points and their teams. Saving under an existing name overwrites it
+ * - SnapshotTile / SnapshotProp / SnapshotNpc / SnapshotSpawner tables
+ *   (private): The saved terrain (without closed doors' stand-in tiles),
+ *   physics props, placed NPCs and spawners. NPCs owned by a spawner aren't
+ *   saved, since the spawner brings them back. Restored blocks become map
+ *   terrain, no longer owned by whoever built them (protection.rs)
+ * - save_world_snapshot / load_world_snapshot / delete_world_snapshot:
+ *   Room-owner (or admin) reducers. Loading builds a new tile instance from
+ *   the snapshot and moves the room onto it (map.rs move_room_to_map, which
+ *   also relocates players), then replaces the room's props, NPCs and
+ *   spawners. Hub rooms stay on the streamed default map and can't be
+ *   snapshotted
+ *
+ * Related files:
+ * - mapgen.rs: instance_tiles
+ * - prop.rs / npc.rs / spawner.rs / npc_persistent.rs: The restored content
+ * - room.rs: close_room deletes a room's snapshots
+ */
+
+use spacetimedb::{Identity, ReducerContext, Table, Timestamp};
+
+use crate::combat;
+use crate::common::{TileHazard, Vector3, MAX_SNAPSHOTS_PER_ROOM, MAX_SNAPSHOT_NAME_LEN};
+use crate::door;
+use crate::game_tile;
+use crate::loot;
+use crate::map;
+use crate::mapgen;
+use crate::npc::{self, npc as _, NpcKind};
+use crate::npc_persistent;
+use crate::prop::{self, prop as _, Prop, PropKind};
+use crate::room::{self, Room};
+use crate::spawn::spawn_point as _;
+use crate::spawner::{self, spawned_npc as _, spawner as _, Spawner};
+
+#[spacetimedb::table(name = world_snapshot, public)]
+#[derive(Clone)]
+pub struct WorldSnapshot {
+    #[primary_key]
+    #[auto_inc]
+    pub snapshot_id: u64,
+    #[index(btree)]
+    pub room_name: String,
+    pub name: String,
+    pub spawn_points: Vec<Vector3>,
+    pub spawn_teams: Vec<u8>, // Team of each spawn point, in the same order
+    pub created_by: Identity,
+    pub created_at: Timestamp,
+}
+
+#[spacetimedb::table(name = snapshot_tile)]
+pub struct SnapshotTile {
+    #[primary_key]
+    #[auto_inc]
+    pub row_id: u64,
+    #[index(btree)]
+    pub snapshot_id: u64,
+    pub position: Vector3,
+    pub size: Vector3,
+    pub hazard: Option<TileHazard>,
+}
+
+#[spacetimedb::table(name = snapshot_prop)]
+pub struct SnapshotProp {
+    #[primary_key]
+    #[auto_inc]
+    pub row_id: u64,
+    #[index(btree)]
+    pub snapshot_id: u64,
+    pub kind: PropKind,
+    pub position: Vector3,
+    pub placed_by: Identity,
+}
+
+#[spacetimedb::table(name = snapshot_npc)]
+pub struct SnapshotNpc {
+    #[primary_key]
+    #[auto_inc]
+    pub row_id: u64,
+    #[index(btree)]
+    pub snapshot_id: u64,
+    pub name: String,
+    pub kind: NpcKind,
+    pub home_position: Vector3,
+    pub personality: String,
+    pub faction: String,
+    pub patrol_points: Vec<Vector3>,
+    pub patrol_route_id: Option<u64>,
+    pub leash_radius: Option<f32>,
+    pub persistent: bool,
+}
+
+#[spacetimedb::table(name = snapshot_spawner)]
+pub struct SnapshotSpawner {
+    #[primary_key]
+    #[auto_inc]
+    pub row_id: u64,
+    #[index(btree)]
+    pub snapshot_id: u64,
+    pub position: Vector3,
+    pub kind: NpcKind,
+    pub personality: String,
+    pub max_alive: u32,
+    pub respawn_delay_secs: u64,
+}
+
+fn find_snapshot(ctx: &ReducerContext, room_name: &str, name: &str) -> Option<WorldSnapshot> {
+    ctx.db.world_snapshot().room_name().filter(room_name).find(|s| s.name == name)
+}
+
+fn require_snapshot_room(ctx: &ReducerContext, room_name: &str) -> Result<Room, String> {
+    let room = room::require_room_owner(ctx, room_name)?;
+    if room.is_hub {
+        return Err("Hub rooms stay on the default map".to_string());
+    }
+    Ok(room)
+}
+
+fn delete_snapshot(ctx: &ReducerContext, snapshot_id: u64) {
+    let tiles: Vec<u64> = ctx.db.snapshot_tile().snapshot_id().filter(snapshot_id).map(|r| r.row_id).collect();
+    for row_id in tiles {
+        ctx.db.snapshot_tile().row_id().delete(row_id);
+    }
+    let props: Vec<u64> = ctx.db.snapshot_prop().snapshot_id().filter(snapshot_id).map(|r| r.row_id).collect();
+    for row_id in props {
+        ctx.db.snapshot_prop().row_id().delete(row_id);
+    }
+    let npcs: Vec<u64> = ctx.db.snapshot_npc().snapshot_id().filter(snapshot_id).map(|r| r.row_id).collect();
+    for row_id in npcs {
+        ctx.db.snapshot_npc().row_id().delete(row_id);
+    }
+    let spawners: Vec<u64> = ctx.db.snapshot_spawner().snapshot_id().filter(snapshot_id).map(|r| r.row_id).collect();
+    for row_id in spawners {
+        ctx.db.snapshot_spawner().row_id().delete(row_id);
+    }
+    ctx.db.world_snapshot().snapshot_id().delete(snapshot_id);
+}
+
+pub fn clear_room(ctx: &ReducerContext, room_name: &str) {
+    let snapshots: Vec<u64> = ctx.db.world_snapshot().room_name().filter(room_name).map(|s| s.snapshot_id).collect();
+    for snapshot_id in snapshots {
+        delete_snapshot(ctx, snapshot_id);
+    }
+}
+
+// Remove the room's NPCs, spawners and props before a snapshot replaces them
+fn clear_content(ctx: &ReducerContext, room_name: &str) {
+    let npcs: Vec<u64> = ctx.db.npc().room_name().filter(room_name).map(|n| n.npc_id).collect();
+    for npc_id in npcs {
+        ctx.db.npc().npc_id().delete(npc_id);
+        combat::forget_npc(ctx, npc_id);
+        loot::clear_npc(ctx, npc_id);
+    }
+    npc_persistent::clear_room(ctx, room_name);
+    spawner::clear_room(ctx, room_name);
+    prop::clear_room(ctx, room_name);
+}
+
+// --- Snapshot Reducers ---
+
+#[spacetimedb::reducer]
+pub fn save_world_snapshot(ctx: &ReducerContext, room_name: String, name: String) -> Result<(), String> {
+    let room = require_snapshot_room(ctx, &room_name)?;
+    let name = name.trim().to_string();
+    if name.is_empty() || name.len() > MAX_SNAPSHOT_NAME_LEN {
+        return Err(format!("Snapshot names must be 1-{} characters", MAX_SNAPSHOT_NAME_LEN));
+    }
+    match find_snapshot(ctx, &room_name, &name) {
+        Some(existing) => delete_snapshot(ctx, existing.snapshot_id),
+        None if ctx.db.world_snapshot().room_name().filter(&room_name).count() >= MAX_SNAPSHOTS_PER_ROOM => {
+            return Err(format!("A room can keep at most {} snapshots", MAX_SNAPSHOTS_PER_ROOM));
+        }
+        None => {}
+    }
+
+    let snapshot = ctx.db.world_snapshot().insert(WorldSnapshot {
+        snapshot_id: 0,
+        room_name: room_name.clone(),
+        name,
+        spawn_points: ctx.db.spawn_point().room_name().filter(&room_name).map(|p| p.position).collect(),
+        spawn_teams: ctx.db.spawn_point().room_name().filter(&room_name).map(|p| p.team).collect(),
+        created_by: ctx.sender,
+        created_at: ctx.timestamp,
+    });
+    let snapshot_id = snapshot.snapshot_id;
+
+    let door_tiles = door::door_tile_ids(ctx, &room_name);
+    for tile in ctx.db.game_tile().map_cell().filter(room.map_id) {
+        if door_tiles.contains(&tile.tile_id) {
+            continue;
+        }
+        ctx.db.snapshot_tile().insert(SnapshotTile {
+            row_id: 0,
+            snapshot_id,
+            position: tile.position,
+            size: tile.size,
+            hazard: tile.hazard,
+        });
+    }
+    for prop in ctx.db.prop().room_name().filter(&room_name) {
+        ctx.db.snapshot_prop().insert(SnapshotProp {
+            row_id: 0,
+            snapshot_id,
+            kind: prop.kind,
+            position: prop.position,
+            placed_by: prop.placed_by,
+        });
+    }
+    for npc in ctx.db.npc().room_name().filter(&room_name) {
+        if ctx.db.spawned_npc().npc_id().find(npc.npc_id).is_some() {
+            continue;
+        }
+        ctx.db.snapshot_npc().insert(SnapshotNpc {
+            row_id: 0,
+            snapshot_id,
+            persistent: npc_persistent::is_persistent(ctx, npc.npc_id),
+            name: npc.name,
+            kind: npc.kind,
+            home_position: npc.home_position,
+            personality: npc.personality,
+            faction: npc.faction,
+            patrol_points: npc.patrol_points,
+            patrol_route_id: npc.patrol_route_id,
+            leash_radius: npc.leash_radius,
+        });
+    }
+    for spawner in ctx.db.spawner().room_name().filter(&room_name) {
+        ctx.db.snapshot_spawner().insert(SnapshotSpawner {
+            row_id: 0,
+            snapshot_id,
+            position: spawner.position,
+            kind: spawner.kind,
+            personality: spawner.personality,
+            max_alive: spawner.max_alive,
+            respawn_delay_secs: spawner.respawn_delay_secs,
+        });
+    }
+    spacetimedb::log::info!("Saved world snapshot '{}' of room '{}'.", snapshot.name, room_name);
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn load_world_snapshot(ctx: &ReducerContext, room_name: String, name: String) -> Result<(), String> {
+    require_snapshot_room(ctx, &room_name)?;
+    let Some(snapshot) = find_snapshot(ctx, &room_name, name.trim()) else {
+        return Err("Snapshot not found".to_string());
+    };
+    let snapshot_id = snapshot.snapshot_id;
+
+    let tiles = ctx.db.snapshot_tile().snapshot_id().filter(snapshot_id)
+        .map(|t| (t.position, t.size, t.hazard))
+        .collect();
+    let map_id = mapgen::instance_tiles(ctx, &room_name, tiles, None);
+    let spawn_points = snapshot.spawn_points.into_iter().zip(snapshot.spawn_teams).collect();
+    map::move_room_to_map(ctx, &room_name, map_id, Some(spawn_points))?;
+
+    clear_content(ctx, &room_name);
+    for saved in ctx.db.snapshot_prop().snapshot_id().filter(snapshot_id) {
+        ctx.db.prop().insert(Prop {
+            prop_id: 0,
+            room_name: room_name.clone(),
+            kind: saved.kind,
+            size: saved.kind.size(),
+            position: saved.position,
+            velocity: Vector3 { x: 0.0, y: 0.0, z: 0.0 },
+            at_rest: false,
+            placed_by: saved.placed_by,
+        });
+    }
+    for saved in ctx.db.snapshot_npc().snapshot_id().filter(snapshot_id) {
+        let mut npc = npc::spawn_npc_at(ctx, &room_name, saved.kind, saved.home_position, saved.personality);
+        npc.name = saved.name;
+        npc.faction = saved.faction;
+        npc.patrol_points = saved.patrol_points;
+        npc.patrol_route_id = saved.patrol_route_id;
+        npc.leash_radius = saved.leash_radius;
+        if saved.persistent {
+            npc_persistent::persist(ctx, &npc);
+        }
+        ctx.db.npc().npc_id().update(npc);
+    }
+    for saved in ctx.db.snapshot_spawner().snapshot_id().filter(snapshot_id) {
+        ctx.db.spawner().insert(Spawner {
+            spawner_id: 0,
+            room_name: room_name.clone(),
+            position: saved.position,
+            kind: saved.kind,
+            personality: saved.personality,
+            max_alive: saved.max_alive,
+            respawn_delay_secs: saved.respawn_delay_secs,
+            next_spawn_at: None,
+        });
+    }
+    spacetimedb::log::info!("Loaded world snapshot '{}' into room '{}'.", snapshot.name, room_name);
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn delete_world_snapshot(ctx: &ReducerContext, room_name: String, name: String) -> Result<(), String> {
+    room::require_room_owner(ctx, &room_name)?;
+    let Some(snapshot) = find_snapshot(ctx, &room_name, name.trim()) else {
+        return Err("Snapshot not found".to_string());
+    };
+    delete_snapshot(ctx, snapshot.snapshot_id);
+    Ok(())
+}
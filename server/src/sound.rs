@@ -0,0 +1,114 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - sound.rs
+ *
+ * Positional audio cues, kept on the server so every client hears the same
+ * door creak from the same place. (NPC hearing is separate, see noise.rs.)
+ *
+ * Key components:
+ * - SoundEmitter table (public): A sound id (an asset name the client
+ *   knows) played at a position, audible within `radius`. Looping emitters
+ *   (a waterfall, a humming generator) stay until removed; one-shot
+ *   emitters expire after SOUND_ONE_SHOT_TTL_SECS, so clients play each one
+ *   when its row is inserted
+ * - play_sound: One-shot cue for gameplay events (doors opening and
+ *   closing, chests opening and unlocking)
+ * - place_sound_emitter / remove_sound_emitter: Room-owner reducers
+ * - prune_sound_emitters: Called from game_tick, drops expired one-shots
+ *
+ * Related files:
+ * - door.rs / container.rs: Trigger cues
+ * - room.rs: close_room clears a room's emitters
+ */
+
+use spacetimedb::{ReducerContext, Table, Timestamp};
+use std::time::Duration;
+
+use crate::common::{Vector3, MAX_SOUND_EMITTERS_PER_ROOM, MAX_SOUND_ID_LEN, MAX_SOUND_RADIUS, SOUND_ONE_SHOT_TTL_SECS};
+use crate::room;
+
+#[spacetimedb::table(name = sound_emitter, public)]
+#[derive(Clone)]
+pub struct SoundEmitter {
+    #[primary_key]
+    #[auto_inc]
+    pub emitter_id: u64,
+    #[index(btree)]
+    pub room_name: String,
+    pub sound_id: String,
+    pub position: Vector3,
+    pub radius: f32,
+    pub looping: bool,
+    pub started_at: Timestamp,
+}
+
+fn insert_emitter(ctx: &ReducerContext, room_name: &str, sound_id: &str, position: Vector3, radius: f32, looping: bool) {
+    ctx.db.sound_emitter().insert(SoundEmitter {
+        emitter_id: 0,
+        room_name: room_name.to_string(),
+        sound_id: sound_id.to_string(),
+        position,
+        radius,
+        looping,
+        started_at: ctx.timestamp,
+    });
+}
+
+pub fn play_sound(ctx: &ReducerContext, room_name: &str, sound_id: &str, position: Vector3, radius: f32) {
+    insert_emitter(ctx, room_name, sound_id, position, radius, false);
+}
+
+pub fn prune_sound_emitters(ctx: &ReducerContext, room_name: &str) {
+    let Some(cutoff) = ctx.timestamp.checked_sub_duration(Duration::from_secs(SOUND_ONE_SHOT_TTL_SECS)) else {
+        return;
+    };
+    let expired: Vec<u64> = ctx.db.sound_emitter().room_name().filter(room_name)
+        .filter(|emitter| !emitter.looping && emitter.started_at < cutoff)
+        .map(|emitter| emitter.emitter_id)
+        .collect();
+    for emitter_id in expired {
+        ctx.db.sound_emitter().emitter_id().delete(emitter_id);
+    }
+}
+
+pub fn clear_room(ctx: &ReducerContext, room_name: &str) {
+    let emitters: Vec<u64> = ctx.db.sound_emitter().room_name().filter(room_name).map(|e| e.emitter_id).collect();
+    for emitter_id in emitters {
+        ctx.db.sound_emitter().emitter_id().delete(emitter_id);
+    }
+}
+
+// --- Sound Reducers ---
+
+#[spacetimedb::reducer]
+pub fn place_sound_emitter(
+    ctx: &ReducerContext,
+    room_name: String,
+    sound_id: String,
+    position: Vector3,
+    radius: f32,
+    looping: bool,
+) -> Result<(), String> {
+    room::require_room_owner(ctx, &room_name)?;
+    let sound_id = sound_id.trim().to_string();
+    if sound_id.is_empty() || sound_id.len() > MAX_SOUND_ID_LEN {
+        return Err(format!("Sound ids must be 1-{} characters", MAX_SOUND_ID_LEN));
+    }
+    if radius <= 0.0 || radius > MAX_SOUND_RADIUS {
+        return Err(format!("Sound radius must be between 0 and {}", MAX_SOUND_RADIUS));
+    }
+    if looping && ctx.db.sound_emitter().room_name().filter(&room_name).filter(|e| e.looping).count() >= MAX_SOUND_EMITTERS_PER_ROOM {
+        return Err(format!("A room can have at most {} looping sounds", MAX_SOUND_EMITTERS_PER_ROOM));
+    }
+    insert_emitter(ctx, &room_name, &sound_id, position, radius, looping);
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn remove_sound_emitter(ctx: &ReducerContext, emitter_id: u64) -> Result<(), String> {
+    let Some(emitter) = ctx.db.sound_emitter().emitter_id().find(emitter_id) else {
+        return Err("Sound emitter not found".to_string());
+    };
+    room::require_room_owner(ctx, &emitter.room_name)?;
+    ctx.db.sound_emitter().emitter_id().delete(emitter_id);
+    Ok(())
+}
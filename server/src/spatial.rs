@@ -0,0 +1,338 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - spatial.rs
+ *
+ * Broad-phase spatial index shared by every system that asks "what is near
+ * this point?" (combat, projectiles, NPC perception).
+ *
+ * Key components:
+ *
+ * 1. Grid:
+ *    - The ground plane is split into GRID_CELL_SIZE squares; grid_cell_of
+ *      packs a cell's (x, z) coordinates into one indexable i64 key
+ *    - grid_cells_in_radius / grid_cells_in_aabb / grid_cells_along_ray
+ *      enumerate the cells a query shape can touch
+ *
+ * 2. Membership Rows:
+ *    - SpatialCell table: One row per tracked entity (player, NPC,
+ *      projectile) holding its room and current cell, indexed by
+ *      (room_name, cell)
+ *    - track: Called whenever an entity moves or spawns. Only writes when the
+ *      entity actually changed cell or room, so per-input movement stays cheap
+ *    - untrack: Called when an entity leaves the world
+ *    - World items (pickups, drops) register through the same API once they
+ *      exist
+ *
+ * 3. Queries:
+ *    - query_aabb / query_radius / query_ray: Collect candidates from the
+ *      touched cells, resolve their live positions and run the exact test.
+ *      Dead players are not part of the world and never returned.
+ *    - has_line_of_sight: Eye-to-eye segment test against world geometry
+ *      (the room map's game tiles, looked up through the (map_id, grid_cell)
+ *      index)
+ *    - entities_in_cells: Raw broad-phase for callers that batch their own
+ *      narrow phase (e.g. projectiles sweeping along grid_cells_along_ray)
+ *
+ * Related files:
+ *    - combat.rs: Melee and AoE target selection
+ *    - projectile.rs: Swept collision of projectiles
+ *    - perception.rs: Players an NPC could see
+ *    - hitscan.rs: Raycasts for hitscan weapons
+ *    - support.rs: Heal range and line-of-sight checks
+ *    - player_logic.rs / npc.rs: Keep membership in sync when entities move
+ */
+
+use spacetimedb::{Identity, ReducerContext, SpacetimeType, Table};
+use std::collections::HashSet;
+
+use crate::common::{EntityId, Vector3, EYE_HEIGHT, GRID_CELL_SIZE};
+use crate::destructible::destructible as _;
+use crate::game_tile as _;
+use crate::npc::npc as _;
+use crate::player as _;
+use crate::projectile::projectile as _;
+
+// Anything that can be placed in the spatial index
+#[derive(SpacetimeType, Clone, Debug, PartialEq)]
+pub enum SpatialEntity {
+    Player(Identity),
+    Npc(u64),
+    Projectile(u64),
+    Destructible(u64),
+}
+
+impl SpatialEntity {
+    // The combat identity of this entity, if it can take damage
+    pub fn combatant(&self) -> Option<EntityId> {
+        match self {
+            SpatialEntity::Player(identity) => Some(EntityId::Player(*identity)),
+            SpatialEntity::Npc(npc_id) => Some(EntityId::Npc(*npc_id)),
+            SpatialEntity::Projectile(_) => None,
+            SpatialEntity::Destructible(destructible_id) => Some(EntityId::Destructible(*destructible_id)),
+        }
+    }
+
+    // Unique key of the membership row
+    fn key(&self) -> String {
+        match self {
+            SpatialEntity::Player(identity) => format!("player:{}", identity),
+            SpatialEntity::Npc(npc_id) => format!("npc:{}", npc_id),
+            SpatialEntity::Projectile(projectile_id) => format!("projectile:{}", projectile_id),
+            SpatialEntity::Destructible(destructible_id) => format!("destructible:{}", destructible_id),
+        }
+    }
+}
+
+#[spacetimedb::table(name = spatial_cell, index(name = room_cell, btree(columns = [room_name, cell])))]
+#[derive(Clone)]
+pub struct SpatialCell {
+    #[primary_key]
+    pub entity_key: String,
+    pub entity: SpatialEntity,
+    pub room_name: String,
+    pub cell: i64,
+}
+
+// A query result: the entity, its current position and its distance (or, for
+// rays, the distance along the ray) from the query origin
+pub struct SpatialHit {
+    pub entity: SpatialEntity,
+    pub position: Vector3,
+    pub distance: f32,
+}
+
+// --- Grid ---
+
+fn cell_key(cx: i32, cz: i32) -> i64 {
+    ((cx as i64) << 32) | (cz as u32 as i64)
+}
+
+fn cell_coord(value: f32) -> i32 {
+    (value / GRID_CELL_SIZE).floor() as i32
+}
+
+// Packs the (x, z) cell coordinates of a position into one indexable key
+pub fn grid_cell_of(position: &Vector3) -> i64 {
+    cell_key(cell_coord(position.x), cell_coord(position.z))
+}
+
+// Every cell key overlapping the axis-aligned box between `min` and `max`
+pub fn grid_cells_in_aabb(min: &Vector3, max: &Vector3) -> Vec<i64> {
+    let (min_x, max_x) = (cell_coord(min.x), cell_coord(max.x));
+    let (min_z, max_z) = (cell_coord(min.z), cell_coord(max.z));
+    (min_x..=max_x)
+        .flat_map(|cx| (min_z..=max_z).map(move |cz| cell_key(cx, cz)))
+        .collect()
+}
+
+// Every cell key overlapping the square that bounds a circle
+pub fn grid_cells_in_radius(center: &Vector3, radius: f32) -> Vec<i64> {
+    grid_cells_in_aabb(
+        &Vector3 { x: center.x - radius, y: center.y, z: center.z - radius },
+        &Vector3 { x: center.x + radius, y: center.y, z: center.z + radius },
+    )
+}
+
+// Cells within `radius` of the segment `start` -> `end`. Walks the segment in
+// half-cell steps, so long rays touch a thin band of cells instead of their
+// whole bounding box.
+pub fn grid_cells_along_ray(start: &Vector3, end: &Vector3, radius: f32) -> Vec<i64> {
+    let length = start.distance_xz(end);
+    let steps = (length / (GRID_CELL_SIZE * 0.5)).ceil().max(1.0) as usize;
+    let mut seen = HashSet::new();
+    let mut cells = Vec::new();
+    for step in 0..=steps {
+        let point = lerp(start, end, step as f32 / steps as f32);
+        for cell in grid_cells_in_radius(&point, radius + GRID_CELL_SIZE * 0.25) {
+            if seen.insert(cell) {
+                cells.push(cell);
+            }
+        }
+    }
+    cells
+}
+
+pub fn lerp(p0: &Vector3, p1: &Vector3, t: f32) -> Vector3 {
+    Vector3 { x: p0.x + (p1.x - p0.x) * t, y: p0.y + (p1.y - p0.y) * t, z: p0.z + (p1.z - p0.z) * t }
+}
+
+// Earliest parameter t in [0, 1] at which the segment p0->p1 enters the
+// circle around `center` (ground plane only)
+pub fn segment_hit(p0: &Vector3, p1: &Vector3, center: &Vector3, radius: f32) -> Option<f32> {
+    let (dx, dz) = (p1.x - p0.x, p1.z - p0.z);
+    let (fx, fz) = (p0.x - center.x, p0.z - center.z);
+    let c = fx * fx + fz * fz - radius * radius;
+    if c <= 0.0 {
+        return Some(0.0);
+    }
+    let a = dx * dx + dz * dz;
+    if a < 1e-6 {
+        return None;
+    }
+    let b = 2.0 * (fx * dx + fz * dz);
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return None;
+    }
+    let t = (-b - discriminant.sqrt()) / (2.0 * a);
+    (0.0..=1.0).contains(&t).then_some(t)
+}
+
+// --- Membership ---
+
+// Record that `entity` is at `position` in `room_name`
+pub fn track(ctx: &ReducerContext, entity: SpatialEntity, room_name: &str, position: &Vector3) {
+    let key = entity.key();
+    let cell = grid_cell_of(position);
+    match ctx.db.spatial_cell().entity_key().find(&key) {
+        Some(row) if row.cell == cell && row.room_name == room_name => {}
+        Some(mut row) => {
+            row.cell = cell;
+            row.room_name = room_name.to_string();
+            ctx.db.spatial_cell().entity_key().update(row);
+        }
+        None => {
+            ctx.db.spatial_cell().insert(SpatialCell {
+                entity_key: key,
+                entity,
+                room_name: room_name.to_string(),
+                cell,
+            });
+        }
+    }
+}
+
+pub fn untrack(ctx: &ReducerContext, entity: &SpatialEntity) {
+    ctx.db.spatial_cell().entity_key().delete(entity.key());
+}
+
+// Current position of a tracked entity; None if it's gone or not in the world
+fn position_of(ctx: &ReducerContext, entity: &SpatialEntity) -> Option<Vector3> {
+    match entity {
+        SpatialEntity::Player(identity) => ctx.db.player().identity().find(*identity)
+            .filter(|p| !p.is_dead)
+            .map(|p| p.position),
+        SpatialEntity::Npc(npc_id) => ctx.db.npc().npc_id().find(*npc_id).map(|n| n.position),
+        SpatialEntity::Projectile(projectile_id) => ctx.db.projectile().projectile_id().find(*projectile_id)
+            .filter(|p| p.active)
+            .map(|p| p.position),
+        SpatialEntity::Destructible(destructible_id) => ctx.db.destructible().destructible_id().find(*destructible_id)
+            .map(|d| d.position),
+    }
+}
+
+// Every live entity registered in the given cells of a room, with its position
+pub fn entities_in_cells(ctx: &ReducerContext, room_name: &str, cells: &[i64]) -> Vec<(SpatialEntity, Vector3)> {
+    let mut found = Vec::new();
+    for &cell in cells {
+        for row in ctx.db.spatial_cell().room_cell().filter((room_name, cell)) {
+            if let Some(position) = position_of(ctx, &row.entity) {
+                found.push((row.entity, position));
+            }
+        }
+    }
+    found
+}
+
+// --- Queries ---
+
+// Entities inside the axis-aligned box between `min` and `max` (ground plane)
+pub fn query_aabb(ctx: &ReducerContext, room_name: &str, min: &Vector3, max: &Vector3) -> Vec<SpatialHit> {
+    let center = lerp(min, max, 0.5);
+    entities_in_cells(ctx, room_name, &grid_cells_in_aabb(min, max))
+        .into_iter()
+        .filter(|(_, p)| p.x >= min.x && p.x <= max.x && p.z >= min.z && p.z <= max.z)
+        .map(|(entity, position)| {
+            let distance = center.distance_xz(&position);
+            SpatialHit { entity, position, distance }
+        })
+        .collect()
+}
+
+// Entities within `radius` of `center` on the ground plane
+pub fn query_radius(ctx: &ReducerContext, room_name: &str, center: &Vector3, radius: f32) -> Vec<SpatialHit> {
+    let min = Vector3 { x: center.x - radius, y: center.y, z: center.z - radius };
+    let max = Vector3 { x: center.x + radius, y: center.y, z: center.z + radius };
+    query_aabb(ctx, room_name, &min, &max)
+        .into_iter()
+        .filter_map(|hit| {
+            let distance = center.distance_xz(&hit.position);
+            (distance <= radius).then_some(SpatialHit { distance, ..hit })
+        })
+        .collect()
+}
+
+// Entities whose `hit_radius` circle the ray from `origin` along `direction`
+// crosses within `max_distance`, nearest first. `distance` is measured along
+// the ray to the entry point.
+pub fn query_ray(
+    ctx: &ReducerContext,
+    room_name: &str,
+    origin: &Vector3,
+    direction: &Vector3,
+    max_distance: f32,
+    hit_radius: f32,
+) -> Vec<SpatialHit> {
+    let length = (direction.x * direction.x + direction.z * direction.z).sqrt();
+    if length < 1e-6 || max_distance <= 0.0 {
+        return Vec::new();
+    }
+    let end = Vector3 {
+        x: origin.x + direction.x / length * max_distance,
+        y: origin.y,
+        z: origin.z + direction.z / length * max_distance,
+    };
+    let mut hits: Vec<SpatialHit> = entities_in_cells(ctx, room_name, &grid_cells_along_ray(origin, &end, hit_radius))
+        .into_iter()
+        .filter_map(|(entity, position)| {
+            let t = segment_hit(origin, &end, &position, hit_radius)?;
+            Some(SpatialHit { entity, position, distance: t * max_distance })
+        })
+        .collect();
+    hits.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+    hits
+}
+
+// --- Line of Sight ---
+
+// Whether the segment p0->p1 passes through the box centered on `center`
+// with extents `size` (slab test, full 3D)
+fn segment_intersects_box(p0: &Vector3, p1: &Vector3, center: &Vector3, size: &Vector3) -> bool {
+    let axes = [
+        (p0.x, p1.x, center.x, size.x),
+        (p0.y, p1.y, center.y, size.y),
+        (p0.z, p1.z, center.z, size.z),
+    ];
+    let (mut t_min, mut t_max) = (0.0_f32, 1.0_f32);
+    for (start, end, mid, extent) in axes {
+        let (lo, hi) = (mid - extent * 0.5, mid + extent * 0.5);
+        let delta = end - start;
+        if delta.abs() < 1e-6 {
+            if start < lo || start > hi {
+                return false;
+            }
+            continue;
+        }
+        let (mut t0, mut t1) = ((lo - start) / delta, (hi - start) / delta);
+        if t0 > t1 {
+            std::mem::swap(&mut t0, &mut t1);
+        }
+        t_min = t_min.max(t0);
+        t_max = t_max.min(t1);
+        if t_min > t_max {
+            return false;
+        }
+    }
+    true
+}
+
+// Whether an entity at `from` can see one at `to`: the line between their
+// eyes must not pass through any world geometry (the tiles of `map_id`)
+pub fn has_line_of_sight(ctx: &ReducerContext, map_id: u32, from: &Vector3, to: &Vector3) -> bool {
+    let eye_from = Vector3 { x: from.x, y: from.y + EYE_HEIGHT, z: from.z };
+    let eye_to = Vector3 { x: to.x, y: to.y + EYE_HEIGHT, z: to.z };
+    // Tiles are indexed by the cell of their center and are at most one cell
+    // wide, so widening the ray by half a cell catches every tile it touches
+    !grid_cells_along_ray(&eye_from, &eye_to, GRID_CELL_SIZE * 0.5).into_iter()
+        .flat_map(|cell| ctx.db.game_tile().map_cell().filter((map_id, cell)))
+        .any(|tile| segment_intersects_box(&eye_from, &eye_to, &tile.position, &tile.size))
+}
@@ -0,0 +1,237 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - spawn.rs
+ *
+ * Spawn points and the respawn flow for dead players.
+ *
+ * Key components:
+ * - SpawnPoint table: Candidate spawn positions, per room, optionally
+ *   tagged with a team (select_spawn_point)
+ * - RespawnSchedule table: One-shot schedule rows driving respawn_player
+ * - schedule_respawn: Called by the damage pipeline when a player dies
+ * - respawn_player: Scheduled reducer that runs revive_player as a job
+ *   (failures are dead-lettered, see jobs.rs)
+ * - reseed_spawn_points / set_spawn_points: Rebuild a room's spawn points
+ *   for a new map (map.rs move_room_to_map)
+ * - add_spawn_point / remove_spawn_point: Room-owner reducers
+ * - select_spawn_point: Where players appear when they register, respawn,
+ *   change rooms or the room changes maps
+ * - revive_player: Revives the player at the spawn point farthest from any
+ *   enemy in their room (or their last checkpoint if the environment killed
+ *   them), with RESPAWN_INVULNERABLE_MS of spawn protection
+ *
+ * Related files:
+ * - combat.rs: Schedules a respawn when a player's health reaches zero
+ * - room.rs: Seeds spawn points when a room is created
+ */
+
+use spacetimedb::{Identity, ReducerContext, ScheduleAt, Table};
+use std::time::Duration;
+
+use crate::checkpoint;
+use crate::common::{EntityId, Vector3, MAX_SPAWN_POINTS_PER_ROOM, MAX_TEAM, NO_TEAM, RESPAWN_DELAY_SECS, RESPAWN_INVULNERABLE_MS};
+use crate::npc::npc as _;
+use crate::game_tile as _;
+use crate::player as _;
+use crate::hitscan;
+use crate::jobs::{self, JobPayload};
+use crate::loadout;
+use crate::map;
+use crate::player_logic;
+use crate::room;
+
+#[spacetimedb::table(name = spawn_point, public)]
+#[derive(Clone)]
+pub struct SpawnPoint {
+    #[primary_key]
+    #[auto_inc]
+    pub spawn_id: u64,
+    #[index(btree)]
+    pub room_name: String,
+    pub position: Vector3,
+    pub team: u8, // NO_TEAM = anyone may spawn here
+}
+
+#[spacetimedb::table(name = respawn_schedule, scheduled(respawn_player))]
+pub struct RespawnSchedule {
+    #[primary_key]
+    #[auto_inc]
+    pub scheduled_id: u64,
+    pub scheduled_at: ScheduleAt,
+    pub identity: Identity,
+}
+
+// Default spawn layout: a ring around the room's origin
+const SPAWN_RING_POINTS: usize = 8;
+
+pub fn seed_spawn_points(ctx: &ReducerContext, room_name: &str) {
+    let radius = 15.0_f32;
+    for i in 0..SPAWN_RING_POINTS {
+        let angle = i as f32 / SPAWN_RING_POINTS as f32 * std::f32::consts::TAU;
+        ctx.db.spawn_point().insert(SpawnPoint {
+            spawn_id: 0,
+            room_name: room_name.to_string(),
+            position: Vector3 { x: angle.cos() * radius, y: 1.0, z: angle.sin() * radius },
+            team: NO_TEAM,
+        });
+    }
+}
+
+pub fn schedule_respawn(ctx: &ReducerContext, identity: Identity) {
+    let respawn_at = ctx.timestamp + Duration::from_secs(RESPAWN_DELAY_SECS);
+    ctx.db.respawn_schedule().insert(RespawnSchedule {
+        scheduled_id: 0,
+        scheduled_at: ScheduleAt::Time(respawn_at),
+        identity,
+    });
+}
+
+// Replace a room's spawn points with the default ring, keeping only points
+// that stand on the map. Falls back to the map's first tiles if none do.
+pub fn reseed_spawn_points(ctx: &ReducerContext, room_name: &str, map_id: u32) {
+    let old: Vec<u64> = ctx.db.spawn_point().room_name().filter(room_name).map(|sp| sp.spawn_id).collect();
+    for spawn_id in old {
+        ctx.db.spawn_point().spawn_id().delete(spawn_id);
+    }
+    seed_spawn_points(ctx, room_name);
+    let invalid: Vec<u64> = ctx.db.spawn_point().room_name().filter(room_name)
+        .filter(|sp| !map::is_on_map(ctx, map_id, &sp.position))
+        .map(|sp| sp.spawn_id)
+        .collect();
+    let all_invalid = invalid.len() == SPAWN_RING_POINTS;
+    for spawn_id in invalid {
+        ctx.db.spawn_point().spawn_id().delete(spawn_id);
+    }
+    if all_invalid {
+        for tile in ctx.db.game_tile().map_cell().filter(map_id).take(SPAWN_RING_POINTS) {
+            ctx.db.spawn_point().insert(SpawnPoint {
+                spawn_id: 0,
+                room_name: room_name.to_string(),
+                position: Vector3 { x: tile.position.x, y: tile.position.y + 1.0, z: tile.position.z },
+                team: NO_TEAM,
+            });
+        }
+    }
+}
+
+// Replace a room's spawn points with `points` (generated maps, snapshots)
+pub fn set_spawn_points(ctx: &ReducerContext, room_name: &str, points: Vec<(Vector3, u8)>) {
+    let old: Vec<u64> = ctx.db.spawn_point().room_name().filter(room_name).map(|sp| sp.spawn_id).collect();
+    for spawn_id in old {
+        ctx.db.spawn_point().spawn_id().delete(spawn_id);
+    }
+    for (position, team) in points {
+        ctx.db.spawn_point().insert(SpawnPoint { spawn_id: 0, room_name: room_name.to_string(), position, team });
+    }
+}
+
+// Pick the spawn point whose nearest enemy is as far away as possible.
+// Players on a team spawn at their team's points when the room has any,
+// everyone else (and teams without points) at the untagged ones. Teammates
+// don't count as enemies. Falls back to the room origin if the room has no
+// spawn points.
+pub fn select_spawn_point(ctx: &ReducerContext, room_name: &str, for_player: Identity) -> Vector3 {
+    let team = ctx.db.player().identity().find(for_player).map(|p| p.team).unwrap_or(NO_TEAM);
+    let enemy_positions: Vec<Vector3> = ctx.db.player().room_name().filter(room_name)
+        .filter(|p| p.identity != for_player && !p.is_dead && (team == NO_TEAM || p.team != team))
+        .map(|p| p.position)
+        .chain(ctx.db.npc().room_name().filter(room_name)
+            .filter(|n| n.is_hostile())
+            .map(|n| n.position))
+        .collect();
+
+    let points: Vec<SpawnPoint> = ctx.db.spawn_point().room_name().filter(room_name).collect();
+    let team_points: Vec<&SpawnPoint> = points.iter().filter(|sp| sp.team == team).collect();
+    let candidates = if team_points.is_empty() {
+        points.iter().filter(|sp| sp.team == NO_TEAM).collect()
+    } else {
+        team_points
+    };
+    let candidates = if candidates.is_empty() { points.iter().collect() } else { candidates };
+
+    candidates.into_iter()
+        .map(|sp| {
+            let nearest_enemy = enemy_positions.iter()
+                .map(|e| sp.position.distance_xz(e))
+                .fold(f32::INFINITY, f32::min);
+            (sp.position.clone(), nearest_enemy)
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(position, _)| position)
+        .unwrap_or(Vector3 { x: 0.0, y: 1.0, z: 0.0 })
+}
+
+#[spacetimedb::reducer]
+pub fn respawn_player(ctx: &ReducerContext, schedule: RespawnSchedule) -> Result<(), String> {
+    if ctx.sender != ctx.identity() {
+        return Err("respawn_player may only be invoked by the scheduler".to_string());
+    }
+    jobs::run_job(ctx, JobPayload::Respawn(schedule.identity));
+    Ok(())
+}
+
+// Revive a dead player at a spawn point in their room. Fails without writing
+// anything if their room is gone, so the job can be retried.
+pub fn revive_player(ctx: &ReducerContext, identity: Identity) -> Result<(), String> {
+    let Some(mut player) = ctx.db.player().identity().find(identity) else {
+        spacetimedb::log::info!("Respawn skipped: player {} is no longer active.", identity);
+        return Ok(());
+    };
+    if !player.is_dead {
+        return Ok(());
+    }
+    if room::find_room(ctx, &player.room_name).is_none() {
+        return Err(format!("Room '{}' no longer exists", player.room_name));
+    }
+
+    // Hazard deaths send the player back to their last checkpoint
+    let checkpoint_position = match player.last_damager {
+        Some(EntityId::Environment) => checkpoint::respawn_position(ctx, &player),
+        _ => None,
+    };
+    let spawn_position = checkpoint_position
+        .unwrap_or_else(|| select_spawn_point(ctx, &player.room_name, player.identity));
+    player_logic::set_player_position(ctx, &mut player, spawn_position);
+    hitscan::clear_history(ctx, player.identity);
+    player.health = player.max_health;
+    player.mana = player.max_mana;
+    player.is_dead = false;
+    player.invulnerable_until = ctx.timestamp + Duration::from_millis(RESPAWN_INVULNERABLE_MS);
+    player.last_damager = None;
+    player.last_damaged_at = None;
+    player.current_animation = "idle".to_string();
+    spacetimedb::log::info!("Player {} respawned in room '{}'.", player.identity, player.room_name);
+    loadout::apply_loadout(ctx, player.identity, &player.room_name);
+    ctx.db.player().identity().update(player);
+    Ok(())
+}
+
+// --- Spawn Point Reducers ---
+
+#[spacetimedb::reducer]
+pub fn add_spawn_point(ctx: &ReducerContext, room_name: String, position: Vector3, team: u8) -> Result<(), String> {
+    let room = room::require_room_owner(ctx, &room_name)?;
+    if team > MAX_TEAM {
+        return Err(format!("Team must be between {} and {}", NO_TEAM, MAX_TEAM));
+    }
+    if ctx.db.spawn_point().room_name().filter(&room_name).count() >= MAX_SPAWN_POINTS_PER_ROOM {
+        return Err(format!("A room can have at most {} spawn points", MAX_SPAWN_POINTS_PER_ROOM));
+    }
+    if !map::is_on_map(ctx, room.map_id, &position) {
+        return Err("Spawn points must be on the map".to_string());
+    }
+    ctx.db.spawn_point().insert(SpawnPoint { spawn_id: 0, room_name, position, team });
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn remove_spawn_point(ctx: &ReducerContext, spawn_id: u64) -> Result<(), String> {
+    let Some(spawn_point) = ctx.db.spawn_point().spawn_id().find(spawn_id) else {
+        return Err("Spawn point not found".to_string());
+    };
+    room::require_room_owner(ctx, &spawn_point.room_name)?;
+    if ctx.db.spawn_point().room_name().filter(&spawn_point.room_name).count() <= 1 {
+        return Err("A room needs at least one spawn point".to_string());
+    }
+    ctx.db.spawn_point().spawn_id().delete(spawn_id);
+    Ok(())
+}
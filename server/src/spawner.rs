@@ -0,0 +1,182 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - spawner.rs
+ *
+ * NPC spawners that keep a configured population alive in a room.
+ *
+ * Key components:
+ * - Spawner table (public): Where to spawn, which NpcKind / AI personality,
+ *   how many may be alive at once and how long to wait before replacing one
+ * - SpawnedNpc table (private): Which NPCs a spawner owns, so it can count
+ *   its living NPCs without touching the npc table's schema
+ * - tick_spawners: Called from game_tick. Rooms without players are skipped
+ *   entirely. When a spawner is below max_alive it waits respawn_delay_secs,
+ *   (scaled by NIGHT_SPAWN_DELAY_FACTOR at night), then spawns one NPC
+ *   (scattered within SPAWNER_SCATTER_RADIUS)
+ * - members: A spawner's living NPCs, which steer as a group (steering.rs)
+ * - place_spawner / remove_spawner: Room-owner reducers. A new spawner fills
+ *   its population right away; removing one leaves its NPCs in place
+ *
+ * Related files:
+ * - npc.rs: spawn_npc_at
+ * - npc_ai.rs: Personalities
+ * - lib.rs: game_tick
+ */
+
+use spacetimedb::rand::Rng;
+use spacetimedb::{ReducerContext, Table, Timestamp};
+use std::time::Duration;
+
+use crate::common::{Vector3, MAX_SPAWNER_ALIVE, NIGHT_SPAWN_DELAY_FACTOR, SPAWNER_SCATTER_RADIUS};
+use crate::npc::{self, npc as _, Npc, NpcKind};
+use crate::npc_ai;
+use crate::room::{self, Room};
+
+#[spacetimedb::table(name = spawner, public)]
+#[derive(Clone)]
+pub struct Spawner {
+    #[primary_key]
+    #[auto_inc]
+    pub spawner_id: u64,
+    #[index(btree)]
+    pub room_name: String,
+    pub position: Vector3,
+    pub kind: NpcKind,
+    pub personality: String, // Empty = use the room difficulty's default
+    pub max_alive: u32,
+    pub respawn_delay_secs: u64,
+    pub next_spawn_at: Option<Timestamp>, // Set while waiting to replace a dead NPC
+}
+
+#[spacetimedb::table(name = spawned_npc)]
+pub struct SpawnedNpc {
+    #[primary_key]
+    pub npc_id: u64,
+    #[index(btree)]
+    pub spawner_id: u64,
+}
+
+// Living NPCs of a spawner; links to NPCs that have died are dropped
+fn alive_count(ctx: &ReducerContext, spawner_id: u64) -> u32 {
+    let links: Vec<u64> = ctx.db.spawned_npc().spawner_id().filter(spawner_id).map(|s| s.npc_id).collect();
+    let mut alive = 0;
+    for npc_id in links {
+        if ctx.db.npc().npc_id().find(npc_id).is_some() {
+            alive += 1;
+        } else {
+            ctx.db.spawned_npc().npc_id().delete(npc_id);
+        }
+    }
+    alive
+}
+
+// The spawner's living NPCs (its flock, see steering.rs)
+pub fn members(ctx: &ReducerContext, spawner_id: u64) -> Vec<Npc> {
+    ctx.db.spawned_npc().spawner_id().filter(spawner_id)
+        .filter_map(|link| ctx.db.npc().npc_id().find(link.npc_id))
+        .collect()
+}
+
+fn spawn_one(ctx: &ReducerContext, spawner: &Spawner) {
+    let mut rng = ctx.rng();
+    let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+    let distance = rng.gen_range(0.0..=SPAWNER_SCATTER_RADIUS);
+    let position = Vector3 {
+        x: spawner.position.x + angle.cos() * distance,
+        y: spawner.position.y,
+        z: spawner.position.z + angle.sin() * distance,
+    };
+    let npc = npc::spawn_npc_at(ctx, &spawner.room_name, spawner.kind, position, spawner.personality.clone());
+    ctx.db.spawned_npc().insert(SpawnedNpc { npc_id: npc.npc_id, spawner_id: spawner.spawner_id });
+}
+
+pub fn tick_spawners(ctx: &ReducerContext, room: &Room) {
+    if room.current_player_count == 0 {
+        return;
+    }
+    let spawners: Vec<Spawner> = ctx.db.spawner().room_name().filter(&room.room_name).collect();
+    for mut spawner in spawners {
+        if alive_count(ctx, spawner.spawner_id) >= spawner.max_alive {
+            if spawner.next_spawn_at.is_some() {
+                spawner.next_spawn_at = None;
+                ctx.db.spawner().spawner_id().update(spawner);
+            }
+            continue;
+        }
+        match spawner.next_spawn_at {
+            None => {
+                let mut delay = Duration::from_secs(spawner.respawn_delay_secs);
+                if room::is_night(room) {
+                    delay = delay.mul_f32(NIGHT_SPAWN_DELAY_FACTOR);
+                }
+                spawner.next_spawn_at = Some(ctx.timestamp + delay);
+            }
+            Some(at) if at <= ctx.timestamp => {
+                spawn_one(ctx, &spawner);
+                spawner.next_spawn_at = None;
+            }
+            Some(_) => continue,
+        }
+        ctx.db.spawner().spawner_id().update(spawner);
+    }
+}
+
+// --- Spawner Reducers ---
+
+#[spacetimedb::reducer]
+pub fn place_spawner(
+    ctx: &ReducerContext,
+    room_name: String,
+    position: Vector3,
+    kind: NpcKind,
+    personality: String,
+    max_alive: u32,
+    respawn_delay_secs: u64,
+) -> Result<(), String> {
+    room::require_room_owner(ctx, &room_name)?;
+    if !personality.is_empty() && !npc_ai::personality_exists(ctx, &personality) {
+        return Err(format!("Unknown AI personality '{}'", personality));
+    }
+    if max_alive == 0 || max_alive > MAX_SPAWNER_ALIVE {
+        return Err(format!("max_alive must be between 1 and {}", MAX_SPAWNER_ALIVE));
+    }
+    let spawner = ctx.db.spawner().insert(Spawner {
+        spawner_id: 0,
+        room_name,
+        position,
+        kind,
+        personality,
+        max_alive,
+        respawn_delay_secs,
+        next_spawn_at: None,
+    });
+    for _ in 0..spawner.max_alive {
+        spawn_one(ctx, &spawner);
+    }
+    spacetimedb::log::info!("Placed spawner {} in room '{}'.", spawner.spawner_id, spawner.room_name);
+    Ok(())
+}
+
+pub fn clear_room(ctx: &ReducerContext, room_name: &str) {
+    let spawners: Vec<u64> = ctx.db.spawner().room_name().filter(room_name).map(|s| s.spawner_id).collect();
+    for spawner_id in spawners {
+        let links: Vec<u64> = ctx.db.spawned_npc().spawner_id().filter(spawner_id).map(|s| s.npc_id).collect();
+        for npc_id in links {
+            ctx.db.spawned_npc().npc_id().delete(npc_id);
+        }
+        ctx.db.spawner().spawner_id().delete(spawner_id);
+    }
+}
+
+#[spacetimedb::reducer]
+pub fn remove_spawner(ctx: &ReducerContext, spawner_id: u64) -> Result<(), String> {
+    let Some(spawner) = ctx.db.spawner().spawner_id().find(spawner_id) else {
+        return Err("Spawner not found".to_string());
+    };
+    room::require_room_owner(ctx, &spawner.room_name)?;
+    let links: Vec<u64> = ctx.db.spawned_npc().spawner_id().filter(spawner_id).map(|s| s.npc_id).collect();
+    for npc_id in links {
+        ctx.db.spawned_npc().npc_id().delete(npc_id);
+    }
+    ctx.db.spawner().spawner_id().delete(spawner_id);
+    Ok(())
+}
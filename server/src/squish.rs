@@ -0,0 +1,151 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - squish.rs
+ *
+ * Season power squish: an admin migration that rescales persistent power
+ * stats by configured factors, all inside one reducer call (one transaction),
+ * so a new season can start from a flatter curve.
+ *
+ * Key components:
+ * - SquishFactors: Multipliers per stat group. Item stats cover weapon damage;
+ *   character stats cover health and mana (online and logged-out players).
+ *   There are no levels or currency yet; their factors belong here once
+ *   those systems exist
+ * - SquishReport table: One row per rescaled table and run, with the row count
+ *   and stat totals before/after. Written in both modes
+ * - power_squish: Admin reducer. With dry_run it only writes the report, so
+ *   operators can check the numbers before running it for real
+ *
+ * Related files:
+ * - item.rs: ItemDef stats
+ * - lib.rs: PlayerData / LoggedOutPlayerData
+ * - admin.rs: require_admin
+ */
+
+use spacetimedb::{ReducerContext, SpacetimeType, Table, Timestamp};
+
+use crate::admin;
+use crate::item::item_def;
+use crate::{logged_out_player, player};
+
+#[derive(SpacetimeType, Clone, Debug)]
+pub struct SquishFactors {
+    pub item_damage: f32,
+    pub character_stats: f32,
+}
+
+#[spacetimedb::table(name = squish_report)]
+#[derive(Clone)]
+pub struct SquishReport {
+    #[primary_key]
+    #[auto_inc]
+    pub report_id: u64,
+    pub season: String,
+    pub dry_run: bool,
+    pub table_name: String,
+    pub rows_affected: u32,
+    pub total_before: i64,
+    pub total_after: i64,
+    pub run_at: Timestamp,
+}
+
+// Scale a stat, keeping anything that was positive at 1 or more
+fn scale(value: i32, factor: f32) -> i32 {
+    if value <= 0 {
+        return value;
+    }
+    ((value as f32 * factor).round() as i32).max(1)
+}
+
+// Running totals for one table's report row
+#[derive(Default)]
+struct Tally {
+    rows: u32,
+    before: i64,
+    after: i64,
+}
+
+impl Tally {
+    fn add(&mut self, before: i32, after: i32) {
+        self.before += before as i64;
+        self.after += after as i64;
+    }
+}
+
+fn write_report(ctx: &ReducerContext, season: &str, dry_run: bool, table_name: &str, tally: Tally) {
+    spacetimedb::log::info!(
+        "[SQUISH] {} {}: {} rows, {} -> {}{}",
+        season, table_name, tally.rows, tally.before, tally.after,
+        if dry_run { " (dry run)" } else { "" }
+    );
+    ctx.db.squish_report().insert(SquishReport {
+        report_id: 0,
+        season: season.to_string(),
+        dry_run,
+        table_name: table_name.to_string(),
+        rows_affected: tally.rows,
+        total_before: tally.before,
+        total_after: tally.after,
+        run_at: ctx.timestamp,
+    });
+}
+
+#[spacetimedb::reducer]
+pub fn power_squish(ctx: &ReducerContext, season: String, factors: SquishFactors, dry_run: bool) -> Result<(), String> {
+    admin::require_admin(ctx)?;
+    if season.is_empty() {
+        return Err("Season label must not be empty".to_string());
+    }
+    for factor in [factors.item_damage, factors.character_stats] {
+        if !factor.is_finite() || factor <= 0.0 {
+            return Err("Squish factors must be positive".to_string());
+        }
+    }
+
+    // Item definitions
+    let mut items = Tally::default();
+    for mut def in ctx.db.item_def().iter().collect::<Vec<_>>() {
+        let damage = scale(def.damage, factors.item_damage);
+        items.rows += 1;
+        items.add(def.damage, damage);
+        if !dry_run {
+            def.damage = damage;
+            ctx.db.item_def().item_def_id().update(def);
+        }
+    }
+    write_report(ctx, &season, dry_run, "item_def", items);
+
+    // Online players: health and mana scale with their maximums
+    let factor = factors.character_stats;
+    let mut players = Tally::default();
+    for mut p in ctx.db.player().iter().collect::<Vec<_>>() {
+        let (max_health, max_mana) = (scale(p.max_health, factor), scale(p.max_mana, factor));
+        players.rows += 1;
+        players.add(p.max_health + p.max_mana, max_health + max_mana);
+        if !dry_run {
+            p.health = scale(p.health, factor).min(max_health);
+            p.mana = scale(p.mana, factor).min(max_mana);
+            p.max_health = max_health;
+            p.max_mana = max_mana;
+            ctx.db.player().identity().update(p);
+        }
+    }
+    write_report(ctx, &season, dry_run, "player", players);
+
+    // Logged-out players, so they come back on the new curve
+    let mut logged_out = Tally::default();
+    for mut p in ctx.db.logged_out_player().iter().collect::<Vec<_>>() {
+        let (max_health, max_mana) = (scale(p.max_health, factor), scale(p.max_mana, factor));
+        logged_out.rows += 1;
+        logged_out.add(p.max_health + p.max_mana, max_health + max_mana);
+        if !dry_run {
+            p.health = scale(p.health, factor).min(max_health);
+            p.mana = scale(p.mana, factor).min(max_mana);
+            p.max_health = max_health;
+            p.max_mana = max_mana;
+            ctx.db.logged_out_player().identity().update(p);
+        }
+    }
+    write_report(ctx, &season, dry_run, "logged_out_player", logged_out);
+
+    Ok(())
+}
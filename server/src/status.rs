@@ -0,0 +1,127 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - status.rs
+ *
+ * Timed status effects on players (heal- and mana-over-time, roots, burns,
+ * slows, haste), ticked by each room's game_tick.
+ *
+ * Key components:
+ * - StatusKind: What an effect does each tick
+ * - StatusEffect table (public): One row per active effect, with its source,
+ *   per-second magnitude and expiry, so clients can show buff icons
+ * - apply_status: Adds an effect, refreshing the duration if the same source
+ *   already applied the same kind
+ * - tick_status_effects: Applies every active effect of the players in a room
+ *   and removes expired ones
+ * - has_status: Whether a player is currently affected (e.g. rooted)
+ * - clear_player: Drops all effects (on death and disconnect)
+ *
+ * Related files:
+ * - support.rs: Paladin heal applies HealOverTime
+ * - trap.rs: Snares apply Rooted
+ * - hazard.rs: Hazardous tiles apply Burning and Slowed
+ * - consumable.rs: Potions and food apply HealOverTime, ManaOverTime and Hasted
+ * - lib.rs: game_tick calls tick_status_effects
+ */
+
+use spacetimedb::{Identity, ReducerContext, SpacetimeType, Table, Timestamp};
+use std::time::Duration;
+
+use crate::combat;
+use crate::common::{CombatEventKind, EntityId};
+use crate::player as _;
+use crate::support;
+
+#[derive(SpacetimeType, Clone, Copy, Debug, PartialEq)]
+pub enum StatusKind {
+    HealOverTime,
+    Rooted, // Can't move (player_logic.rs ignores movement input)
+    Burning, // Damage over time from hazardous terrain
+    Slowed, // Movement scaled by SLOW_MOVE_MULTIPLIER
+    ManaOverTime,
+    Hasted, // Movement scaled by HASTE_MOVE_MULTIPLIER
+}
+
+#[spacetimedb::table(name = status_effect, public)]
+#[derive(Clone)]
+pub struct StatusEffect {
+    #[primary_key]
+    #[auto_inc]
+    pub effect_id: u64,
+    #[index(btree)]
+    pub identity: Identity,
+    pub kind: StatusKind,
+    pub source: EntityId,
+    pub amount_per_sec: i32,
+    pub applied_at: Timestamp,
+    pub expires_at: Timestamp,
+}
+
+pub fn apply_status(
+    ctx: &ReducerContext,
+    identity: Identity,
+    kind: StatusKind,
+    source: EntityId,
+    amount_per_sec: i32,
+    duration: Duration,
+) {
+    let expires_at = ctx.timestamp + duration;
+    let existing = ctx.db.status_effect().identity().filter(identity)
+        .find(|e| e.kind == kind && e.source == source);
+    match existing {
+        Some(mut effect) => {
+            effect.amount_per_sec = amount_per_sec;
+            effect.applied_at = ctx.timestamp;
+            effect.expires_at = expires_at;
+            ctx.db.status_effect().effect_id().update(effect);
+        }
+        None => {
+            ctx.db.status_effect().insert(StatusEffect {
+                effect_id: 0,
+                identity,
+                kind,
+                source,
+                amount_per_sec,
+                applied_at: ctx.timestamp,
+                expires_at,
+            });
+        }
+    }
+}
+
+// Apply one tick of every effect on the players in `room_name`
+pub fn tick_status_effects(ctx: &ReducerContext, room_name: &str, delta_time: f64) {
+    let effects: Vec<StatusEffect> = ctx.db.player().room_name().filter(room_name)
+        .flat_map(|p| ctx.db.status_effect().identity().filter(p.identity))
+        .collect();
+    for effect in effects {
+        let amount = (effect.amount_per_sec as f64 * delta_time).round() as i32;
+        match effect.kind {
+            StatusKind::HealOverTime => {
+                support::heal_target(ctx, &effect.source, effect.identity, amount);
+            }
+            StatusKind::Burning => {
+                combat::apply_damage(ctx, &effect.source, &EntityId::Player(effect.identity), amount, CombatEventKind::Hazard);
+            }
+            StatusKind::ManaOverTime => {
+                support::restore_mana(ctx, effect.identity, amount);
+            }
+            StatusKind::Rooted | StatusKind::Slowed | StatusKind::Hasted => {}
+        }
+        if effect.expires_at <= ctx.timestamp {
+            ctx.db.status_effect().effect_id().delete(effect.effect_id);
+        }
+    }
+}
+
+// Whether the player currently has an unexpired effect of this kind
+pub fn has_status(ctx: &ReducerContext, identity: Identity, kind: StatusKind) -> bool {
+    ctx.db.status_effect().identity().filter(identity)
+        .any(|e| e.kind == kind && e.expires_at > ctx.timestamp)
+}
+
+pub fn clear_player(ctx: &ReducerContext, identity: Identity) {
+    let effects: Vec<u64> = ctx.db.status_effect().identity().filter(identity).map(|e| e.effect_id).collect();
+    for effect_id in effects {
+        ctx.db.status_effect().effect_id().delete(effect_id);
+    }
+}
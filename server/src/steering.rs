@@ -0,0 +1,126 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - steering.rs
+ *
+ * Local avoidance between moving entities. After the AI tick has moved NPCs
+ * towards their goals, a separation pass pushes overlapping entities apart so
+ * groups converging on the same player don't stack inside each other.
+ *
+ * Key components:
+ * - apply_cohesion: Boids-style cohesion for NPCs of the same spawner.
+ *   Moving members (patrolling, investigating, chasing, returning) that
+ *   strayed further than COHESION_RADIUS from their group's center are
+ *   pulled back towards it, so groups travel together. Bounded by
+ *   FLOCK_MAX_NPCS_PER_TICK per room
+ * - apply_separation: Post-pass over one room. Every mobile NPC is pushed
+ *   away from nearby NPCs and players, weighted by overlap and capped per tick.
+ *   Players are obstacles only; their movement stays input-driven.
+ *
+ * Related files:
+ * - npc_ai.rs: Runs this pass after moving a room's NPCs
+ * - common.rs: SEPARATION_RADIUS / SEPARATION_MAX_STEP / COHESION_*
+ * - spawner.rs: Spawner groups
+ */
+
+use spacetimedb::ReducerContext;
+
+use crate::common::{
+    Vector3, COHESION_MAX_STEP, COHESION_RADIUS, FLOCK_MAX_NPCS_PER_TICK, SEPARATION_MAX_STEP, SEPARATION_RADIUS,
+};
+use crate::npc::{npc as _, Npc, NpcKind, NpcState};
+use crate::player as _;
+use crate::spatial::{self, SpatialEntity};
+use crate::spawner::{self, spawner as _};
+
+// Static NPCs act as obstacles but are never pushed
+fn is_mobile(npc: &Npc) -> bool {
+    npc.kind != NpcKind::TrainingDummy
+}
+
+// Members that are travelling somewhere; idle and attacking NPCs hold still
+fn is_travelling(npc: &Npc) -> bool {
+    matches!(npc.state, NpcState::Patrol | NpcState::Investigate | NpcState::Chase | NpcState::Return)
+}
+
+pub fn apply_cohesion(ctx: &ReducerContext, room_name: &str) {
+    let mut budget = FLOCK_MAX_NPCS_PER_TICK;
+    for group in ctx.db.spawner().room_name().filter(room_name) {
+        if budget == 0 {
+            break;
+        }
+        let members = spawner::members(ctx, group.spawner_id);
+        if members.len() < 2 {
+            continue;
+        }
+        let count = members.len() as f32;
+        let center_x = members.iter().map(|n| n.position.x).sum::<f32>() / count;
+        let center_z = members.iter().map(|n| n.position.z).sum::<f32>() / count;
+        for mut npc in members {
+            if budget == 0 {
+                break;
+            }
+            if !is_mobile(&npc) || !is_travelling(&npc) {
+                continue;
+            }
+            budget -= 1;
+            let (dx, dz) = (center_x - npc.position.x, center_z - npc.position.z);
+            let distance = (dx * dx + dz * dz).sqrt();
+            if distance <= COHESION_RADIUS {
+                continue;
+            }
+            let step = (distance - COHESION_RADIUS).min(COHESION_MAX_STEP);
+            npc.position.x += dx / distance * step;
+            npc.position.z += dz / distance * step;
+            spatial::track(ctx, SpatialEntity::Npc(npc.npc_id), room_name, &npc.position);
+            ctx.db.npc().npc_id().update(npc);
+        }
+    }
+}
+
+pub fn apply_separation(ctx: &ReducerContext, room_name: &str) {
+    let npcs: Vec<Npc> = ctx.db.npc().room_name().filter(room_name).collect();
+    if npcs.is_empty() {
+        return;
+    }
+    let obstacles: Vec<Vector3> = npcs.iter().map(|n| n.position.clone())
+        .chain(ctx.db.player().room_name().filter(room_name)
+            .filter(|p| !p.is_dead)
+            .map(|p| p.position))
+        .collect();
+
+    for (index, npc) in npcs.iter().enumerate() {
+        if !is_mobile(npc) {
+            continue;
+        }
+        let (mut push_x, mut push_z) = (0.0_f32, 0.0_f32);
+        for (other_index, other) in obstacles.iter().enumerate() {
+            if other_index == index {
+                continue;
+            }
+            let distance = npc.position.distance_xz(other);
+            if distance >= SEPARATION_RADIUS {
+                continue;
+            }
+            let overlap = (SEPARATION_RADIUS - distance) / SEPARATION_RADIUS;
+            if distance < 0.001 {
+                // Exactly stacked: break the tie deterministically by index
+                let angle = index as f32 * 2.399; // Golden angle spreads stacked entities
+                push_x += angle.cos() * overlap;
+                push_z += angle.sin() * overlap;
+            } else {
+                push_x += (npc.position.x - other.x) / distance * overlap;
+                push_z += (npc.position.z - other.z) / distance * overlap;
+            }
+        }
+
+        let magnitude = (push_x * push_x + push_z * push_z).sqrt();
+        if magnitude < 0.001 {
+            continue;
+        }
+        let step = (magnitude * SEPARATION_RADIUS * 0.5).min(SEPARATION_MAX_STEP);
+        let mut moved = npc.clone();
+        moved.position.x += push_x / magnitude * step;
+        moved.position.z += push_z / magnitude * step;
+        spatial::track(ctx, SpatialEntity::Npc(moved.npc_id), room_name, &moved.position);
+        ctx.db.npc().npc_id().update(moved);
+    }
+}
@@ -0,0 +1,143 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - support.rs
+ *
+ * Healing and support abilities.
+ *
+ * Key components:
+ * - heal_target: The healing counterpart of apply_damage. Restores health on a
+ *   living player (capped at max_health) and records a Heal entry in the
+ *   combat log so clients can show green numbers
+ * - cast_heal: The Paladin class spell. Heals the most injured ally the
+ *   Paladin is facing within HEAL_RANGE and line of sight (or the Paladin
+ *   themself if there is none), then applies a heal-over-time effect
+ * - is_ally: Who counts as a friendly heal target under the room's rules
+ *
+ * Related files:
+ * - combat.rs: resolve_spell_cast dispatches class spells here
+ * - status.rs: HealOverTime ticks call back into heal_target
+ * - spatial.rs: Range query and line-of-sight check
+ */
+
+use spacetimedb::{Identity, ReducerContext, Table};
+use std::time::Duration;
+
+use crate::combat::{combat_log, CombatLogEntry};
+use crate::common::{
+    forward_from_yaw, CombatEventKind, EntityId, HEAL_AMOUNT, HEAL_MANA_COST, HEAL_OVER_TIME_PER_SEC,
+    HEAL_OVER_TIME_SECS, HEAL_RANGE, HEAL_TARGET_CONE_COS, NO_TEAM,
+};
+use crate::player as _;
+use crate::room;
+use crate::scoreboard;
+use crate::spatial::{self, SpatialEntity};
+use crate::status::{self, StatusKind};
+use crate::PlayerData;
+
+// Restore up to `amount` health on a living player. Returns the amount
+// actually healed, or None if the target doesn't exist or is dead.
+pub fn heal_target(ctx: &ReducerContext, source: &EntityId, target_identity: Identity, amount: i32) -> Option<i32> {
+    let mut target = ctx.db.player().identity().find(target_identity)?;
+    if target.is_dead || amount <= 0 {
+        return None;
+    }
+    let healed = amount.min(target.max_health - target.health).max(0);
+    if healed == 0 {
+        return Some(0);
+    }
+    target.health += healed;
+    let room_name = target.room_name.clone();
+    ctx.db.player().identity().update(target);
+
+    scoreboard::record_healing(ctx, &room_name, source, healed);
+    ctx.db.combat_log().insert(CombatLogEntry {
+        log_id: 0,
+        room_name,
+        source: source.clone(),
+        target: EntityId::Player(target_identity),
+        amount: healed,
+        kind: CombatEventKind::Heal,
+        is_crit: false,
+        timestamp: ctx.timestamp,
+    });
+    Some(healed)
+}
+
+// Give mana back, capped at the player's maximum. Returns how much was restored
+pub fn restore_mana(ctx: &ReducerContext, identity: Identity, amount: i32) -> i32 {
+    let Some(mut player) = ctx.db.player().identity().find(identity) else {
+        return 0;
+    };
+    if player.is_dead || amount <= 0 {
+        return 0;
+    }
+    let restored = amount.min(player.max_mana - player.mana).max(0);
+    if restored > 0 {
+        player.mana += restored;
+        ctx.db.player().identity().update(player);
+    }
+    restored
+}
+
+// Whether `healer` may heal `target`: everyone in a non-PvP room, only
+// teammates once PvP is on
+pub fn is_ally(ctx: &ReducerContext, healer: &PlayerData, target: &PlayerData) -> bool {
+    if healer.identity == target.identity {
+        return true;
+    }
+    if healer.room_name != target.room_name {
+        return false;
+    }
+    let pvp = room::find_room(ctx, &target.room_name).is_some_and(|r| r.pvp_enabled);
+    !pvp || (healer.team != NO_TEAM && healer.team == target.team)
+}
+
+// The injured ally in front of `caster` with the lowest health fraction
+fn select_heal_target(ctx: &ReducerContext, caster: &PlayerData) -> Option<PlayerData> {
+    let map_id = room::find_room(ctx, &caster.room_name)?.map_id;
+    let forward = forward_from_yaw(caster.rotation.y);
+    spatial::query_radius(ctx, &caster.room_name, &caster.position, HEAL_RANGE)
+        .into_iter()
+        .filter_map(|hit| match hit.entity {
+            SpatialEntity::Player(identity) if identity != caster.identity => {
+                let dot = if hit.distance < 0.01 {
+                    1.0
+                } else {
+                    ((hit.position.x - caster.position.x) * forward.x
+                        + (hit.position.z - caster.position.z) * forward.z) / hit.distance
+                };
+                (dot >= HEAL_TARGET_CONE_COS).then(|| ctx.db.player().identity().find(identity)).flatten()
+            }
+            _ => None,
+        })
+        .filter(|p| p.health < p.max_health && is_ally(ctx, caster, p))
+        .filter(|p| spatial::has_line_of_sight(ctx, map_id, &caster.position, &p.position))
+        .min_by(|a, b| {
+            let fa = a.health as f32 / a.max_health.max(1) as f32;
+            let fb = b.health as f32 / b.max_health.max(1) as f32;
+            fa.total_cmp(&fb)
+        })
+}
+
+// Paladin spell: instant heal plus a heal-over-time on the chosen ally
+pub fn cast_heal(ctx: &ReducerContext, caster: &PlayerData) {
+    let Some(mut caster) = ctx.db.player().identity().find(caster.identity) else {
+        return;
+    };
+    if caster.mana < HEAL_MANA_COST {
+        return;
+    }
+    caster.mana -= HEAL_MANA_COST;
+    ctx.db.player().identity().update(caster.clone());
+
+    let target = select_heal_target(ctx, &caster).map(|p| p.identity).unwrap_or(caster.identity);
+    let source = EntityId::Player(caster.identity);
+    heal_target(ctx, &source, target, HEAL_AMOUNT);
+    status::apply_status(
+        ctx,
+        target,
+        StatusKind::HealOverTime,
+        source,
+        HEAL_OVER_TIME_PER_SEC,
+        Duration::from_secs(HEAL_OVER_TIME_SECS),
+    );
+}
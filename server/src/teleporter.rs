@@ -0,0 +1,138 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - teleporter.rs
+ *
+ * Teleporter pads that link two places of a room.
+ *
+ * Key components:
+ * - Teleporter table (public): A source volume (min/max box) and the
+ *   destination players standing in it are sent to
+ * - tick_teleporters: Called from game_tick. Moves every living player
+ *   standing on a pad to its destination, then keeps them from teleporting
+ *   again for the pad's cooldown_secs, so a pad whose destination is another
+ *   pad doesn't bounce them straight back
+ * - TeleportCooldown table (private): When each player may teleport again
+ * - place_teleporter / remove_teleporter: Room-owner reducers
+ *
+ * Related files:
+ * - player_logic.rs: set_player_position
+ * - room.rs: close_room clears a room's teleporters
+ */
+
+use spacetimedb::{Identity, ReducerContext, Table, Timestamp};
+use std::time::Duration;
+
+use crate::common::{Vector3, MAX_TELEPORTER_COOLDOWN_SECS};
+use crate::player as _;
+use crate::player_logic;
+use crate::room;
+use crate::PlayerData;
+
+#[spacetimedb::table(name = teleporter, public)]
+#[derive(Clone)]
+pub struct Teleporter {
+    #[primary_key]
+    #[auto_inc]
+    pub teleporter_id: u64,
+    #[index(btree)]
+    pub room_name: String,
+    pub min: Vector3,
+    pub max: Vector3,
+    pub destination: Vector3,
+    pub cooldown_secs: u64,
+}
+
+#[spacetimedb::table(name = teleport_cooldown)]
+pub struct TeleportCooldown {
+    #[primary_key]
+    pub identity: Identity,
+    pub ready_at: Timestamp,
+}
+
+impl Teleporter {
+    fn contains(&self, position: &Vector3) -> bool {
+        (self.min.x..=self.max.x).contains(&position.x)
+            && (self.min.y..=self.max.y).contains(&position.y)
+            && (self.min.z..=self.max.z).contains(&position.z)
+    }
+}
+
+fn on_cooldown(ctx: &ReducerContext, identity: Identity) -> bool {
+    ctx.db.teleport_cooldown().identity().find(identity).is_some_and(|c| c.ready_at > ctx.timestamp)
+}
+
+fn start_cooldown(ctx: &ReducerContext, identity: Identity, secs: u64) {
+    let cooldown = TeleportCooldown { identity, ready_at: ctx.timestamp + Duration::from_secs(secs) };
+    if ctx.db.teleport_cooldown().identity().find(identity).is_some() {
+        ctx.db.teleport_cooldown().identity().update(cooldown);
+    } else {
+        ctx.db.teleport_cooldown().insert(cooldown);
+    }
+}
+
+pub fn tick_teleporters(ctx: &ReducerContext, room_name: &str) {
+    let pads: Vec<Teleporter> = ctx.db.teleporter().room_name().filter(room_name).collect();
+    if pads.is_empty() {
+        return;
+    }
+    let players: Vec<PlayerData> = ctx.db.player().room_name().filter(room_name)
+        .filter(|p| !p.is_dead)
+        .collect();
+    for mut player in players {
+        let Some(pad) = pads.iter().find(|pad| pad.contains(&player.position)) else {
+            continue;
+        };
+        if on_cooldown(ctx, player.identity) {
+            continue;
+        }
+        start_cooldown(ctx, player.identity, pad.cooldown_secs);
+        player_logic::set_player_position(ctx, &mut player, pad.destination.clone());
+        ctx.db.player().identity().update(player);
+    }
+}
+
+pub fn clear_player(ctx: &ReducerContext, identity: Identity) {
+    ctx.db.teleport_cooldown().identity().delete(identity);
+}
+
+pub fn clear_room(ctx: &ReducerContext, room_name: &str) {
+    let pads: Vec<u64> = ctx.db.teleporter().room_name().filter(room_name).map(|t| t.teleporter_id).collect();
+    for teleporter_id in pads {
+        ctx.db.teleporter().teleporter_id().delete(teleporter_id);
+    }
+}
+
+// --- Teleporter Reducers ---
+
+#[spacetimedb::reducer]
+pub fn place_teleporter(
+    ctx: &ReducerContext,
+    room_name: String,
+    min: Vector3,
+    max: Vector3,
+    destination: Vector3,
+    cooldown_secs: u64,
+) -> Result<(), String> {
+    room::require_room_owner(ctx, &room_name)?;
+    if min.x >= max.x || min.y >= max.y || min.z >= max.z {
+        return Err("A pad's min corner must be below its max corner".to_string());
+    }
+    if cooldown_secs > MAX_TELEPORTER_COOLDOWN_SECS {
+        return Err(format!("Teleporter cooldowns are at most {} seconds", MAX_TELEPORTER_COOLDOWN_SECS));
+    }
+    let pad = Teleporter { teleporter_id: 0, room_name, min, max, destination, cooldown_secs };
+    if pad.contains(&pad.destination) {
+        return Err("A teleporter can't lead onto itself".to_string());
+    }
+    ctx.db.teleporter().insert(pad);
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn remove_teleporter(ctx: &ReducerContext, teleporter_id: u64) -> Result<(), String> {
+    let Some(pad) = ctx.db.teleporter().teleporter_id().find(teleporter_id) else {
+        return Err("Teleporter not found".to_string());
+    };
+    room::require_room_owner(ctx, &pad.room_name)?;
+    ctx.db.teleporter().teleporter_id().delete(teleporter_id);
+    Ok(())
+}
@@ -0,0 +1,227 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - theme.rs
+ *
+ * Seasonal and holiday world themes ("winter", "halloween"), driven entirely
+ * by data so running an event needs no code change.
+ *
+ * Key components:
+ * - ThemeAsset table (public): What a theme swaps. Each row maps a base
+ *   asset (a decoration model, a PropKind or an NpcKind name) to the
+ *   replacement clients draw while the theme is active
+ * - ThemeWindow table (public): The event calendar. Where windows overlap,
+ *   the one that started last wins
+ * - RoomTheme table (public): Each room's active theme; rooms without a row
+ *   are unthemed. Rooms follow the calendar unless their owner pinned a
+ *   theme (set_room_theme)
+ * - roll_over_themes: Scheduled every THEME_ROLLOVER_CHECK_SECS. Moves every
+ *   unpinned room onto the calendar's current theme
+ * - add_theme_asset / remove_theme_asset / schedule_theme /
+ *   cancel_theme_window: Admin reducers
+ *
+ * Related files:
+ * - decoration.rs / prop.rs / npc.rs: The themed assets
+ * - room.rs: create_room applies the current theme; close_room clears the
+ *   room's theme
+ */
+
+use spacetimedb::{ReducerContext, ScheduleAt, SpacetimeType, Table, Timestamp};
+use std::time::Duration;
+
+use crate::admin;
+use crate::common::{MAX_THEME_ASSET_NAME_LEN, MAX_THEME_NAME_LEN, THEME_ROLLOVER_CHECK_SECS};
+use crate::room::{self, room as _};
+
+#[derive(SpacetimeType, Clone, Copy, Debug, PartialEq)]
+pub enum ThemeAssetKind {
+    Decoration,
+    Prop,
+    NpcSkin,
+}
+
+#[spacetimedb::table(name = theme_asset, public)]
+#[derive(Clone)]
+pub struct ThemeAsset {
+    #[primary_key]
+    #[auto_inc]
+    pub asset_id: u64,
+    #[index(btree)]
+    pub theme: String,
+    pub kind: ThemeAssetKind,
+    pub base: String,
+    pub replacement: String,
+}
+
+#[spacetimedb::table(name = theme_window, public)]
+#[derive(Clone)]
+pub struct ThemeWindow {
+    #[primary_key]
+    #[auto_inc]
+    pub window_id: u64,
+    pub theme: String,
+    pub starts_at: Timestamp,
+    pub ends_at: Timestamp,
+}
+
+#[spacetimedb::table(name = room_theme, public)]
+#[derive(Clone)]
+pub struct RoomTheme {
+    #[primary_key]
+    pub room_name: String,
+    pub theme: String,
+    pub pinned: bool, // Set by the room owner; the calendar leaves it alone
+}
+
+#[spacetimedb::table(name = theme_schedule, scheduled(roll_over_themes))]
+pub struct ThemeSchedule {
+    #[primary_key]
+    #[auto_inc]
+    pub scheduled_id: u64,
+    pub scheduled_at: ScheduleAt,
+}
+
+pub fn schedule_themes(ctx: &ReducerContext) {
+    if ctx.db.theme_schedule().count() > 0 {
+        return;
+    }
+    ctx.db.theme_schedule().insert(ThemeSchedule {
+        scheduled_id: 0,
+        scheduled_at: ScheduleAt::Interval(Duration::from_secs(THEME_ROLLOVER_CHECK_SECS).into()),
+    });
+}
+
+fn validate_name(name: &str, max_len: usize, what: &str) -> Result<(), String> {
+    if name.is_empty() || name.len() > max_len {
+        return Err(format!("{} must be 1-{} characters", what, max_len));
+    }
+    Ok(())
+}
+
+// The calendar's theme right now, if any
+fn calendar_theme(ctx: &ReducerContext) -> Option<String> {
+    ctx.db.theme_window().iter()
+        .filter(|w| w.starts_at <= ctx.timestamp && ctx.timestamp < w.ends_at)
+        .max_by_key(|w| w.starts_at)
+        .map(|w| w.theme)
+}
+
+fn set_room_state(ctx: &ReducerContext, room_name: &str, theme: Option<String>, pinned: bool) {
+    let existing = ctx.db.room_theme().room_name().find(room_name.to_string());
+    match (existing, theme) {
+        (Some(state), Some(theme)) if state.theme == theme && state.pinned == pinned => {}
+        (Some(_), Some(theme)) => {
+            ctx.db.room_theme().room_name().update(RoomTheme { room_name: room_name.to_string(), theme, pinned });
+        }
+        (None, Some(theme)) => {
+            ctx.db.room_theme().insert(RoomTheme { room_name: room_name.to_string(), theme, pinned });
+        }
+        (Some(_), None) => {
+            ctx.db.room_theme().room_name().delete(room_name.to_string());
+        }
+        (None, None) => {}
+    }
+}
+
+// Put a room that follows the calendar on its current theme
+pub fn apply_calendar(ctx: &ReducerContext, room_name: &str) {
+    if ctx.db.room_theme().room_name().find(room_name.to_string()).is_some_and(|s| s.pinned) {
+        return;
+    }
+    set_room_state(ctx, room_name, calendar_theme(ctx), false);
+}
+
+fn apply_calendar_everywhere(ctx: &ReducerContext) {
+    let room_names: Vec<String> = ctx.db.room().iter().map(|r| r.room_name).collect();
+    for room_name in room_names {
+        apply_calendar(ctx, &room_name);
+    }
+}
+
+pub fn clear_room(ctx: &ReducerContext, room_name: &str) {
+    ctx.db.room_theme().room_name().delete(room_name.to_string());
+}
+
+// --- Theme Reducers ---
+
+#[spacetimedb::reducer]
+pub fn roll_over_themes(ctx: &ReducerContext, _schedule: ThemeSchedule) -> Result<(), String> {
+    if ctx.sender != ctx.identity() {
+        return Err("roll_over_themes may only be invoked by the scheduler".to_string());
+    }
+    apply_calendar_everywhere(ctx);
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn add_theme_asset(
+    ctx: &ReducerContext,
+    theme: String,
+    kind: ThemeAssetKind,
+    base: String,
+    replacement: String,
+) -> Result<(), String> {
+    admin::require_admin(ctx)?;
+    let (theme, base, replacement) = (theme.trim().to_string(), base.trim().to_string(), replacement.trim().to_string());
+    validate_name(&theme, MAX_THEME_NAME_LEN, "Theme names")?;
+    validate_name(&base, MAX_THEME_ASSET_NAME_LEN, "Asset names")?;
+    validate_name(&replacement, MAX_THEME_ASSET_NAME_LEN, "Asset names")?;
+    let duplicate = ctx.db.theme_asset().theme().filter(&theme).find(|a| a.kind == kind && a.base == base);
+    match duplicate {
+        Some(mut asset) => {
+            asset.replacement = replacement;
+            ctx.db.theme_asset().asset_id().update(asset);
+        }
+        None => {
+            ctx.db.theme_asset().insert(ThemeAsset { asset_id: 0, theme, kind, base, replacement });
+        }
+    }
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn remove_theme_asset(ctx: &ReducerContext, asset_id: u64) -> Result<(), String> {
+    admin::require_admin(ctx)?;
+    if !ctx.db.theme_asset().asset_id().delete(asset_id) {
+        return Err("Theme asset not found".to_string());
+    }
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn schedule_theme(ctx: &ReducerContext, theme: String, starts_at: Timestamp, ends_at: Timestamp) -> Result<(), String> {
+    admin::require_admin(ctx)?;
+    let theme = theme.trim().to_string();
+    validate_name(&theme, MAX_THEME_NAME_LEN, "Theme names")?;
+    if ends_at <= starts_at {
+        return Err("A theme must end after it starts".to_string());
+    }
+    ctx.db.theme_window().insert(ThemeWindow { window_id: 0, theme, starts_at, ends_at });
+    apply_calendar_everywhere(ctx);
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn cancel_theme_window(ctx: &ReducerContext, window_id: u64) -> Result<(), String> {
+    admin::require_admin(ctx)?;
+    if !ctx.db.theme_window().window_id().delete(window_id) {
+        return Err("Theme window not found".to_string());
+    }
+    apply_calendar_everywhere(ctx);
+    Ok(())
+}
+
+// Pin a room to `theme`, or with None go back to following the calendar
+#[spacetimedb::reducer]
+pub fn set_room_theme(ctx: &ReducerContext, room_name: String, theme: Option<String>) -> Result<(), String> {
+    room::require_room_owner(ctx, &room_name)?;
+    match theme.map(|t| t.trim().to_string()) {
+        Some(theme) => {
+            validate_name(&theme, MAX_THEME_NAME_LEN, "Theme names")?;
+            set_room_state(ctx, &room_name, Some(theme), true);
+        }
+        None => {
+            clear_room(ctx, &room_name);
+            apply_calendar(ctx, &room_name);
+        }
+    }
+    Ok(())
+}
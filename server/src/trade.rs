@@ -0,0 +1,345 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - trade.rs
+ *
+ * Player-to-player trading. Offered items and gold leave their owner's
+ * inventory and wallet into escrow, so nothing offered can be spent, sold
+ * or dropped mid-trade, and are swapped in one reducer once both players
+ * confirm.
+ *
+ * Key components:
+ * - TradeSession table (public): The two traders, their room, the gold each
+ *   put in and who has confirmed. Only the traders see it (RLS)
+ * - TradeItem table (public): One escrowed stack (or rolled item) and who
+ *   offered it
+ * - offer_trade: Opens a session with a player within TRADE_RANGE (one open
+ *   trade per player)
+ * - add_trade_item / set_trade_gold: Move items and gold into escrow. Any
+ *   change clears both confirmations, so nobody confirms a deal that then
+ *   changes under them
+ * - confirm_trade: When the second trader confirms (still in the room and
+ *   within TRADE_RANGE), each side's escrow goes to the other. If either
+ *   inventory can't take it, the confirmation fails and nothing moves
+ * - Every reducer but cancel_trade requires the trading feature flag, so
+ *   turning trading off freezes open trades until they are cancelled
+ * - cancel_trade: Either trader; escrow goes back to its owner
+ * - tick_trades: Called from game_tick. Cancels sessions idle for
+ *   TRADE_TIMEOUT_SECS or whose traders left the room
+ * - Refunded items that no longer fit are dropped at their owner's feet
+ *
+ * Related files:
+ * - inventory.rs / wallet.rs: What changes hands
+ * - config.rs: trading_enabled
+ * - lib.rs: identity_disconnected cancels the player's trade
+ * - room.rs: close_room cancels the room's trades
+ */
+
+use spacetimedb::{client_visibility_filter, Filter, Identity, ReducerContext, Table, Timestamp};
+use std::time::Duration;
+
+use crate::common::{MAX_TRADE_ITEMS, TRADE_RANGE, TRADE_TIMEOUT_SECS};
+use crate::config;
+use crate::inventory;
+use crate::item::item_def as _;
+use crate::loot;
+use crate::notification::{self, NotificationKind};
+use crate::player as _;
+use crate::wallet::{self, WalletReason};
+use crate::PlayerData;
+
+#[spacetimedb::table(name = trade_session, public)]
+#[derive(Clone)]
+pub struct TradeSession {
+    #[primary_key]
+    #[auto_inc]
+    pub trade_id: u64,
+    #[index(btree)]
+    pub room_name: String,
+    #[index(btree)]
+    pub initiator: Identity,
+    #[index(btree)]
+    pub partner: Identity,
+    pub initiator_gold: u64,
+    pub partner_gold: u64,
+    pub initiator_confirmed: bool,
+    pub partner_confirmed: bool,
+    pub updated_at: Timestamp,
+}
+
+#[spacetimedb::table(name = trade_item, public)]
+#[derive(Clone)]
+pub struct TradeItem {
+    #[primary_key]
+    #[auto_inc]
+    pub trade_item_id: u64,
+    #[index(btree)]
+    pub trade_id: u64,
+    pub offered_by: Identity,
+    pub item_def_id: u64,
+    pub quantity: u32,
+    pub instance_id: Option<u64>, // Rolled items (item_instance.rs)
+}
+
+#[client_visibility_filter]
+const INITIATOR_SEES_TRADE: Filter = Filter::Sql(
+    "SELECT * FROM trade_session WHERE initiator = :sender",
+);
+
+#[client_visibility_filter]
+const PARTNER_SEES_TRADE: Filter = Filter::Sql(
+    "SELECT * FROM trade_session WHERE partner = :sender",
+);
+
+#[client_visibility_filter]
+const INITIATOR_SEES_TRADE_ITEMS: Filter = Filter::Sql(
+    "SELECT trade_item.* FROM trade_item JOIN trade_session ON trade_item.trade_id = trade_session.trade_id WHERE trade_session.initiator = :sender",
+);
+
+#[client_visibility_filter]
+const PARTNER_SEES_TRADE_ITEMS: Filter = Filter::Sql(
+    "SELECT trade_item.* FROM trade_item JOIN trade_session ON trade_item.trade_id = trade_session.trade_id WHERE trade_session.partner = :sender",
+);
+
+impl TradeSession {
+    fn other(&self, identity: Identity) -> Identity {
+        if self.initiator == identity { self.partner } else { self.initiator }
+    }
+
+    fn gold_of(&self, identity: Identity) -> u64 {
+        if self.initiator == identity { self.initiator_gold } else { self.partner_gold }
+    }
+}
+
+fn open_trade(ctx: &ReducerContext, identity: Identity) -> Option<TradeSession> {
+    ctx.db.trade_session().initiator().filter(identity)
+        .chain(ctx.db.trade_session().partner().filter(identity))
+        .next()
+}
+
+fn require_trading(ctx: &ReducerContext) -> Result<(), String> {
+    if !config::current(ctx).trading_enabled {
+        return Err("Trading is disabled".to_string());
+    }
+    Ok(())
+}
+
+// The sender's session `trade_id`
+fn own_trade(ctx: &ReducerContext, trade_id: u64) -> Result<TradeSession, String> {
+    match ctx.db.trade_session().trade_id().find(trade_id) {
+        Some(trade) if trade.initiator == ctx.sender || trade.partner == ctx.sender => Ok(trade),
+        _ => Err("Trade not found".to_string()),
+    }
+}
+
+fn within_range(a: &PlayerData, b: &PlayerData, room_name: &str) -> bool {
+    a.room_name == room_name && b.room_name == room_name && a.position.distance_xz(&b.position) <= TRADE_RANGE
+}
+
+// Record a change to the offer: both sides have to look at it again
+fn touch(ctx: &ReducerContext, mut trade: TradeSession) {
+    trade.initiator_confirmed = false;
+    trade.partner_confirmed = false;
+    trade.updated_at = ctx.timestamp;
+    ctx.db.trade_session().trade_id().update(trade);
+}
+
+fn refund_item(ctx: &ReducerContext, owner: &PlayerData, item: &TradeItem) {
+    let Some(def) = ctx.db.item_def().item_def_id().find(item.item_def_id) else {
+        return;
+    };
+    if inventory::add_item(ctx, owner.identity, &def, item.quantity, item.instance_id).is_err() {
+        loot::spawn_drop(
+            ctx,
+            &owner.room_name,
+            owner.position.clone(),
+            item.item_def_id,
+            item.quantity,
+            item.instance_id,
+            Some(owner.identity),
+        );
+    }
+}
+
+// Give everything in escrow back and close the session. `leaving` is a
+// trader whose player row is already gone (disconnects)
+fn cancel(ctx: &ReducerContext, trade: TradeSession, leaving: Option<&PlayerData>) {
+    let owner_of = |identity: Identity| {
+        leaving.filter(|p| p.identity == identity).cloned()
+            .or_else(|| ctx.db.player().identity().find(identity))
+    };
+    let items: Vec<TradeItem> = ctx.db.trade_item().trade_id().filter(trade.trade_id).collect();
+    for item in items {
+        ctx.db.trade_item().trade_item_id().delete(item.trade_item_id);
+        match owner_of(item.offered_by) {
+            Some(owner) => refund_item(ctx, &owner, &item),
+            None => spacetimedb::log::warn!("[TRADE] Lost {}x item {} of {}", item.quantity, item.item_def_id, item.offered_by),
+        }
+    }
+    for identity in [trade.initiator, trade.partner] {
+        let gold = trade.gold_of(identity);
+        if let Err(e) = wallet::earn(ctx, identity, gold, WalletReason::Trade) {
+            spacetimedb::log::warn!("[TRADE] {} lost {} escrowed gold: {}", identity, gold, e);
+        }
+    }
+    ctx.db.trade_session().trade_id().delete(trade.trade_id);
+}
+
+// Hand each side's escrow to the other. Fails (and the reducer rolls back)
+// if an inventory can't take what it receives
+fn settle(ctx: &ReducerContext, trade: &TradeSession) -> Result<(), String> {
+    let items: Vec<TradeItem> = ctx.db.trade_item().trade_id().filter(trade.trade_id).collect();
+    for item in items {
+        let receiver = trade.other(item.offered_by);
+        ctx.db.trade_item().trade_item_id().delete(item.trade_item_id);
+        let Some(def) = ctx.db.item_def().item_def_id().find(item.item_def_id) else {
+            continue;
+        };
+        inventory::add_item(ctx, receiver, &def, item.quantity, item.instance_id)
+            .map_err(|_| "One of you doesn't have room for the items".to_string())?;
+    }
+    wallet::earn(ctx, trade.partner, trade.initiator_gold, WalletReason::Trade)?;
+    wallet::earn(ctx, trade.initiator, trade.partner_gold, WalletReason::Trade)?;
+    ctx.db.trade_session().trade_id().delete(trade.trade_id);
+    Ok(())
+}
+
+pub fn tick_trades(ctx: &ReducerContext, room_name: &str) {
+    let trades: Vec<TradeSession> = ctx.db.trade_session().room_name().filter(room_name).collect();
+    for trade in trades {
+        let present = |identity: Identity| {
+            ctx.db.player().identity().find(identity).is_some_and(|p| p.room_name == trade.room_name)
+        };
+        let idle = trade.updated_at + Duration::from_secs(TRADE_TIMEOUT_SECS) <= ctx.timestamp;
+        if idle || !present(trade.initiator) || !present(trade.partner) {
+            cancel(ctx, trade, None);
+        }
+    }
+}
+
+pub fn on_player_disconnected(ctx: &ReducerContext, player: &PlayerData) {
+    if let Some(trade) = open_trade(ctx, player.identity) {
+        cancel(ctx, trade, Some(player));
+    }
+}
+
+pub fn clear_room(ctx: &ReducerContext, room_name: &str) {
+    let trades: Vec<TradeSession> = ctx.db.trade_session().room_name().filter(room_name).collect();
+    for trade in trades {
+        cancel(ctx, trade, None);
+    }
+}
+
+// --- Trade Reducers ---
+
+#[spacetimedb::reducer]
+pub fn offer_trade(ctx: &ReducerContext, target: Identity) -> Result<(), String> {
+    require_trading(ctx)?;
+    if target == ctx.sender {
+        return Err("You can't trade with yourself".to_string());
+    }
+    let Some(player) = ctx.db.player().identity().find(ctx.sender) else {
+        return Err("Player not found".to_string());
+    };
+    let Some(partner) = ctx.db.player().identity().find(target) else {
+        return Err("Target player not found".to_string());
+    };
+    if !within_range(&player, &partner, &player.room_name) {
+        return Err("Too far away".to_string());
+    }
+    if open_trade(ctx, ctx.sender).is_some() || open_trade(ctx, target).is_some() {
+        return Err("One of you is already trading".to_string());
+    }
+    notification::notify(
+        ctx,
+        target,
+        NotificationKind::TradeOffer,
+        format!("{} wants to trade with you", player.username),
+    );
+    ctx.db.trade_session().insert(TradeSession {
+        trade_id: 0,
+        room_name: player.room_name,
+        initiator: ctx.sender,
+        partner: target,
+        initiator_gold: 0,
+        partner_gold: 0,
+        initiator_confirmed: false,
+        partner_confirmed: false,
+        updated_at: ctx.timestamp,
+    });
+    Ok(())
+}
+
+// Put `quantity` of an inventory stack into escrow
+#[spacetimedb::reducer]
+pub fn add_trade_item(ctx: &ReducerContext, trade_id: u64, item_id: u64, quantity: u32) -> Result<(), String> {
+    require_trading(ctx)?;
+    let trade = own_trade(ctx, trade_id)?;
+    let Some(player) = ctx.db.player().identity().find(ctx.sender) else {
+        return Err("Player not found".to_string());
+    };
+    let offered = ctx.db.trade_item().trade_id().filter(trade_id).filter(|i| i.offered_by == ctx.sender).count();
+    if offered >= MAX_TRADE_ITEMS {
+        return Err(format!("You can offer at most {} stacks", MAX_TRADE_ITEMS));
+    }
+    let (item_def_id, instance_id) = inventory::remove_from_stack(ctx, &player, item_id, quantity)?;
+    ctx.db.trade_item().insert(TradeItem {
+        trade_item_id: 0,
+        trade_id,
+        offered_by: ctx.sender,
+        item_def_id,
+        quantity,
+        instance_id,
+    });
+    touch(ctx, trade);
+    Ok(())
+}
+
+// Set how much gold the sender puts in; the difference moves between wallet and escrow
+#[spacetimedb::reducer]
+pub fn set_trade_gold(ctx: &ReducerContext, trade_id: u64, gold: u64) -> Result<(), String> {
+    require_trading(ctx)?;
+    let mut trade = own_trade(ctx, trade_id)?;
+    let current = trade.gold_of(ctx.sender);
+    if gold > current {
+        wallet::spend(ctx, ctx.sender, gold - current, WalletReason::Trade)?;
+    } else {
+        wallet::earn(ctx, ctx.sender, current - gold, WalletReason::Trade)?;
+    }
+    if trade.initiator == ctx.sender {
+        trade.initiator_gold = gold;
+    } else {
+        trade.partner_gold = gold;
+    }
+    touch(ctx, trade);
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn confirm_trade(ctx: &ReducerContext, trade_id: u64) -> Result<(), String> {
+    require_trading(ctx)?;
+    let mut trade = own_trade(ctx, trade_id)?;
+    if trade.initiator == ctx.sender {
+        trade.initiator_confirmed = true;
+    } else {
+        trade.partner_confirmed = true;
+    }
+    if !(trade.initiator_confirmed && trade.partner_confirmed) {
+        ctx.db.trade_session().trade_id().update(trade);
+        return Ok(());
+    }
+    let initiator = ctx.db.player().identity().find(trade.initiator);
+    let partner = ctx.db.player().identity().find(trade.partner);
+    let (Some(initiator), Some(partner)) = (initiator, partner) else {
+        return Err("Your trading partner is gone".to_string());
+    };
+    if !within_range(&initiator, &partner, &trade.room_name) {
+        return Err("Too far away".to_string());
+    }
+    settle(ctx, &trade)
+}
+
+#[spacetimedb::reducer]
+pub fn cancel_trade(ctx: &ReducerContext, trade_id: u64) -> Result<(), String> {
+    let trade = own_trade(ctx, trade_id)?;
+    cancel(ctx, trade, None);
+    Ok(())
+}
@@ -0,0 +1,146 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - trap.rs
+ *
+ * Traps that players place on the ground. A trap arms shortly after being
+ * placed, triggers on the first enemy that steps within TRAP_TRIGGER_RADIUS
+ * and disappears after TRAP_TTL_SECS if nothing does.
+ *
+ * Key components:
+ * - TrapKind: Spike (damage) or Snare (light damage plus a root)
+ * - Trap table (public): Owner, room, position, arming and expiry times
+ * - max_traps_for_class: Per-class limit on active traps
+ * - place_trap: Player reducer. The point must be within TRAP_PLACE_RANGE of
+ *   the player and on a tile of the room's map
+ * - tick_traps: Called from game_tick; triggers and expires the room's traps
+ *
+ * Related files:
+ * - combat.rs: Trap damage goes through apply_damage (room PvP rules apply)
+ * - status.rs: Snares apply the Rooted status
+ * - map.rs: is_on_map terrain validation
+ */
+
+use spacetimedb::{Identity, ReducerContext, SpacetimeType, Table, Timestamp};
+use std::time::Duration;
+
+use crate::combat;
+use crate::common::{
+    CombatEventKind, EntityId, Vector3, CLASS_PALADIN, CLASS_WIZARD, SNARE_DAMAGE, SNARE_ROOT_MS, SPIKE_TRAP_DAMAGE,
+    TRAP_ARM_DELAY_MS, TRAP_PLACE_RANGE, TRAP_TRIGGER_RADIUS, TRAP_TTL_SECS,
+};
+use crate::config;
+use crate::map;
+use crate::player as _;
+use crate::room;
+use crate::spatial::{self, SpatialEntity};
+use crate::status::{self, StatusKind};
+
+#[derive(SpacetimeType, Clone, Copy, Debug, PartialEq)]
+pub enum TrapKind {
+    Spike,
+    Snare,
+}
+
+#[spacetimedb::table(name = trap, public)]
+#[derive(Clone)]
+pub struct Trap {
+    #[primary_key]
+    #[auto_inc]
+    pub trap_id: u64,
+    #[index(btree)]
+    pub room_name: String,
+    #[index(btree)]
+    pub owner: Identity,
+    pub kind: TrapKind,
+    pub position: Vector3,
+    pub armed_at: Timestamp,
+    pub expires_at: Timestamp,
+}
+
+pub fn max_traps_for_class(character_class: &str) -> usize {
+    match character_class {
+        CLASS_WIZARD => 3,
+        CLASS_PALADIN => 1,
+        _ => 2,
+    }
+}
+
+#[spacetimedb::reducer]
+pub fn place_trap(ctx: &ReducerContext, kind: TrapKind, position: Vector3) -> Result<(), String> {
+    if !config::combat_enabled(ctx) {
+        return Err("Combat is disabled".to_string());
+    }
+    let Some(player) = ctx.db.player().identity().find(ctx.sender) else {
+        return Err("Player not found".to_string());
+    };
+    if player.is_dead {
+        return Err("Dead players can't place traps".to_string());
+    }
+    if player.position.distance_xz(&position) > TRAP_PLACE_RANGE {
+        return Err("Too far away to place a trap there".to_string());
+    }
+    let Some(room) = room::find_room(ctx, &player.room_name) else {
+        return Err("Room not found".to_string());
+    };
+    if !map::is_on_map(ctx, room.map_id, &position) {
+        return Err("Traps must be placed on solid ground".to_string());
+    }
+    let active = ctx.db.trap().owner().filter(ctx.sender).count();
+    if active >= max_traps_for_class(&player.character_class) {
+        return Err(format!("{}s can have at most {} traps", player.character_class, max_traps_for_class(&player.character_class)));
+    }
+    ctx.db.trap().insert(Trap {
+        trap_id: 0,
+        room_name: player.room_name,
+        owner: ctx.sender,
+        kind,
+        position,
+        armed_at: ctx.timestamp + Duration::from_millis(TRAP_ARM_DELAY_MS),
+        expires_at: ctx.timestamp + Duration::from_secs(TRAP_TTL_SECS),
+    });
+    Ok(())
+}
+
+pub fn clear_room(ctx: &ReducerContext, room_name: &str) {
+    let traps: Vec<u64> = ctx.db.trap().room_name().filter(room_name).map(|t| t.trap_id).collect();
+    for trap_id in traps {
+        ctx.db.trap().trap_id().delete(trap_id);
+    }
+}
+
+// Trigger armed traps that have a victim in range and remove expired ones
+pub fn tick_traps(ctx: &ReducerContext, room_name: &str) {
+    let traps: Vec<Trap> = ctx.db.trap().room_name().filter(room_name).collect();
+    for trap in traps {
+        if trap.expires_at <= ctx.timestamp {
+            ctx.db.trap().trap_id().delete(trap.trap_id);
+            continue;
+        }
+        if trap.armed_at > ctx.timestamp {
+            continue;
+        }
+        let source = EntityId::Player(trap.owner);
+        // The nearest living enemy that the trap can actually hurt
+        let victim = spatial::query_radius(ctx, room_name, &trap.position, TRAP_TRIGGER_RADIUS)
+            .into_iter()
+            .filter(|hit| matches!(hit.entity, SpatialEntity::Player(_) | SpatialEntity::Npc(_)))
+            .filter_map(|hit| Some((hit.entity.combatant()?, hit.distance)))
+            .filter(|(target, _)| target != &source)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(target, _)| target);
+        let Some(victim) = victim else {
+            continue;
+        };
+        let damage = match trap.kind {
+            TrapKind::Spike => SPIKE_TRAP_DAMAGE,
+            TrapKind::Snare => SNARE_DAMAGE,
+        };
+        // Friendly players (no PvP) walk over traps without springing them
+        if combat::apply_damage(ctx, &source, &victim, damage, CombatEventKind::Trap).is_none() {
+            continue;
+        }
+        if let (TrapKind::Snare, EntityId::Player(identity)) = (trap.kind, &victim) {
+            status::apply_status(ctx, *identity, StatusKind::Rooted, source, 0, Duration::from_millis(SNARE_ROOT_MS));
+        }
+        ctx.db.trap().trap_id().delete(trap.trap_id);
+    }
+}
@@ -0,0 +1,56 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - vote.rs
+ *
+ * Per-room vote counters, kept up to date incrementally so "has everyone
+ * voted?" never needs a scan over the players.
+ *
+ * Key components:
+ * - RoomVoteState table (public): voters (players in the room) and submitted
+ *   (how many of them have voted); clients use it for progress bars
+ * - adjust_counts: Applies a delta to a room's counters, creating the row on
+ *   first use. Called by submit_vote / reset_votes and whenever a player
+ *   joins or leaves a room
+ * - reset_all: Zeroes every room's submitted counter (reset_votes)
+ *
+ * Related files:
+ * - lib.rs: submit_vote / reset_votes reducers
+ * - room.rs: on_player_joined / on_player_left
+ */
+
+use spacetimedb::{ReducerContext, Table};
+
+#[spacetimedb::table(name = room_vote_state, public)]
+#[derive(Clone)]
+pub struct RoomVoteState {
+    #[primary_key]
+    pub room_name: String,
+    pub voters: u32,
+    pub submitted: u32,
+}
+
+pub fn adjust_counts(ctx: &ReducerContext, room_name: &str, voters_delta: i32, submitted_delta: i32) {
+    let apply = |count: u32, delta: i32| count.saturating_add_signed(delta);
+    match ctx.db.room_vote_state().room_name().find(room_name.to_string()) {
+        Some(mut state) => {
+            state.voters = apply(state.voters, voters_delta);
+            state.submitted = apply(state.submitted, submitted_delta).min(state.voters);
+            ctx.db.room_vote_state().room_name().update(state);
+        }
+        None => {
+            let voters = apply(0, voters_delta);
+            ctx.db.room_vote_state().insert(RoomVoteState {
+                room_name: room_name.to_string(),
+                voters,
+                submitted: apply(0, submitted_delta).min(voters),
+            });
+        }
+    }
+}
+
+pub fn reset_all(ctx: &ReducerContext) {
+    let states: Vec<RoomVoteState> = ctx.db.room_vote_state().iter().filter(|s| s.submitted > 0).collect();
+    for mut state in states {
+        state.submitted = 0;
+        ctx.db.room_vote_state().room_name().update(state);
+    }
+}
@@ -0,0 +1,134 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - wallet.rs
+ *
+ * Player gold. Every change goes through earn / spend, which refuse to
+ * overdraw or overflow a wallet and log what happened.
+ *
+ * Key components:
+ * - Wallet table (public): A player's gold. Players without a row have none.
+ *   Players only see their own wallet (RLS)
+ * - WalletTransaction table (public): One row per change with its reason and
+ *   the balance after it, for auditing. Players see their own; the newest
+ *   WALLET_LOG_KEPT per player are kept
+ * - earn / spend: Helper APIs for vendors (shop.rs), loot gold (loot.rs),
+ *   trading and the auction house. They return an error without changing
+ *   anything when the balance would overflow or go negative
+ * - grant_gold: Admin reducer (negative amounts take gold away)
+ *
+ * Related files:
+ * - shop.rs: Buying and selling
+ * - loot.rs: Gold from loot tables
+ * - trade.rs: Gold held in escrow during trades
+ * - market.rs: Listing fees, purchases and sales
+ * - durability.rs: Repairs
+ */
+
+use spacetimedb::{client_visibility_filter, Filter, Identity, ReducerContext, SpacetimeType, Table, Timestamp};
+
+use crate::admin;
+use crate::common::WALLET_LOG_KEPT;
+
+#[derive(SpacetimeType, Clone, Copy, Debug, PartialEq)]
+pub enum WalletReason {
+    Loot,
+    VendorBuy,
+    VendorSell,
+    Trade,
+    MarketFee,
+    MarketPurchase,
+    MarketSale,
+    Repair,
+    Admin,
+}
+
+#[spacetimedb::table(name = wallet, public)]
+#[derive(Clone)]
+pub struct Wallet {
+    #[primary_key]
+    pub identity: Identity,
+    pub gold: u64,
+}
+
+#[spacetimedb::table(name = wallet_transaction, public)]
+#[derive(Clone)]
+pub struct WalletTransaction {
+    #[primary_key]
+    #[auto_inc]
+    pub transaction_id: u64,
+    #[index(btree)]
+    pub identity: Identity,
+    pub delta: i128, // Positive for gold earned
+    pub reason: WalletReason,
+    pub balance: u64, // After the change
+    pub timestamp: Timestamp,
+}
+
+#[client_visibility_filter]
+const OWNER_SEES_WALLET: Filter = Filter::Sql(
+    "SELECT * FROM wallet WHERE identity = :sender",
+);
+
+#[client_visibility_filter]
+const OWNER_SEES_WALLET_TRANSACTIONS: Filter = Filter::Sql(
+    "SELECT * FROM wallet_transaction WHERE identity = :sender",
+);
+
+pub fn balance(ctx: &ReducerContext, identity: Identity) -> u64 {
+    ctx.db.wallet().identity().find(identity).map_or(0, |w| w.gold)
+}
+
+fn write(ctx: &ReducerContext, identity: Identity, gold: u64, delta: i128, reason: WalletReason) {
+    let wallet = Wallet { identity, gold };
+    if ctx.db.wallet().identity().find(identity).is_some() {
+        ctx.db.wallet().identity().update(wallet);
+    } else {
+        ctx.db.wallet().insert(wallet);
+    }
+    ctx.db.wallet_transaction().insert(WalletTransaction {
+        transaction_id: 0,
+        identity,
+        delta,
+        reason,
+        balance: gold,
+        timestamp: ctx.timestamp,
+    });
+    let mut log: Vec<u64> = ctx.db.wallet_transaction().identity().filter(identity).map(|t| t.transaction_id).collect();
+    log.sort_unstable_by(|a, b| b.cmp(a)); // Newest first
+    for transaction_id in log.into_iter().skip(WALLET_LOG_KEPT) {
+        ctx.db.wallet_transaction().transaction_id().delete(transaction_id);
+    }
+}
+
+pub fn earn(ctx: &ReducerContext, identity: Identity, amount: u64, reason: WalletReason) -> Result<(), String> {
+    if amount == 0 {
+        return Ok(());
+    }
+    let Some(gold) = balance(ctx, identity).checked_add(amount) else {
+        return Err("That wallet can't hold any more gold".to_string());
+    };
+    write(ctx, identity, gold, amount as i128, reason);
+    Ok(())
+}
+
+pub fn spend(ctx: &ReducerContext, identity: Identity, amount: u64, reason: WalletReason) -> Result<(), String> {
+    if amount == 0 {
+        return Ok(());
+    }
+    let Some(gold) = balance(ctx, identity).checked_sub(amount) else {
+        return Err("Not enough gold".to_string());
+    };
+    write(ctx, identity, gold, -(amount as i128), reason);
+    Ok(())
+}
+
+// --- Wallet Reducers ---
+
+#[spacetimedb::reducer]
+pub fn grant_gold(ctx: &ReducerContext, identity: Identity, amount: i64) -> Result<(), String> {
+    admin::require_admin(ctx)?;
+    if amount >= 0 {
+        earn(ctx, identity, amount as u64, WalletReason::Admin)
+    } else {
+        spend(ctx, identity, amount.unsigned_abs(), WalletReason::Admin)
+    }
+}
@@ -0,0 +1,134 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - wardrobe.rs
+ *
+ * Cosmetics: how a player looks, kept apart from the gear that decides how
+ * they fight. Nothing here touches stats.
+ *
+ * Key components:
+ * - CosmeticUnlock table (public): A cosmetic a player owns, per slot.
+ *   Players only see their own unlocks (RLS). The PLAYER_COLORS every
+ *   player starts with need no unlock
+ * - Wardrobe table (public): What a player shows in each CosmeticSlot, so
+ *   every client can draw it. Clients draw a worn cosmetic over the
+ *   equipment's cosmetic_id (equipment.rs) in that spot
+ * - set_cosmetic: Player reducer. Shows an owned cosmetic in a slot, or
+ *   clears the slot with None. The Color slot can't be cleared: it replaces
+ *   the color assigned at registration (PlayerData.color) and is kept across
+ *   rejoins
+ * - grant_cosmetic: Admin reducer unlocking a cosmetic for a player
+ *
+ * Related files:
+ * - lib.rs: register_player restores a chosen color
+ * - equipment.rs: Gear cosmetics
+ */
+
+use spacetimedb::{client_visibility_filter, Filter, Identity, ReducerContext, SpacetimeType, Table, Timestamp};
+
+use crate::admin;
+use crate::common::{MAX_COSMETIC_ID_LEN, PLAYER_COLORS};
+use crate::player as _;
+
+#[derive(SpacetimeType, Clone, Copy, Debug, PartialEq)]
+pub enum CosmeticSlot {
+    Color,
+    Hat,
+    Outfit,
+    Cape,
+    WeaponSkin,
+}
+
+#[spacetimedb::table(name = cosmetic_unlock, public)]
+#[derive(Clone)]
+pub struct CosmeticUnlock {
+    #[primary_key]
+    #[auto_inc]
+    pub unlock_id: u64,
+    #[index(btree)]
+    pub owner: Identity,
+    pub slot: CosmeticSlot,
+    pub cosmetic_id: String,
+    pub unlocked_at: Timestamp,
+}
+
+#[spacetimedb::table(name = wardrobe, public)]
+#[derive(Clone)]
+pub struct Wardrobe {
+    #[primary_key]
+    #[auto_inc]
+    pub wardrobe_id: u64,
+    #[index(btree)]
+    pub owner: Identity,
+    pub slot: CosmeticSlot,
+    pub cosmetic_id: String,
+}
+
+#[client_visibility_filter]
+const OWNER_SEES_COSMETIC_UNLOCKS: Filter = Filter::Sql(
+    "SELECT * FROM cosmetic_unlock WHERE owner = :sender",
+);
+
+fn owns(ctx: &ReducerContext, owner: Identity, slot: CosmeticSlot, cosmetic_id: &str) -> bool {
+    (slot == CosmeticSlot::Color && PLAYER_COLORS.contains(&cosmetic_id))
+        || ctx.db.cosmetic_unlock().owner().filter(owner).any(|u| u.slot == slot && u.cosmetic_id == cosmetic_id)
+}
+
+fn worn(ctx: &ReducerContext, owner: Identity, slot: CosmeticSlot) -> Option<Wardrobe> {
+    ctx.db.wardrobe().owner().filter(owner).find(|w| w.slot == slot)
+}
+
+// The color a player picked, if any (register_player)
+pub fn chosen_color(ctx: &ReducerContext, identity: Identity) -> Option<String> {
+    worn(ctx, identity, CosmeticSlot::Color).map(|w| w.cosmetic_id)
+}
+
+// --- Wardrobe Reducers ---
+
+#[spacetimedb::reducer]
+pub fn set_cosmetic(ctx: &ReducerContext, slot: CosmeticSlot, cosmetic_id: Option<String>) -> Result<(), String> {
+    let Some(mut player) = ctx.db.player().identity().find(ctx.sender) else {
+        return Err("Player not found".to_string());
+    };
+    if let Some(id) = cosmetic_id.as_deref().filter(|id| !owns(ctx, ctx.sender, slot, id)) {
+        return Err(format!("You haven't unlocked {}", id));
+    }
+    if slot == CosmeticSlot::Color {
+        let Some(color) = cosmetic_id.clone() else {
+            return Err("Pick a color".to_string());
+        };
+        player.color = color;
+        ctx.db.player().identity().update(player);
+    }
+    match (worn(ctx, ctx.sender, slot), cosmetic_id) {
+        (Some(mut row), Some(cosmetic_id)) => {
+            row.cosmetic_id = cosmetic_id;
+            ctx.db.wardrobe().wardrobe_id().update(row);
+        }
+        (None, Some(cosmetic_id)) => {
+            ctx.db.wardrobe().insert(Wardrobe { wardrobe_id: 0, owner: ctx.sender, slot, cosmetic_id });
+        }
+        (Some(row), None) => {
+            ctx.db.wardrobe().wardrobe_id().delete(row.wardrobe_id);
+        }
+        (None, None) => {}
+    }
+    Ok(())
+}
+
+#[spacetimedb::reducer]
+pub fn grant_cosmetic(ctx: &ReducerContext, identity: Identity, slot: CosmeticSlot, cosmetic_id: String) -> Result<(), String> {
+    admin::require_admin(ctx)?;
+    if cosmetic_id.is_empty() || cosmetic_id.len() > MAX_COSMETIC_ID_LEN {
+        return Err(format!("Cosmetic ids are 1-{} characters", MAX_COSMETIC_ID_LEN));
+    }
+    if owns(ctx, identity, slot, &cosmetic_id) {
+        return Err("They already own that".to_string());
+    }
+    ctx.db.cosmetic_unlock().insert(CosmeticUnlock {
+        unlock_id: 0,
+        owner: identity,
+        slot,
+        cosmetic_id,
+        unlocked_at: ctx.timestamp,
+    });
+    Ok(())
+}
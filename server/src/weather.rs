@@ -0,0 +1,165 @@
+/*!
+ * Vibe Coding Starter Pack: 3D Multiplayer - weather.rs
+ *
+ * Per-room weather that drifts between clear skies, rain, storms and fog.
+ *
+ * Key components:
+ * - WeatherState table (public): Each room's current weather and when it
+ *   changes next. Rooms without a row have clear weather
+ * - WeatherSchedule table: One interval row (every WEATHER_CHECK_SECS)
+ *   driving update_weather, which moves every room whose weather is due to
+ *   its next state. Transitions are weighted per state (storms only build
+ *   out of rain and calm down into rain or clear skies)
+ * - Effects: Rain and storms slow player movement (move_multiplier, used by
+ *   player_logic.rs); fog shortens how far NPCs see players
+ *   (vision_multiplier, used by npc_ai.rs)
+ * - set_weather: Room-owner reducer that forces a weather for a while
+ *
+ * Related files:
+ * - lib.rs: init schedules the updates
+ * - room.rs: close_room clears a room's weather
+ */
+
+use spacetimedb::rand::Rng;
+use spacetimedb::{ReducerContext, ScheduleAt, SpacetimeType, Table, Timestamp};
+use std::time::Duration;
+
+use crate::common::{
+    FOG_VISION_MULTIPLIER, MAX_WEATHER_SECS, MIN_WEATHER_SECS, RAIN_MOVE_MULTIPLIER, STORM_MOVE_MULTIPLIER,
+    WEATHER_CHECK_SECS,
+};
+use crate::room::{self, room as _};
+
+#[derive(SpacetimeType, Clone, Copy, Debug, PartialEq)]
+pub enum Weather {
+    Clear,
+    Rain,
+    Storm,
+    Fog,
+}
+
+impl Weather {
+    // Weighted next states
+    fn transitions(self) -> &'static [(Weather, u32)] {
+        match self {
+            Weather::Clear => &[(Weather::Clear, 50), (Weather::Rain, 30), (Weather::Fog, 20)],
+            Weather::Rain => &[(Weather::Clear, 40), (Weather::Rain, 20), (Weather::Storm, 30), (Weather::Fog, 10)],
+            Weather::Storm => &[(Weather::Rain, 70), (Weather::Clear, 30)],
+            Weather::Fog => &[(Weather::Clear, 60), (Weather::Rain, 20), (Weather::Fog, 20)],
+        }
+    }
+
+    // Player movement speed factor
+    pub fn move_multiplier(self) -> f32 {
+        match self {
+            Weather::Rain => RAIN_MOVE_MULTIPLIER,
+            Weather::Storm => STORM_MOVE_MULTIPLIER,
+            Weather::Clear | Weather::Fog => 1.0,
+        }
+    }
+
+    // NPC sight range factor
+    pub fn vision_multiplier(self) -> f32 {
+        match self {
+            Weather::Fog => FOG_VISION_MULTIPLIER,
+            Weather::Clear | Weather::Rain | Weather::Storm => 1.0,
+        }
+    }
+}
+
+#[spacetimedb::table(name = weather_state, public)]
+#[derive(Clone)]
+pub struct WeatherState {
+    #[primary_key]
+    pub room_name: String,
+    pub weather: Weather,
+    pub started_at: Timestamp,
+    pub changes_at: Timestamp,
+}
+
+#[spacetimedb::table(name = weather_schedule, scheduled(update_weather))]
+pub struct WeatherSchedule {
+    #[primary_key]
+    #[auto_inc]
+    pub scheduled_id: u64,
+    pub scheduled_at: ScheduleAt,
+}
+
+pub fn schedule_weather(ctx: &ReducerContext) {
+    if ctx.db.weather_schedule().count() > 0 {
+        return;
+    }
+    ctx.db.weather_schedule().insert(WeatherSchedule {
+        scheduled_id: 0,
+        scheduled_at: ScheduleAt::Interval(Duration::from_secs(WEATHER_CHECK_SECS).into()),
+    });
+}
+
+pub fn current(ctx: &ReducerContext, room_name: &str) -> Weather {
+    ctx.db.weather_state().room_name().find(room_name.to_string())
+        .map(|state| state.weather)
+        .unwrap_or(Weather::Clear)
+}
+
+fn next_weather(rng: &mut impl Rng, weather: Weather) -> Weather {
+    let transitions = weather.transitions();
+    let total: u32 = transitions.iter().map(|(_, weight)| weight).sum();
+    let mut roll = rng.gen_range(0..total);
+    for (next, weight) in transitions {
+        if roll < *weight {
+            return *next;
+        }
+        roll -= weight;
+    }
+    weather
+}
+
+fn set_state(ctx: &ReducerContext, room_name: &str, weather: Weather, duration: Duration) {
+    let state = WeatherState {
+        room_name: room_name.to_string(),
+        weather,
+        started_at: ctx.timestamp,
+        changes_at: ctx.timestamp + duration,
+    };
+    if ctx.db.weather_state().room_name().find(room_name.to_string()).is_some() {
+        ctx.db.weather_state().room_name().update(state);
+    } else {
+        ctx.db.weather_state().insert(state);
+    }
+}
+
+pub fn clear_room(ctx: &ReducerContext, room_name: &str) {
+    ctx.db.weather_state().room_name().delete(room_name.to_string());
+}
+
+// --- Weather Reducers ---
+
+#[spacetimedb::reducer]
+pub fn update_weather(ctx: &ReducerContext, _schedule: WeatherSchedule) -> Result<(), String> {
+    if ctx.sender != ctx.identity() {
+        return Err("update_weather may only be invoked by the scheduler".to_string());
+    }
+    let mut rng = ctx.rng();
+    let room_names: Vec<String> = ctx.db.room().iter().map(|r| r.room_name).collect();
+    for room_name in room_names {
+        let state = ctx.db.weather_state().room_name().find(room_name.clone());
+        if state.as_ref().is_some_and(|s| s.changes_at > ctx.timestamp) {
+            continue;
+        }
+        let weather = next_weather(&mut rng, state.map(|s| s.weather).unwrap_or(Weather::Clear));
+        let duration = Duration::from_secs(rng.gen_range(MIN_WEATHER_SECS..=MAX_WEATHER_SECS));
+        set_state(ctx, &room_name, weather, duration);
+    }
+    Ok(())
+}
+
+// Force a room's weather for `duration_secs`, after which it drifts again
+#[spacetimedb::reducer]
+pub fn set_weather(ctx: &ReducerContext, room_name: String, weather: Weather, duration_secs: u64) -> Result<(), String> {
+    room::require_room_owner(ctx, &room_name)?;
+    if !(MIN_WEATHER_SECS..=MAX_WEATHER_SECS).contains(&duration_secs) {
+        return Err(format!("Weather lasts {}-{} seconds", MIN_WEATHER_SECS, MAX_WEATHER_SECS));
+    }
+    set_state(ctx, &room_name, weather, Duration::from_secs(duration_secs));
+    Ok(())
+}